@@ -51,6 +51,40 @@ fn start(c: &mut Criterion) {
     });
 }
 
+// the node tree build cost which is paid once for each model version by the cache
+fn build(c: &mut Criterion) {
+    c.bench_function("build", |b| {
+        let text = include_str!("./start.yml");
+        let workflow = Workflow::from_yml(text).unwrap();
+        b.iter(move || {
+            workflow.valid().unwrap();
+        })
+    });
+}
+
+fn start_large(c: &mut Criterion) {
+    c.bench_function("start_large", |b| {
+        let rt = Runtime::new().unwrap();
+        rt.block_on(async move {
+            let engine = Engine::new();
+            let mut workflow = Workflow::new().with_id("large");
+            for i in 0..100 {
+                let mut step = acts::Step::new().with_id(&format!("step{i}"));
+                step.acts.push(acts::Act::msg(|msg| msg.with_key("msg1")));
+                workflow.steps.push(step);
+            }
+            engine.executor().model().deploy(&workflow).unwrap();
+            b.iter(move || {
+                engine
+                    .executor()
+                    .proc()
+                    .start(&workflow.id, &Vars::new())
+                    .unwrap();
+            })
+        });
+    });
+}
+
 fn act(c: &mut Criterion) {
     c.bench_function("act", |b| {
         let rt = Runtime::new().unwrap();
@@ -102,5 +136,46 @@ fn act(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, load, deploy, start, act);
+// the script evaluations of the `if` and `run` in the model, reported as evaluations per second
+fn eval(c: &mut Criterion) {
+    const EVALS: u64 = 7;
+    let mut group = c.benchmark_group("eval");
+    group.throughput(Throughput::Elements(EVALS));
+    group.bench_function("script", |b| {
+        let rt = Runtime::new().unwrap();
+
+        b.to_async(rt).iter_custom(|iters| async move {
+            let engine = Engine::new();
+            let (s, sig) = engine.signal(()).double();
+            let text = include_str!("./eval.yml");
+            let workflow = Workflow::from_yml(text).unwrap();
+            engine.executor().model().deploy(&workflow).unwrap();
+
+            let count = Arc::new(Mutex::new(0));
+            engine.channel().on_complete(move |e| {
+                if e.is_type("workflow") {
+                    let mut count = count.lock().unwrap();
+                    *count += 1;
+                    if *count >= iters {
+                        s.close();
+                    }
+                }
+            });
+
+            let start = Instant::now();
+            for _ in 0..iters {
+                engine
+                    .executor()
+                    .proc()
+                    .start(&workflow.id, &Vars::new())
+                    .unwrap();
+            }
+            sig.recv().await;
+            start.elapsed()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, load, deploy, build, start, start_large, act, eval);
 criterion_main!(benches);
@@ -26,20 +26,28 @@ async fn adapter_set_extern_store_test() {
 #[derive(Debug, Clone)]
 pub struct TestStore {
     models: Collect<data::Model>,
+    model_versions: Collect<data::Model>,
+    fragments: Collect<data::Model>,
     procs: Collect<data::Proc>,
     tasks: Collect<data::Task>,
     packages: Collect<data::Package>,
+    package_versions: Collect<data::Package>,
     messages: Collect<data::Message>,
+    logs: Collect<data::Log>,
 }
 
 impl TestStore {
     pub fn new() -> Self {
         Self {
             models: Collect::new(),
+            model_versions: Collect::new(),
+            fragments: Collect::new(),
             procs: Collect::new(),
             tasks: Collect::new(),
             packages: Collect::new(),
+            package_versions: Collect::new(),
             messages: Collect::new(),
+            logs: Collect::new(),
         }
     }
 }
@@ -52,6 +60,14 @@ impl StoreAdapter for TestStore {
         Arc::new(self.models.clone())
     }
 
+    fn model_versions(&self) -> Arc<dyn DbSet<Item = data::Model>> {
+        Arc::new(self.model_versions.clone())
+    }
+
+    fn fragments(&self) -> Arc<dyn DbSet<Item = data::Model>> {
+        Arc::new(self.fragments.clone())
+    }
+
     fn procs(&self) -> Arc<dyn DbSet<Item = data::Proc>> {
         Arc::new(self.procs.clone())
     }
@@ -64,9 +80,17 @@ impl StoreAdapter for TestStore {
         Arc::new(self.packages.clone())
     }
 
+    fn package_versions(&self) -> Arc<dyn DbSet<Item = data::Package>> {
+        Arc::new(self.package_versions.clone())
+    }
+
     fn messages(&self) -> Arc<dyn DbSet<Item = data::Message>> {
         Arc::new(self.messages.clone())
     }
+
+    fn logs(&self) -> Arc<dyn DbSet<Item = data::Log>> {
+        Arc::new(self.logs.clone())
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -1,10 +1,11 @@
 use std::sync::Arc;
 
-use crate::{Config, Engine, StoreAdapter};
+use crate::{Clock, Config, Engine, Result, StoreAdapter};
 
 pub struct Builder {
     config: Config,
     store: Option<Arc<dyn StoreAdapter>>,
+    clock: Option<Arc<dyn Clock>>,
 }
 
 impl Default for Builder {
@@ -18,6 +19,7 @@ impl Builder {
         Self {
             config: Config::default(),
             store: None,
+            clock: None,
         }
     }
 
@@ -60,12 +62,33 @@ impl Builder {
         self
     }
 
+    /// capture the latest console logs of each task in store, 0 means no capture
+    pub fn script_log_limit(mut self, limit: usize) -> Self {
+        self.config.script_log_limit = limit;
+        self
+    }
+
     pub fn store<STORE: StoreAdapter + Clone + 'static>(mut self, store: &STORE) -> Self {
         self.store = Some(Arc::new(store.clone()));
         self
     }
 
+    /// set the engine time source, the default is the system clock
+    pub fn clock<CLOCK: Clock + Clone + 'static>(mut self, clock: &CLOCK) -> Self {
+        self.clock = Some(Arc::new(clock.clone()));
+        self
+    }
+
+    /// builds the engine
+    ///
+    /// # Panics
+    /// panics when the store cannot be opened or migrated, use `try_build` to get the error
     pub fn build(&self) -> Engine {
-        Engine::new_with_config(&self.config, self.store.clone())
+        self.try_build().unwrap()
+    }
+
+    /// builds the engine, the error is returned when the store cannot be opened or migrated
+    pub fn try_build(&self) -> Result<Engine> {
+        Engine::new_with_config(&self.config, self.store.clone(), self.clock.clone())
     }
 }
@@ -1,7 +1,8 @@
 use crate::{
-    sch::{Proc, Runtime, Task},
+    env::{self, Enviroment},
+    sch::{NodeTree, Proc, Runtime, Task},
     store::Store,
-    Engine, Result, ShareLock, StoreAdapter,
+    Engine, ModelInfo, Result, ShareLock, StoreAdapter, Workflow,
 };
 use moka::sync::Cache as MokaCache;
 use std::sync::{Arc, RwLock};
@@ -11,7 +12,12 @@ use tracing::{debug, error, instrument};
 pub struct Cache {
     cap: usize,
     procs: MokaCache<String, Arc<Proc>>,
+    models: MokaCache<(String, u32), Arc<Workflow>>,
+    latest: MokaCache<String, u32>,
+    trees: MokaCache<(String, u32), Arc<NodeTree>>,
     store: ShareLock<Arc<Store>>,
+    /// the env to load the bytecode which is kept with the models
+    env: ShareLock<Option<Arc<Enviroment>>>,
 }
 
 impl std::fmt::Debug for Cache {
@@ -28,7 +34,11 @@ impl Cache {
         Self {
             cap,
             procs: MokaCache::new(cap as u64),
+            models: MokaCache::new(cap as u64),
+            latest: MokaCache::new(cap as u64),
+            trees: MokaCache::new(cap as u64),
             store: Arc::new(RwLock::new(Store::default())),
+            env: Arc::new(RwLock::new(None)),
         }
     }
 
@@ -45,16 +55,95 @@ impl Cache {
         self.procs.entry_count() as usize
     }
 
-    pub fn init(&self, engine: &Engine) {
+    pub fn init(&self, engine: &Engine) -> Result<()> {
         debug!("cache::init");
+        *self.env.write().unwrap() = Some(engine.runtime().env().clone());
+        let clock = self.store().clock();
         #[cfg(feature = "store")]
         {
             let config = engine.config();
-            *self.store.write().unwrap() =
-                Arc::new(Store::local(&config.data_dir, &config.db_name));
+            let store = Store::local(&config.data_dir, &config.db_name)?;
+            store.set_clock(&clock);
+            *self.store.write().unwrap() = Arc::new(store);
         }
         if let Some(store) = engine.adapter().store() {
-            *self.store.write().unwrap() = Arc::new(Store::create(store));
+            let store = Store::create(store);
+            store.set_clock(&clock);
+            *self.store.write().unwrap() = Arc::new(store);
+        }
+        Ok(())
+    }
+
+    /// gets the resolved model by the id and version, it is parsed only once for each version
+    /// the cached model is returned without reading the store, and the latest version is
+    /// cached until the model is deployed or removed
+    pub fn model(&self, mid: &str, ver: Option<u32>) -> Result<Arc<Workflow>> {
+        if let Some(ver) = ver.or_else(|| self.latest.get(mid)) {
+            if let Some(model) = self.models.get(&(mid.to_string(), ver)) {
+                return Ok(model);
+            }
+        }
+
+        let store = self.store();
+        let data = store.find_model(mid, ver)?;
+        let key = (data.id.clone(), data.ver);
+        if ver.is_none() {
+            self.latest.insert(data.id.clone(), data.ver);
+        }
+
+        // loads the bytecode which is compiled at deploy, so the scripts are not compiled again
+        let codes = env::decode_codes(&data.code);
+        let info: ModelInfo = data.into();
+        let model = Arc::new(store.resolve_model(&info.workflow()?)?);
+        if let Some(env) = self.env.read().unwrap().as_ref() {
+            for (script, code) in &codes {
+                env.load_code(&model.lang, script, code);
+            }
+        }
+        self.models.insert(key, model.clone());
+        Ok(model)
+    }
+
+    /// caches the resolved model which is deployed as the latest version
+    pub fn push_model(&self, model: Workflow) {
+        self.latest.insert(model.id.clone(), model.ver());
+        self.models
+            .insert((model.id.clone(), model.ver()), Arc::new(model));
+    }
+
+    #[cfg(test)]
+    pub fn is_model_cached(&self, mid: &str, ver: u32) -> bool {
+        self.models.contains_key(&(mid.to_string(), ver))
+    }
+
+    /// gets the compiled node tree by the model id and version
+    /// the model without version is not deployed, so it is built every time
+    pub fn tree(&self, model: &Workflow) -> Result<Arc<NodeTree>> {
+        if model.ver() == 0 {
+            return Ok(Arc::new(NodeTree::build(&mut model.clone())?));
+        }
+
+        let key = (model.id.clone(), model.ver());
+        if let Some(tree) = self.trees.get(&key) {
+            return Ok(tree);
+        }
+        let tree = Arc::new(NodeTree::build(&mut model.clone())?);
+        self.trees.insert(key, tree.clone());
+        Ok(tree)
+    }
+
+    /// removes the cached models and node trees when deploying or removing the model
+    pub fn remove_model(&self, mid: &str) {
+        self.latest.invalidate(mid);
+        for (key, _) in self.models.iter() {
+            if key.0 == mid {
+                self.models.invalidate(&*key);
+            }
+        }
+        for (key, _) in self.trees.iter() {
+            if key.0 == mid {
+                self.trees.invalidate(&*key);
+            }
         }
     }
 
@@ -2,8 +2,8 @@ use crate::{
     data::{self, MessageStatus},
     sch::{self, Node, Runtime, StatementBatch, TaskLifeCycle, TaskState},
     store::{Cond, Expr, Query, Store},
-    utils::{self, Id},
-    ActError, Error, Message, Result, StoreAdapter, Workflow,
+    utils::Id,
+    ActError, Error, LogInfo, Message, Result, StoreAdapter, Workflow,
 };
 use std::{collections::HashMap, sync::Arc};
 use tracing::debug;
@@ -24,7 +24,7 @@ impl Store {
                 .set_limit(cap);
             let procs = self.procs().query(&query)?;
             for p in procs.rows {
-                let model = Workflow::from_json(&p.model)?;
+                let model = self.proc_model(&p, rt)?;
                 let env_local: serde_json::Value = serde_json::from_str(&p.env_local)
                     .map_err(|err| ActError::Store(err.to_string()))?;
                 let state = p.state.clone();
@@ -53,8 +53,7 @@ impl Store {
         debug!("load proc pid={}", pid);
         match self.procs().find(pid) {
             Ok(p) => {
-                // println!("proc model={}", p.model);
-                let model = Workflow::from_json(&p.model)?;
+                let model = self.proc_model(&p, rt)?;
                 let proc = sch::Proc::new(pid, rt);
                 let env_local: serde_json::Value = serde_json::from_str(&p.env_local)
                     .map_err(|err| ActError::Store(err.to_string()))?;
@@ -75,6 +74,22 @@ impl Store {
         }
     }
 
+    /// resolves the proc model from the versioned model store
+    /// the legacy proc and the proc with the undeployed model have the full model copy
+    fn proc_model(&self, p: &data::Proc, rt: &Arc<Runtime>) -> Result<Workflow> {
+        if p.model.is_empty() {
+            let mut model = rt.cache().model(&p.mid, Some(p.ver))?.as_ref().clone();
+            model.inputs =
+                serde_json::from_str(&p.inputs).map_err(|err| ActError::Store(err.to_string()))?;
+            return Ok(model);
+        }
+
+        let mut model = Workflow::from_json(&p.model)?;
+        // the model copy may differ from the deployed version, so do not share the cached tree
+        model.set_ver(0);
+        Ok(model)
+    }
+
     pub fn remove_proc(&self, pid: &str) -> Result<bool> {
         debug!("remove_proc pid={}", pid);
         let q = Query::new().push(Cond::and().push(Expr::eq("pid", pid.to_string())));
@@ -82,14 +97,45 @@ impl Store {
         for task in tasks.rows {
             self.tasks().delete(&task.id)?;
         }
+        let logs = self.logs().query(&q)?;
+        for log in logs.rows {
+            self.logs().delete(&log.id)?;
+        }
         self.procs().delete(pid)?;
         Ok(true)
     }
 
+    /// saves the buffered script logs of the task, it is called once when the task is completed
+    pub fn save_logs(&self, pid: &str, tid: &str, nid: &str, logs: &[LogInfo]) -> Result<()> {
+        let data = data::Log {
+            id: Id::new(pid, tid).id(),
+            pid: pid.to_string(),
+            tid: tid.to_string(),
+            nid: nid.to_string(),
+            data: serde_json::to_string(logs)?,
+            timestamp: self.clock().timestamp(),
+        };
+        if self.logs().exists(&data.id)? {
+            self.logs().update(&data)?;
+        } else {
+            self.logs().create(&data)?;
+        }
+        Ok(())
+    }
+
+    /// the captured script logs of the task, it is empty if there is no log
+    pub fn logs_of(&self, pid: &str, tid: &str) -> Result<Vec<LogInfo>> {
+        match self.logs().find(&Id::new(pid, tid).id()) {
+            Ok(data) => Ok(serde_json::from_str(&data.data)?),
+            Err(ActError::Store(_)) => Ok(Vec::new()),
+            Err(err) => Err(err),
+        }
+    }
+
     pub fn set_message(&self, id: &str, status: MessageStatus) -> Result<()> {
         if let Ok(mut message) = self.messages().find(id) {
             message.status = status;
-            message.update_time = utils::time::time_millis();
+            message.update_time = self.clock().now();
 
             self.messages().update(&message)?;
         }
@@ -109,7 +155,7 @@ impl Store {
             for m in messages.rows.iter() {
                 let mut m = m.clone();
                 m.status = status;
-                m.update_time = utils::time::time_millis();
+                m.update_time = self.clock().now();
                 self.messages().update(&m)?;
             }
         }
@@ -131,13 +177,13 @@ impl Store {
                 .push(Expr::eq("status", MessageStatus::Created))
                 .push(Expr::lt(
                     "update_time",
-                    utils::time::time_millis() as u64 - timeout_millis,
+                    self.clock().now() as u64 - timeout_millis,
                 )),
         );
         if let Ok(messages) = self.messages().query(&q) {
             for m in messages.rows.iter() {
                 let mut message = m.clone();
-                message.update_time = utils::time::time_millis();
+                message.update_time = self.clock().now();
                 if message.retry_times < max_message_retry_times {
                     message.retry_times += 1;
                     let _ = self.messages().update(&message);
@@ -159,7 +205,7 @@ impl Store {
                 let mut message = m.clone();
                 message.status = MessageStatus::Created;
                 message.retry_times = 0;
-                message.update_time = utils::time::time_millis();
+                message.update_time = self.clock().now();
                 self.messages().update(&message)?;
             }
         }
@@ -221,7 +267,7 @@ impl Store {
         let tasks = self.tasks().query(&query)?;
         for t in tasks.rows {
             let state: TaskState = t.state.into();
-            let node = Node::from_str(&t.node_data, tree);
+            let node = Node::load(&t.node_data, tree)?;
             let mut task = sch::Task::new(proc, &t.tid, node, rt);
             task.set_pure_state(state.clone());
             task.set_start_time(t.start_time);
@@ -131,8 +131,11 @@ async fn cache_restore_count() {
             end_time: 0,
             timestamp: 0,
             model: model.to_json().unwrap(),
+            ver: 0,
+            inputs: "{}".to_string(),
             env_local: "{}".to_string(),
             err: None,
+            parent_id: String::new(),
         };
         cache.store().base().procs().create(&proc).unwrap();
     }
@@ -178,8 +181,11 @@ async fn cache_restore_working_state() {
             end_time: 0,
             timestamp: 0,
             model: model.to_json().unwrap(),
+            ver: 0,
+            inputs: "{}".to_string(),
             env_local: "{}".to_string(),
             err: None,
+            parent_id: String::new(),
         };
         cache.store().base().procs().create(&proc).unwrap();
     }
@@ -225,8 +231,11 @@ async fn cache_restore_completed_state() {
             end_time: 0,
             timestamp: 0,
             model: model.to_json().unwrap(),
+            ver: 0,
+            inputs: "{}".to_string(),
             env_local: "{}".to_string(),
             err: None,
+            parent_id: String::new(),
         };
         cache.store().base().procs().create(&proc).unwrap();
     }
@@ -261,8 +270,11 @@ async fn cache_restore_less_cap() {
             end_time: 0,
             timestamp: 0,
             model: model.to_json().unwrap(),
+            ver: 0,
+            inputs: "{}".to_string(),
             env_local: "{}".to_string(),
             err: None,
+            parent_id: String::new(),
         };
         cache.store().base().procs().create(&proc).unwrap();
     }
@@ -274,3 +286,245 @@ async fn cache_restore_less_cap() {
         .unwrap();
     assert_eq!(cache.count(), 3);
 }
+
+#[tokio::test]
+async fn cache_tree_shared_by_ver() {
+    let cache = Cache::new(5);
+    let mut model = Workflow::new()
+        .with_id("m1")
+        .with_step(|step| step.with_id("step1"));
+    model.set_ver(1);
+
+    let tree1 = cache.tree(&model).unwrap();
+    let tree2 = cache.tree(&model).unwrap();
+    assert!(std::sync::Arc::ptr_eq(&tree1, &tree2));
+
+    model.set_ver(2);
+    let tree3 = cache.tree(&model).unwrap();
+    assert!(!std::sync::Arc::ptr_eq(&tree1, &tree3));
+}
+
+#[tokio::test]
+async fn cache_tree_not_deployed() {
+    let cache = Cache::new(5);
+    let model = Workflow::new()
+        .with_id("m1")
+        .with_step(|step| step.with_id("step1"));
+
+    let tree1 = cache.tree(&model).unwrap();
+    let tree2 = cache.tree(&model).unwrap();
+    assert!(!std::sync::Arc::ptr_eq(&tree1, &tree2));
+}
+
+#[tokio::test]
+async fn cache_tree_remove() {
+    let cache = Cache::new(5);
+    let mut model = Workflow::new()
+        .with_id("m1")
+        .with_step(|step| step.with_id("step1"));
+    model.set_ver(1);
+
+    let tree1 = cache.tree(&model).unwrap();
+    cache.remove_model("m1");
+    let tree2 = cache.tree(&model).unwrap();
+    assert!(!std::sync::Arc::ptr_eq(&tree1, &tree2));
+}
+
+#[tokio::test]
+async fn cache_tree_error() {
+    let cache = Cache::new(5);
+    let mut model = Workflow::new()
+        .with_id("m1")
+        .with_step(|step| step.with_id("step1"))
+        .with_step(|step| step.with_id("step1"));
+    model.set_ver(1);
+    assert!(cache.tree(&model).is_err());
+}
+
+#[tokio::test]
+async fn cache_tree_proc_inputs() {
+    let engine = Engine::new();
+    let rt = engine.runtime();
+    let mut model = Workflow::new()
+        .with_id("m1")
+        .with_step(|step| step.with_id("step1"));
+    model.set_ver(1);
+
+    let mut m1 = model.clone();
+    m1.inputs.set("a", 1);
+    let proc1 = Proc::new(&utils::longid(), &rt);
+    proc1.load(&m1).unwrap();
+
+    let mut m2 = model.clone();
+    m2.inputs.set("a", 2);
+    let proc2 = Proc::new(&utils::longid(), &rt);
+    proc2.load(&m2).unwrap();
+
+    assert!(std::sync::Arc::ptr_eq(&proc1.tree(), &proc2.tree()));
+    assert_eq!(proc1.model().inputs.get::<i32>("a").unwrap(), 1);
+    assert_eq!(proc2.model().inputs.get::<i32>("a").unwrap(), 2);
+}
+
+#[tokio::test]
+async fn cache_model_parsed_once() {
+    let cache = Cache::new(5);
+    let model = Workflow::new()
+        .with_id("m1")
+        .with_step(|step| step.with_id("step1"));
+    cache.store().deploy(&model).unwrap();
+
+    let m1 = cache.model("m1", None).unwrap();
+    let m2 = cache.model("m1", Some(1)).unwrap();
+    assert!(std::sync::Arc::ptr_eq(&m1, &m2));
+    assert_eq!(m1.ver(), 1);
+
+    cache.store().deploy(&model).unwrap();
+    cache.remove_model("m1");
+    let m3 = cache.model("m1", None).unwrap();
+    assert_eq!(m3.ver(), 2);
+}
+
+#[tokio::test]
+async fn cache_model_no_store_read() {
+    let cache = Cache::new(5);
+    let model = Workflow::new()
+        .with_id("m1")
+        .with_step(|step| step.with_id("step1"));
+    cache.store().deploy(&model).unwrap();
+    cache.model("m1", None).unwrap();
+
+    // the cached model is returned after it is deleted from the store
+    let store = cache.store();
+    store.base().models().delete("m1").unwrap();
+    store.base().model_versions().delete("m1@1").unwrap();
+    assert_eq!(cache.model("m1", None).unwrap().ver(), 1);
+    assert_eq!(cache.model("m1", Some(1)).unwrap().ver(), 1);
+
+    cache.remove_model("m1");
+    assert!(cache.model("m1", None).is_err());
+}
+
+#[tokio::test]
+async fn cache_upsert_proc_by_model_ref() {
+    let engine = Engine::new();
+    let rt = engine.runtime();
+    let model = Workflow::new()
+        .with_id("m1")
+        .with_step(|step| step.with_name("step1"));
+    rt.cache().store().deploy(&model).unwrap();
+
+    let mut model = rt.cache().model("m1", None).unwrap().as_ref().clone();
+    model.inputs.set("a", 1);
+    let proc = Proc::new(&utils::longid(), &rt);
+    proc.load(&model).unwrap();
+
+    let data = proc.into_data().unwrap();
+    assert_eq!(data.model, "");
+    assert_eq!(data.ver, 1);
+    assert_eq!(data.inputs, r#"{"a":1}"#);
+}
+
+#[tokio::test]
+async fn cache_upsert_proc_not_deployed() {
+    let engine = Engine::new();
+    let rt = engine.runtime();
+    let model = Workflow::new()
+        .with_id("m1")
+        .with_step(|step| step.with_name("step1"));
+    let proc = Proc::new(&utils::longid(), &rt);
+    proc.load(&model).unwrap();
+
+    let data = proc.into_data().unwrap();
+    assert_eq!(data.ver, 0);
+    assert!(Workflow::from_json(&data.model).is_ok());
+}
+
+#[tokio::test]
+async fn cache_load_proc_by_model_ref() {
+    let engine = Engine::new();
+    let rt = engine.runtime();
+    let model = Workflow::new()
+        .with_id("m1")
+        .with_step(|step| step.with_name("step1"));
+    rt.cache().store().deploy(&model).unwrap();
+
+    let proc = Proc::new(&utils::longid(), &rt);
+    proc.load(&rt.cache().model("m1", None).unwrap()).unwrap();
+    let node = proc.tree().root.clone().unwrap();
+    let task = proc.create_task(&node.children()[0], None);
+    rt.cache().store().upsert_proc(&proc).unwrap();
+    rt.cache().store().upsert_task(&task).unwrap();
+
+    let mut data = rt.cache().store().base().procs().find(proc.id()).unwrap();
+    data.inputs = r#"{"a":1}"#.to_string();
+    rt.cache().store().base().procs().update(&data).unwrap();
+
+    let loaded = rt
+        .cache()
+        .store()
+        .load_proc(proc.id(), &rt)
+        .unwrap()
+        .unwrap();
+    assert!(std::sync::Arc::ptr_eq(&loaded.tree(), &proc.tree()));
+    assert_eq!(loaded.model().steps[0].name, "step1");
+    assert_eq!(loaded.model().inputs.get::<i32>("a").unwrap(), 1);
+
+    let task = loaded.task(&task.id).unwrap();
+    assert_eq!(task.node().content.name(), "step1");
+}
+
+#[tokio::test]
+async fn cache_load_proc_with_model_copy() {
+    let engine = Engine::new();
+    let rt = engine.runtime();
+    let model = Workflow::new()
+        .with_id("m1")
+        .with_step(|step| step.with_id("step1"));
+    let proc = data::Proc {
+        id: utils::longid(),
+        name: "test".to_string(),
+        mid: "m1".to_string(),
+        state: TaskState::Running.into(),
+        start_time: 0,
+        end_time: 0,
+        timestamp: 0,
+        model: model.to_json().unwrap(),
+        ver: 0,
+        inputs: "{}".to_string(),
+        env_local: "{}".to_string(),
+        err: None,
+        parent_id: String::new(),
+    };
+    rt.cache().store().base().procs().create(&proc).unwrap();
+
+    let loaded = rt
+        .cache()
+        .store()
+        .load_proc(&proc.id, &rt)
+        .unwrap()
+        .unwrap();
+    assert_eq!(loaded.model().steps[0].id, "step1");
+}
+
+#[tokio::test]
+async fn cache_load_proc_model_not_found() {
+    let engine = Engine::new();
+    let rt = engine.runtime();
+    let proc = data::Proc {
+        id: utils::longid(),
+        name: "test".to_string(),
+        mid: "not_exists".to_string(),
+        state: TaskState::Running.into(),
+        start_time: 0,
+        end_time: 0,
+        timestamp: 0,
+        model: "".to_string(),
+        ver: 1,
+        inputs: "{}".to_string(),
+        env_local: "{}".to_string(),
+        err: None,
+        parent_id: String::new(),
+    };
+    rt.cache().store().base().procs().create(&proc).unwrap();
+    assert!(rt.cache().store().load_proc(&proc.id, &rt).is_err());
+}
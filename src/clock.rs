@@ -0,0 +1,151 @@
+use crate::utils;
+use async_trait::async_trait;
+use std::{
+    sync::{
+        atomic::{AtomicI64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::sync::watch;
+
+/// the last timestamp of the system clock
+static LAST_TIMESTAMP: AtomicI64 = AtomicI64::new(0);
+
+/// keeps the timestamp increasing, so the timestamps created in the same micro are still ordered
+fn next_timestamp(last: &AtomicI64, ts: i64) -> i64 {
+    let mut prev = last.load(Ordering::SeqCst);
+    loop {
+        let next = ts.max(prev + 1);
+        match last.compare_exchange(prev, next, Ordering::SeqCst, Ordering::SeqCst) {
+            Ok(_) => return next,
+            Err(v) => prev = v,
+        }
+    }
+}
+
+/// Time source of the engine
+///
+/// All of the engine times, such as the proc/task start and end time, the timeout checking
+/// and the message re-sending, are read from the clock. The default is [`SystemClock`],
+/// use [`ManualClock`] to control the time in tests.
+///
+/// ## Example
+///
+/// ```rust,no_run
+/// use acts::{Builder, ManualClock};
+/// use std::time::Duration;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let clock = ManualClock::new();
+///     let engine = Builder::new().clock(&clock).build();
+///
+///     // let the timeout hooks to be triggered
+///     clock.advance(Duration::from_secs(3600));
+/// }
+/// ```
+#[async_trait]
+pub trait Clock: Send + Sync {
+    /// current time in millis
+    fn now(&self) -> i64;
+
+    /// timestamp in micros, it is used to sort the procs and tasks
+    fn timestamp(&self) -> i64 {
+        self.now() * 1000
+    }
+
+    /// sleep until the clock reaches the deadline millis
+    async fn sleep_until(&self, deadline: i64);
+}
+
+impl std::fmt::Debug for dyn Clock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Clock").field("now", &self.now()).finish()
+    }
+}
+
+/// Clock of the system time
+#[derive(Debug, Default, Clone)]
+pub struct SystemClock;
+
+impl SystemClock {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl Clock for SystemClock {
+    fn now(&self) -> i64 {
+        utils::time::time_millis()
+    }
+
+    fn timestamp(&self) -> i64 {
+        next_timestamp(&LAST_TIMESTAMP, utils::time::timestamp())
+    }
+
+    async fn sleep_until(&self, deadline: i64) {
+        let millis = deadline - self.now();
+        if millis > 0 {
+            tokio::time::sleep(Duration::from_millis(millis as u64)).await;
+        }
+    }
+}
+
+/// Clock which only moves by calling [`ManualClock::advance`]
+#[derive(Debug, Clone)]
+pub struct ManualClock {
+    now: Arc<watch::Sender<i64>>,
+    last_timestamp: Arc<AtomicI64>,
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ManualClock {
+    /// create a clock starting from the current system time
+    pub fn new() -> Self {
+        Self::at(utils::time::time_millis())
+    }
+
+    /// create a clock starting from the given millis
+    pub fn at(millis: i64) -> Self {
+        let (tx, _) = watch::channel(millis);
+        Self {
+            now: Arc::new(tx),
+            last_timestamp: Arc::new(AtomicI64::new(0)),
+        }
+    }
+
+    /// move the clock forward and wake up the sleepers whose deadline is reached
+    pub fn advance(&self, duration: Duration) {
+        let millis = duration.as_millis() as i64;
+        self.now.send_modify(|now| *now += millis);
+    }
+
+    /// set the clock to the given millis
+    pub fn set(&self, millis: i64) {
+        self.now.send_replace(millis);
+    }
+}
+
+#[async_trait]
+impl Clock for ManualClock {
+    fn now(&self) -> i64 {
+        *self.now.borrow()
+    }
+
+    fn timestamp(&self) -> i64 {
+        // keep the timestamp increasing even if the clock is not moved
+        next_timestamp(&self.last_timestamp, self.now() * 1000)
+    }
+
+    async fn sleep_until(&self, deadline: i64) {
+        let mut rx = self.now.subscribe();
+        let _ = rx.wait_for(|now| *now >= deadline).await;
+    }
+}
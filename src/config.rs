@@ -1,3 +1,5 @@
+use crate::Limits;
+
 #[derive(Debug, Clone)]
 pub struct Config {
     pub cache_cap: usize,
@@ -10,6 +12,17 @@ pub struct Config {
     // will delete message after the max retries
     // cancel the settings by setting to 0
     pub max_message_retry_times: i32,
+
+    // the sandbox limits for each script evaluation
+    // cancel the memory and time limits by setting to 0
+    // the stack limit is 256k by setting to 0
+    pub script_memory_limit: usize,
+    pub script_stack_limit: usize,
+    pub script_timeout_millis: u64,
+
+    // the max captured console logs of each task in store
+    // cancel the capture by setting to 0
+    pub script_log_limit: usize,
 }
 
 impl Default for Config {
@@ -24,6 +37,25 @@ impl Default for Config {
             // default to 15s
             tick_interval_secs: 15,
             max_message_retry_times: 20,
+
+            // default to 128M memory, 256k stack and 10s
+            script_memory_limit: 128 * 1024 * 1024,
+            script_stack_limit: 256 * 1024,
+            script_timeout_millis: 10_000,
+
+            // default to no capture
+            script_log_limit: 0,
+        }
+    }
+}
+
+impl Config {
+    /// the default sandbox limits
+    pub fn limits(&self) -> Limits {
+        Limits {
+            memory: Some(self.script_memory_limit),
+            stack: Some(self.script_stack_limit),
+            timeout: Some(self.script_timeout_millis),
         }
     }
 }
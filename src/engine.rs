@@ -4,7 +4,7 @@ use crate::{
     export::{Channel, Executor, Extender},
     plugin,
     sch::Runtime,
-    ActPlugin, ChannelOptions, Signal, StoreAdapter,
+    ActPlugin, ChannelOptions, Clock, Result, Signal, StoreAdapter, SystemClock,
 };
 
 use std::sync::{Arc, Mutex};
@@ -53,8 +53,12 @@ impl Default for Engine {
 }
 
 impl Engine {
+    /// creates the engine with the default config
+    ///
+    /// # Panics
+    /// panics when the store cannot be opened or migrated, use `Builder::try_build` to get the error
     pub fn new() -> Self {
-        Self::new_with_config(&Config::default(), None)
+        Self::new_with_config(&Config::default(), None, None).unwrap()
     }
 
     pub fn config(&self) -> Arc<Config> {
@@ -130,16 +134,21 @@ impl Engine {
         self.runtime.is_running()
     }
 
-    fn init(&self) {
+    fn init(&self) -> Result<()> {
         info!("init");
         plugin::init(self);
         adapter::init(self);
-        self.runtime.init(self);
+        self.runtime.init(self)
     }
 
-    pub(crate) fn new_with_config(config: &Config, store: Option<Arc<dyn StoreAdapter>>) -> Self {
+    pub(crate) fn new_with_config(
+        config: &Config,
+        store: Option<Arc<dyn StoreAdapter>>,
+        clock: Option<Arc<dyn Clock>>,
+    ) -> Result<Self> {
         info!("config: {:?}", config);
-        let runtime = Runtime::new(config);
+        let clock = clock.unwrap_or_else(|| Arc::new(SystemClock::new()));
+        let runtime = Runtime::new(config, &clock);
 
         let extender = Arc::new(Extender::new(&runtime));
         let adapter = Arc::new(Adapter::new());
@@ -151,7 +160,7 @@ impl Engine {
             extender,
             adapter,
         };
-        engine.init();
-        engine
+        engine.init()?;
+        Ok(engine)
     }
 }
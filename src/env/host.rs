@@ -0,0 +1,150 @@
+use super::{source::code_only, value::ActValue};
+use crate::Result;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use rquickjs::{
+    function::Rest, Ctx as JsCtx, Exception, Function, IntoJs, JsLifetime, Value as JsValue,
+};
+use serde_json::Value as JsonValue;
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc::Sender,
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+type HostFuture = Pin<Box<dyn Future<Output = Result<JsonValue>> + Send>>;
+type HostFn = Arc<dyn Fn(Vec<JsonValue>) -> HostFuture + Send + Sync>;
+
+/// the settled result of the async host function by the job id
+pub type HostResult = (usize, Result<JsonValue>);
+
+/// the callback to resume the suspended eval, it is called once
+pub type Wake = Box<dyn FnOnce() + Send>;
+
+/// the wake of the suspended eval, which is taken by the first settled host function or the timeout
+pub type WakeSlot = Arc<Mutex<Option<Wake>>>;
+
+/// the runtime to run the async host functions, so the futures are not blocked
+/// by the js runtime which is waiting for them
+static HOST_RUNTIME: Lazy<tokio::runtime::Runtime> = Lazy::new(|| {
+    tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(2)
+        .thread_name("acts-host")
+        .enable_all()
+        .build()
+        .unwrap()
+});
+
+static AWAIT_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\bawait\b").unwrap());
+
+/// checks the script uses the top-level `await` out of the comments and strings
+pub fn is_async(source: &str) -> bool {
+    AWAIT_RE.is_match(&code_only(source))
+}
+
+/// the host job queue of a js runtime, the settled results are sent to the runtime worker
+pub struct HostQueue {
+    next: AtomicUsize,
+    tx: Sender<HostResult>,
+    wake: WakeSlot,
+}
+
+unsafe impl<'js> JsLifetime<'js> for HostQueue {
+    type Changed<'to> = HostQueue;
+}
+
+impl HostQueue {
+    pub fn new(tx: Sender<HostResult>, wake: WakeSlot) -> Self {
+        Self {
+            next: AtomicUsize::new(1),
+            tx,
+            wake,
+        }
+    }
+}
+
+/// calls the wake once when it is still set
+pub fn wake(slot: &WakeSlot) {
+    let wake = slot.lock().unwrap().take();
+    if let Some(wake) = wake {
+        wake();
+    }
+}
+
+/// calls the wake after the timeout, so the suspended eval checks its waiting time
+pub fn wake_after(slot: &WakeSlot, timeout: Duration) {
+    let slot = slot.clone();
+    HOST_RUNTIME.spawn(async move {
+        tokio::time::sleep(timeout).await;
+        wake(&slot);
+    });
+}
+
+/// the async host function which returns a promise to the script
+/// the future runs on the host runtime and the promise is settled by the runtime worker,
+/// the suspended eval is woken after the future is settled
+///
+/// # Example
+/// ```rust
+///   use acts::{ActModule, AsyncFn, Result};
+///   use serde_json::json;
+///   #[derive(Clone)]
+///   pub struct LookupModule;
+///   impl ActModule for LookupModule {
+///     fn init<'a>(&self, ctx: &rquickjs::Ctx<'a>) -> Result<()> {
+///         let lookup = AsyncFn::new(|args| async move { Ok(json!({ "key": args[0] })) });
+///         ctx.globals().set("lookup", lookup)?;
+///         Ok(())
+///     }
+///   }
+/// ```
+#[derive(Clone)]
+pub struct AsyncFn(HostFn);
+
+impl AsyncFn {
+    pub fn new<F, Fut>(f: F) -> Self
+    where
+        F: Fn(Vec<JsonValue>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<JsonValue>> + Send + 'static,
+    {
+        Self(Arc::new(move |args| Box::pin(f(args))))
+    }
+}
+
+impl<'js> IntoJs<'js> for AsyncFn {
+    fn into_js(self, ctx: &JsCtx<'js>) -> rquickjs::Result<JsValue<'js>> {
+        let f = self.0;
+        let func = Function::new(
+            ctx.clone(),
+            move |ctx: JsCtx<'js>, args: Rest<ActValue>| -> rquickjs::Result<JsValue<'js>> {
+                let (id, tx, slot) = match ctx.userdata::<HostQueue>() {
+                    Some(queue) => (
+                        queue.next.fetch_add(1, Ordering::SeqCst),
+                        queue.tx.clone(),
+                        queue.wake.clone(),
+                    ),
+                    None => {
+                        return Err(Exception::throw_message(
+                            &ctx,
+                            "the async function is not supported in this runtime",
+                        ))
+                    }
+                };
+                let future = f(args.0.into_iter().map(|v| v.into()).collect());
+                HOST_RUNTIME.spawn(async move {
+                    let _ = tx.send((id, future.await));
+                    wake(&slot);
+                });
+
+                let job: Function = ctx.globals().get("__acts_job")?;
+                job.call((id,))
+            },
+        )?;
+        func.into_js(ctx)
+    }
+}
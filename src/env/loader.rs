@@ -0,0 +1,180 @@
+use super::source::code_only;
+use crate::{
+    data::Package, model::parse_package_key, store::Store, utils::consts, ActError, Context, Result,
+};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use rquickjs::{
+    loader::{Loader, Resolver},
+    module::Declared,
+    Ctx as JsCtx, Error as JsError, Module,
+};
+
+/// the static `import` or `export` statement at the line start
+static MODULE_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"(?m)^\s*(import\s*[\w*{'"]|export\s)"#).unwrap());
+
+/// the imported module path of the `import` or `export ... from` statement
+static IMPORT_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?:import|export)\s*(?:[^'";]*?from\s*)?['"]([^'"]*)['"]"#).unwrap()
+});
+
+/// checks the script is an es module by the static `import` or `export` statements,
+/// the statements in the comments and strings are not matched
+pub fn is_module(source: &str) -> bool {
+    MODULE_RE.is_match(&code_only(source))
+}
+
+/// gets the imported package ids, such as `import { fmt } from '@pack/utils'`
+/// the statements are matched in the code without the comments and strings,
+/// and the module path is read from the source by the same position
+pub fn imports(source: &str) -> Vec<String> {
+    let code = code_only(source);
+    let mut ret: Vec<String> = Vec::new();
+    for caps in IMPORT_RE.captures_iter(&code) {
+        let path = &source[caps.get(1).unwrap().range()];
+        if let Some(id) = path.strip_prefix(consts::PACK_MODULE_PREFIX) {
+            if !id.is_empty() && !ret.iter().any(|item| item == id) {
+                ret.push(id.to_string());
+            }
+        }
+    }
+    ret
+}
+
+/// the module name of the package, which is pinned to the package version
+pub fn module_name(pack: &Package) -> String {
+    if pack.manifest.version.is_empty() {
+        return format!("{}{}", consts::PACK_MODULE_PREFIX, pack.id);
+    }
+    format!(
+        "{}{}@{}",
+        consts::PACK_MODULE_PREFIX,
+        pack.id,
+        pack.manifest.version
+    )
+}
+
+/// checks the imported packages are published and not imported in cycle
+/// the `find` fn gets the package by id and the version requirement of the importing package
+/// `dependencies`, the latest package is used when the import is not in the dependencies
+pub fn check_imports<F>(pack: &Package, source: &str, find: &F) -> Result<()>
+where
+    F: Fn(&str, Option<&str>) -> Result<Package>,
+{
+    let mut path = vec![pack.id.to_string()];
+    check_imports_with(pack, source, find, &mut path)
+}
+
+fn check_imports_with<F>(
+    pack: &Package,
+    source: &str,
+    find: &F,
+    path: &mut Vec<String>,
+) -> Result<()>
+where
+    F: Fn(&str, Option<&str>) -> Result<Package>,
+{
+    for import in imports(source) {
+        if path.contains(&import) {
+            path.push(import.clone());
+            let chain = path
+                .iter()
+                .map(|id| format!("{}{id}", consts::PACK_MODULE_PREFIX))
+                .collect::<Vec<_>>()
+                .join(" -> ");
+            return Err(ActError::Script(format!(
+                "the module '{}{import}' is imported in cycle: {chain}",
+                consts::PACK_MODULE_PREFIX
+            )));
+        }
+
+        let req = pack
+            .manifest
+            .dependencies
+            .get(&import)
+            .map(|req| req.as_str());
+        let imported = find(&import, req).map_err(|_| {
+            ActError::Script(format!(
+                "cannot find module '{}{import}' imported by '{}'",
+                consts::PACK_MODULE_PREFIX,
+                pack.id
+            ))
+        })?;
+        let source = String::from_utf8(imported.data.clone()).map_err(ActError::from)?;
+        path.push(import);
+        check_imports_with(&imported, &source, find, path)?;
+        path.pop();
+    }
+    Ok(())
+}
+
+/// finds the imported package by the `dependencies` of the importing module
+fn resolve_import(store: &Store, base: &str, id: &str) -> Result<Package> {
+    let req = base
+        .strip_prefix(consts::PACK_MODULE_PREFIX)
+        .map(parse_package_key)
+        .and_then(|(base, ver)| store.find_package(base, ver).ok())
+        .and_then(|pack| pack.manifest.dependencies.get(id).cloned());
+    store.find_package(id, req.as_deref())
+}
+
+/// resolves the `@pack/{id}` module to the package version by the `dependencies` of
+/// the importing package, such as `@pack/{id}@{version}`, other modules keep the name
+pub struct PackageResolver;
+
+impl Resolver for PackageResolver {
+    fn resolve(&mut self, _ctx: &JsCtx<'_>, base: &str, name: &str) -> rquickjs::Result<String> {
+        let id = match name.strip_prefix(consts::PACK_MODULE_PREFIX) {
+            Some(id) => id,
+            None => return Ok(name.to_string()),
+        };
+        // the syntax checking runs without the context
+        let context = match Context::current() {
+            Ok(context) => context,
+            Err(_) => return Ok(name.to_string()),
+        };
+        let pack = resolve_import(&context.runtime.cache().store(), base, id)
+            .map_err(|_| JsError::new_resolving_message(base, name, "cannot find module"))?;
+        Ok(module_name(&pack))
+    }
+}
+
+/// loads the resolved `@pack/{id}@{version}` module from the published packages in store
+pub struct PackageLoader;
+
+impl Loader for PackageLoader {
+    fn load<'js>(
+        &mut self,
+        ctx: &JsCtx<'js>,
+        name: &str,
+    ) -> rquickjs::Result<Module<'js, Declared>> {
+        let not_found = || JsError::new_loading_message(name, "cannot find module");
+        let (id, ver) = match name.strip_prefix(consts::PACK_MODULE_PREFIX) {
+            Some(key) => parse_package_key(key),
+            None => return Err(not_found()),
+        };
+        let context = Context::current().map_err(|_| not_found())?;
+        let pack = context
+            .runtime
+            .cache()
+            .store()
+            .find_package(id, ver)
+            .map_err(|_| not_found())?;
+        let source = String::from_utf8(pack.data).map_err(|_| not_found())?;
+        Module::declare(ctx.clone(), name, source)
+    }
+}
+
+/// declares the imported modules as empty modules to check the syntax only
+pub struct SyntaxLoader;
+
+impl Loader for SyntaxLoader {
+    fn load<'js>(
+        &mut self,
+        ctx: &JsCtx<'js>,
+        name: &str,
+    ) -> rquickjs::Result<Module<'js, Declared>> {
+        Module::declare(ctx.clone(), name, "")
+    }
+}
@@ -1,15 +1,48 @@
+#[cfg(feature = "quickjs")]
+mod host;
+#[cfg(feature = "quickjs")]
+mod loader;
+#[cfg(feature = "quickjs")]
 mod moudle;
+#[cfg(feature = "quickjs")]
+mod pool;
+#[cfg(feature = "quickjs")]
+mod quickjs;
+#[cfg(feature = "rhai")]
+mod rhai;
+mod script;
+#[cfg(feature = "quickjs")]
+mod source;
 #[cfg(test)]
 mod tests;
+#[cfg(feature = "quickjs")]
 mod value;
 
-use crate::{ActError, Result, ShareLock, Vars};
+use crate::{ActError, Limits, Result, ShareLock, Vars};
 use core::fmt;
-use rquickjs::{Context as JsContext, Ctx as JsCtx, FromJs, Runtime as JsRuntime};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
-use std::sync::{Arc, RwLock};
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
 
-use self::value::ActValue;
+#[cfg(feature = "quickjs")]
+pub use host::{AsyncFn, Wake};
+#[cfg(feature = "quickjs")]
+pub use loader::{check_imports, is_module, module_name};
+#[cfg(feature = "quickjs")]
+use quickjs::QuickJs;
+#[cfg(feature = "quickjs")]
+pub use quickjs::{JsEval, JsSuspended};
+#[cfg(feature = "rhai")]
+use rhai::Rhai;
+pub use script::ScriptEngine;
+
+/// the default script language when the model `lang` is empty
+#[cfg(feature = "quickjs")]
+pub const DEFAULT_LANG: &str = "js";
+#[cfg(not(feature = "quickjs"))]
+pub const DEFAULT_LANG: &str = "rhai";
 
 /// ActModule to extend the js features
 ///
@@ -24,13 +57,17 @@ use self::value::ActValue;
 ///     }
 ///   }
 /// ```
+#[cfg(feature = "quickjs")]
 pub trait ActModule: Send + Sync {
-    fn init(&self, ctx: &JsCtx<'_>) -> Result<()>;
+    fn init(&self, ctx: &rquickjs::Ctx<'_>) -> Result<()>;
 }
 
 pub struct Enviroment {
     vars: ShareLock<Vars>,
-    modules: ShareLock<Vec<Box<dyn ActModule>>>,
+    #[cfg(feature = "quickjs")]
+    js: Arc<QuickJs>,
+    /// the script engines by the lang
+    engines: RwLock<HashMap<String, Arc<dyn ScriptEngine>>>,
 }
 
 impl fmt::Debug for Enviroment {
@@ -52,22 +89,76 @@ impl Default for Enviroment {
 
 impl Enviroment {
     pub fn new() -> Self {
+        let mut engines: HashMap<String, Arc<dyn ScriptEngine>> = HashMap::new();
+        #[cfg(feature = "quickjs")]
+        let js = Arc::new(QuickJs::new());
+        #[cfg(feature = "quickjs")]
+        engines.insert(js.lang().to_string(), js.clone());
+        #[cfg(feature = "rhai")]
+        engines.insert("rhai".to_string(), Arc::new(Rhai::new()));
+
+        #[allow(unused_mut)]
         let mut env = Enviroment {
-            modules: Arc::new(RwLock::new(Vec::new())),
             vars: Arc::new(RwLock::new(Vars::new())),
+            #[cfg(feature = "quickjs")]
+            js,
+            engines: RwLock::new(engines),
         };
+        #[cfg(feature = "quickjs")]
         env.init();
         env
     }
 
-    #[cfg(test)]
+    #[cfg(all(test, feature = "quickjs"))]
     pub fn modules_count(&self) -> usize {
-        self.modules.read().unwrap().len()
+        self.js.modules_count()
     }
 
+    #[cfg(feature = "quickjs")]
     pub fn register_module<T: ActModule + Clone + 'static>(&self, module: &T) {
-        let mut modules = self.modules.write().unwrap();
-        modules.push(Box::new(module.clone()));
+        self.js.register_module(module)
+    }
+
+    /// registers the script engine, the engine with the same lang is replaced
+    pub fn register_engine<T: ScriptEngine + 'static>(&self, engine: T) {
+        let mut engines = self.engines.write().unwrap();
+        engines.insert(engine.lang().to_string(), Arc::new(engine));
+    }
+
+    /// gets the script engine by lang, the empty lang is the default lang
+    pub fn engine(&self, lang: &str) -> Result<Arc<dyn ScriptEngine>> {
+        let lang = if lang.is_empty() { DEFAULT_LANG } else { lang };
+        self.engines
+            .read()
+            .unwrap()
+            .get(lang)
+            .cloned()
+            .ok_or(ActError::Script(format!(
+                "the script lang '{lang}' is not supported"
+            )))
+    }
+
+    /// the builtin quickjs engine is used for the lang, it is not replaced by `register_engine`
+    #[cfg(feature = "quickjs")]
+    fn is_quickjs(&self, lang: &str) -> bool {
+        self.engine(lang)
+            .map(|engine| std::ptr::addr_eq(Arc::as_ptr(&engine), Arc::as_ptr(&self.js)))
+            .unwrap_or(false)
+    }
+
+    #[cfg(all(test, feature = "quickjs"))]
+    pub fn pool_count(&self) -> usize {
+        self.js.pool_count()
+    }
+
+    #[cfg(all(test, feature = "quickjs"))]
+    pub fn is_compiled(&self, script: &str) -> bool {
+        self.js.is_compiled(script)
+    }
+
+    #[cfg(all(test, feature = "quickjs"))]
+    pub fn is_module_compiled(&self, name: &str, source: &str) -> bool {
+        self.js.is_module_compiled(name, source)
     }
 
     pub fn get<T>(&self, name: &str) -> Option<T>
@@ -89,31 +180,190 @@ impl Enviroment {
         f(&mut vars);
     }
 
+    /// checks the script syntax of the default lang by compiling it without running
+    pub fn check_syntax(script: &str) -> Result<()> {
+        Self::check_syntax_with(DEFAULT_LANG, script)
+    }
+
+    /// checks the script syntax by the builtin engine of the lang,
+    /// the scripts of the other langs are checked when deploying
+    pub fn check_syntax_with(lang: &str, script: &str) -> Result<()> {
+        match lang {
+            #[cfg(feature = "quickjs")]
+            "js" | "" => QuickJs::check_syntax(script),
+            #[cfg(feature = "rhai")]
+            "rhai" => Rhai::check_syntax(script),
+            _ => Ok(()),
+        }
+    }
+
+    /// checks the es module syntax without running it, the imported modules are not loaded
+    #[cfg(feature = "quickjs")]
+    pub fn check_module(name: &str, source: &str) -> Result<()> {
+        QuickJs::check_module(name, source)
+    }
+
     pub fn eval<T>(&self, expr: &str) -> Result<T>
     where
         T: DeserializeOwned,
     {
-        let runtime = JsRuntime::new().unwrap();
-        let ctx = JsContext::full(&runtime).unwrap();
-        ctx.with(|ctx| {
-            let modules = self.modules.read().unwrap();
-            for m in modules.iter() {
-                m.init(&ctx)?;
-            }
-
-            let result = ctx.eval::<ActValue, &str>(expr);
-            if let Err(rquickjs::Error::Exception) = result {
-                let exception = rquickjs::Exception::from_js(&ctx, ctx.catch()).unwrap();
-                eprintln!("error: {exception:?}");
-                return Err(ActError::Exception {
-                    ecode: "".to_string(),
-                    message: exception.message().unwrap_or_default(),
-                });
-            }
-
-            let value = result.map_err(ActError::from)?;
-            let ret = serde_json::from_value::<T>(value.into()).map_err(ActError::from)?;
-            Ok(ret)
-        })
+        self.eval_with_limits(expr, &Limits::default())
+    }
+
+    /// evaluates the expression of the default lang with the sandbox limits
+    pub fn eval_with_limits<T>(&self, expr: &str, limits: &Limits) -> Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        self.eval_with(DEFAULT_LANG, expr, limits)
+    }
+
+    /// evaluates the expression by the script engine of the lang
+    pub fn eval_with<T>(&self, lang: &str, expr: &str, limits: &Limits) -> Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        self.eval_with_vars(lang, expr, &Vars::new(), limits)
+    }
+
+    /// evaluates the expression by the script engine of the lang, the vars are bound as the variables
+    pub fn eval_with_vars<T>(
+        &self,
+        lang: &str,
+        expr: &str,
+        vars: &Vars,
+        limits: &Limits,
+    ) -> Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        let value = self.engine(lang)?.eval_with_vars(expr, vars, limits)?;
+        let ret = serde_json::from_value::<T>(value).map_err(ActError::from)?;
+        Ok(ret)
+    }
+
+    /// compiles the js script to the bytecode and caches it by the script source,
+    /// the syntax error is returned without running the script
+    #[cfg(feature = "quickjs")]
+    pub fn compile(&self, script: &str) -> Result<Arc<Vec<u8>>> {
+        self.js.compile(script)
+    }
+
+    /// compiles the script by the script engine of the lang without running it
+    pub fn compile_with(&self, lang: &str, script: &str) -> Result<()> {
+        self.engine(lang)?.compile(script)
+    }
+
+    /// evaluates the es module in a pooled runtime, the imported modules are evaluated
+    /// once in each runtime, so their top-level states are shared by the runs
+    #[cfg(feature = "quickjs")]
+    pub fn eval_module(&self, name: &str, source: &str, limits: &Limits) -> Result<()> {
+        self.js.eval_module(name, source, limits)
+    }
+
+    /// evaluates the script by the lang, the js eval waiting for the async host functions is suspended
+    /// instead of blocking the thread, and the `wake` is called once to resume it by `resume`
+    #[cfg(feature = "quickjs")]
+    pub fn start(&self, lang: &str, script: &str, limits: &Limits, wake: Wake) -> Result<JsEval> {
+        if self.is_quickjs(lang) {
+            return self.js.start(script, limits, wake);
+        }
+        self.engine(lang)?
+            .eval_with_vars(script, &Vars::new(), limits)
+            .map(JsEval::Ready)
+    }
+
+    /// evaluates the es module like `eval_module`, but the eval can be suspended like `start`
+    #[cfg(feature = "quickjs")]
+    pub fn start_module(
+        &self,
+        name: &str,
+        source: &str,
+        limits: &Limits,
+        wake: Wake,
+    ) -> Result<JsEval> {
+        self.js.start_module(name, source, limits, wake)
+    }
+
+    /// resumes the suspended js eval after it is woken, it can be suspended again
+    #[cfg(feature = "quickjs")]
+    pub fn resume(&self, suspended: JsSuspended, wake: Wake) -> Result<JsEval> {
+        self.js.resume(suspended, wake)
+    }
+
+    /// compiles the script by the lang and returns the bytecode to keep with the source,
+    /// the bytecode is empty for the engines without it
+    pub fn compile_code(&self, lang: &str, script: &str) -> Result<Vec<u8>> {
+        #[cfg(feature = "quickjs")]
+        if self.is_quickjs(lang) {
+            return self.js.compile(script).map(|code| code.to_vec());
+        }
+        self.compile_with(lang, script)?;
+        Ok(Vec::new())
+    }
+
+    /// loads the bytecode which is kept with the script source, so the script is not compiled again
+    #[allow(unused_variables)]
+    pub fn load_code(&self, lang: &str, script: &str, code: &[u8]) {
+        #[cfg(feature = "quickjs")]
+        if !code.is_empty() && self.is_quickjs(lang) {
+            self.js.load(script, code);
+        }
+    }
+
+    /// compiles the es module to the bytecode without running it
+    #[cfg(feature = "quickjs")]
+    pub fn compile_module(name: &str, source: &str) -> Result<Vec<u8>> {
+        QuickJs::compile_module(name, source)
+    }
+
+    /// loads the es module bytecode which is kept with the package source
+    #[cfg(feature = "quickjs")]
+    pub fn load_module(&self, name: &str, source: &str, code: &[u8]) {
+        if !code.is_empty() {
+            self.js.load_module(name, source, code);
+        }
+    }
+
+    /// drops the loaded package modules after the package is published
+    pub fn reset_modules(&self) {
+        #[cfg(feature = "quickjs")]
+        self.js.reset_modules();
+    }
+}
+
+/// encodes the compiled scripts with the bytecode to keep them with the model source,
+/// each one is written as the script and the bytecode with their u32 lengths
+pub(crate) fn encode_codes(codes: &[(String, Vec<u8>)]) -> Vec<u8> {
+    let mut ret = Vec::new();
+    for (script, code) in codes {
+        for bytes in [script.as_bytes(), code.as_slice()] {
+            ret.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+            ret.extend_from_slice(bytes);
+        }
+    }
+    ret
+}
+
+/// decodes the compiled scripts which are encoded by `encode_codes`, the broken data is ignored
+pub(crate) fn decode_codes(data: &[u8]) -> Vec<(String, Vec<u8>)> {
+    fn next<'a>(data: &mut &'a [u8]) -> Option<&'a [u8]> {
+        let len = u32::from_le_bytes(data.get(..4)?.try_into().ok()?) as usize;
+        let bytes = data.get(4..4 + len)?;
+        *data = &data[4 + len..];
+        Some(bytes)
+    }
+
+    let mut ret = Vec::new();
+    let mut data = data;
+    while !data.is_empty() {
+        let (Some(script), Some(code)) = (next(&mut data), next(&mut data)) else {
+            return Vec::new();
+        };
+        match String::from_utf8(script.to_vec()) {
+            Ok(script) => ret.push((script, code.to_vec())),
+            Err(_) => return Vec::new(),
+        }
     }
+    ret
 }
@@ -1,4 +1,4 @@
-use crate::{env::ActModule, Result};
+use crate::{env::ActModule, Context, Result};
 use rquickjs::{class::Trace, JsLifetime};
 
 #[derive(Trace, Clone, JsLifetime)]
@@ -18,19 +18,24 @@ impl Console {
     }
 
     fn log(&self, message: String) {
-        println!("[log] {message}");
+        Context::console("log", &message);
     }
 
     fn info(&self, message: String) {
-        println!("{}", format!("[info] {}", message));
+        Context::console("info", &message);
     }
 
+    fn warn(&self, message: String) {
+        Context::console("warn", &message);
+    }
+
+    // keeps the misspelled name for the existing scripts
     fn wran(&self, message: String) {
-        println!("{}", format!("[wran] {}", message));
+        Context::console("warn", &message);
     }
 
     fn error(&self, message: String) {
-        println!("{}", format!("[error] {}", message));
+        Context::console("error", &message);
     }
 }
 
@@ -0,0 +1,57 @@
+use crate::{ActModule, Result};
+use rquickjs::Module as JsModule;
+
+#[derive(Clone)]
+pub struct Crypto;
+impl Crypto {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[allow(clippy::module_inception)]
+#[rquickjs::module(rename_vars = "camelCase")]
+mod crypto {
+    use crate::ActError;
+    use base64::{engine::general_purpose::STANDARD, Engine};
+    use hmac::{Hmac, Mac};
+    use sha2::{Digest, Sha256};
+
+    /// the sha256 hex digest of the text
+    #[rquickjs::function]
+    pub fn sha256(text: String) -> String {
+        hex::encode(Sha256::digest(text.as_bytes()))
+    }
+
+    /// the hmac-sha256 hex digest of the text with the key
+    #[rquickjs::function]
+    pub fn hmac(key: String, text: String) -> rquickjs::Result<String> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(key.as_bytes())
+            .map_err(|err| ActError::Script(err.to_string()))?;
+        mac.update(text.as_bytes());
+        Ok(hex::encode(mac.finalize().into_bytes()))
+    }
+
+    // the fns are named in camel case, because the module macro does not declare the renamed fns
+    #[rquickjs::function]
+    #[allow(non_snake_case)]
+    pub fn base64Encode(text: String) -> String {
+        STANDARD.encode(text.as_bytes())
+    }
+
+    #[rquickjs::function]
+    #[allow(non_snake_case)]
+    pub fn base64Decode(text: String) -> rquickjs::Result<String> {
+        let data = STANDARD
+            .decode(text.as_bytes())
+            .map_err(|err| ActError::Script(format!("the base64 text is invalid: {err}")))?;
+        Ok(String::from_utf8(data).map_err(|err| ActError::Script(err.to_string()))?)
+    }
+}
+
+impl ActModule for Crypto {
+    fn init(&self, ctx: &rquickjs::Ctx<'_>) -> Result<()> {
+        JsModule::declare_def::<js_crypto, _>(ctx.clone(), "@acts/crypto").unwrap();
+        Ok(())
+    }
+}
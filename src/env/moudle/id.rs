@@ -0,0 +1,36 @@
+use crate::{ActModule, Result};
+use rquickjs::Module as JsModule;
+
+#[derive(Clone)]
+pub struct Id;
+impl Id {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[allow(clippy::module_inception)]
+#[rquickjs::module(rename_vars = "camelCase")]
+mod id {
+    use rquickjs::function::Opt;
+
+    /// the nanoid with the size, the default size is 21
+    #[rquickjs::function]
+    pub fn nanoid(size: Opt<usize>) -> String {
+        let size = size.0.unwrap_or(21);
+        nanoid::nanoid!(size)
+    }
+
+    /// the random uuid v4
+    #[rquickjs::function]
+    pub fn uuid() -> String {
+        uuid::Uuid::new_v4().to_string()
+    }
+}
+
+impl ActModule for Id {
+    fn init(&self, ctx: &rquickjs::Ctx<'_>) -> Result<()> {
+        JsModule::declare_def::<js_id, _>(ctx.clone(), "@acts/id").unwrap();
+        Ok(())
+    }
+}
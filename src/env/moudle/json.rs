@@ -0,0 +1,119 @@
+use crate::{ActModule, Result};
+use rquickjs::Module as JsModule;
+
+#[derive(Clone)]
+pub struct Json;
+impl Json {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[allow(clippy::module_inception)]
+#[rquickjs::module(rename_vars = "camelCase")]
+mod json {
+    use crate::{env::value::ActValue, ActError, Result};
+    use serde_json::Value as JsonValue;
+
+    /// the max array index of the path, so the array is not resized without limit
+    const MAX_INDEX: usize = 10_000;
+
+    /// the path keys, such as `a.b[0].c` is `["a", "b", "0", "c"]`
+    fn keys(path: &str) -> Vec<String> {
+        path.replace('[', ".")
+            .replace(']', "")
+            .split('.')
+            .filter(|key| !key.is_empty())
+            .map(|key| key.to_string())
+            .collect()
+    }
+
+    fn set_value(target: &mut JsonValue, keys: &[String], value: JsonValue) -> Result<()> {
+        let (key, rest) = match keys.split_first() {
+            Some(v) => v,
+            None => {
+                *target = value;
+                return Ok(());
+            }
+        };
+        if let Ok(index) = key.parse::<usize>() {
+            if !target.is_object() {
+                if !target.is_array() {
+                    *target = JsonValue::Array(Vec::new());
+                }
+                let items = target.as_array_mut().unwrap();
+                if items.len() <= index {
+                    if index > MAX_INDEX {
+                        return Err(ActError::Script(format!(
+                            "the array index {index} exceeds the max index {MAX_INDEX}"
+                        )));
+                    }
+                    items.resize(index + 1, JsonValue::Null);
+                }
+                return set_value(&mut items[index], rest, value);
+            }
+        }
+        if !target.is_object() {
+            *target = JsonValue::Object(serde_json::Map::new());
+        }
+        let item = target
+            .as_object_mut()
+            .unwrap()
+            .entry(key.clone())
+            .or_insert(JsonValue::Null);
+        set_value(item, rest, value)
+    }
+
+    fn merge_value(target: &mut JsonValue, source: JsonValue) {
+        match (target, source) {
+            (JsonValue::Object(target), JsonValue::Object(source)) => {
+                for (key, value) in source {
+                    merge_value(target.entry(key).or_insert(JsonValue::Null), value);
+                }
+            }
+            (target, source) => *target = source,
+        }
+    }
+
+    /// gets the value by the path, such as `a.b[0].c`
+    #[rquickjs::function]
+    pub fn get(value: ActValue, path: String) -> ActValue {
+        let mut ret = value.inner();
+        for key in keys(&path) {
+            let item = match ret {
+                JsonValue::Array(items) => key.parse::<usize>().ok().and_then(|i| items.get(i)),
+                JsonValue::Object(map) => map.get(&key),
+                _ => None,
+            };
+            match item {
+                Some(item) => ret = item,
+                None => return ActValue::new(JsonValue::Null),
+            }
+        }
+        ActValue::new(ret.clone())
+    }
+
+    /// sets the value by the path and returns the new value, the missing objects and arrays are created
+    /// the new array index can not exceed `MAX_INDEX`
+    #[rquickjs::function]
+    pub fn set(value: ActValue, path: String, item: ActValue) -> rquickjs::Result<ActValue> {
+        let mut ret: JsonValue = value.into();
+        set_value(&mut ret, &keys(&path), item.into())?;
+        Ok(ActValue::new(ret))
+    }
+
+    /// merges the source to the target deeply and returns the new value, the arrays are replaced
+    #[rquickjs::function]
+    pub fn merge(target: ActValue, source: ActValue) -> ActValue {
+        let mut ret: JsonValue = target.into();
+        merge_value(&mut ret, source.into());
+        ActValue::new(ret)
+    }
+}
+
+impl ActModule for Json {
+    fn init(&self, ctx: &rquickjs::Ctx<'_>) -> Result<()> {
+        JsModule::declare_def::<js_json, _>(ctx.clone(), "@acts/json").unwrap();
+        Ok(())
+    }
+}
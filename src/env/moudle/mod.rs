@@ -3,14 +3,22 @@ use super::Enviroment;
 mod act;
 mod array;
 mod console;
+mod crypto;
 mod env;
+mod id;
+mod json;
+mod time;
 
 impl Enviroment {
     pub fn init(&mut self) {
-        let mut modules = self.modules.write().unwrap();
+        let mut modules = self.js.modules.write().unwrap();
         modules.push(Box::new(console::Console::new()));
         modules.push(Box::new(array::Array::new()));
         modules.push(Box::new(act::ActPackage::new()));
         modules.push(Box::new(env::Env::new()));
+        modules.push(Box::new(time::Time::new()));
+        modules.push(Box::new(id::Id::new()));
+        modules.push(Box::new(crypto::Crypto::new()));
+        modules.push(Box::new(json::Json::new()));
     }
 }
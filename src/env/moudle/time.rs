@@ -0,0 +1,252 @@
+use crate::{ActModule, Result};
+use rquickjs::Module as JsModule;
+
+/// the time module, the times are the iso strings or the timestamp millis
+#[derive(Clone)]
+pub struct Time;
+impl Time {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[allow(clippy::module_inception)]
+#[rquickjs::module(rename_vars = "camelCase")]
+mod time {
+    use crate::{
+        env::value::ActValue,
+        utils::time::{self, DATE_FORMAT},
+        ActError, Context, Result,
+    };
+    use chrono::{DateTime, Datelike, Duration, Months, NaiveDate, Utc, Weekday};
+    use rquickjs::function::Opt;
+
+    fn parse(value: &ActValue) -> Result<DateTime<Utc>> {
+        time::parse_time(value.inner()).ok_or(ActError::Script(format!(
+            "the value {} is not a time",
+            value.inner()
+        )))
+    }
+
+    fn to_string(time: &DateTime<Utc>) -> String {
+        time.format(DATE_FORMAT).to_string()
+    }
+
+    /// the business day is not weekend and not in the holidays of `%Y-%m-%d`
+    fn is_business(date: NaiveDate, holidays: &[String]) -> bool {
+        !matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+            && !holidays.contains(&date.format("%Y-%m-%d").to_string())
+    }
+
+    /// the business holidays which are not in the weekend, sorted by date
+    fn weekday_holidays(holidays: &[String]) -> Vec<NaiveDate> {
+        let mut ret = holidays
+            .iter()
+            .filter_map(|h| NaiveDate::parse_from_str(h, "%Y-%m-%d").ok())
+            .filter(|d| !matches!(d.weekday(), Weekday::Sat | Weekday::Sun))
+            .collect::<Vec<_>>();
+        ret.sort();
+        ret.dedup();
+        ret
+    }
+
+    /// adds the weekdays to the date by the full weeks, the weekend date is counted
+    /// from the friday when adding, or from the next monday when subtracting
+    fn add_weekdays(date: NaiveDate, amount: i64) -> Option<NaiveDate> {
+        let weekday = date.weekday().num_days_from_monday() as i64;
+        let (weeks, rem) = (amount.abs() / 5, amount.abs() % 5);
+        let (base, days) = if amount >= 0 {
+            let (base, weekday) = match weekday {
+                5 | 6 => (date - Duration::days(weekday - 4), 4),
+                _ => (date, weekday),
+            };
+            let extra = if weekday + rem > 4 { 2 } else { 0 };
+            (base, weeks * 7 + rem + extra)
+        } else {
+            let (base, weekday) = match weekday {
+                5 | 6 => (date + Duration::days(7 - weekday), 0),
+                _ => (date, weekday),
+            };
+            let extra = if weekday - rem < 0 { 2 } else { 0 };
+            (base, -(weeks * 7 + rem + extra))
+        };
+        base.checked_add_signed(Duration::try_days(days)?)
+    }
+
+    /// adds the business days to the date, the days are computed by the full weeks,
+    /// and the skipped holidays are added again until no holiday is in the added range
+    fn add_business_days(date: NaiveDate, amount: i64, holidays: &[String]) -> Option<NaiveDate> {
+        let holidays = weekday_holidays(holidays);
+        let mut ret = date;
+        let mut count = amount;
+        while count != 0 {
+            let next = add_weekdays(ret, count)?;
+            let (start, end) = if count > 0 { (ret, next) } else { (next, ret) };
+            let skipped = holidays
+                .iter()
+                .filter(|h| {
+                    if count > 0 {
+                        **h > start && **h <= end
+                    } else {
+                        **h >= start && **h < end
+                    }
+                })
+                .count() as i64;
+            ret = next;
+            count = skipped * count.signum();
+        }
+        Some(ret)
+    }
+
+    /// the business days in `(start, end]`, which are counted by the full weeks
+    fn business_days_between(start: NaiveDate, end: NaiveDate, holidays: &[String]) -> i64 {
+        let days = (end - start).num_days();
+        let weeks = days / 7;
+        let mut ret = weeks * 5;
+        let mut date = start + Duration::days(weeks * 7);
+        while date < end {
+            date = date.succ_opt().unwrap_or(end);
+            if !matches!(date.weekday(), Weekday::Sat | Weekday::Sun) {
+                ret += 1;
+            }
+        }
+        let holidays = weekday_holidays(holidays)
+            .into_iter()
+            .filter(|h| *h > start && *h <= end)
+            .count() as i64;
+        ret - holidays
+    }
+
+    fn add_months(time: DateTime<Utc>, months: i64) -> Option<DateTime<Utc>> {
+        let value = Months::new(months.unsigned_abs() as u32);
+        if months >= 0 {
+            time.checked_add_months(value)
+        } else {
+            time.checked_sub_months(value)
+        }
+    }
+
+    fn unit_millis(unit: &str) -> Result<i64> {
+        match unit {
+            "ms" => Ok(1),
+            "s" => Ok(1000),
+            "m" => Ok(60 * 1000),
+            "h" => Ok(3600 * 1000),
+            "d" => Ok(24 * 3600 * 1000),
+            "w" => Ok(7 * 24 * 3600 * 1000),
+            _ => Err(ActError::Script(format!(
+                "the time unit '{unit}' is not supported"
+            ))),
+        }
+    }
+
+    /// the current time of the engine clock, the system time is used out of the engine
+    #[rquickjs::function]
+    pub fn now() -> String {
+        let now = Context::current()
+            .ok()
+            .and_then(|ctx| DateTime::from_timestamp_millis(ctx.runtime.clock().now()))
+            .unwrap_or_else(Utc::now);
+        to_string(&now)
+    }
+
+    /// adds the amount of unit to the time, the unit is `ms`, `s`, `m`, `h`, `d`, `w`, `M`, `y`
+    /// or `bd` for the business days
+    #[rquickjs::function]
+    pub fn add(
+        time: ActValue,
+        amount: i64,
+        unit: Opt<String>,
+        holidays: Opt<Vec<String>>,
+    ) -> rquickjs::Result<String> {
+        let time = parse(&time)?;
+        let unit = unit.0.unwrap_or("ms".to_string());
+        let overflow = || ActError::Script(format!("the time is out of range by {amount}{unit}"));
+        let ret = match unit.as_str() {
+            "M" => add_months(time, amount).ok_or_else(overflow)?,
+            "y" => add_months(time, amount.saturating_mul(12)).ok_or_else(overflow)?,
+            "bd" => {
+                let holidays = holidays.0.unwrap_or_default();
+                let date = time.date_naive();
+                let days = add_business_days(date, amount, &holidays)
+                    .map(|d| (d - date).num_days())
+                    .ok_or_else(overflow)?;
+                Duration::try_days(days)
+                    .and_then(|days| time.checked_add_signed(days))
+                    .ok_or_else(overflow)?
+            }
+            unit => time
+                .checked_add_signed(Duration::milliseconds(
+                    amount.saturating_mul(unit_millis(unit)?),
+                ))
+                .ok_or_else(overflow)?,
+        };
+        Ok(to_string(&ret))
+    }
+
+    /// the difference from `from` to `to` in the unit, which is truncated to the integer
+    #[rquickjs::function]
+    pub fn diff(
+        from: ActValue,
+        to: ActValue,
+        unit: Opt<String>,
+        holidays: Opt<Vec<String>>,
+    ) -> rquickjs::Result<i64> {
+        let (from, to) = (parse(&from)?, parse(&to)?);
+        let unit = unit.0.unwrap_or("ms".to_string());
+        let ret = match unit.as_str() {
+            "M" | "y" => {
+                let mut months =
+                    (to.year() - from.year()) as i64 * 12 + to.month() as i64 - from.month() as i64;
+                // the last month is not full
+                if months > 0 && add_months(from, months).is_some_and(|t| t > to) {
+                    months -= 1;
+                } else if months < 0 && add_months(from, months).is_some_and(|t| t < to) {
+                    months += 1;
+                }
+                if unit == "y" {
+                    months / 12
+                } else {
+                    months
+                }
+            }
+            "bd" => {
+                let holidays = holidays.0.unwrap_or_default();
+                let (start, end, sign) = if from <= to {
+                    (from.date_naive(), to.date_naive(), 1)
+                } else {
+                    (to.date_naive(), from.date_naive(), -1)
+                };
+                business_days_between(start, end, &holidays) * sign
+            }
+            unit => (to - from).num_milliseconds() / unit_millis(unit)?,
+        };
+        Ok(ret)
+    }
+
+    /// formats the time by the `strftime` format, the default is the iso string
+    #[rquickjs::function]
+    pub fn format(time: ActValue, fmt: Opt<String>) -> rquickjs::Result<String> {
+        let time = parse(&time)?;
+        let fmt = fmt.0.unwrap_or(DATE_FORMAT.to_string());
+        Ok(time::format_time(&time, &fmt)?)
+    }
+
+    // the js name is used as the fn name, the renamed fn is not declared by the module macro
+    #[rquickjs::function]
+    #[allow(non_snake_case)]
+    pub fn isBusinessDay(time: ActValue, holidays: Opt<Vec<String>>) -> rquickjs::Result<bool> {
+        let time = parse(&time)?;
+        Ok(is_business(
+            time.date_naive(),
+            &holidays.0.unwrap_or_default(),
+        ))
+    }
+}
+
+impl ActModule for Time {
+    fn init(&self, ctx: &rquickjs::Ctx<'_>) -> Result<()> {
+        JsModule::declare_def::<js_time, _>(ctx.clone(), "@acts/time").unwrap();
+        Ok(())
+    }
+}
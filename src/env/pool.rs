@@ -0,0 +1,752 @@
+use super::{
+    host::{self, is_async, HostQueue, HostResult, Wake, WakeSlot},
+    loader::{PackageLoader, PackageResolver},
+    value::ActValue,
+    ActModule,
+};
+use crate::{ActError, Limits, Result, Vars};
+use rquickjs::{
+    qjs, Context as JsContext, Ctx as JsCtx, Exception, FromJs, Function, Object, Promise,
+    Runtime as JsRuntime, Value,
+};
+use serde_json::Value as JsonValue;
+use std::{
+    ffi::CString,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        mpsc::{self, Receiver},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+use tokio::runtime::{Handle, RuntimeFlavor};
+
+/// the max idle runtimes kept in the pool
+const POOL_CAP: usize = 8;
+
+/// the max es module runs of a pooled runtime, each run declares a new root module
+/// which is kept by the runtime, so the runtime is dropped after the cap
+const MODULE_RUNS_CAP: usize = 64;
+
+/// the default max stack size of quickjs
+const DEFAULT_STACK_SIZE: usize = 256 * 1024;
+
+/// the max waiting time of the async host functions when the script timeout is disabled
+const HOST_WAIT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// freezes the builtin objects and the module globals, so the prototype and builtin changes
+/// can not leak to the next eval, the `constructor`, `name`, `message`, `toString`, `toLocaleString`,
+/// `valueOf` and `toJSON` of the prototypes become accessors which define the property on the
+/// instance, so the assignment like `this.name = "..."` in an error class still works
+const FREEZE_SCRIPT: &str = r#"
+(() => {
+    const { defineProperty, freeze, getOwnPropertyDescriptor, getPrototypeOf } = Object;
+    const { ownKeys } = Reflect;
+    const overrides = ["constructor", "name", "message", "toString", "toLocaleString", "valueOf", "toJSON"];
+    const prototypes = new Set();
+    const frozen = new Set();
+    const tame = (proto) => {
+        for (const key of overrides) {
+            const desc = getOwnPropertyDescriptor(proto, key);
+            if (!desc || !("value" in desc) || !desc.configurable) continue;
+            const value = desc.value;
+            // the methods have no `prototype` to tame again
+            const { get, set } = {
+                get() {
+                    return value;
+                },
+                set(v) {
+                    if (this === proto) throw new TypeError(`Cannot assign to read only property '${key}'`);
+                    defineProperty(this, key, { value: v, writable: true, enumerable: true, configurable: true });
+                },
+            };
+            defineProperty(proto, key, { get, set, enumerable: desc.enumerable, configurable: false });
+            freezeAll(get);
+            freezeAll(set);
+        }
+    };
+    const freezeAll = (obj) => {
+        if ((typeof obj !== "object" && typeof obj !== "function") || obj === null || obj === globalThis) return;
+        if (frozen.has(obj)) return;
+        frozen.add(obj);
+        if (typeof obj === "function") {
+            const desc = getOwnPropertyDescriptor(obj, "prototype");
+            if (desc && typeof desc.value === "object" && desc.value !== null) prototypes.add(desc.value);
+        }
+        for (const key of ownKeys(obj)) {
+            const desc = getOwnPropertyDescriptor(obj, key);
+            if ("value" in desc) {
+                freezeAll(desc.value);
+            } else {
+                freezeAll(desc.get);
+                freezeAll(desc.set);
+            }
+        }
+        freezeAll(getPrototypeOf(obj));
+    };
+    for (const key of ownKeys(globalThis)) {
+        const desc = getOwnPropertyDescriptor(globalThis, key);
+        if ("value" in desc) freezeAll(desc.value);
+    }
+    freezeAll(getPrototypeOf(globalThis));
+    // the intrinsics which are not reachable from the globals
+    const hidden = [
+        [][Symbol.iterator](),
+        ""[Symbol.iterator](),
+        new Map()[Symbol.iterator](),
+        new Set()[Symbol.iterator](),
+        /a/[Symbol.matchAll](""),
+        function* () {},
+        async function () {},
+        async function* () {},
+    ];
+    for (const obj of hidden) freezeAll(getPrototypeOf(obj));
+    prototypes.add(Object.prototype);
+    for (const proto of prototypes) tame(proto);
+    for (const obj of frozen) freeze(obj);
+})();
+"#;
+
+/// records the globals after the modules init, and restores them after each eval
+/// the `__acts_jobs` keeps the pending promises of the async host functions,
+/// and the `__acts_pending` keeps the promise of the suspended eval
+const GLOBALS_SCRIPT: &str = r#"
+Object.defineProperty(globalThis, "__acts_jobs", { value: new Map() });
+Object.defineProperty(globalThis, "__acts_pending", { value: undefined, writable: true });
+Object.defineProperty(globalThis, "__acts_job", {
+    value: (id) => new Promise((resolve, reject) => __acts_jobs.set(id, { resolve, reject })),
+});
+Object.defineProperty(globalThis, "__acts_settle", {
+    value: (id, ok, value) => {
+        const job = __acts_jobs.get(id);
+        if (!job) return;
+        __acts_jobs.delete(id);
+        ok ? job.resolve(value) : job.reject(new Error(value));
+    },
+});
+Object.defineProperty(globalThis, "__acts_globals", {
+    value: new Map(Object.getOwnPropertyNames(globalThis).map((name) => [name, globalThis[name]])),
+});
+Object.defineProperty(globalThis, "__acts_reset", {
+    value: () => {
+        __acts_jobs.clear();
+        for (const name of Object.getOwnPropertyNames(globalThis)) {
+            if (__acts_globals.has(name) || name === "__acts_globals" || name === "__acts_reset") continue;
+            // the global `var` can not be deleted, just clear its value
+            if (!Reflect.deleteProperty(globalThis, name)) {
+                try {
+                    globalThis[name] = undefined;
+                } catch (_) {}
+            }
+        }
+        for (const [name, value] of __acts_globals) {
+            if (globalThis[name] === value) continue;
+            try {
+                globalThis[name] = value;
+            } catch (_) {}
+        }
+    },
+});
+"#;
+
+/// the js runtime with the initialized modules
+pub struct JsWorker {
+    ver: usize,
+    // the context should be dropped before the runtime
+    context: JsContext,
+    runtime: JsRuntime,
+    // the deadline of the current eval which is checked by the interrupt handler
+    deadline: Arc<Mutex<Option<Instant>>>,
+    interrupted: Arc<AtomicBool>,
+    // the settled results of the async host functions
+    jobs: Receiver<HostResult>,
+    // the es module runs in this runtime
+    module_runs: AtomicUsize,
+    // the wake of the suspended eval, which is called by the settled host functions
+    wake: WakeSlot,
+    // the state of the suspended eval
+    pending: Mutex<Option<Pending>>,
+}
+
+/// the state of the suspended eval, the pending promise is kept in the context
+struct Pending {
+    is_async: bool,
+    limits: Limits,
+    deadline: Option<Instant>,
+    // the waiting is failed when the eval is woken after it
+    wait_until: Instant,
+}
+
+impl JsWorker {
+    pub fn new(ver: usize, modules: &[Box<dyn ActModule>]) -> Result<Self> {
+        let runtime = JsRuntime::new().map_err(ActError::from)?;
+        runtime.set_loader(PackageResolver, PackageLoader);
+        let context = JsContext::full(&runtime).map_err(ActError::from)?;
+        let (tx, jobs) = mpsc::channel();
+        let wake = WakeSlot::default();
+        context.with(|ctx| {
+            let _ = ctx.store_userdata(HostQueue::new(tx, wake.clone()));
+            for m in modules.iter() {
+                m.init(&ctx)?;
+            }
+            ctx.eval::<(), &str>(FREEZE_SCRIPT)
+                .map_err(|err| ActError::Script(err.to_string()))?;
+            ctx.eval::<(), &str>(GLOBALS_SCRIPT)
+                .map_err(|err| ActError::Script(err.to_string()))
+        })?;
+
+        let deadline = Arc::new(Mutex::new(None::<Instant>));
+        let interrupted = Arc::new(AtomicBool::new(false));
+        {
+            let deadline = deadline.clone();
+            let interrupted = interrupted.clone();
+            runtime.set_interrupt_handler(Some(Box::new(move || {
+                match *deadline.lock().unwrap() {
+                    Some(deadline) if Instant::now() >= deadline => {
+                        interrupted.store(true, Ordering::SeqCst);
+                        true
+                    }
+                    _ => false,
+                }
+            })));
+        }
+
+        Ok(Self {
+            ver,
+            context,
+            runtime,
+            deadline,
+            interrupted,
+            jobs,
+            module_runs: AtomicUsize::new(0),
+            wake,
+            pending: Mutex::new(None),
+        })
+    }
+
+    pub fn ver(&self) -> usize {
+        self.ver
+    }
+
+    pub fn module_runs(&self) -> usize {
+        self.module_runs.load(Ordering::SeqCst)
+    }
+
+    /// compiles the script to the quickjs bytecode without running it
+    /// the script is wrapped in a block, so the `let` and `const` declarations do not leak
+    /// to the next eval, the script with the top-level `await` is compiled as an async script
+    pub fn compile(&self, expr: &str) -> Result<Vec<u8>> {
+        let script = CString::new(format!("{{{expr}\n}}"))
+            .map_err(|err| ActError::Script(err.to_string()))?;
+        self.context.with(|ctx| {
+            let raw = ctx.as_raw().as_ptr();
+            let mut flags = qjs::JS_EVAL_TYPE_GLOBAL
+                | qjs::JS_EVAL_FLAG_STRICT
+                | qjs::JS_EVAL_FLAG_COMPILE_ONLY;
+            if is_async(expr) {
+                flags |= qjs::JS_EVAL_FLAG_ASYNC;
+            }
+            unsafe {
+                let func = qjs::JS_Eval(
+                    raw,
+                    script.as_ptr(),
+                    script.as_bytes().len() as _,
+                    c"eval_script".as_ptr(),
+                    flags as _,
+                );
+                if qjs::JS_IsException(func) {
+                    return Err(ActError::Script(Self::exception(&ctx)));
+                }
+                let func = Value::from_raw(ctx.clone(), func);
+
+                let mut size = 0;
+                let buf = qjs::JS_WriteObject(
+                    raw,
+                    &mut size,
+                    func.as_raw(),
+                    qjs::JS_WRITE_OBJ_BYTECODE as _,
+                );
+                if buf.is_null() {
+                    return Err(ActError::Script(Self::exception(&ctx)));
+                }
+                let code = std::slice::from_raw_parts(buf, size as _).to_vec();
+                qjs::js_free(raw, buf as _);
+                Ok(code)
+            }
+        })
+    }
+
+    /// runs the compiled bytecode with the vars as the globals and restores the globals after the eval
+    /// the returned promise is settled before converting to the json value
+    pub fn run(
+        &self,
+        code: &[u8],
+        is_async: bool,
+        vars: &Vars,
+        limits: &Limits,
+    ) -> Result<JsonValue> {
+        self.set_limits(limits);
+        let ret = self.context.with(|ctx| {
+            let globals = ctx.globals();
+            let result = vars
+                .iter()
+                .try_for_each(|(name, value)| globals.set(name, ActValue::new(value.clone())));
+            result
+                .and_then(|_| self.eval_script(&ctx, code))
+                .and_then(|value| self.resolve(&ctx, value, is_async))
+                .and_then(|value| ActValue::from_js(&ctx, value))
+                .map(|value| value.into())
+                .map_err(|err| self.error(&ctx, err, limits))
+        });
+        self.set_limits(&Limits::default());
+        self.reset()?;
+        ret
+    }
+
+    /// runs the compiled es module which can import the other packages by `@pack/{id}`,
+    /// the imported modules are loaded by the runtime loader once and shared by the next runs
+    pub fn run_module(&self, code: &[u8], limits: &Limits) -> Result<()> {
+        self.module_runs.fetch_add(1, Ordering::SeqCst);
+        self.set_limits(limits);
+        let ret = self.context.with(|ctx| {
+            self.eval_module(&ctx, code)
+                .and_then(|value| self.resolve(&ctx, value, false))
+                .map(|_| ())
+                .map_err(|err| self.error(&ctx, err, limits))
+        });
+        self.set_limits(&Limits::default());
+        self.reset()?;
+        ret
+    }
+
+    /// runs the compiled script or es module like `run` and `run_module`, but the eval is suspended
+    /// instead of waiting for the async host functions, it returns `None` when it is suspended
+    /// and the `wake` is called once to resume it by `resume`
+    pub fn start(
+        &self,
+        code: &[u8],
+        is_module: bool,
+        is_async: bool,
+        limits: &Limits,
+        wake: Wake,
+    ) -> Result<Option<JsonValue>> {
+        if is_module {
+            self.module_runs.fetch_add(1, Ordering::SeqCst);
+        }
+        self.set_limits(limits);
+        let ret = self.context.with(|ctx| {
+            let value = match is_module {
+                true => self.eval_module(&ctx, code),
+                false => self.eval_script(&ctx, code),
+            };
+            value
+                .and_then(|value| self.poll(&ctx, value, is_async, limits, None, wake))
+                .and_then(|value| value.map(|v| ActValue::from_js(&ctx, v)).transpose())
+                .map(|value| value.map(|v| v.into()))
+                .map_err(|err| self.error(&ctx, err, limits))
+        });
+        self.finish(ret)
+    }
+
+    /// resumes the suspended eval after it is woken, the eval is suspended again
+    /// when the promise still waits for the async host functions
+    pub fn resume(&self, wake: Wake) -> Result<Option<JsonValue>> {
+        let pending = self
+            .pending
+            .lock()
+            .unwrap()
+            .take()
+            .ok_or(ActError::Script("the eval is not suspended".to_string()))?;
+        self.set_limits(&pending.limits);
+        *self.deadline.lock().unwrap() = pending.deadline;
+        let ret = self.context.with(|ctx| {
+            let globals = ctx.globals();
+            globals
+                .get::<_, Value>("__acts_pending")
+                .and_then(|promise| {
+                    globals.set("__acts_pending", Value::new_undefined(ctx.clone()))?;
+                    self.poll(
+                        &ctx,
+                        promise,
+                        pending.is_async,
+                        &pending.limits,
+                        Some(pending.wait_until),
+                        wake,
+                    )
+                })
+                .and_then(|value| value.map(|v| ActValue::from_js(&ctx, v)).transpose())
+                .map(|value| value.map(|v| v.into()))
+                .map_err(|err| self.error(&ctx, err, &pending.limits))
+        });
+        self.finish(ret)
+    }
+
+    fn eval_script<'js>(&self, ctx: &JsCtx<'js>, code: &[u8]) -> rquickjs::Result<Value<'js>> {
+        let raw = ctx.as_raw().as_ptr();
+        unsafe {
+            let func = qjs::JS_ReadObject(
+                raw,
+                code.as_ptr(),
+                code.len() as _,
+                qjs::JS_READ_OBJ_BYTECODE as _,
+            );
+            if qjs::JS_IsException(func) {
+                return Err(rquickjs::Error::Exception);
+            }
+            // the function is freed by the eval
+            let value = qjs::JS_EvalFunction(raw, func);
+            if qjs::JS_IsException(value) {
+                return Err(rquickjs::Error::Exception);
+            }
+            Ok(Value::from_raw(ctx.clone(), value))
+        }
+    }
+
+    fn eval_module<'js>(&self, ctx: &JsCtx<'js>, code: &[u8]) -> rquickjs::Result<Value<'js>> {
+        let raw = ctx.as_raw().as_ptr();
+        unsafe {
+            let module = qjs::JS_ReadObject(
+                raw,
+                code.as_ptr(),
+                code.len() as _,
+                qjs::JS_READ_OBJ_BYTECODE as _,
+            );
+            // the imported modules are resolved by the loader before the eval,
+            // the module is kept by the runtime and freed by the eval once, so dup it first
+            if qjs::JS_IsException(module) || qjs::JS_ResolveModule(raw, module) < 0 {
+                return Err(rquickjs::Error::Exception);
+            }
+            qjs::JS_DupValue(raw, module);
+            let value = qjs::JS_EvalFunction(raw, module);
+            if qjs::JS_IsException(value) {
+                return Err(rquickjs::Error::Exception);
+            }
+            let value = Value::from_raw(ctx.clone(), value);
+            match value.is_promise() {
+                true => Ok(value),
+                false => Err(rquickjs::Error::Unknown),
+            }
+        }
+    }
+
+    /// resets the limits, and restores the globals after the eval is settled,
+    /// the suspended eval keeps its globals
+    fn finish(&self, ret: Result<Option<JsonValue>>) -> Result<Option<JsonValue>> {
+        self.set_limits(&Limits::default());
+        if let Ok(None) = ret {
+            return ret;
+        }
+        self.wake.lock().unwrap().take();
+        self.pending.lock().unwrap().take();
+        self.reset()?;
+        ret
+    }
+
+    fn reset(&self) -> Result<()> {
+        self.context.with(|ctx| {
+            ctx.eval::<(), &str>("__acts_reset()")
+                .map_err(|err| ActError::Script(err.to_string()))
+        })
+    }
+
+    /// settles the returned promise, the async script result is wrapped in `{ value }`
+    fn resolve<'js>(
+        &self,
+        ctx: &JsCtx<'js>,
+        value: Value<'js>,
+        is_async: bool,
+    ) -> rquickjs::Result<Value<'js>> {
+        let promise = match value.as_promise() {
+            Some(promise) => promise.clone(),
+            None => return Ok(value),
+        };
+        let value = self.settle(ctx, promise)?;
+        if !is_async {
+            return Ok(value);
+        }
+        match value.as_object() {
+            Some(obj) => {
+                let value = obj.get::<_, Value>("value")?;
+                self.resolve(ctx, value, false)
+            }
+            None => Ok(value),
+        }
+    }
+
+    /// settles the returned promise like `resolve`, but returns `None` when the promise waits for
+    /// the async host functions, the promise is kept and the eval is suspended until it is woken,
+    /// the eval fails when it is woken after `wait_until` and no host function is settled
+    fn poll<'js>(
+        &self,
+        ctx: &JsCtx<'js>,
+        value: Value<'js>,
+        is_async: bool,
+        limits: &Limits,
+        wait_until: Option<Instant>,
+        mut wake: Wake,
+    ) -> rquickjs::Result<Option<Value<'js>>> {
+        let promise = match value.as_promise() {
+            Some(promise) => promise.clone(),
+            None => return Ok(Some(value)),
+        };
+        let value = loop {
+            if let Some(value) = self.drive(ctx, &promise)? {
+                break value;
+            }
+            if wait_until.is_some_and(|time| Instant::now() >= time) {
+                return Err(self.timeout(ctx));
+            }
+            match self.park(ctx, &promise, is_async, limits, wake)? {
+                Some(w) => wake = w,
+                None => return Ok(None),
+            }
+        };
+        if !is_async {
+            return Ok(Some(value));
+        }
+        match value.as_object() {
+            Some(obj) => {
+                let value = obj.get::<_, Value>("value")?;
+                self.poll(ctx, value, false, limits, None, wake)
+            }
+            None => Ok(Some(value)),
+        }
+    }
+
+    /// drives the job queue with the settled host functions without waiting,
+    /// returns `None` when the promise still waits for the async host functions
+    fn drive<'js>(
+        &self,
+        ctx: &JsCtx<'js>,
+        promise: &Promise<'js>,
+    ) -> rquickjs::Result<Option<Value<'js>>> {
+        loop {
+            while ctx.execute_pending_job() {}
+            if let Some(ret) = promise.result::<Value>() {
+                return ret.map(Some);
+            }
+            self.check_jobs(ctx)?;
+            match self.jobs.try_recv() {
+                Ok(result) => self.settle_job(ctx, result)?,
+                Err(_) => return Ok(None),
+            }
+        }
+    }
+
+    /// keeps the pending promise and sets the wake, which is called by the next settled host function
+    /// or after the waiting timeout, the wake is returned when a host function is settled before
+    /// the wake is set, so the promise is driven again
+    fn park<'js>(
+        &self,
+        ctx: &JsCtx<'js>,
+        promise: &Promise<'js>,
+        is_async: bool,
+        limits: &Limits,
+        wake: Wake,
+    ) -> rquickjs::Result<Option<Wake>> {
+        *self.wake.lock().unwrap() = Some(wake);
+        if let Ok(result) = self.jobs.try_recv() {
+            self.settle_job(ctx, result)?;
+            // the wake is taken when another host function is settled, which resumes the eval
+            if let Some(wake) = self.wake.lock().unwrap().take() {
+                return Ok(Some(wake));
+            }
+        }
+
+        ctx.globals().set("__acts_pending", promise.clone())?;
+        let deadline = *self.deadline.lock().unwrap();
+        let timeout = match deadline {
+            Some(deadline) => deadline.saturating_duration_since(Instant::now()),
+            None => HOST_WAIT_TIMEOUT,
+        };
+        *self.pending.lock().unwrap() = Some(Pending {
+            is_async,
+            limits: limits.clone(),
+            deadline,
+            wait_until: Instant::now() + timeout,
+        });
+        host::wake_after(&self.wake, timeout);
+        Ok(None)
+    }
+
+    /// drives the job queue and waits for the async host functions until the promise is settled
+    fn settle<'js>(&self, ctx: &JsCtx<'js>, promise: Promise<'js>) -> rquickjs::Result<Value<'js>> {
+        loop {
+            while ctx.execute_pending_job() {}
+            if let Some(ret) = promise.result::<Value>() {
+                return ret;
+            }
+
+            self.check_jobs(ctx)?;
+            match self.wait() {
+                Some(result) => self.settle_job(ctx, result)?,
+                None => return Err(self.timeout(ctx)),
+            }
+        }
+    }
+
+    /// checks the promise is waiting for the async host functions
+    fn check_jobs(&self, ctx: &JsCtx<'_>) -> rquickjs::Result<()> {
+        let jobs = ctx.globals().get::<_, Object>("__acts_jobs")?;
+        if jobs.get::<_, usize>("size")? == 0 {
+            return Err(Exception::throw_message(
+                ctx,
+                "the promise is never settled",
+            ));
+        }
+        Ok(())
+    }
+
+    /// settles the promise of the host function by the job id
+    fn settle_job(&self, ctx: &JsCtx<'_>, (id, result): HostResult) -> rquickjs::Result<()> {
+        let settle = ctx.globals().get::<_, Function>("__acts_settle")?;
+        match result {
+            Ok(value) => settle.call::<_, ()>((id, true, ActValue::new(value))),
+            Err(err) => settle.call::<_, ()>((id, false, err.to_string())),
+        }
+    }
+
+    /// the error when the async host functions are not settled in time
+    fn timeout(&self, ctx: &JsCtx<'_>) -> rquickjs::Error {
+        if self.deadline.lock().unwrap().is_some() {
+            self.interrupted.store(true, Ordering::SeqCst);
+            return Exception::throw_internal(ctx, "interrupted");
+        }
+        Exception::throw_message(
+            ctx,
+            &format!(
+                "the async function is not settled in {}ms",
+                HOST_WAIT_TIMEOUT.as_millis()
+            ),
+        )
+    }
+
+    /// waits for the next settled host function until the eval deadline, or the `HOST_WAIT_TIMEOUT`
+    /// when the timeout is disabled, so the waiting is always bounded
+    /// the waiting is moved out of the scheduler worker in the multi-thread runtime
+    fn wait(&self) -> Option<HostResult> {
+        let timeout = match *self.deadline.lock().unwrap() {
+            Some(deadline) => deadline.saturating_duration_since(Instant::now()),
+            None => HOST_WAIT_TIMEOUT,
+        };
+        let recv = || self.jobs.recv_timeout(timeout).ok();
+        match Handle::try_current() {
+            Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+                tokio::task::block_in_place(recv)
+            }
+            _ => recv(),
+        }
+    }
+
+    fn error(&self, ctx: &JsCtx<'_>, err: rquickjs::Error, limits: &Limits) -> ActError {
+        match err {
+            rquickjs::Error::Exception => {
+                let message = Self::exception(ctx);
+                eprintln!("error: {message}");
+                self.limit_error(&message, limits)
+                    .unwrap_or(ActError::Exception {
+                        ecode: "".to_string(),
+                        message,
+                    })
+            }
+            err => self
+                .limit_error(&err.to_string(), limits)
+                .unwrap_or(ActError::from(err)),
+        }
+    }
+
+    /// takes the pending exception message
+    fn exception(ctx: &JsCtx<'_>) -> String {
+        let value = ctx.catch();
+        match rquickjs::Exception::from_js(ctx, value.clone()) {
+            Ok(exception) => exception.message().unwrap_or_default(),
+            Err(_) => value
+                .as_string()
+                .and_then(|s| s.to_string().ok())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// sets the limits before the eval, the memory limit is counted from the current usage
+    fn set_limits(&self, limits: &Limits) {
+        let memory = limits.memory.unwrap_or_default();
+        if memory > 0 {
+            let used = self.runtime.memory_usage().malloc_size as usize;
+            self.runtime.set_memory_limit(used + memory);
+        } else {
+            self.runtime.set_memory_limit(0);
+        }
+        let stack = limits.stack.filter(|stack| *stack > 0);
+        self.runtime
+            .set_max_stack_size(stack.unwrap_or(DEFAULT_STACK_SIZE));
+
+        self.interrupted.store(false, Ordering::SeqCst);
+        *self.deadline.lock().unwrap() = limits
+            .timeout
+            .filter(|millis| *millis > 0)
+            .map(|millis| Instant::now() + Duration::from_millis(millis));
+    }
+
+    fn limit_error(&self, message: &str, limits: &Limits) -> Option<ActError> {
+        if self.interrupted.load(Ordering::SeqCst) {
+            return Some(ActError::Limit(format!(
+                "the script exceeds the time limit {}ms",
+                limits.timeout.unwrap_or_default()
+            )));
+        }
+        if message.contains("out of memory") {
+            return Some(ActError::Limit(format!(
+                "the script exceeds the memory limit {} bytes",
+                limits.memory.unwrap_or_default()
+            )));
+        }
+        if message.contains("stack overflow") || message.contains("call stack size exceeded") {
+            return Some(ActError::Limit(format!(
+                "the script exceeds the stack limit {} bytes",
+                limits.stack.unwrap_or_default()
+            )));
+        }
+        None
+    }
+}
+
+/// the runtime pool to reuse the initialized js runtimes across the evaluations
+/// the nested eval from the module functions takes another runtime from the pool
+#[derive(Default)]
+pub struct JsPool {
+    workers: Mutex<Vec<JsWorker>>,
+}
+
+impl JsPool {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// takes an idle runtime with the same modules version or creates a new one
+    pub fn take(&self, ver: usize, modules: &[Box<dyn ActModule>]) -> Result<JsWorker> {
+        let worker = self.workers.lock().unwrap().pop();
+        match worker {
+            Some(worker) if worker.ver() == ver => Ok(worker),
+            _ => JsWorker::new(ver, modules),
+        }
+    }
+
+    pub fn release(&self, worker: JsWorker) {
+        if worker.module_runs() >= MODULE_RUNS_CAP {
+            return;
+        }
+        let mut workers = self.workers.lock().unwrap();
+        if workers.len() < POOL_CAP {
+            workers.push(worker);
+        }
+    }
+
+    pub fn clear(&self) {
+        self.workers.lock().unwrap().clear();
+    }
+
+    #[cfg(test)]
+    pub fn len(&self) -> usize {
+        self.workers.lock().unwrap().len()
+    }
+}
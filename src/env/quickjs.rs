@@ -0,0 +1,274 @@
+use super::{
+    host::{self, Wake},
+    loader::{PackageResolver, SyntaxLoader},
+    pool::{JsPool, JsWorker},
+    ActModule, ScriptEngine,
+};
+use crate::{ActError, Limits, Result, ShareLock, Vars};
+use moka::sync::Cache;
+use rquickjs::{Context as JsContext, FromJs, Runtime as JsRuntime};
+use serde_json::Value as JsonValue;
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc, RwLock,
+};
+
+/// the max compiled scripts kept in the cache
+const SCRIPT_CACHE_CAP: u64 = 1024;
+
+/// the max compiled es modules kept in the cache
+const MODULE_CACHE_CAP: u64 = 256;
+
+/// the js eval result, the eval waiting for the async host functions is suspended
+pub enum JsEval {
+    Ready(JsonValue),
+    Suspended(JsSuspended),
+}
+
+/// the suspended js eval, which keeps its runtime out of the pool until it is resumed
+pub struct JsSuspended(JsWorker);
+
+/// the quickjs script engine with the pooled runtimes and the compiled bytecode cache
+pub struct QuickJs {
+    pub(super) modules: ShareLock<Vec<Box<dyn ActModule>>>,
+    /// the modules version, the pooled runtimes are recreated when it changes
+    ver: AtomicUsize,
+    pool: JsPool,
+    /// the compiled bytecode by the script source
+    scripts: Cache<String, Arc<Vec<u8>>>,
+    /// the compiled es module bytecode by the module name and source
+    module_codes: Cache<(String, String), Arc<Vec<u8>>>,
+}
+
+impl Default for QuickJs {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl QuickJs {
+    pub fn new() -> Self {
+        Self {
+            modules: Arc::new(RwLock::new(Vec::new())),
+            ver: AtomicUsize::new(0),
+            pool: JsPool::new(),
+            scripts: Cache::new(SCRIPT_CACHE_CAP),
+            module_codes: Cache::new(MODULE_CACHE_CAP),
+        }
+    }
+
+    #[cfg(test)]
+    pub fn modules_count(&self) -> usize {
+        self.modules.read().unwrap().len()
+    }
+
+    pub fn register_module<T: ActModule + Clone + 'static>(&self, module: &T) {
+        let mut modules = self.modules.write().unwrap();
+        modules.push(Box::new(module.clone()));
+        self.ver.fetch_add(1, Ordering::SeqCst);
+        self.pool.clear();
+    }
+
+    #[cfg(test)]
+    pub fn pool_count(&self) -> usize {
+        self.pool.len()
+    }
+
+    /// drops the pooled runtimes, so the imported package modules are loaded again
+    /// by the next eval, it is called after the package is published
+    pub fn reset_modules(&self) {
+        self.ver.fetch_add(1, Ordering::SeqCst);
+        self.pool.clear();
+    }
+
+    #[cfg(test)]
+    pub fn is_compiled(&self, script: &str) -> bool {
+        self.scripts.contains_key(script)
+    }
+
+    #[cfg(test)]
+    pub fn is_module_compiled(&self, name: &str, source: &str) -> bool {
+        self.module_codes
+            .contains_key(&(name.to_string(), source.to_string()))
+    }
+
+    /// checks the script syntax by compiling it without running
+    pub fn check_syntax(script: &str) -> Result<()> {
+        let runtime = JsRuntime::new().unwrap();
+        let ctx = JsContext::full(&runtime).unwrap();
+        // the script with the top-level `await` is checked as an async function body
+        let func = if host::is_async(script) {
+            "new (async function () {}).constructor(__acts_script)"
+        } else {
+            "new Function(__acts_script)"
+        };
+        ctx.with(|ctx| {
+            ctx.globals().set("__acts_script", script)?;
+            let result = ctx.eval::<rquickjs::Value, &str>(func);
+            if let Err(rquickjs::Error::Exception) = result {
+                let exception = rquickjs::Exception::from_js(&ctx, ctx.catch()).unwrap();
+                return Err(ActError::Script(exception.message().unwrap_or_default()));
+            }
+            result.map_err(ActError::from)?;
+            Ok(())
+        })
+    }
+
+    /// checks the es module syntax without running it, the imported modules are not loaded
+    pub fn check_module(name: &str, source: &str) -> Result<()> {
+        Self::compile_module(name, source).map(|_| ())
+    }
+
+    /// compiles the es module to the bytecode in a new runtime without running it,
+    /// so the declared module is not kept by the pooled runtimes
+    pub fn compile_module(name: &str, source: &str) -> Result<Vec<u8>> {
+        let runtime = JsRuntime::new().unwrap();
+        runtime.set_loader(PackageResolver, SyntaxLoader);
+        let ctx = JsContext::full(&runtime).unwrap();
+        ctx.with(|ctx| {
+            let result = rquickjs::Module::declare(ctx.clone(), name, source)
+                .and_then(|module| module.write(false));
+            if let Err(rquickjs::Error::Exception) = result {
+                let exception = rquickjs::Exception::from_js(&ctx, ctx.catch()).unwrap();
+                return Err(ActError::Script(exception.message().unwrap_or_default()));
+            }
+            result.map_err(ActError::from)
+        })
+    }
+
+    /// evaluates the expression with the sandbox limits
+    pub fn eval_with_limits(&self, expr: &str, limits: &Limits) -> Result<JsonValue> {
+        self.eval_vars(expr, &Vars::new(), limits)
+    }
+
+    /// evaluates the expression with the vars as the globals, so the compiled bytecode
+    /// is shared by the evals with different vars
+    pub fn eval_vars(&self, expr: &str, vars: &Vars, limits: &Limits) -> Result<JsonValue> {
+        let worker = self.take()?;
+        let result = self
+            .compile_by(&worker, expr)
+            .and_then(|code| worker.run(&code, host::is_async(expr), vars, limits));
+        self.pool.release(worker);
+        result
+    }
+
+    /// compiles the script to the bytecode and caches it by the script source,
+    /// the syntax error is returned without running the script
+    pub fn compile(&self, script: &str) -> Result<Arc<Vec<u8>>> {
+        let worker = self.take()?;
+        let result = self.compile_by(&worker, script);
+        self.pool.release(worker);
+        result
+    }
+
+    /// evaluates the es module in a pooled runtime by the cached bytecode, the imported
+    /// modules are evaluated once in each runtime and reloaded after the package is published
+    pub fn eval_module(&self, name: &str, source: &str, limits: &Limits) -> Result<()> {
+        let code = self.module_code(name, source)?;
+        let worker = self.take()?;
+        let result = worker.run_module(&code, limits);
+        self.pool.release(worker);
+        result
+    }
+
+    /// evaluates the script like `eval_with_limits`, but the eval waiting for the async host functions
+    /// is suspended instead of blocking the thread, and the `wake` is called once to resume it
+    pub fn start(&self, expr: &str, limits: &Limits, wake: Wake) -> Result<JsEval> {
+        let worker = self.take()?;
+        let ret = self
+            .compile_by(&worker, expr)
+            .and_then(|code| worker.start(&code, false, host::is_async(expr), limits, wake));
+        self.suspend(worker, ret)
+    }
+
+    /// evaluates the es module like `eval_module`, but the eval can be suspended like `start`
+    pub fn start_module(
+        &self,
+        name: &str,
+        source: &str,
+        limits: &Limits,
+        wake: Wake,
+    ) -> Result<JsEval> {
+        let code = self.module_code(name, source)?;
+        let worker = self.take()?;
+        let ret = worker.start(&code, true, false, limits, wake);
+        self.suspend(worker, ret)
+    }
+
+    /// resumes the suspended eval after it is woken
+    pub fn resume(&self, suspended: JsSuspended, wake: Wake) -> Result<JsEval> {
+        let worker = suspended.0;
+        let ret = worker.resume(wake);
+        self.suspend(worker, ret)
+    }
+
+    /// keeps the runtime with the suspended eval, or releases it to the pool
+    fn suspend(&self, worker: JsWorker, ret: Result<Option<JsonValue>>) -> Result<JsEval> {
+        match ret {
+            Ok(None) => Ok(JsEval::Suspended(JsSuspended(worker))),
+            ret => {
+                self.pool.release(worker);
+                ret.map(|value| JsEval::Ready(value.unwrap_or_default()))
+            }
+        }
+    }
+
+    /// loads the bytecode which is kept with the script source, so the script is not compiled again
+    pub fn load(&self, script: &str, code: &[u8]) {
+        if !self.scripts.contains_key(script) {
+            self.scripts
+                .insert(script.to_string(), Arc::new(code.to_vec()));
+        }
+    }
+
+    /// loads the es module bytecode which is kept with the package source
+    pub fn load_module(&self, name: &str, source: &str, code: &[u8]) {
+        let key = (name.to_string(), source.to_string());
+        if !self.module_codes.contains_key(&key) {
+            self.module_codes.insert(key, Arc::new(code.to_vec()));
+        }
+    }
+
+    fn take(&self) -> Result<JsWorker> {
+        let ver = self.ver.load(Ordering::SeqCst);
+        let modules = self.modules.read().unwrap();
+        self.pool.take(ver, &modules)
+    }
+
+    fn module_code(&self, name: &str, source: &str) -> Result<Arc<Vec<u8>>> {
+        let key = (name.to_string(), source.to_string());
+        if let Some(code) = self.module_codes.get(&key) {
+            return Ok(code);
+        }
+        let code = Arc::new(Self::compile_module(name, source)?);
+        self.module_codes.insert(key, code.clone());
+        Ok(code)
+    }
+
+    fn compile_by(&self, worker: &JsWorker, script: &str) -> Result<Arc<Vec<u8>>> {
+        if let Some(code) = self.scripts.get(script) {
+            return Ok(code);
+        }
+        let code = Arc::new(worker.compile(script)?);
+        self.scripts.insert(script.to_string(), code.clone());
+        Ok(code)
+    }
+}
+
+impl ScriptEngine for QuickJs {
+    fn lang(&self) -> &str {
+        "js"
+    }
+
+    fn compile(&self, script: &str) -> Result<()> {
+        QuickJs::compile(self, script).map(|_| ())
+    }
+
+    fn eval(&self, script: &str, limits: &Limits) -> Result<JsonValue> {
+        self.eval_with_limits(script, limits)
+    }
+
+    fn eval_with_vars(&self, script: &str, vars: &Vars, limits: &Limits) -> Result<JsonValue> {
+        self.eval_vars(script, vars, limits)
+    }
+}
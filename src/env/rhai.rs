@@ -0,0 +1,235 @@
+use super::ScriptEngine;
+use crate::{utils::consts, ActError, Context, Limits, Result, Vars};
+use ::rhai::{
+    packages::{Package, StandardPackage},
+    serde::{from_dynamic, to_dynamic},
+    Dynamic, Engine, EvalAltResult, Scope, AST,
+};
+use moka::sync::Cache;
+use serde_json::Value as JsonValue;
+use std::{
+    cell::Cell,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// the max compiled scripts kept in the cache
+const SCRIPT_CACHE_CAP: u64 = 1024;
+
+/// the max engines kept in the cache, the engine is built once for each memory and stack limits
+const ENGINE_CACHE_CAP: u64 = 64;
+
+/// the size of a rhai value, which is used to count the array and map sizes by the memory limit
+const VALUE_SIZE: usize = 16;
+
+/// the rhai script engine, which has no io and runs deterministically
+///
+/// the scripts access the vars by the functions:
+/// `get(name)`, `set(name, value)`, `env(name)`, `set_env(name, value)`, `inputs()` and `expose(key, value)`
+pub struct Rhai {
+    std: StandardPackage,
+    /// the compiled ast by the script source
+    scripts: Cache<String, Arc<AST>>,
+    /// the built engines by the memory and stack limits
+    engines: Cache<(usize, usize), Arc<Engine>>,
+}
+
+thread_local! {
+    /// the deadline of the eval on the current thread, which is checked by the engine progress
+    static DEADLINE: Cell<Option<Instant>> = const { Cell::new(None) };
+}
+
+impl Default for Rhai {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Rhai {
+    pub fn new() -> Self {
+        Self {
+            std: StandardPackage::new(),
+            scripts: Cache::new(SCRIPT_CACHE_CAP),
+            engines: Cache::new(ENGINE_CACHE_CAP),
+        }
+    }
+
+    #[cfg(test)]
+    pub fn engines_count(&self) -> u64 {
+        self.engines.run_pending_tasks();
+        self.engines.entry_count()
+    }
+
+    /// checks the script syntax by compiling it without running
+    pub fn check_syntax(script: &str) -> Result<()> {
+        Engine::new_raw()
+            .compile(script)
+            .map(|_| ())
+            .map_err(|err| ActError::Script(err.to_string()))
+    }
+
+    fn ast(&self, script: &str) -> Result<Arc<AST>> {
+        if let Some(ast) = self.scripts.get(script) {
+            return Ok(ast);
+        }
+        let ast = Arc::new(
+            self.engine(&Limits::default())
+                .compile(script)
+                .map_err(|err| ActError::Script(err.to_string()))?,
+        );
+        self.scripts.insert(script.to_string(), ast.clone());
+        Ok(ast)
+    }
+
+    /// gets the engine by the memory and stack limits, the timeout is checked by the eval deadline
+    fn engine(&self, limits: &Limits) -> Arc<Engine> {
+        let memory = limits.memory.unwrap_or_default();
+        let stack = limits.stack.unwrap_or_default();
+        self.engines
+            .get_with((memory, stack), || Arc::new(self.build(memory, stack)))
+    }
+
+    /// creates the engine with the std package, the act functions and the limits,
+    /// the limit is not set when it is 0
+    fn build(&self, memory: usize, stack: usize) -> Engine {
+        let mut engine = Engine::new_raw();
+        engine.register_global_module(self.std.as_shared_module());
+        engine
+            .on_print(|text| Context::console("log", text))
+            .on_debug(|text, _, _| Context::console("debug", text));
+
+        engine
+            .register_fn("get", |name: &str| -> Dynamic {
+                Context::current()
+                    .ok()
+                    .and_then(|ctx| ctx.task().find::<JsonValue>(name))
+                    .and_then(|v| to_dynamic(v).ok())
+                    .unwrap_or_default()
+            })
+            .register_fn("set", |name: &str, value: Dynamic| {
+                if let (Ok(ctx), Ok(value)) =
+                    (Context::current(), from_dynamic::<JsonValue>(&value))
+                {
+                    ctx.task().update_data(&Vars::new().with(name, value));
+                }
+            })
+            .register_fn("env", |name: &str| -> Dynamic {
+                Context::current()
+                    .ok()
+                    .and_then(|ctx| ctx.get_env::<JsonValue>(name))
+                    .and_then(|v| to_dynamic(v).ok())
+                    .unwrap_or_default()
+            })
+            .register_fn("set_env", |name: &str, value: Dynamic| {
+                if let (Ok(ctx), Ok(value)) =
+                    (Context::current(), from_dynamic::<JsonValue>(&value))
+                {
+                    ctx.set_env(name, value);
+                }
+            })
+            .register_fn("inputs", || -> Dynamic {
+                Context::current()
+                    .ok()
+                    .and_then(|ctx| to_dynamic(ctx.task().inputs()).ok())
+                    .unwrap_or_default()
+            })
+            .register_fn("expose", |key: &str, value: Dynamic| {
+                if let (Ok(ctx), Ok(value)) =
+                    (Context::current(), from_dynamic::<JsonValue>(&value))
+                {
+                    ctx.task().set_data_with(|data| {
+                        let outputs = data.get::<Vars>(consts::ACT_OUTPUTS).unwrap_or_default();
+                        data.set(consts::ACT_OUTPUTS, outputs.with(key, &value));
+                    });
+                }
+            });
+
+        if memory > 0 {
+            let size = (memory / VALUE_SIZE).max(1);
+            engine
+                .set_max_string_size(memory)
+                .set_max_array_size(size)
+                .set_max_map_size(size);
+        }
+        if stack > 0 {
+            // each call level is counted as 1kb stack
+            engine.set_max_call_levels((stack / 1024).max(1));
+        }
+        engine.on_progress(|_| match DEADLINE.get() {
+            Some(deadline) if Instant::now() >= deadline => Some(Dynamic::UNIT),
+            _ => None,
+        });
+        engine
+    }
+
+    /// runs the eval with the deadline of the timeout limit,
+    /// the deadline of the outer eval is restored for the nested eval
+    fn with_deadline<T>(limits: &Limits, f: impl FnOnce() -> T) -> T {
+        let deadline = limits
+            .timeout
+            .filter(|millis| *millis > 0)
+            .map(|millis| Instant::now() + Duration::from_millis(millis));
+        let prev = DEADLINE.replace(deadline);
+        let ret = f();
+        DEADLINE.set(prev);
+        ret
+    }
+
+    fn error(err: EvalAltResult, limits: &Limits) -> ActError {
+        match err {
+            EvalAltResult::ErrorTerminated(..) => ActError::Limit(format!(
+                "the script exceeds the time limit {}ms",
+                limits.timeout.unwrap_or_default()
+            )),
+            EvalAltResult::ErrorDataTooLarge(..) => ActError::Limit(format!(
+                "the script exceeds the memory limit {} bytes",
+                limits.memory.unwrap_or_default()
+            )),
+            EvalAltResult::ErrorStackOverflow(..) => ActError::Limit(format!(
+                "the script exceeds the stack limit {} bytes",
+                limits.stack.unwrap_or_default()
+            )),
+            EvalAltResult::ErrorParsing(..) => ActError::Script(err.to_string()),
+            EvalAltResult::ErrorRuntime(value, _) => ActError::Exception {
+                ecode: "".to_string(),
+                message: value.to_string(),
+            },
+            err => ActError::Exception {
+                ecode: "".to_string(),
+                message: err.to_string(),
+            },
+        }
+    }
+}
+
+impl ScriptEngine for Rhai {
+    fn lang(&self) -> &str {
+        "rhai"
+    }
+
+    fn compile(&self, script: &str) -> Result<()> {
+        self.ast(script).map(|_| ())
+    }
+
+    fn eval(&self, script: &str, limits: &Limits) -> Result<JsonValue> {
+        self.eval_with_vars(script, &Vars::new(), limits)
+    }
+
+    fn eval_with_vars(&self, script: &str, vars: &Vars, limits: &Limits) -> Result<JsonValue> {
+        let ast = self.ast(script)?;
+        let mut scope = Scope::new();
+        for (name, value) in vars.iter() {
+            let value = to_dynamic(value).map_err(|err| ActError::Script(err.to_string()))?;
+            scope.push_dynamic(name.as_str(), value);
+        }
+        let engine = self.engine(limits);
+        let value = Self::with_deadline(limits, || {
+            engine.eval_ast_with_scope::<Dynamic>(&mut scope, &ast)
+        })
+        .map_err(|err| Self::error(*err, limits))?;
+        if value.is_unit() {
+            return Ok(JsonValue::Null);
+        }
+        from_dynamic::<JsonValue>(&value).map_err(|err| ActError::Script(err.to_string()))
+    }
+}
@@ -0,0 +1,27 @@
+use crate::{ActError, Limits, Result, Vars};
+use serde_json::Value as JsonValue;
+
+/// the script engine to evaluate the `if` and `on` conditions, the `${}` expressions,
+/// the step `run` scripts and the packages, the model chooses the engine by `lang`
+pub trait ScriptEngine: Send + Sync {
+    /// the language name which is used by the model `lang`, such as `js` or `rhai`
+    fn lang(&self) -> &str;
+
+    /// compiles the script without running it, the syntax errors are returned
+    fn compile(&self, script: &str) -> Result<()>;
+
+    /// evaluates the script with the sandbox limits and returns the last value
+    fn eval(&self, script: &str, limits: &Limits) -> Result<JsonValue>;
+
+    /// evaluates the script with the vars which are bound as the variables by name,
+    /// the engine which does not support the variables only evaluates the script without vars
+    fn eval_with_vars(&self, script: &str, vars: &Vars, limits: &Limits) -> Result<JsonValue> {
+        if !vars.is_empty() {
+            return Err(ActError::Script(format!(
+                "the script lang '{}' does not support the variables",
+                self.lang()
+            )));
+        }
+        self.eval(script, limits)
+    }
+}
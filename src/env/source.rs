@@ -0,0 +1,120 @@
+/// the scanning state of the js source
+#[derive(Clone, Copy, PartialEq)]
+enum State {
+    Code,
+    LineComment,
+    BlockComment,
+    Str(char),
+    Template,
+}
+
+/// replaces the comments and the string literals with the spaces, so the keywords
+/// in them are not matched, the line breaks and the `${}` expressions of the template
+/// strings are kept, and the byte offsets are the same as the source
+pub fn code_only(source: &str) -> String {
+    let mut ret = String::with_capacity(source.len());
+    let mut state = State::Code;
+    // the brace depth of each nested template expression
+    let mut templates: Vec<usize> = Vec::new();
+    let mut chars = source.chars().peekable();
+    while let Some(c) = chars.next() {
+        match state {
+            State::Code => match c {
+                '/' if chars.peek() == Some(&'/') => {
+                    chars.next();
+                    ret.push_str("  ");
+                    state = State::LineComment;
+                }
+                '/' if chars.peek() == Some(&'*') => {
+                    chars.next();
+                    ret.push_str("  ");
+                    state = State::BlockComment;
+                }
+                '\'' | '"' => {
+                    ret.push(c);
+                    state = State::Str(c);
+                }
+                '`' => {
+                    ret.push(c);
+                    state = State::Template;
+                }
+                '{' => {
+                    if let Some(depth) = templates.last_mut() {
+                        *depth += 1;
+                    }
+                    ret.push(c);
+                }
+                '}' => {
+                    match templates.last_mut() {
+                        Some(0) => {
+                            templates.pop();
+                            state = State::Template;
+                        }
+                        Some(depth) => *depth -= 1,
+                        None => {}
+                    }
+                    ret.push(c);
+                }
+                _ => ret.push(c),
+            },
+            State::LineComment => {
+                if c == '\n' {
+                    ret.push(c);
+                    state = State::Code;
+                } else {
+                    blank(&mut ret, c);
+                }
+            }
+            State::BlockComment => {
+                if c == '*' && chars.peek() == Some(&'/') {
+                    chars.next();
+                    ret.push_str("  ");
+                    state = State::Code;
+                } else {
+                    blank(&mut ret, c);
+                }
+            }
+            State::Str(quote) => {
+                if c == '\\' {
+                    ret.push(' ');
+                    if let Some(next) = chars.next() {
+                        blank(&mut ret, next);
+                    }
+                } else if c == quote || c == '\n' {
+                    ret.push(c);
+                    state = State::Code;
+                } else {
+                    blank(&mut ret, c);
+                }
+            }
+            State::Template => {
+                if c == '\\' {
+                    ret.push(' ');
+                    if let Some(next) = chars.next() {
+                        blank(&mut ret, next);
+                    }
+                } else if c == '`' {
+                    ret.push(c);
+                    state = State::Code;
+                } else if c == '$' && chars.peek() == Some(&'{') {
+                    chars.next();
+                    ret.push_str("${");
+                    templates.push(0);
+                    state = State::Code;
+                } else {
+                    blank(&mut ret, c);
+                }
+            }
+        }
+    }
+    ret
+}
+
+/// pushes the spaces with the same bytes of the char, the line break is kept
+fn blank(ret: &mut String, c: char) {
+    if c == '\n' {
+        ret.push(c);
+    } else {
+        ret.extend(std::iter::repeat_n(' ', c.len_utf8()));
+    }
+}
@@ -1,6 +1,9 @@
 use crate::{
-    env::Enviroment, Act, ActError, Context, Engine, Event, Message, Signal, Vars, Workflow,
+    env::{value::ActValue, Enviroment, JsEval, Wake},
+    Act, ActError, ActModule, AsyncFn, Context, Engine, Event, Limits, Message, Signal, Vars,
+    Workflow,
 };
+use proptest::prelude::*;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 
@@ -551,3 +554,1173 @@ async fn run_test_result<T: Clone + Send + 'static + Default>(
     context.eval::<()>(script)?;
     Ok(sig2.recv().await)
 }
+
+#[test]
+fn env_compile_ok() {
+    let env = Enviroment::new();
+    let code = env.compile("let a = 1; a + 1").unwrap();
+    assert!(!code.is_empty());
+    assert!(env.is_compiled("let a = 1; a + 1"));
+}
+
+#[test]
+fn env_compile_syntax_error() {
+    let env = Enviroment::new();
+    let result = env.compile("let a = ;");
+    assert!(matches!(result, Err(ActError::Script(_))));
+    assert!(!env.is_compiled("let a = ;"));
+}
+
+#[test]
+fn env_compile_not_run() {
+    let env = Enviroment::new();
+    env.compile(r#"throw new Error("should not run")"#).unwrap();
+}
+
+#[test]
+fn env_eval_cache_bytecode() {
+    #[derive(Clone)]
+    struct TestModule;
+    impl ActModule for TestModule {
+        fn init(&self, ctx: &rquickjs::Ctx<'_>) -> crate::Result<()> {
+            ctx.globals().set("test_value", 10)?;
+            Ok(())
+        }
+    }
+
+    let env = Enviroment::new();
+    assert!(!env.is_compiled("1 + 1"));
+    assert_eq!(env.eval::<i64>("1 + 1").unwrap(), 2);
+    assert!(env.is_compiled("1 + 1"));
+
+    // the cached bytecode can be run by another runtime
+    env.compile("let a = 5; a * test_value").unwrap();
+    env.register_module(&TestModule);
+    assert_eq!(env.eval::<i64>("let a = 5; a * test_value").unwrap(), 50);
+}
+
+#[test]
+fn env_check_syntax() {
+    assert!(Enviroment::check_syntax("let a = 1; a + 1").is_ok());
+    assert!(Enviroment::check_syntax("return 5").is_ok());
+    assert!(Enviroment::check_syntax("let a = ;").is_err());
+}
+
+#[test]
+fn env_eval_reuse_runtime() {
+    let env = Enviroment::new();
+    assert_eq!(env.pool_count(), 0);
+    for _ in 0..5 {
+        assert_eq!(env.eval::<i64>("1 + 1").unwrap(), 2);
+    }
+    assert_eq!(env.pool_count(), 1);
+}
+
+#[test]
+fn env_eval_let_isolation() {
+    let env = Enviroment::new();
+    let script = r#"
+    let v = 5;
+    const c = 1;
+    v + c
+    "#;
+    assert_eq!(env.eval::<i64>(script).unwrap(), 6);
+    assert_eq!(env.eval::<i64>(script).unwrap(), 6);
+}
+
+#[test]
+fn env_eval_global_isolation() {
+    let env = Enviroment::new();
+    assert_eq!(
+        env.eval::<i64>("globalThis.a = 1; var b = 2; a + b")
+            .unwrap(),
+        3
+    );
+    assert_eq!(
+        env.eval::<String>("typeof a + typeof b").unwrap(),
+        "undefinedundefined"
+    );
+}
+
+#[test]
+fn env_eval_prototype_isolation() {
+    let env = Enviroment::new();
+    for script in [
+        "Array.prototype.map = () => 1",
+        "Object.prototype.leak = 1",
+        "JSON.parse = () => 2",
+        "delete Array.prototype.filter",
+    ] {
+        assert!(env.eval::<()>(script).is_err());
+    }
+    assert_eq!(env.pool_count(), 1);
+    assert_eq!(env.eval::<Vec<i64>>("[1].map(x => x + 1)").unwrap(), [2]);
+    assert_eq!(env.eval::<String>("typeof ({}).leak").unwrap(), "undefined");
+    assert_eq!(env.eval::<i64>(r#"JSON.parse("3")"#).unwrap(), 3);
+    assert_eq!(
+        env.eval::<String>("typeof Array.prototype.filter").unwrap(),
+        "function"
+    );
+}
+
+#[test]
+fn env_eval_prototype_override() {
+    let env = Enviroment::new();
+    let script = r#"
+    class MyError extends Error {
+        constructor(message) {
+            super(message);
+            this.name = "MyError";
+        }
+    }
+    const obj = {};
+    obj.toString = () => "obj";
+    `${new MyError("err1")} ${obj}`
+    "#;
+    assert_eq!(env.eval::<String>(script).unwrap(), "MyError: err1 obj");
+    assert_eq!(
+        env.eval::<String>("String(new Error('err2'))").unwrap(),
+        "Error: err2"
+    );
+}
+
+#[test]
+fn env_eval_with_vars() {
+    let env = Enviroment::new();
+    let expr = "value > 0";
+    let vars = Vars::new().with("value", 10);
+    assert!(env
+        .eval_with_vars::<bool>("js", expr, &vars, &Limits::default())
+        .unwrap());
+    let vars = Vars::new().with("value", -1);
+    assert!(!env
+        .eval_with_vars::<bool>("js", expr, &vars, &Limits::default())
+        .unwrap());
+    // the bytecode is shared and the vars do not leak to the next eval
+    assert!(env.is_compiled(expr));
+    assert_eq!(env.eval::<String>("typeof value").unwrap(), "undefined");
+}
+
+#[test]
+fn env_eval_global_isolation_after_error() {
+    let env = Enviroment::new();
+    assert!(env
+        .eval::<()>("globalThis.a = 1; throw new Error('err1')")
+        .is_err());
+    assert_eq!(env.eval::<String>("typeof a").unwrap(), "undefined");
+}
+
+#[test]
+fn env_eval_modules_after_reuse() {
+    let env = Enviroment::new();
+    env.eval::<()>("act = undefined").unwrap();
+    assert_eq!(env.eval::<String>("typeof act").unwrap(), "object");
+    assert_eq!(
+        env.eval::<Vec<String>>(r#"["a", "b"].union(["c"])"#)
+            .unwrap(),
+        ["a", "b", "c"]
+    );
+}
+
+#[test]
+fn env_eval_register_module_after_eval() {
+    #[derive(Clone)]
+    struct TestModule;
+    impl ActModule for TestModule {
+        fn init(&self, ctx: &rquickjs::Ctx<'_>) -> crate::Result<()> {
+            ctx.globals().set("test_value", 10)?;
+            Ok(())
+        }
+    }
+
+    let env = Enviroment::new();
+    assert_eq!(
+        env.eval::<String>("typeof test_value").unwrap(),
+        "undefined"
+    );
+    env.register_module(&TestModule);
+    assert_eq!(env.eval::<i64>("test_value").unwrap(), 10);
+}
+
+#[test]
+fn env_eval_timeout_limit() {
+    let env = Enviroment::new();
+    let limits = Limits::new().with_timeout(100);
+    let result = env.eval_with_limits::<()>("while(true) {}", &limits);
+    assert_eq!(
+        result.unwrap_err(),
+        ActError::Limit("the script exceeds the time limit 100ms".to_string())
+    );
+}
+
+#[test]
+fn env_eval_memory_limit() {
+    let env = Enviroment::new();
+    let limits = Limits::new()
+        .with_memory(10 * 1024 * 1024)
+        .with_timeout(5000);
+    let script = r#"
+    let list = [];
+    while(true) {
+        list.push(new Array(10000).fill(1));
+    }
+    "#;
+    let result = env.eval_with_limits::<()>(script, &limits);
+    assert!(matches!(result, Err(ActError::Limit(message)) if message.contains("memory")));
+}
+
+#[test]
+fn env_eval_stack_limit() {
+    let env = Enviroment::new();
+    let limits = Limits::new().with_stack(64 * 1024);
+    let script = r#"
+    function f(i) { return f(i + 1) + 1; }
+    f(0)
+    "#;
+    let result = env.eval_with_limits::<()>(script, &limits);
+    assert!(matches!(result, Err(ActError::Limit(message)) if message.contains("stack")));
+}
+
+#[test]
+fn env_eval_after_limit() {
+    let env = Enviroment::new();
+    let limits = Limits::new().with_timeout(50);
+    assert!(env
+        .eval_with_limits::<()>("while(true) {}", &limits)
+        .is_err());
+    assert_eq!(env.eval::<i64>("1 + 1").unwrap(), 2);
+    assert_eq!(env.pool_count(), 1);
+}
+
+#[test]
+fn env_module_is_module() {
+    assert!(crate::env::is_module("import { a } from '@pack/a';"));
+    assert!(crate::env::is_module("import * as a from \"@pack/a\";"));
+    assert!(crate::env::is_module("let a = 1;\nexport const b = a;"));
+    assert!(!crate::env::is_module("act.msg({ key: 'import' });"));
+    assert!(!crate::env::is_module("import('@pack/a').then(() => {});"));
+}
+
+#[test]
+fn env_module_imports() {
+    let source = r#"
+    import { a } from '@pack/a';
+    import * as b from "@pack/b";
+    import '@pack/c';
+    export { d } from '@pack/d';
+    import { e } from '@acts/act';
+    import { a as a2 } from '@pack/a';
+    "#;
+    assert_eq!(super::loader::imports(source), ["a", "b", "c", "d"]);
+}
+
+#[test]
+fn env_module_not_in_comment_or_string() {
+    assert!(!crate::env::is_module(
+        "// import { a } from '@pack/a';\nlet a = 1;"
+    ));
+    assert!(!crate::env::is_module("/*\nexport const a = 1;\n*/"));
+    assert!(!crate::env::is_module(
+        "let s = `\nimport { a } from '@pack/a';\n`;"
+    ));
+
+    let source = r#"
+    // import { x } from '@pack/x';
+    /* import { y } from '@pack/y'; */
+    const s = "import { z } from '@pack/z'";
+    import { a } from '@pack/a'; // 中文
+    "#;
+    assert_eq!(super::loader::imports(source), ["a"]);
+}
+
+#[test]
+fn env_module_reuse_runtime() {
+    let env = Enviroment::new();
+    let source = "globalThis.leak = 1; export const a = 1;";
+    for _ in 0..5 {
+        env.eval_module("@pack/a", source, &Limits::default())
+            .unwrap();
+    }
+    assert!(env.is_module_compiled("@pack/a", source));
+    assert_eq!(env.pool_count(), 1);
+    assert_eq!(env.eval::<String>("typeof leak").unwrap(), "undefined");
+
+    env.reset_modules();
+    assert_eq!(env.pool_count(), 0);
+    env.eval_module("@pack/a", source, &Limits::default())
+        .unwrap();
+    assert_eq!(env.pool_count(), 1);
+}
+
+#[test]
+fn env_module_error() {
+    let env = Enviroment::new();
+    let result = env.eval_module(
+        "@pack/a",
+        "throw new Error('module error');",
+        &Limits::default(),
+    );
+    assert!(
+        matches!(result, Err(ActError::Exception { message, .. }) if message == "module error")
+    );
+    assert_eq!(env.pool_count(), 1);
+}
+
+#[test]
+fn env_load_code() {
+    let env = Enviroment::new();
+    let code = env.compile_code("js", "1 + 2").unwrap();
+    assert!(!code.is_empty());
+
+    // the bytecode kept with the source is loaded by the other env without compiling
+    let env = Enviroment::new();
+    env.load_code("js", "1 + 2", &code);
+    assert!(env.is_compiled("1 + 2"));
+    assert_eq!(env.eval::<i32>("1 + 2").unwrap(), 3);
+}
+
+#[test]
+fn env_load_module_code() {
+    let source = "globalThis.loaded = 1; export const a = 1;";
+    let code = Enviroment::compile_module("@pack/a", source).unwrap();
+
+    let env = Enviroment::new();
+    env.load_module("@pack/a", source, &code);
+    assert!(env.is_module_compiled("@pack/a", source));
+    env.eval_module("@pack/a", source, &Limits::default())
+        .unwrap();
+}
+
+#[test]
+fn env_encode_decode_codes() {
+    let codes = vec![
+        ("1 + 2".to_string(), vec![1, 2, 3]),
+        ("let a = 1;".to_string(), vec![]),
+    ];
+    let data = crate::env::encode_codes(&codes);
+    assert_eq!(crate::env::decode_codes(&data), codes);
+    assert!(crate::env::decode_codes(&data[..data.len() - 1]).is_empty());
+    assert!(crate::env::decode_codes(&[]).is_empty());
+}
+
+#[test]
+fn env_module_check_imports() {
+    use crate::{data::Package, Manifest};
+    let package = |id: &str, source: &str| Package {
+        id: id.to_string(),
+        data: source.as_bytes().to_vec(),
+        ..Default::default()
+    };
+    let find = |id: &str, _req: Option<&str>| match id {
+        "a" => Ok(package("a", "import { b } from '@pack/b';")),
+        "b" => Ok(package("b", "export const b = 1;")),
+        "c" => Ok(package("c", "import { d } from '@pack/d';")),
+        "d" => Ok(package("d", "import { c } from '@pack/c';")),
+        _ => Err(ActError::Store(format!("cannot find package '{id}'"))),
+    };
+    let main = |source: &str| package("main", source);
+    assert!(crate::env::check_imports(
+        &main("import { a } from '@pack/a';"),
+        "import { a } from '@pack/a';",
+        &find
+    )
+    .is_ok());
+    let source = "import { x } from '@pack/x';";
+    assert_eq!(
+        crate::env::check_imports(&main(source), source, &find),
+        Err(ActError::Script(
+            "cannot find module '@pack/x' imported by 'main'".to_string()
+        ))
+    );
+    let source = "import { c } from '@pack/c';";
+    assert_eq!(
+        crate::env::check_imports(&main(source), source, &find),
+        Err(ActError::Script(
+            "the module '@pack/c' is imported in cycle: @pack/main -> @pack/c -> @pack/d -> @pack/c"
+                .to_string()
+        ))
+    );
+
+    // the imports are found by the dependencies requirement
+    let find = |id: &str, req: Option<&str>| match (id, req) {
+        ("a", Some("^1.0")) => Ok(package("a", "export const a = 1;")),
+        _ => Err(ActError::Store(format!("cannot find package '{id}'"))),
+    };
+    let source = "import { a } from '@pack/a';";
+    let pack = Package {
+        manifest: Manifest::new().with_dependency("a", "^1.0"),
+        ..main(source)
+    };
+    assert!(crate::env::check_imports(&pack, source, &find).is_ok());
+    assert!(crate::env::check_imports(&main(source), source, &find).is_err());
+}
+
+#[test]
+fn env_module_name() {
+    use crate::{data::Package, Manifest};
+    let mut pack = Package {
+        id: "utils".to_string(),
+        ..Default::default()
+    };
+    assert_eq!(crate::env::module_name(&pack), "@pack/utils");
+    pack.manifest = Manifest::new().with_version("1.2.0");
+    assert_eq!(crate::env::module_name(&pack), "@pack/utils@1.2.0");
+}
+
+#[test]
+fn env_module_check_syntax() {
+    assert!(Enviroment::check_module(
+        "@pack/a",
+        "import { b } from '@pack/b'; export const a = b;"
+    )
+    .is_ok());
+    assert!(
+        Enviroment::check_module("@pack/a", "import { b } from '@pack/b'; export const a = ;")
+            .is_err()
+    );
+}
+
+#[derive(Clone)]
+struct AsyncModule;
+impl ActModule for AsyncModule {
+    fn init(&self, ctx: &rquickjs::Ctx<'_>) -> crate::Result<()> {
+        let lookup = AsyncFn::new(|args| async move {
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            match args.first().and_then(|v| v.as_str()) {
+                Some("err") => Err(ActError::Action("lookup error".to_string())),
+                Some(key) => Ok(json!({ "key": key, "value": 10 })),
+                None => Ok(json!(null)),
+            }
+        });
+        ctx.globals().set("lookup", lookup)?;
+
+        let slow = AsyncFn::new(|_| async move {
+            tokio::time::sleep(std::time::Duration::from_millis(2000)).await;
+            Ok(json!(1))
+        });
+        ctx.globals().set("slow", slow)?;
+        Ok(())
+    }
+}
+
+#[test]
+fn env_async_await() {
+    let env = Enviroment::new();
+    env.register_module(&AsyncModule);
+    let script = r#"
+    let ret = await lookup("a");
+    ret.value + 1
+    "#;
+    assert_eq!(env.eval::<i64>(script).unwrap(), 11);
+}
+
+#[test]
+fn env_async_await_multiple() {
+    let env = Enviroment::new();
+    env.register_module(&AsyncModule);
+    let script = r#"
+    let [a, b] = await Promise.all([lookup("a"), lookup("b")]);
+    a.key + b.key
+    "#;
+    assert_eq!(env.eval::<String>(script).unwrap(), "ab");
+}
+
+#[test]
+fn env_async_return_promise() {
+    let env = Enviroment::new();
+    env.register_module(&AsyncModule);
+    let ret = env
+        .eval::<serde_json::Value>(r#"lookup("a").then((v) => v.key)"#)
+        .unwrap();
+    assert_eq!(ret, json!("a"));
+}
+
+#[test]
+fn env_async_error() {
+    let env = Enviroment::new();
+    env.register_module(&AsyncModule);
+    let result = env.eval::<()>(r#"await lookup("err")"#);
+    assert!(
+        matches!(result, Err(ActError::Exception { message, .. }) if message.contains("lookup error"))
+    );
+
+    let script = r#"
+    let ret = "";
+    try {
+        await lookup("err");
+    } catch (e) {
+        ret = "catch";
+    }
+    ret
+    "#;
+    assert_eq!(env.eval::<String>(script).unwrap(), "catch");
+}
+
+#[test]
+fn env_async_timeout_limit() {
+    let env = Enviroment::new();
+    env.register_module(&AsyncModule);
+    let limits = Limits::new().with_timeout(100);
+    let result = env.eval_with_limits::<()>("await slow()", &limits);
+    assert_eq!(
+        result.unwrap_err(),
+        ActError::Limit("the script exceeds the time limit 100ms".to_string())
+    );
+
+    // the runtime is reused after the timeout
+    assert_eq!(env.eval::<i64>(r#"(await lookup("a")).value"#).unwrap(), 10);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn env_async_multi_thread() {
+    let env = Enviroment::new();
+    env.register_module(&AsyncModule);
+    assert_eq!(env.eval::<i64>(r#"(await lookup("a")).value"#).unwrap(), 10);
+}
+
+/// runs the script by `start` and resumes it after it is woken until it is settled,
+/// returns the value and the suspended times
+fn run_suspended(
+    env: &Enviroment,
+    script: &str,
+    limits: &Limits,
+) -> crate::Result<(serde_json::Value, usize)> {
+    let (tx, rx) = std::sync::mpsc::channel();
+    let wake = |tx: &std::sync::mpsc::Sender<()>| -> Wake {
+        let tx = tx.clone();
+        Box::new(move || {
+            let _ = tx.send(());
+        })
+    };
+    let mut ret = env.start("js", script, limits, wake(&tx))?;
+    let mut count = 0;
+    loop {
+        match ret {
+            JsEval::Ready(value) => return Ok((value, count)),
+            JsEval::Suspended(suspended) => {
+                count += 1;
+                rx.recv_timeout(std::time::Duration::from_secs(5)).unwrap();
+                ret = env.resume(suspended, wake(&tx))?;
+            }
+        }
+    }
+}
+
+#[test]
+fn env_async_suspend() {
+    let env = Enviroment::new();
+    env.register_module(&AsyncModule);
+    let script = r#"
+    let ret = await lookup("a");
+    ret.value + 1
+    "#;
+    let (value, count) = run_suspended(&env, script, &Limits::default()).unwrap();
+    assert_eq!(value, json!(11));
+    assert!(count > 0);
+    // the runtime is released to the pool after the eval is settled
+    assert_eq!(env.pool_count(), 1);
+
+    let script = r#"
+    let [a, b] = await Promise.all([lookup("a"), lookup("b")]);
+    a.key + b.key
+    "#;
+    let (value, _) = run_suspended(&env, script, &Limits::default()).unwrap();
+    assert_eq!(value, json!("ab"));
+    assert_eq!(env.pool_count(), 1);
+}
+
+#[test]
+fn env_async_suspend_error() {
+    let env = Enviroment::new();
+    env.register_module(&AsyncModule);
+    let result = run_suspended(&env, r#"await lookup("err")"#, &Limits::default());
+    assert!(
+        matches!(result, Err(ActError::Exception { message, .. }) if message.contains("lookup error"))
+    );
+    assert_eq!(env.eval::<String>("typeof ret").unwrap(), "undefined");
+}
+
+#[test]
+fn env_async_suspend_timeout_limit() {
+    let env = Enviroment::new();
+    env.register_module(&AsyncModule);
+    let limits = Limits::new().with_timeout(100);
+    let result = run_suspended(&env, "await slow()", &limits);
+    assert_eq!(
+        result.unwrap_err(),
+        ActError::Limit("the script exceeds the time limit 100ms".to_string())
+    );
+    assert_eq!(env.eval::<i64>(r#"(await lookup("a")).value"#).unwrap(), 10);
+}
+
+#[test]
+fn env_async_check_syntax() {
+    assert!(Enviroment::check_syntax(r#"let a = await lookup("a"); a"#).is_ok());
+    assert!(Enviroment::check_syntax("await;").is_err());
+}
+
+#[test]
+fn env_async_is_async() {
+    use super::host::is_async;
+    assert!(is_async(r#"let a = await lookup("a");"#));
+    assert!(is_async("let a = `${await lookup('a')}`;"));
+    assert!(!is_async(r#"let a = "await";"#));
+    assert!(!is_async("let a = 'it\\'s await';"));
+    assert!(!is_async("// await lookup()\nlet a = 1;"));
+    assert!(!is_async("/* await\n lookup() */ let a = 1;"));
+    assert!(!is_async("let a = `await ${1 + 1}`;"));
+    assert!(!is_async("let awaits = 1;"));
+}
+
+#[test]
+fn env_async_not_await_in_string() {
+    let env = Enviroment::new();
+    // the script is not compiled as an async script, so the last value is returned
+    assert_eq!(
+        env.eval::<String>(r#"let a = "await"; a + 1"#).unwrap(),
+        "await1"
+    );
+}
+
+#[test]
+fn env_lang_default() {
+    let env = Enviroment::new();
+    assert_eq!(
+        env.eval_with::<i64>("", "1 + 1", &Limits::default())
+            .unwrap(),
+        2
+    );
+    assert_eq!(
+        env.eval_with::<i64>("js", "1 + 1", &Limits::default())
+            .unwrap(),
+        2
+    );
+}
+
+#[test]
+fn env_lang_not_supported() {
+    let env = Enviroment::new();
+    let result = env.eval_with::<i64>("python", "1 + 1", &Limits::default());
+    assert_eq!(
+        result.unwrap_err(),
+        ActError::Script("the script lang 'python' is not supported".to_string())
+    );
+}
+
+#[test]
+fn env_lang_register_engine() {
+    struct EchoEngine;
+    impl crate::ScriptEngine for EchoEngine {
+        fn lang(&self) -> &str {
+            "echo"
+        }
+
+        fn compile(&self, _script: &str) -> crate::Result<()> {
+            Ok(())
+        }
+
+        fn eval(&self, script: &str, _limits: &Limits) -> crate::Result<serde_json::Value> {
+            Ok(json!(script))
+        }
+    }
+
+    let env = Enviroment::new();
+    env.register_engine(EchoEngine);
+    assert_eq!(
+        env.eval_with::<String>("echo", "abc", &Limits::default())
+            .unwrap(),
+        "abc"
+    );
+}
+
+#[cfg(feature = "rhai")]
+#[test]
+fn env_rhai_eval() {
+    let env = Enviroment::new();
+    let script = r#"
+    let a = 5;
+    let b = [1, 2, 3];
+    #{ sum: a + b.len(), name: "abc" }
+    "#;
+    let ret = env
+        .eval_with::<serde_json::Value>("rhai", script, &Limits::default())
+        .unwrap();
+    assert_eq!(ret, json!({ "sum": 8, "name": "abc" }));
+}
+
+#[cfg(feature = "rhai")]
+#[test]
+fn env_rhai_syntax_error() {
+    let env = Enviroment::new();
+    let result = env.compile_with("rhai", "let a = ;");
+    assert!(matches!(result, Err(ActError::Script(_))));
+    assert!(Enviroment::check_syntax_with("rhai", "let a = ;").is_err());
+    assert!(Enviroment::check_syntax_with("rhai", "let a = 1; a + 1").is_ok());
+}
+
+#[cfg(feature = "rhai")]
+#[test]
+fn env_rhai_throw_error() {
+    let env = Enviroment::new();
+    let result = env.eval_with::<()>("rhai", r#"throw "err1""#, &Limits::default());
+    assert_eq!(
+        result.unwrap_err(),
+        ActError::Exception {
+            ecode: "".to_string(),
+            message: "err1".to_string()
+        }
+    );
+}
+
+#[cfg(feature = "rhai")]
+#[test]
+fn env_rhai_timeout_limit() {
+    let env = Enviroment::new();
+    let limits = Limits::new().with_timeout(100);
+    let result = env.eval_with::<()>("rhai", "loop {}", &limits);
+    assert_eq!(
+        result.unwrap_err(),
+        ActError::Limit("the script exceeds the time limit 100ms".to_string())
+    );
+}
+
+#[cfg(feature = "rhai")]
+#[test]
+fn env_rhai_stack_limit() {
+    let env = Enviroment::new();
+    let limits = Limits::new().with_stack(8 * 1024);
+    let script = r#"
+    fn f(i) { f(i + 1) + 1 }
+    f(0)
+    "#;
+    let result = env.eval_with::<()>("rhai", script, &limits);
+    assert!(matches!(result, Err(ActError::Limit(message)) if message.contains("stack")));
+}
+
+#[cfg(feature = "rhai")]
+#[test]
+fn env_rhai_engine_reuse() {
+    use super::{rhai::Rhai, ScriptEngine};
+    let rhai = Rhai::new();
+    for timeout in [100, 200, 300] {
+        let limits = Limits::new().with_timeout(timeout);
+        assert_eq!(rhai.eval("1 + 1", &limits).unwrap(), json!(2));
+    }
+    assert_eq!(rhai.engines_count(), 1);
+
+    let limits = Limits::new().with_memory(1024 * 1024);
+    assert_eq!(rhai.eval("1 + 1", &limits).unwrap(), json!(2));
+    assert_eq!(rhai.engines_count(), 2);
+
+    // the timeout of the last eval does not affect the next one
+    let limits = Limits::new().with_timeout(50);
+    assert!(rhai.eval("loop {}", &limits).is_err());
+    assert_eq!(
+        rhai.eval(
+            "let a = 0; for i in 0..1000 { a += i; } a",
+            &Limits::default()
+        )
+        .unwrap(),
+        json!(499500)
+    );
+}
+
+#[test]
+fn env_value_safe_int() {
+    let env = Enviroment::new();
+    let result = env.eval::<i64>("2 ** 53 - 1").unwrap();
+    assert_eq!(result, 9007199254740991);
+}
+
+#[test]
+fn env_value_big_int() {
+    let env = Enviroment::new();
+    let result = env.eval::<u64>("2n ** 64n - 1n").unwrap();
+    assert_eq!(result, u64::MAX);
+
+    let result = env.eval::<serde_json::Value>("2n ** 64n").unwrap();
+    assert_eq!(result, json!("18446744073709551616"));
+}
+
+#[test]
+fn env_value_date() {
+    let env = Enviroment::new();
+    let result = env
+        .eval::<serde_json::Value>("({ time: new Date(Date.UTC(2024, 0, 2, 3, 4, 5)) })")
+        .unwrap();
+    assert_eq!(result, json!({ "time": "2024-01-02T03:04:05.000Z" }));
+
+    let result = env.eval::<serde_json::Value>("new Date(NaN)").unwrap();
+    assert_eq!(result, json!(null));
+}
+
+#[test]
+fn env_value_function() {
+    let env = Enviroment::new();
+    let result = env
+        .eval::<serde_json::Value>("({ a: 1, f: () => 1, s: Symbol('s'), b: [() => 1, 2] })")
+        .unwrap();
+    assert_eq!(result, json!({ "a": 1, "b": [null, 2] }));
+
+    let result = env.eval::<serde_json::Value>("() => 1").unwrap();
+    assert_eq!(result, json!(null));
+}
+
+#[test]
+fn env_value_non_finite() {
+    let env = Enviroment::new();
+    let result = env
+        .eval::<serde_json::Value>("[NaN, Infinity, -Infinity, 1.5, 4 / 2]")
+        .unwrap();
+    assert_eq!(result, json!([null, null, null, 1.5, 2]));
+}
+
+#[tokio::test]
+async fn env_value_task_big_int() {
+    let engine = Engine::new();
+    let sig = engine.signal(());
+    let s1 = sig.clone();
+
+    let env = engine.runtime().env().clone();
+    let workflow = Workflow::new()
+        .with_input("id", json!(1_u64 << 60))
+        .with_input("amount", json!(1_u64 << 40))
+        .with_step(|step| step.with_id("step1"));
+    let proc = engine.runtime().start(&workflow, &Vars::new()).unwrap();
+    engine.channel().on_complete(move |_| s1.close());
+    sig.recv().await;
+    let task = proc.root().unwrap();
+    let script = r#"
+    ({ id: $("id") + 1n, amount: $("amount") + 1, types: [typeof $("id"), typeof $("amount")] })
+    "#;
+
+    let context = task.create_context();
+    Context::scope(context, || {
+        let result = env.eval::<serde_json::Value>(script).unwrap();
+        assert_eq!(
+            result,
+            json!({
+                "id": (1_u64 << 60) + 1,
+                "amount": (1_u64 << 40) + 1,
+                "types": ["bigint", "number"]
+            })
+        );
+    });
+}
+
+/// the json values without the integer floats, which are converted to the json integers
+fn json_value() -> impl Strategy<Value = serde_json::Value> {
+    let leaf = prop_oneof![
+        Just(serde_json::Value::Null),
+        any::<bool>().prop_map(serde_json::Value::from),
+        any::<i64>().prop_map(serde_json::Value::from),
+        any::<u64>().prop_map(serde_json::Value::from),
+        any::<f64>()
+            .prop_filter("finite float", |v| v.is_finite() && v.fract() != 0.0)
+            .prop_map(serde_json::Value::from),
+        ".*".prop_map(serde_json::Value::from),
+    ];
+    leaf.prop_recursive(4, 64, 8, |inner| {
+        prop_oneof![
+            prop::collection::vec(inner.clone(), 0..8).prop_map(serde_json::Value::from),
+            prop::collection::btree_map(".*", inner, 0..8)
+                .prop_map(|map| serde_json::Value::Object(map.into_iter().collect())),
+        ]
+    })
+}
+
+fn round_trip(value: &serde_json::Value) -> serde_json::Value {
+    let runtime = rquickjs::Runtime::new().unwrap();
+    let ctx = rquickjs::Context::full(&runtime).unwrap();
+    ctx.with(|ctx| {
+        let identity: rquickjs::Function = ctx.eval("(v) => v").unwrap();
+        let result: ActValue = identity.call((ActValue::new(value.clone()),)).unwrap();
+        result.into()
+    })
+}
+
+#[test]
+fn env_value_cyclic_object() {
+    let env = Enviroment::new();
+    let err = env
+        .eval::<serde_json::Value>("const a = { b: 1 }; a.self = a; a")
+        .unwrap_err();
+    assert!(err.to_string().contains("cyclic object value"), "{err}");
+}
+
+#[test]
+fn env_value_cyclic_array() {
+    let env = Enviroment::new();
+    let err = env
+        .eval::<serde_json::Value>("const a = [1]; a.push({ items: a }); a")
+        .unwrap_err();
+    assert!(err.to_string().contains("cyclic object value"), "{err}");
+}
+
+#[test]
+fn env_value_shared_object() {
+    let env = Enviroment::new();
+    let result = env
+        .eval::<serde_json::Value>("const a = { b: 1 }; ({ x: a, y: [a, a] })")
+        .unwrap();
+    assert_eq!(
+        result,
+        json!({ "x": { "b": 1 }, "y": [{ "b": 1 }, { "b": 1 }] })
+    );
+}
+
+#[test]
+fn env_value_too_deep() {
+    let env = Enviroment::new();
+    let err = env
+        .eval::<serde_json::Value>("let a = []; for (let i = 0; i < 5000; i++) { a = [a]; } a")
+        .unwrap_err();
+    assert!(err.to_string().contains("nested too deeply"), "{err}");
+}
+
+#[test]
+fn env_value_proto_key() {
+    let value = json!({ "__proto__": { "a": 1 }, "b": [1.5, "c"] });
+    assert_eq!(round_trip(&value), value);
+}
+
+proptest! {
+    #[test]
+    fn env_value_round_trip(value in json_value()) {
+        prop_assert_eq!(round_trip(&value), value);
+    }
+
+    #[test]
+    fn env_value_int_round_trip(v in any::<i64>()) {
+        let value = json!({ "v": v, "items": [v] });
+        prop_assert_eq!(round_trip(&value), value);
+    }
+
+    #[test]
+    fn env_value_big_int_to_json(v in any::<i128>()) {
+        let env = Enviroment::new();
+        let result = env.eval::<serde_json::Value>(&format!(r#"[BigInt("{v}")]"#)).unwrap();
+        let expected = if let Ok(v) = i64::try_from(v) {
+            json!(v)
+        } else if let Ok(v) = u64::try_from(v) {
+            json!(v)
+        } else {
+            json!(v.to_string())
+        };
+        prop_assert_eq!(result, json!([expected]));
+    }
+}
+
+#[test]
+fn env_std_time() {
+    let env = Enviroment::new();
+    let script = r#"
+    const { add, diff, format, isBusinessDay } = await import("@acts/time");
+    const t = "2024-01-05T10:00:00Z";
+    ({
+        add: add(t, 2, "h"),
+        month: add("2024-01-31T00:00:00Z", 1, "M"),
+        bd: add(t, 1, "bd"),
+        holiday: add(t, 1, "bd", ["2024-01-08"]),
+        diff: diff(t, "2024-01-06T10:00:00Z", "h"),
+        diff_bd: diff(t, "2024-01-12T10:00:00Z", "bd"),
+        diff_month: diff("2024-01-31T00:00:00Z", "2024-03-30T00:00:00Z", "M"),
+        format: format(t, "%Y/%m/%d"),
+        weekend: isBusinessDay("2024-01-06"),
+    })
+    "#;
+    let result = env.eval::<serde_json::Value>(script).unwrap();
+    assert_eq!(
+        result,
+        json!({
+            "add": "2024-01-05T12:00:00.000Z",
+            "month": "2024-02-29T00:00:00.000Z",
+            "bd": "2024-01-08T10:00:00.000Z",
+            "holiday": "2024-01-09T10:00:00.000Z",
+            "diff": 24,
+            "diff_bd": 5,
+            "diff_month": 1,
+            "format": "2024/01/05",
+            "weekend": false,
+        })
+    );
+}
+
+#[test]
+fn env_std_time_now() {
+    let env = Enviroment::new();
+    let script = r#"
+    const { now, diff } = await import("@acts/time");
+    diff(now(), Date.now(), "s")
+    "#;
+    let result = env.eval::<i64>(script).unwrap();
+    assert!(result.abs() <= 1);
+}
+
+/// adds the business days by the days one by one
+fn add_business_days(date: chrono::NaiveDate, amount: i64, holidays: &[&str]) -> chrono::NaiveDate {
+    use chrono::{Datelike, Weekday};
+    let is_business = |d: chrono::NaiveDate| {
+        !matches!(d.weekday(), Weekday::Sat | Weekday::Sun)
+            && !holidays.contains(&d.format("%Y-%m-%d").to_string().as_str())
+    };
+    let mut ret = date;
+    let mut count = amount.abs();
+    while count > 0 {
+        ret += chrono::Duration::days(amount.signum());
+        if is_business(ret) {
+            count -= 1;
+        }
+    }
+    ret
+}
+
+#[test]
+fn env_std_time_business_days() {
+    let env = Enviroment::new();
+    let holidays = ["2024-01-10", "2024-01-15", "2024-01-16", "2024-01-20"];
+    let start = chrono::NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+    for offset in 0..21 {
+        let date = start + chrono::Duration::days(offset);
+        for amount in -12..=12 {
+            let script = format!(
+                r#"
+                const {{ add, diff }} = await import("@acts/time");
+                const to = add("{date}", {amount}, "bd", {holidays:?});
+                [to.slice(0, 10), diff("{date}", to, "bd", {holidays:?})]
+                "#
+            );
+            let result = env.eval::<(String, i64)>(&script).unwrap();
+            let expected = add_business_days(date, amount, &holidays);
+            assert_eq!(result.0, expected.to_string(), "{date} {amount}");
+
+            let from = date.format("%Y-%m-%d").to_string();
+            let is_business = !holidays.contains(&from.as_str())
+                && !matches!(
+                    chrono::Datelike::weekday(&date),
+                    chrono::Weekday::Sat | chrono::Weekday::Sun
+                );
+            if is_business || amount >= 0 {
+                assert_eq!(result.1, amount, "{date} {amount}");
+            }
+        }
+    }
+}
+
+#[test]
+fn env_std_time_business_days_large() {
+    let env = Enviroment::new();
+    let script = r#"
+    const { add, diff } = await import("@acts/time");
+    const to = add("2024-01-01", 1000000, "bd");
+    [to.slice(0, 10), diff("2024-01-01", to, "bd")]
+    "#;
+    let result = env.eval::<(String, i64)>(script).unwrap();
+    let date = chrono::NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+    let expected = add_business_days(date, 1000000, &[]);
+    assert_eq!(result, (expected.to_string(), 1000000));
+
+    let script = r#"
+    const { add } = await import("@acts/time");
+    add("2024-01-01", 9007199254740991, "bd")
+    "#;
+    let result = env.eval::<String>(script);
+    assert!(result.unwrap_err().to_string().contains("out of range"));
+}
+
+#[test]
+fn env_std_time_error() {
+    let env = Enviroment::new();
+    let script = r#"
+    const { add } = await import("@acts/time");
+    add("abc", 1, "d")
+    "#;
+    let result = env.eval::<String>(script);
+    assert!(result.unwrap_err().to_string().contains("not a time"));
+
+    let script = r#"
+    const { add } = await import("@acts/time");
+    add("2024-01-05", 1, "q")
+    "#;
+    let result = env.eval::<String>(script);
+    assert!(result
+        .unwrap_err()
+        .to_string()
+        .contains("the time unit 'q' is not supported"));
+}
+
+#[test]
+fn env_std_id() {
+    let env = Enviroment::new();
+    let script = r#"
+    const { nanoid, uuid } = await import("@acts/id");
+    [nanoid(), nanoid(8), uuid()]
+    "#;
+    let result = env.eval::<Vec<String>>(script).unwrap();
+    assert_eq!(result[0].len(), 21);
+    assert_eq!(result[1].len(), 8);
+    assert_eq!(result[2].len(), 36);
+    assert_eq!(&result[2][14..15], "4");
+}
+
+#[test]
+fn env_std_crypto() {
+    let env = Enviroment::new();
+    let script = r#"
+    const { sha256, hmac, base64Encode, base64Decode } = await import("@acts/crypto");
+    [sha256("abc"), hmac("key", "abc"), base64Encode("abc"), base64Decode("YWJj")]
+    "#;
+    let result = env.eval::<Vec<String>>(script).unwrap();
+    assert_eq!(
+        result,
+        [
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+            "9c196e32dc0175f86f4b1cb89289d6619de6bee699e4c378e68309ed97a1a6ab",
+            "YWJj",
+            "abc"
+        ]
+    );
+}
+
+#[test]
+fn env_std_json() {
+    let env = Enviroment::new();
+    let script = r#"
+    const { get, set, merge } = await import("@acts/json");
+    const obj = { a: { b: [{ c: 1 }] } };
+    ({
+        get: get(obj, "a.b[0].c"),
+        none: get(obj, "a.x.y"),
+        set: set(obj, "a.b[1].c", 2),
+        create: set({}, "x.y[1]", 3),
+        merge: merge({ a: { b: 1, c: [1] } }, { a: { c: [2], d: 3 } }),
+        origin: obj,
+    })
+    "#;
+    let result = env.eval::<serde_json::Value>(script).unwrap();
+    assert_eq!(
+        result,
+        json!({
+            "get": 1,
+            "none": null,
+            "set": { "a": { "b": [{ "c": 1 }, { "c": 2 }] } },
+            "create": { "x": { "y": [null, 3] } },
+            "merge": { "a": { "b": 1, "c": [2], "d": 3 } },
+            "origin": { "a": { "b": [{ "c": 1 }] } },
+        })
+    );
+}
+
+#[test]
+fn env_std_json_max_index() {
+    let env = Enviroment::new();
+    let script = r#"
+    const { set } = await import("@acts/json");
+    set({}, "a[4294967295]", 1)
+    "#;
+    let result = env.eval::<serde_json::Value>(script);
+    assert!(result
+        .unwrap_err()
+        .to_string()
+        .contains("the array index 4294967295 exceeds the max index 10000"));
+}
@@ -1,10 +1,17 @@
 use crate::{ActError, Result, Vars};
 use rquickjs::{
-    Array as JsArray, FromJs, IntoAtom, IntoJs, Object as JsObject, String as JsString,
-    Value as JsValue,
+    convert::Coerced, function::This, object::Property, Array as JsArray, BigInt, Exception,
+    FromJs, Function, IntoAtom, IntoJs, Object as JsObject, String as JsString, Value as JsValue,
 };
 use serde::de::DeserializeOwned;
 
+/// the max integer which is kept exactly by the js number, the larger integer is the js `BigInt`
+const MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+/// the max nested depth of the arrays and objects which are converted to json, the same
+/// as the recursion limit of `serde_json` when parsing
+const MAX_DEPTH: usize = 128;
+
 #[derive(Debug)]
 pub struct ActValue(serde_json::Value);
 
@@ -31,33 +38,39 @@ impl<'js> IntoJs<'js> for ActValue {
             serde_json::Value::Null => JsValue::new_null(ctx.clone()),
             serde_json::Value::Bool(v) => JsValue::new_bool(ctx.clone(), v),
             serde_json::Value::Number(v) => {
-                if v.is_i64() {
-                    let v = v.as_i64().unwrap_or_default() as i32;
-                    JsValue::new_int(ctx.clone(), v)
-                } else if v.is_f64() {
-                    let v = v.as_f64().unwrap_or_default();
-                    JsValue::new_float(ctx.clone(), v)
+                if let Some(v) = v.as_i64() {
+                    if let Ok(v) = i32::try_from(v) {
+                        JsValue::new_int(ctx.clone(), v)
+                    } else if v.unsigned_abs() <= MAX_SAFE_INTEGER {
+                        JsValue::new_float(ctx.clone(), v as f64)
+                    } else {
+                        JsValue::from_big_int(BigInt::from_i64(ctx.clone(), v)?)
+                    }
+                } else if let Some(v) = v.as_u64() {
+                    JsValue::from_big_int(BigInt::from_u64(ctx.clone(), v)?)
                 } else {
-                    let v = v.as_i64().unwrap_or_default() as i32;
-                    JsValue::new_int(ctx.clone(), v)
+                    JsValue::new_float(ctx.clone(), v.as_f64().unwrap_or_default())
                 }
             }
             serde_json::Value::String(v) => {
-                JsValue::from_string(JsString::from_str(ctx.clone(), &v).unwrap())
+                JsValue::from_string(JsString::from_str(ctx.clone(), &v)?)
             }
             serde_json::Value::Array(v) => {
-                let arr = JsArray::new(ctx.clone()).unwrap();
-                for (idx, v) in v.iter().enumerate() {
-                    let val = ActValue(v.clone()).into_js(ctx).unwrap();
-                    arr.set(idx, val).unwrap();
+                let arr = JsArray::new(ctx.clone())?;
+                for (idx, v) in v.into_iter().enumerate() {
+                    arr.set(idx, ActValue(v).into_js(ctx)?)?;
                 }
                 JsValue::from_array(arr)
             }
             serde_json::Value::Object(v) => {
-                let obj = JsObject::new(ctx.clone()).unwrap();
+                let obj = JsObject::new(ctx.clone())?;
                 for (k, v) in v {
-                    obj.set(k.into_atom(ctx).unwrap(), ActValue(v).into_js(ctx).unwrap())
-                        .unwrap();
+                    // defines the property, so the keys such as `__proto__` are kept as the plain keys
+                    let value = ActValue(v).into_js(ctx)?;
+                    obj.prop(
+                        k.into_atom(ctx)?,
+                        Property::from(value).writable().enumerable().configurable(),
+                    )?;
                 }
 
                 JsValue::from_object(obj)
@@ -70,77 +83,143 @@ impl<'js> IntoJs<'js> for ActValue {
 
 impl<'js> FromJs<'js> for ActValue {
     fn from_js(ctx: &rquickjs::Ctx<'js>, v: JsValue<'js>) -> rquickjs::Result<Self> {
-        let result = match v.type_of() {
-            rquickjs::Type::Null | rquickjs::Type::Undefined | rquickjs::Type::Uninitialized => {
-                Ok(serde_json::json!(null))
-            }
-            rquickjs::Type::Bool => Ok(serde_json::json!(v.as_bool().unwrap_or(false))),
-            rquickjs::Type::Int => Ok(serde_json::json!(v.as_int().unwrap_or(0))),
-            rquickjs::Type::Float => Ok(serde_json::json!(v.as_float().unwrap_or(0.0))),
-            rquickjs::Type::String => Ok(serde_json::json!(v
-                .as_string()
-                .unwrap()
-                .to_string()
-                .unwrap_or(String::from("")))),
-            rquickjs::Type::Array => {
-                let empty = &JsArray::new(ctx.clone())?;
-                Ok(serde_json::Value::Array(
-                    v.as_array()
-                        .unwrap_or(empty)
-                        .iter::<JsValue>()
-                        .filter_map(|v| {
-                            v.map(|v| ActValue::from_js(ctx, v.clone()).unwrap().into())
-                                .ok()
-                        })
-                        .collect(),
-                ))
+        let value = to_json(ctx, v, &mut Vec::new())?.unwrap_or(serde_json::Value::Null);
+        Ok(ActValue(value))
+    }
+}
+
+/// converts the js value to the json value like `JSON.stringify`,
+/// returns `None` for the functions and symbols which are skipped in the objects
+///
+/// the `BigInt` in the i64 or u64 range is a json number, the larger one can not be
+/// kept by the json number and becomes the string of its decimal digits, which is
+/// a js string rather than a `BigInt` when it is converted back
+///
+/// the `parents` are the arrays and objects being converted, it throws the `TypeError`
+/// for the cyclic value and the `RangeError` for the value nested too deeply
+fn to_json<'js>(
+    ctx: &rquickjs::Ctx<'js>,
+    v: JsValue<'js>,
+    parents: &mut Vec<JsValue<'js>>,
+) -> rquickjs::Result<Option<serde_json::Value>> {
+    if matches!(v.type_of(), rquickjs::Type::Array | rquickjs::Type::Object) {
+        if parents.contains(&v) {
+            return Err(Exception::throw_type(ctx, "cyclic object value"));
+        }
+        if parents.len() >= MAX_DEPTH {
+            return Err(Exception::throw_range(
+                ctx,
+                "the value is nested too deeply",
+            ));
+        }
+        parents.push(v.clone());
+        let value = to_json_value(ctx, v, parents);
+        parents.pop();
+        return value;
+    }
+    to_json_value(ctx, v, parents)
+}
+
+fn to_json_value<'js>(
+    ctx: &rquickjs::Ctx<'js>,
+    v: JsValue<'js>,
+    parents: &mut Vec<JsValue<'js>>,
+) -> rquickjs::Result<Option<serde_json::Value>> {
+    let value = match v.type_of() {
+        rquickjs::Type::Null | rquickjs::Type::Undefined | rquickjs::Type::Uninitialized => {
+            serde_json::Value::Null
+        }
+        rquickjs::Type::Bool => serde_json::json!(v.as_bool().unwrap_or(false)),
+        rquickjs::Type::Int => serde_json::json!(v.as_int().unwrap_or(0)),
+        rquickjs::Type::Float => from_float(v.as_float().unwrap_or_default()),
+        rquickjs::Type::String => serde_json::json!(v
+            .as_string()
+            .map(|v| v.to_string())
+            .transpose()?
+            .unwrap_or_default()),
+        rquickjs::Type::BigInt => {
+            let Coerced(text) = Coerced::<String>::from_js(ctx, v)?;
+            if let Ok(v) = text.parse::<i64>() {
+                serde_json::json!(v)
+            } else if let Ok(v) = text.parse::<u64>() {
+                serde_json::json!(v)
+            } else {
+                // keeps the digits in string instead of losing the precision
+                serde_json::json!(text)
             }
-            rquickjs::Type::Object => {
-                let mut value = serde_json::Map::<String, serde_json::Value>::new();
-                let inner = JsObject::new(ctx.clone())?;
-                let object = v.as_object().unwrap_or(&inner);
-                let keys = object
-                    .keys::<String>()
-                    .filter_map(|v| v.ok())
-                    .collect::<Vec<_>>();
-                let values = keys
-                    .iter()
-                    .filter_map(|key| {
-                        match object.get::<String, JsValue>(key.clone()) {
-                            Ok(value) => Ok((key, value)),
-                            Err(err) => Err(err),
-                        }
-                        .ok()
-                    })
-                    .collect::<Vec<_>>();
-                for (k, v) in values {
-                    value.insert(k.clone(), ActValue::from_js(ctx, v)?.into());
+        }
+        rquickjs::Type::Array => {
+            let mut arr = Vec::new();
+            if let Some(items) = v.as_array() {
+                for item in items.iter::<JsValue>() {
+                    arr.push(to_json(ctx, item?, parents)?.unwrap_or(serde_json::Value::Null));
                 }
-                Ok(serde_json::Value::Object(value))
             }
-            rquickjs::Type::BigInt => {
-                let bigint = v.as_big_int().unwrap().clone();
-                let v = bigint.to_i64().unwrap();
-                Ok(serde_json::json!(v))
+            serde_json::Value::Array(arr)
+        }
+        rquickjs::Type::Object => {
+            let object = v
+                .as_object()
+                .cloned()
+                .unwrap_or(JsObject::new(ctx.clone())?);
+            let date: JsValue = ctx.globals().get("Date")?;
+            if object.is_instance_of(&date) {
+                return from_date(&object).map(Some);
             }
-            rquickjs::Type::Exception => {
-                let ex = v.as_exception().unwrap().clone();
-                Err(ex.throw())
+
+            let mut map = serde_json::Map::<String, serde_json::Value>::new();
+            // reads the keys as the js strings, so the keys with `\0` are not truncated
+            for key in object.keys::<JsString>() {
+                let key = key?;
+                let value = object.get::<_, JsValue>(key.clone())?;
+                if let Some(value) = to_json(ctx, value, parents)? {
+                    map.insert(key.to_string()?, value);
+                }
             }
-            rquickjs::Type::Unknown
-            | rquickjs::Type::Module
-            | rquickjs::Type::Constructor
-            | rquickjs::Type::Symbol
-            | rquickjs::Type::Function
-            | rquickjs::Type::Promise => Err(rquickjs::Error::new_from_js_message(
+            serde_json::Value::Object(map)
+        }
+        rquickjs::Type::Function | rquickjs::Type::Constructor | rquickjs::Type::Symbol => {
+            return Ok(None)
+        }
+        rquickjs::Type::Exception => {
+            let ex = v.as_exception().unwrap().clone();
+            return Err(ex.throw());
+        }
+        rquickjs::Type::Unknown | rquickjs::Type::Module | rquickjs::Type::Promise => {
+            return Err(rquickjs::Error::new_from_js_message(
                 v.type_name(),
                 "",
                 "cannot convert js to json value",
-            )),
-        }?;
+            ))
+        }
+    };
+
+    Ok(Some(value))
+}
+
+/// the integer float is converted to the json integer, the non-finite float is `null`
+fn from_float(v: f64) -> serde_json::Value {
+    if !v.is_finite() {
+        return serde_json::Value::Null;
+    }
+    if v.fract() == 0.0 && v >= i64::MIN as f64 && v < i64::MAX as f64 {
+        return serde_json::json!(v as i64);
+    }
+    serde_json::json!(v)
+}
 
-        Ok(ActValue(result))
+/// the date is converted to the iso string, the invalid date is `null`
+fn from_date(date: &JsObject) -> rquickjs::Result<serde_json::Value> {
+    let time: f64 = date
+        .get::<_, Function>("getTime")?
+        .call((This(date.clone()),))?;
+    if !time.is_finite() {
+        return Ok(serde_json::Value::Null);
     }
+    let iso: String = date
+        .get::<_, Function>("toISOString")?
+        .call((This(date.clone()),))?;
+    Ok(serde_json::json!(iso))
 }
 
 impl From<ActValue> for serde_json::Value {
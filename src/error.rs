@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 use std::{io::ErrorKind, string::FromUtf8Error};
 use thiserror::Error;
 
-use crate::{Result, Vars};
+use crate::{utils::consts, Result, Vars};
 
 #[derive(Deserialize, Serialize, Error, Debug, Clone, PartialEq)]
 pub enum ActError {
@@ -33,6 +33,37 @@ pub enum ActError {
 
     #[error("{0}")]
     IoError(String),
+
+    #[error("{}", FieldError::join(.0))]
+    Validation(Vec<FieldError>),
+
+    /// the script exceeds the sandbox limits of memory, stack or time
+    #[error("{0}")]
+    Limit(String),
+}
+
+/// the validation error of a field
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct FieldError {
+    pub key: String,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(key: &str, message: &str) -> Self {
+        Self {
+            key: key.to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    fn join(errors: &[FieldError]) -> String {
+        errors
+            .iter()
+            .map(|e| e.message.clone())
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
 }
 
 #[derive(Default, Debug, Clone, Deserialize, Serialize)]
@@ -41,6 +72,9 @@ pub struct Error {
     pub ecode: String,
     #[serde(default)]
     pub message: String,
+    /// the field errors of the validation error
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<FieldError>,
 }
 
 impl fmt::Display for Error {
@@ -55,6 +89,7 @@ impl Error {
         Self {
             message: message.to_string(),
             ecode: ecode.to_string(),
+            fields: Vec::new(),
         }
     }
 
@@ -72,11 +107,14 @@ impl From<ActError> for String {
 impl From<ActError> for Error {
     fn from(val: ActError) -> Self {
         match val {
-            ActError::Exception { ecode, message } => Error { ecode, message },
-            err => Error {
+            ActError::Exception { ecode, message } => Error::new(&message, &ecode),
+            ActError::Limit(message) => Error::new(&message, consts::ACT_LIMIT_ECODE),
+            ActError::Validation(fields) => Error {
                 ecode: "".to_string(),
-                message: err.to_string(),
+                message: FieldError::join(&fields),
+                fields,
             },
+            err => Error::new(&err.to_string(), ""),
         }
     }
 }
@@ -93,12 +131,14 @@ impl From<ActError> for std::io::Error {
     }
 }
 
+#[cfg(feature = "quickjs")]
 impl From<rquickjs::Error> for ActError {
     fn from(error: rquickjs::Error) -> Self {
         ActError::Script(error.to_string())
     }
 }
 
+#[cfg(feature = "quickjs")]
 impl From<ActError> for rquickjs::Error {
     fn from(val: ActError) -> Self {
         std::io::Error::other(val.to_string()).into()
@@ -117,6 +157,7 @@ impl From<serde_json::Error> for ActError {
     }
 }
 
+#[cfg(feature = "quickjs")]
 impl<'a> From<rquickjs::CaughtError<'a>> for ActError {
     fn from(error: rquickjs::CaughtError<'a>) -> Self {
         ActError::Script(error.to_string())
@@ -127,7 +168,7 @@ impl<'a> From<rquickjs::CaughtError<'a>> for ActError {
 mod tests {
     use serde_json::json;
 
-    use crate::{ActError, Error, Vars};
+    use crate::{utils::consts, ActError, Error, FieldError, Vars};
 
     #[test]
     fn engine_error_default() {
@@ -159,6 +200,51 @@ mod tests {
         assert_eq!(v.ecode, "");
     }
 
+    #[test]
+    fn engine_act_limit_error_into() {
+        let err = ActError::Limit("the script exceeds the time limit 100ms".to_string());
+        let v: Error = err.into();
+        assert_eq!(v.ecode, consts::ACT_LIMIT_ECODE);
+        assert_eq!(v.message, "the script exceeds the time limit 100ms");
+    }
+
+    #[test]
+    fn engine_act_validation_error() {
+        let err = ActError::Validation(vec![
+            FieldError::new("a", "the key 'a' is required"),
+            FieldError::new("b", "the value b(1) is not matched the type 'String'"),
+        ]);
+        assert_eq!(
+            err.to_string(),
+            "the key 'a' is required; the value b(1) is not matched the type 'String'"
+        );
+    }
+
+    #[test]
+    fn engine_act_validation_error_into() {
+        let err = ActError::Validation(vec![
+            FieldError::new("a", "the key 'a' is required"),
+            FieldError::new("b", "the value b(1) is not matched the type 'String'"),
+        ]);
+        let v: Error = err.into();
+        assert_eq!(
+            v.message,
+            "the key 'a' is required; the value b(1) is not matched the type 'String'"
+        );
+        assert_eq!(
+            v.fields.iter().map(|e| e.key.as_str()).collect::<Vec<_>>(),
+            ["a", "b"]
+        );
+
+        // the fields are kept in the json payload
+        let value = serde_json::to_value(&v).unwrap();
+        assert_eq!(value["fields"][1]["key"], "b");
+        assert!(serde_json::to_value(Error::new("error", ""))
+            .unwrap()
+            .get("fields")
+            .is_none());
+    }
+
     #[test]
     fn engine_act_exception_into() {
         let err = ActError::Exception {
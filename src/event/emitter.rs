@@ -1,7 +1,7 @@
 use crate::{
     event::Message,
     sch::{Proc, Runtime, Task},
-    utils, Event, Result, ShareLock,
+    Event, Result, ShareLock,
 };
 use std::{
     collections::HashMap,
@@ -210,8 +210,7 @@ impl Emitter {
         dispatch_key_event!(self, errors, &e);
     }
 
-    pub fn emit_tick(&self) {
-        let time_millis = utils::time::time_millis();
+    pub fn emit_tick(&self, time_millis: i64) {
         debug!("emit_tick {time_millis}");
         dispatch_event!(self, ticks, &time_millis);
     }
@@ -5,7 +5,10 @@ use tracing::{debug, error, info};
 fn store_if(runtime: &Arc<Runtime>, ack: bool, chan_id: &str, pattern: &str, message: &Message) {
     if ack && !chan_id.is_empty() && message.retry_times == 0 {
         println!("store: {message:?}");
-        let msg = message.into(chan_id, pattern);
+        let mut msg = message.into(chan_id, pattern);
+        let clock = runtime.clock();
+        msg.create_time = clock.now();
+        msg.timestamp = clock.timestamp();
         runtime
             .cache()
             .store()
@@ -1,7 +1,9 @@
 use crate::{
+    env,
+    model::{json_schema, parse_extends, parse_package_key, Linter, YamlLocator},
     sch::Runtime,
     store::{PageData, StoreAdapter},
-    ModelInfo, Result, Workflow,
+    ActError, Diagnostic, Fragment, ModelInfo, Result, Workflow,
 };
 use std::sync::Arc;
 use tracing::instrument;
@@ -22,11 +24,87 @@ impl ModelExecutor {
 
     #[instrument(skip(self))]
     pub fn deploy(&self, model: &Workflow) -> Result<bool> {
-        model.valid()?;
-        let ret = self.runtime.cache().store().deploy(model)?;
+        let store = self.runtime.cache().store();
+        let mut model = model.clone();
+        let fragments = self.fragments(&model)?;
+        model.expand(&fragments)?;
+
+        // pins the base model to the latest version
+        if let Some(extends) = &model.extends {
+            let (id, ver) = parse_extends(extends);
+            if ver.is_none() {
+                let base = store
+                    .find_model(id, None)
+                    .map_err(|_| ActError::Model(format!("cannot find base model '{id}'")))?;
+                model.extends = Some(format!("{id}@{}", base.ver));
+            }
+        }
+        let mut resolved = store.resolve_model(&model)?;
+        resolved.valid()?;
+
+        // precompiles the scripts and keeps the bytecode with the model, so the procs only run the bytecode
+        let env = self.runtime.env();
+        let mut codes = Vec::new();
+        for (script, path) in Linter::new().lint(&resolved).scripts() {
+            let code = env
+                .compile_code(&resolved.lang, script)
+                .map_err(|err| ActError::Model(format!("the script '{path}' is invalid: {err}")))?;
+            if !code.is_empty() && !codes.iter().any(|(s, _)| s == script) {
+                codes.push((script.clone(), code));
+            }
+        }
+        let ret = store.deploy_with_code(&model, env::encode_codes(&codes))?;
+
+        // caches the resolved model with the pinned base version, so the procs do not resolve it again
+        let cache = self.runtime.cache();
+        cache.remove_model(&model.id);
+        resolved.set_ver(store.find_model(&model.id, None)?.ver);
+        cache.push_model(resolved);
         Ok(ret)
     }
 
+    /// deploys the fragment which can be included by the models
+    #[instrument(skip(self))]
+    pub fn deploy_fragment(&self, fragment: &Fragment) -> Result<bool> {
+        self.runtime.cache().store().deploy_fragment(fragment)
+    }
+
+    #[instrument(skip(self))]
+    pub fn rm_fragment(&self, id: &str) -> Result<bool> {
+        self.runtime.cache().store().fragments().delete(id)
+    }
+
+    /// checks the yml model and returns all of the diagnostics with line and column
+    /// it also checks the referenced packages are published
+    #[instrument(skip(self))]
+    pub fn check(&self, text: &str) -> Result<Vec<Diagnostic>> {
+        let acts = self.runtime.act_handlers().names();
+        let mut diagnostics = Workflow::lint_yml_with(text, &acts);
+        if let Ok(workflow) = Workflow::from_yml(text) {
+            let locator = YamlLocator::new(text);
+            let linter = Linter::new().lint(&workflow);
+            let store = self.runtime.cache().store();
+            for (key, path) in linter.packages() {
+                let (id, ver) = parse_package_key(key);
+                if store.find_package(id, ver).is_err() {
+                    let mut d = Diagnostic::new(
+                        "unknown-package",
+                        path,
+                        &format!("the package '{key}' is not published"),
+                    );
+                    d.locate(&locator);
+                    diagnostics.push(d);
+                }
+            }
+        }
+        Ok(diagnostics)
+    }
+
+    /// generates the json schema of the workflow model with the acts registered to the engine
+    pub fn schema(&self) -> serde_json::Value {
+        json_schema(&self.runtime.act_handlers().names())
+    }
+
     #[instrument(skip(self))]
     pub fn list(&self, q: &ExecutorQuery) -> Result<PageData<ModelInfo>> {
         let query = q.into_query();
@@ -47,9 +125,23 @@ impl ModelExecutor {
         match self.runtime.cache().store().models().find(id) {
             Ok(m) => {
                 let mut model: ModelInfo = m.into();
-                if fmt == "tree" {
-                    let workflow = Workflow::from_yml(&model.data)?;
-                    model.data = workflow.tree_output();
+                if !matches!(
+                    fmt,
+                    "tree" | "mermaid" | "dot" | "json-graph" | "bpmn" | "resolved"
+                ) {
+                    return Ok(model);
+                }
+
+                // renders the resolved model with the inherited and included steps, which is what runs
+                let workflow = self.runtime.cache().model(id, Some(model.ver))?;
+                match fmt {
+                    "tree" => model.data = workflow.tree_output(),
+                    "mermaid" | "dot" | "json-graph" => {
+                        model.data = workflow.graph()?.render(fmt)?;
+                    }
+                    "bpmn" => model.data = workflow.to_bpmn()?,
+                    "resolved" => model.data = workflow.to_yml()?,
+                    _ => {}
                 }
                 Ok(model)
             }
@@ -59,6 +151,28 @@ impl ModelExecutor {
 
     #[instrument(skip(self))]
     pub fn rm(&self, id: &str) -> Result<bool> {
+        self.runtime.cache().remove_model(id);
         self.runtime.cache().store().models().delete(id)
     }
+
+    /// loads the deployed fragments which are included by the model but not defined in it
+    fn fragments(&self, model: &Workflow) -> Result<Vec<Fragment>> {
+        let store = self.runtime.cache().store();
+        let mut ret: Vec<Fragment> = Vec::new();
+        let mut ids = model.includes();
+        ids.extend(model.fragments.iter().flat_map(|f| f.includes()));
+        while let Some(id) = ids.pop() {
+            if model.fragments.iter().any(|f| f.id == id) || ret.iter().any(|f| f.id == id) {
+                continue;
+            }
+            let data = store
+                .fragments()
+                .find(&id)
+                .map_err(|_| ActError::Model(format!("cannot find fragment '{id}'")))?;
+            let fragment = Fragment::from_yml(&data.data)?;
+            ids.extend(fragment.includes());
+            ret.push(fragment);
+        }
+        Ok(ret)
+    }
 }
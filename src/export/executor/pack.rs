@@ -1,9 +1,11 @@
 use super::ExecutorQuery;
+#[cfg(feature = "quickjs")]
+use crate::env::{self, Enviroment};
 use crate::{
     data::Package,
     sch::Runtime,
     store::{PageData, StoreAdapter},
-    PackageInfo, Result,
+    ActError, PackageInfo, Result,
 };
 use std::sync::Arc;
 use tracing::instrument;
@@ -22,10 +24,34 @@ impl PackageExecutor {
 
     #[instrument(skip(self))]
     pub fn publish(&self, pack: &Package) -> Result<bool> {
-        let ret = self.runtime.cache().store().publish(pack)?;
+        // validates and precompiles the script by the manifest lang, the bytecode is kept with the package
+        let script = String::from_utf8(pack.data.clone()).map_err(ActError::from)?;
+        let result = self.compile(pack, &script);
+        let code = result
+            .map_err(|err| ActError::Action(format!("the package script is invalid: {err}")))?;
+        let pack = Package {
+            code,
+            ..pack.clone()
+        };
+        let ret = self.runtime.cache().store().publish(&pack)?;
+        self.runtime.env().reset_modules();
         Ok(ret)
     }
 
+    #[cfg(feature = "quickjs")]
+    fn compile(&self, pack: &Package, script: &str) -> Result<Vec<u8>> {
+        let lang = &pack.manifest.lang;
+        if matches!(lang.as_str(), "" | "js") && env::is_module(script) {
+            return Enviroment::compile_module(&env::module_name(pack), script);
+        }
+        self.runtime.env().compile_code(lang, script)
+    }
+
+    #[cfg(not(feature = "quickjs"))]
+    fn compile(&self, pack: &Package, script: &str) -> Result<Vec<u8>> {
+        self.runtime.env().compile_code(&pack.manifest.lang, script)
+    }
+
     #[instrument(skip(self))]
     pub fn list(&self, q: &ExecutorQuery) -> Result<PageData<PackageInfo>> {
         let query = q.into_query();
@@ -47,6 +73,13 @@ impl PackageExecutor {
         Ok(package.into())
     }
 
+    /// lists the published versions of the package, the latest version is the first
+    #[instrument(skip(self))]
+    pub fn versions(&self, id: &str) -> Result<Vec<PackageInfo>> {
+        let versions = self.runtime.cache().store().package_versions_of(id)?;
+        Ok(versions.iter().map(|p| p.into()).collect())
+    }
+
     #[instrument(skip(self))]
     pub fn rm(&self, id: &str) -> Result<bool> {
         self.runtime.cache().store().packages().delete(id)
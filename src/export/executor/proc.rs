@@ -1,11 +1,11 @@
 use super::ExecutorQuery;
 use crate::{
     sch::Runtime,
-    store::{PageData, StoreAdapter},
+    store::{Cond, Expr, PageData, Query, StoreAdapter},
     utils::consts,
-    ModelInfo, ProcInfo, Result, TaskInfo, Vars,
+    ActError, ProcInfo, Result, TaskInfo, Vars,
 };
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 use tracing::instrument;
 
 #[derive(Clone)]
@@ -21,8 +21,12 @@ impl ProcExecutor {
     }
 
     pub fn start(&self, mid: &str, options: &Vars) -> Result<String> {
-        let model: ModelInfo = self.runtime.cache().store().models().find(mid)?.into();
-        let workflow = model.workflow()?;
+        self.start_with_ver(mid, None, options)
+    }
+
+    /// start the proc with the given model version
+    pub fn start_with_ver(&self, mid: &str, ver: Option<u32>, options: &Vars) -> Result<String> {
+        let workflow = self.runtime.cache().model(mid, ver)?;
 
         let mut vars = options.clone();
         // set the workflow initiator
@@ -69,4 +73,56 @@ impl ProcExecutor {
             Err(err) => Err(err),
         }
     }
+
+    /// get the sub procs which are started by the `call` acts of the proc
+    /// the detached sub procs are included, and they are still found after the proc is completed
+    #[instrument(skip(self))]
+    pub fn children(&self, pid: &str) -> Result<Vec<ProcInfo>> {
+        let q = Query::new().push(Cond::and().push(Expr::eq("parent_id", pid.to_string())));
+        let mut procs = Vec::new();
+        for proc in self.runtime.cache().store().procs().query(&q)?.rows {
+            // the completed sub proc is removed from the store
+            if let Ok(info) = self.get(&proc.id) {
+                procs.push(info);
+            }
+        }
+        procs.sort_by_key(|p| p.timestamp);
+        Ok(procs)
+    }
+
+    /// get the parent proc which starts the proc by `call` act
+    /// it is `None` if the proc is not a sub proc or the parent proc is completed,
+    /// and the parent id is still kept in `parent_id` of the proc info
+    #[instrument(skip(self))]
+    pub fn parent(&self, pid: &str) -> Result<Option<ProcInfo>> {
+        let info = self.get(pid)?;
+        if info.parent_id.is_empty() {
+            return Ok(None);
+        }
+        match self.get(&info.parent_id) {
+            Ok(parent) => Ok(Some(parent)),
+            Err(ActError::Store(_)) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// renders the proc model as 'mermaid', 'dot' or 'json-graph' and colors the nodes by the task states
+    #[instrument(skip(self))]
+    pub fn graph(&self, pid: &str, fmt: &str) -> Result<String> {
+        let proc = self
+            .runtime
+            .proc(pid)
+            .ok_or(ActError::Runtime(format!("cannot find proc '{pid}'")))?;
+
+        let mut graph = proc.tree().graph();
+        let mut states = HashMap::new();
+        let mut tasks = proc.tasks();
+        tasks.sort_by_key(|t| t.timestamp);
+        // the later created task overrides the state of the same node
+        for task in tasks.iter() {
+            states.insert(task.node().id().to_string(), task.state());
+        }
+        graph.set_states(&states);
+        graph.render(fmt)
+    }
 }
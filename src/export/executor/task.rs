@@ -3,7 +3,7 @@ use crate::{
     sch::Runtime,
     store::{PageData, StoreAdapter},
     utils::Id,
-    Result, TaskInfo,
+    LogInfo, Result, TaskInfo,
 };
 use std::sync::Arc;
 use tracing::instrument;
@@ -43,4 +43,10 @@ impl TaskExecutor {
             Err(err) => Err(err),
         }
     }
+
+    /// the captured script logs of the task, which are enabled by `script_log_limit` in config
+    #[instrument(skip(self))]
+    pub fn logs(&self, pid: &str, tid: &str) -> Result<Vec<LogInfo>> {
+        self.runtime.cache().store().logs_of(pid, tid)
+    }
 }
@@ -1,7 +1,7 @@
 use core::fmt;
 use std::sync::{Arc, Mutex};
 
-use crate::{sch::Runtime, ActModule, ActPlugin};
+use crate::{sch::Runtime, ActHandler, ActPlugin, Result, ScriptEngine};
 
 #[derive(Clone)]
 pub struct Extender {
@@ -42,7 +42,8 @@ impl Extender {
     /// let module = test_module::TestModule;
     /// engine.extender().register_module(&module);
     /// ```
-    pub fn register_module<T: ActModule + Clone + 'static>(&self, module: &T) {
+    #[cfg(feature = "quickjs")]
+    pub fn register_module<T: crate::ActModule + Clone + 'static>(&self, module: &T) {
         self.runtime.env().register_module(module)
     }
 
@@ -76,6 +77,43 @@ impl Extender {
         plugins.push(Box::new(plugin.clone()));
     }
 
+    /// register act handler, the models can use it by `act: {name}`
+    /// the handler is only registered to this engine and can not override the builtin acts
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// use acts::{ActContext, ActHandler, Engine, Result};
+    ///
+    /// #[derive(Clone)]
+    /// struct SumHandler;
+    /// impl ActHandler for SumHandler {
+    ///     fn run(&self, ctx: &ActContext) -> Result<()> {
+    ///         let inputs = ctx.inputs();
+    ///         let sum = inputs.get::<i64>("a").unwrap_or_default() + inputs.get::<i64>("b").unwrap_or_default();
+    ///         ctx.expose("sum", sum);
+    ///         Ok(())
+    ///     }
+    /// }
+    /// let engine = Engine::new();
+    /// engine.extender().register_act("sum", &SumHandler).unwrap();
+    /// ```
+    pub fn register_act<T: ActHandler + Clone + 'static>(
+        &self,
+        name: &str,
+        handler: &T,
+    ) -> Result<()> {
+        self.runtime
+            .act_handlers()
+            .register(name, Arc::new(handler.clone()))
+    }
+
+    /// register script engine, the models can use it by `lang: {lang}`
+    /// the engine with the same lang is replaced
+    pub fn register_engine<T: ScriptEngine + 'static>(&self, engine: T) {
+        self.runtime.env().register_engine(engine)
+    }
+
     pub fn plugins(&self) -> Arc<Mutex<Vec<Box<dyn ActPlugin>>>> {
         self.plugins.clone()
     }
@@ -23,6 +23,7 @@ impl Manager {
     #[instrument(skip(self))]
     pub fn publish(&self, pack: &Package) -> Result<bool> {
         let ret = self.runtime.cache().store().publish(pack)?;
+        self.runtime.env().reset_modules();
         Ok(ret)
     }
 
@@ -63,6 +64,7 @@ impl Manager {
     pub fn deploy(&self, model: &Workflow) -> Result<bool> {
         model.valid()?;
         let ret = self.runtime.cache().store().deploy(model)?;
+        self.runtime.cache().remove_model(&model.id);
         Ok(ret)
     }
 
@@ -99,6 +101,7 @@ impl Manager {
 
     #[instrument(skip(self))]
     pub fn rm_model(&self, id: &str) -> Result<bool> {
+        self.runtime.cache().remove_model(id);
         self.runtime.cache().store().models().delete(id)
     }
 
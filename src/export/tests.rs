@@ -4,7 +4,8 @@ use crate::{
     export::ExecutorQuery,
     sch::TaskState,
     store::{Cond, Expr},
-    utils, Act, ActPlugin, ChannelOptions, Engine, Message, Query, StoreAdapter, Vars, Workflow,
+    utils, Act, ActContext, ActError, ActHandler, ActPlugin, ChannelOptions, Engine, Fragment,
+    Limits, Manifest, Message, Query, Result, StoreAdapter, Vars, Workflow,
 };
 use serde_json::json;
 use std::sync::{Arc, Mutex};
@@ -16,7 +17,7 @@ async fn export_manager_publish_ok() {
     let pack = data::Package {
         id: "pack1".to_string(),
         name: "package 1".to_string(),
-        data: vec![0x01, 0x02],
+        data: b"let a = 1;".to_vec(),
         ..Default::default()
     };
 
@@ -26,6 +27,181 @@ async fn export_manager_publish_ok() {
     assert!(manager.pack().publish(&pack).is_ok());
 }
 
+#[tokio::test]
+async fn export_manager_package_versions() {
+    let engine = Engine::new();
+    let manager = engine.executor();
+    for ver in ["0.1.0", "0.2.0"] {
+        let pack = data::Package {
+            id: "pack1".to_string(),
+            name: "package 1".to_string(),
+            data: b"let a = 1;".to_vec(),
+            manifest: Manifest::new()
+                .with_version(ver)
+                .with_description("the package 1"),
+            ..Default::default()
+        };
+        manager.pack().publish(&pack).unwrap();
+    }
+
+    let versions = manager.pack().versions("pack1").unwrap();
+    assert_eq!(versions.len(), 2);
+    assert_eq!(versions[0].id, "pack1");
+    assert_eq!(versions[0].manifest.version, "0.2.0");
+    assert_eq!(versions[1].manifest.version, "0.1.0");
+    assert_eq!(versions[1].manifest.description, "the package 1");
+    assert!(manager.pack().versions("not_exists").unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn export_manager_model_check_package_version() {
+    let engine = Engine::new();
+    let manager = engine.executor();
+    let pack = data::Package {
+        id: "pack1".to_string(),
+        name: "package 1".to_string(),
+        data: b"let a = 1;".to_vec(),
+        manifest: Manifest::new().with_version("1.0.0"),
+        ..Default::default()
+    };
+    manager.pack().publish(&pack).unwrap();
+
+    let text = r#"
+id: m1
+steps:
+  - id: step1
+    acts:
+      - act: pack
+        key: pack1@1.0.0
+      - act: pack
+        key: pack1@2.0.0
+"#;
+    let diagnostics = manager.model().check(text).unwrap();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].code, "unknown-package");
+    assert_eq!(
+        diagnostics[0].message,
+        "the package 'pack1@2.0.0' is not published"
+    );
+}
+
+#[tokio::test]
+async fn export_manager_publish_syntax_error() {
+    let engine = Engine::new();
+    let manager = engine.executor();
+    let pack = data::Package {
+        id: "pack1".to_string(),
+        name: "package 1".to_string(),
+        data: b"let a = ;".to_vec(),
+        ..Default::default()
+    };
+
+    let result = manager.pack().publish(&pack);
+    assert!(
+        matches!(result, Err(ActError::Action(message)) if message.starts_with("the package script is invalid"))
+    );
+    assert!(manager.pack().get("pack1").is_err());
+}
+
+#[tokio::test]
+async fn export_manager_publish_module() {
+    let engine = Engine::new();
+    let manager = engine.executor();
+    let pack = data::Package {
+        id: "pack1".to_string(),
+        name: "package 1".to_string(),
+        data: b"import { fmt } from '@pack/utils'; act.expose('a', fmt(1));".to_vec(),
+        ..Default::default()
+    };
+    assert!(manager.pack().publish(&pack).is_ok());
+
+    let pack = data::Package {
+        data: b"import { fmt } from '@pack/utils'; act.expose('a' 1);".to_vec(),
+        ..pack
+    };
+    let result = manager.pack().publish(&pack);
+    assert!(
+        matches!(result, Err(ActError::Action(message)) if message.starts_with("the package script is invalid"))
+    );
+}
+
+#[tokio::test]
+async fn export_manager_publish_precompile() {
+    let engine = Engine::new();
+    let manager = engine.executor();
+    let pack = data::Package {
+        id: "pack1".to_string(),
+        name: "package 1".to_string(),
+        data: b"let a = 1;".to_vec(),
+        ..Default::default()
+    };
+
+    manager.pack().publish(&pack).unwrap();
+    assert!(engine.runtime().env().is_compiled("let a = 1;"));
+}
+
+#[tokio::test]
+async fn export_manager_publish_keep_code() {
+    let engine = Engine::new();
+    let manager = engine.executor();
+    let pack = data::Package {
+        id: "pack1".to_string(),
+        name: "package 1".to_string(),
+        data: b"let a = 1;".to_vec(),
+        ..Default::default()
+    };
+    manager.pack().publish(&pack).unwrap();
+
+    let pack = data::Package {
+        id: "pack2".to_string(),
+        name: "package 2".to_string(),
+        data: b"export const a = 1;".to_vec(),
+        ..Default::default()
+    };
+    manager.pack().publish(&pack).unwrap();
+
+    let packages = engine.runtime().cache().store().base().packages();
+    assert!(!packages.find("pack1").unwrap().code.is_empty());
+    assert!(!packages.find("pack2").unwrap().code.is_empty());
+}
+
+#[tokio::test]
+async fn export_manager_model_check() {
+    let engine = Engine::new();
+    let manager = engine.executor();
+    let pack = data::Package {
+        id: "pack1".to_string(),
+        name: "package 1".to_string(),
+        data: b"let a = 1;".to_vec(),
+        ..Default::default()
+    };
+    manager.pack().publish(&pack).unwrap();
+
+    let text = r#"
+id: m1
+steps:
+  - id: step1
+    uses: not_exists
+    acts:
+      - act: pack
+        key: pack1
+      - act: pack
+        key: pack2
+"#;
+    let diagnostics = manager.model().check(text).unwrap();
+    let ret: Vec<_> = diagnostics
+        .iter()
+        .map(|d| (d.code.as_str(), d.path.as_str(), d.line))
+        .collect();
+    assert_eq!(
+        ret,
+        [
+            ("unknown-package", "steps[0].uses", 5),
+            ("unknown-package", "steps[0].acts[1].key", 10)
+        ]
+    );
+}
+
 #[tokio::test]
 async fn export_manager_deploy_ok() {
     let engine = Engine::new();
@@ -150,8 +326,11 @@ async fn export_executor_start_dup_pid_error() {
         end_time: 0,
         timestamp: 0,
         model: model.to_json().unwrap(),
+        ver: 0,
+        inputs: "{}".to_string(),
         env_local: "{}".to_string(),
         err: None,
+        parent_id: String::new(),
     };
     store.procs().create(&proc).expect("create proc");
     engine
@@ -268,6 +447,291 @@ async fn export_manager_model_get_tree() {
     assert!(!result.data.is_empty());
 }
 
+#[tokio::test]
+async fn export_manager_model_get_graph() {
+    let engine = Engine::new();
+    let manager = engine.executor();
+    let mut model = Workflow::new().with_step(|step| step.with_id("step1"));
+
+    model.set_id(&utils::longid());
+    manager.model().deploy(&model).unwrap();
+
+    let result = manager.model().get(&model.id, "mermaid").unwrap();
+    assert!(result.data.starts_with("flowchart TD"));
+
+    let result = manager.model().get(&model.id, "dot").unwrap();
+    assert!(result.data.starts_with("digraph"));
+
+    let result = manager.model().get(&model.id, "json-graph").unwrap();
+    let value: serde_json::Value = serde_json::from_str(&result.data).unwrap();
+    assert_eq!(value["id"], json!(model.id));
+}
+
+#[tokio::test]
+async fn export_manager_model_get_graph_resolved() {
+    let engine = Engine::new();
+    let manager = engine.executor();
+    let fragment = Fragment::new()
+        .with_id(&utils::longid())
+        .with_step(|step| step.with_id("step1"));
+    manager.model().deploy_fragment(&fragment).unwrap();
+    let mut base = Workflow::new()
+        .with_id(&utils::longid())
+        .with_step(|step| step.with_id("step1"));
+    base.steps
+        .push(crate::Step::new().with_id("f1").with_include(&fragment.id));
+    manager.model().deploy(&base).unwrap();
+
+    let mut model = Workflow::new()
+        .with_extends(&base.id)
+        .with_step(|step| step.with_id("step2"));
+    model.set_id(&utils::longid());
+    manager.model().deploy(&model).unwrap();
+
+    // the inherited and included steps are rendered
+    for fmt in ["tree", "mermaid", "dot", "json-graph"] {
+        let result = manager.model().get(&model.id, fmt).unwrap();
+        for id in ["step1", "f1.step1", "step2"] {
+            assert!(result.data.contains(id), "{fmt}: {id}");
+        }
+    }
+
+    let result = manager.model().get(&model.id, "bpmn").unwrap();
+    let workflow = Workflow::from_bpmn(&result.data).unwrap();
+    let ids = workflow
+        .steps
+        .iter()
+        .map(|s| s.id.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(ids, ["step1", "f1.step1", "step2"]);
+}
+
+#[tokio::test]
+async fn export_manager_model_get_bpmn() {
+    let engine = Engine::new();
+    let manager = engine.executor();
+    let mut model = Workflow::new().with_step(|step| step.with_id("step1"));
+
+    model.set_id(&utils::longid());
+    manager.model().deploy(&model).unwrap();
+
+    let result = manager.model().get(&model.id, "bpmn").unwrap();
+    let workflow = Workflow::from_bpmn(&result.data).unwrap();
+    assert_eq!(workflow.id, model.id);
+    assert_eq!(workflow.steps[0].id, "step1");
+}
+
+#[tokio::test]
+async fn export_manager_model_deploy_precompile() {
+    let engine = Engine::new();
+    let manager = engine.executor();
+    let model = Workflow::new()
+        .with_id(&utils::longid())
+        .with_input("a", json!(r#"${ 1 + 2 }"#))
+        .with_step(|step| {
+            step.with_id("step1")
+                .with_if(r#"$("a") > 0"#)
+                .with_run(r#"let b = $("a") * 2"#)
+        });
+    manager.model().deploy(&model).unwrap();
+
+    let env = engine.runtime().env().clone();
+    assert!(env.is_compiled("1 + 2"));
+    assert!(env.is_compiled(r#"$("a") > 0"#));
+    assert!(env.is_compiled(r#"let b = $("a") * 2"#));
+}
+
+#[tokio::test]
+async fn export_manager_model_deploy_keep_code() {
+    let engine = Engine::new();
+    let manager = engine.executor();
+    let model = Workflow::new()
+        .with_id(&utils::longid())
+        .with_step(|step| step.with_id("step1").with_if(r#"$("a") > 0"#));
+    manager.model().deploy(&model).unwrap();
+
+    let data = engine
+        .runtime()
+        .cache()
+        .store()
+        .base()
+        .models()
+        .find(&model.id)
+        .unwrap();
+    let codes = crate::env::decode_codes(&data.code);
+    assert!(codes.iter().any(|(script, _)| script == r#"$("a") > 0"#));
+
+    // the kept bytecode is loaded after restarting without compiling the scripts again
+    let engine2 = Engine::new();
+    let rt = engine2.runtime();
+    rt.cache().store().base().models().create(&data).unwrap();
+    rt.cache().model(&model.id, None).unwrap();
+    assert!(rt.env().is_compiled(r#"$("a") > 0"#));
+}
+
+#[tokio::test]
+async fn export_manager_model_deploy_script_error() {
+    let engine = Engine::new();
+    let manager = engine.executor();
+    let model = Workflow::new()
+        .with_id(&utils::longid())
+        .with_step(|step| step.with_id("step1").with_run("let a = ;"));
+
+    let result = manager.model().deploy(&model);
+    assert!(
+        matches!(result, Err(ActError::Model(message)) if message.starts_with("the script 'steps[0].run' is invalid"))
+    );
+    assert!(manager.model().get(&model.id, "text").is_err());
+}
+
+#[tokio::test]
+async fn export_manager_model_deploy_with_fragment() {
+    let engine = Engine::new();
+    let manager = engine.executor();
+    let fragment = Fragment::new()
+        .with_id(&utils::longid())
+        .with_param("key", "msg1")
+        .with_step(|step| {
+            step.with_id("step1")
+                .with_act(Act::msg(|msg| msg.with_key("{{ key }}")))
+        });
+    manager.model().deploy_fragment(&fragment).unwrap();
+
+    let mut model = Workflow::new().with_step(|step| step.with_id("step1"));
+    model
+        .steps
+        .push(crate::Step::new().with_id("f1").with_include(&fragment.id));
+    model.set_id(&utils::longid());
+    manager.model().deploy(&model).unwrap();
+
+    let result = manager.model().get(&model.id, "text").unwrap();
+    let workflow = Workflow::from_yml(&result.data).unwrap();
+    assert_eq!(workflow.steps[1].id, "f1.step1");
+    assert_eq!(workflow.steps[1].acts[0].key, "msg1");
+}
+
+#[tokio::test]
+async fn export_manager_model_deploy_with_missing_fragment() {
+    let engine = Engine::new();
+    let manager = engine.executor();
+    let mut model = Workflow::new().with_step(|step| step.with_include("not_exists"));
+    model.set_id(&utils::longid());
+
+    let result = manager.model().deploy(&model);
+    assert_eq!(
+        result.err().unwrap(),
+        ActError::Model("cannot find fragment 'not_exists'".to_string())
+    );
+}
+
+#[tokio::test]
+async fn export_manager_model_deploy_with_extends() {
+    let engine = Engine::new();
+    let manager = engine.executor();
+    let base = Workflow::new()
+        .with_id(&utils::longid())
+        .with_step(|step| step.with_id("step1"))
+        .with_step(|step| step.with_id("step2"));
+    manager.model().deploy(&base).unwrap();
+
+    let mut model = Workflow::new()
+        .with_extends(&base.id)
+        .with_remove("step2")
+        .with_step(|step| step.with_id("step1"))
+        .with_step(|step| step.with_id("step3"));
+    model.set_id(&utils::longid());
+    manager.model().deploy(&model).unwrap();
+
+    // the resolved model is cached at deploy
+    assert!(engine.runtime().cache().is_model_cached(&model.id, 1));
+
+    // the base version is pinned
+    let result = manager.model().get(&model.id, "text").unwrap();
+    let workflow = Workflow::from_yml(&result.data).unwrap();
+    assert_eq!(workflow.extends, Some(format!("{}@1", base.id)));
+
+    // the later base changes are not merged
+    manager
+        .model()
+        .deploy(&base.clone().with_step(|step| step.with_id("step4")))
+        .unwrap();
+    let result = manager.model().get(&model.id, "resolved").unwrap();
+    let workflow = Workflow::from_yml(&result.data).unwrap();
+    let ids = workflow
+        .steps
+        .iter()
+        .map(|s| s.id.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(ids, ["step1", "step3"]);
+}
+
+#[tokio::test]
+async fn export_manager_model_deploy_with_missing_base() {
+    let engine = Engine::new();
+    let manager = engine.executor();
+    let mut model = Workflow::new()
+        .with_extends("not_exists")
+        .with_step(|step| step.with_id("step1"));
+    model.set_id(&utils::longid());
+
+    let result = manager.model().deploy(&model);
+    assert_eq!(
+        result.err().unwrap(),
+        ActError::Model("cannot find base model 'not_exists'".to_string())
+    );
+}
+
+#[tokio::test]
+async fn export_manager_proc_start_after_redeploy() {
+    let engine = Engine::new();
+    let manager = engine.executor();
+    let mut model = Workflow::new().with_step(|step| step.with_id("step1"));
+    model.set_id(&utils::longid());
+    manager.model().deploy(&model).unwrap();
+    manager.proc().start(&model.id, &Vars::new()).unwrap();
+    let cached = engine.runtime().cache().model(&model.id, None).unwrap();
+    assert_eq!(cached.steps.len(), 1);
+
+    let model = model.with_step(|step| step.with_id("step2"));
+    manager.model().deploy(&model).unwrap();
+    manager.proc().start(&model.id, &Vars::new()).unwrap();
+    let cached = engine.runtime().cache().model(&model.id, None).unwrap();
+    assert_eq!(cached.steps.len(), 2);
+    assert_eq!(cached.ver(), 2);
+}
+
+#[tokio::test]
+async fn export_manager_proc_start_with_extends() {
+    let engine = Engine::new();
+    let manager = engine.executor();
+    let base = Workflow::new()
+        .with_id(&utils::longid())
+        .with_input("a", json!(1))
+        .with_step(|step| step.with_id("step1"));
+    manager.model().deploy(&base).unwrap();
+
+    let mut model = Workflow::new()
+        .with_extends(&base.id)
+        .with_input("b", json!(2));
+    model.set_id(&utils::longid());
+    manager.model().deploy(&model).unwrap();
+
+    let sig = engine.signal(());
+    let s1 = sig.clone();
+    let inputs = Arc::new(Mutex::new(Vars::new()));
+    let ret = inputs.clone();
+    engine.channel().on_complete(move |e| {
+        *ret.lock().unwrap() = e.inputs.clone();
+        s1.close();
+    });
+    manager.proc().start(&model.id, &Vars::new()).unwrap();
+    sig.recv().await;
+
+    let inputs = inputs.lock().unwrap();
+    assert_eq!(inputs.get::<i32>("a").unwrap(), 1);
+    assert_eq!(inputs.get::<i32>("b").unwrap(), 2);
+}
+
 #[tokio::test]
 async fn export_manager_model_remove() {
     let engine = Engine::new();
@@ -526,6 +990,264 @@ async fn export_manager_proc_get() {
     assert!(!info.tasks.is_empty());
 }
 
+#[tokio::test]
+async fn export_manager_proc_graph() {
+    let engine = Engine::new();
+    let manager = engine.executor();
+    let model = Workflow::new()
+        .with_id("m1")
+        .with_step(|step| {
+            step.with_id("step1")
+                .with_act(Act::irq(|act| act.with_key("act1")).with_id("act1"))
+        })
+        .with_step(|step| step.with_id("step2"));
+
+    let rt = engine.runtime();
+    let sig = engine.signal(());
+    let s1 = sig.clone();
+    engine.channel().on_message(move |e| {
+        if e.is_key("act1") && e.is_state("created") {
+            s1.close()
+        }
+    });
+    let pid = utils::longid();
+    let proc = rt.create_proc(&pid, &model);
+    rt.launch(&proc);
+    sig.recv().await;
+
+    let text = manager.proc().graph(&pid, "json-graph").unwrap();
+    let graph: crate::Graph = serde_json::from_str(&text).unwrap();
+    let state = |id: &str| {
+        graph
+            .nodes
+            .iter()
+            .find(|n| n.id == id)
+            .unwrap()
+            .state
+            .clone()
+    };
+    assert_eq!(state("step1"), Some("running".to_string()));
+    assert_eq!(state("act1"), Some("interrupted".to_string()));
+    assert_eq!(state("step2"), None);
+
+    let text = manager.proc().graph(&pid, "mermaid").unwrap();
+    assert!(text.contains("classDef running"));
+    let text = manager.proc().graph(&pid, "dot").unwrap();
+    assert!(text.contains("style=filled"));
+}
+
+#[tokio::test]
+async fn export_manager_proc_children_and_parent() {
+    let engine = Engine::new();
+    let manager = engine.executor();
+    let main = Workflow::new().with_id("main").with_step(|step| {
+        step.with_id("step1").with_act(
+            Act::call(|act| act.with_key("sub").with_input("pid", json!("export_sub1")))
+                .with_id("call1"),
+        )
+    });
+    let sub = Workflow::new().with_id("sub").with_step(|step| {
+        step.with_id("step1")
+            .with_act(Act::irq(|act| act.with_key("act1")))
+    });
+    manager.model().deploy(&main).unwrap();
+    manager.model().deploy(&sub).unwrap();
+
+    let sig = engine.signal(());
+    let s1 = sig.clone();
+    engine.channel().on_message(move |e| {
+        if e.is_key("act1") && e.is_state("created") {
+            s1.close()
+        }
+    });
+    let pid = utils::longid();
+    manager
+        .proc()
+        .start("main", &Vars::new().with("pid", &pid))
+        .unwrap();
+    sig.recv().await;
+
+    let children = manager.proc().children(&pid).unwrap();
+    assert_eq!(children.len(), 1);
+    assert_eq!(children[0].id, "export_sub1");
+
+    let parent = manager.proc().parent("export_sub1").unwrap();
+    assert_eq!(parent.map(|p| p.id), Some(pid.clone()));
+    assert!(manager.proc().parent(&pid).unwrap().is_none());
+    assert!(manager.proc().children("export_sub1").unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn export_manager_proc_children_detached() {
+    let engine = Engine::new();
+    let manager = engine.executor();
+    let main = Workflow::new()
+        .with_id(&utils::longid())
+        .with_step(|step| {
+            step.with_id("step1").with_act(
+                Act::call(|act| {
+                    act.with_key("detached_sub")
+                        .with_wait(false)
+                        .with_input("pid", json!("export_detached_sub1"))
+                })
+                .with_id("call1"),
+            )
+        })
+        .with_step(|step| {
+            step.with_id("step2")
+                .with_act(Act::irq(|act| act.with_key("main_act")))
+        });
+    let sub = Workflow::new().with_id("detached_sub").with_step(|step| {
+        step.with_id("step1")
+            .with_act(Act::irq(|act| act.with_key("sub_act")))
+    });
+    manager.model().deploy(&main).unwrap();
+    manager.model().deploy(&sub).unwrap();
+
+    let sig = engine.signal(());
+    let s1 = sig.clone();
+    engine.channel().on_message(move |e| {
+        if e.is_key("main_act") && e.is_state("created") {
+            s1.close()
+        }
+    });
+    let pid = utils::longid();
+    manager
+        .proc()
+        .start(&main.id, &Vars::new().with("pid", &pid))
+        .unwrap();
+    sig.recv().await;
+
+    let children = manager.proc().children(&pid).unwrap();
+    assert_eq!(children.len(), 1);
+    assert_eq!(children[0].id, "export_detached_sub1");
+
+    let parent = manager.proc().parent("export_detached_sub1").unwrap();
+    assert_eq!(parent.map(|p| p.id), Some(pid.clone()));
+}
+
+#[tokio::test]
+async fn export_manager_proc_children_completed_parent() {
+    let engine = Engine::new();
+    let manager = engine.executor();
+    let main = Workflow::new().with_id(&utils::longid()).with_step(|step| {
+        step.with_id("step1").with_act(
+            Act::call(|act| {
+                act.with_key("completed_parent_sub")
+                    .with_wait(false)
+                    .with_input("pid", json!("export_completed_sub1"))
+            })
+            .with_id("call1"),
+        )
+    });
+    let sub = Workflow::new()
+        .with_id("completed_parent_sub")
+        .with_step(|step| {
+            step.with_id("step1")
+                .with_act(Act::irq(|act| act.with_key("sub_act")))
+        });
+    manager.model().deploy(&main).unwrap();
+    manager.model().deploy(&sub).unwrap();
+
+    let pid = utils::longid();
+    let sig = engine.signal(());
+    let s1 = sig.clone();
+    let main_pid = pid.clone();
+    engine.channel().on_complete(move |e| {
+        if e.pid == main_pid {
+            s1.close()
+        }
+    });
+    manager
+        .proc()
+        .start(&main.id, &Vars::new().with("pid", &pid))
+        .unwrap();
+    sig.recv().await;
+
+    // the parent proc is completed and removed, the detached sub proc is still running
+    assert!(manager.proc().get(&pid).is_err());
+    let children = manager.proc().children(&pid).unwrap();
+    assert_eq!(children.len(), 1);
+    assert_eq!(children[0].id, "export_completed_sub1");
+    assert_eq!(children[0].parent_id, pid);
+    assert!(manager
+        .proc()
+        .parent("export_completed_sub1")
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn export_manager_proc_start_with_schema() {
+    let engine = Engine::new();
+    let manager = engine.executor();
+    let text = r#"
+    id: schema1
+    schema:
+      a:
+        type: Number
+        required: true
+      b:
+        enum: [x, y]
+        default: x
+    steps:
+      - id: step1
+    "#;
+    let model = Workflow::from_yml(text).unwrap();
+    manager.model().deploy(&model).unwrap();
+
+    let sig = engine.signal(());
+    let s1 = sig.clone();
+    let inputs = Arc::new(Mutex::new(Vars::new()));
+    let ret = inputs.clone();
+    engine.channel().on_complete(move |e| {
+        *ret.lock().unwrap() = e.inputs.clone();
+        s1.close();
+    });
+    manager
+        .proc()
+        .start("schema1", &Vars::new().with("a", 10))
+        .unwrap();
+    sig.recv().await;
+
+    // the default value is set
+    assert_eq!(inputs.lock().unwrap().get::<String>("b").unwrap(), "x");
+}
+
+#[tokio::test]
+async fn export_manager_proc_start_with_schema_error() {
+    let engine = Engine::new();
+    let manager = engine.executor();
+    let text = r#"
+    id: schema1
+    schema:
+      a:
+        type: Number
+        required: true
+      b:
+        enum: [x, y]
+      c:
+        pattern: ^[0-9]+$
+    steps:
+      - id: step1
+    "#;
+    let model = Workflow::from_yml(text).unwrap();
+    manager.model().deploy(&model).unwrap();
+
+    let ret = manager
+        .proc()
+        .start("schema1", &Vars::new().with("b", "z").with("c", "abc"));
+    match ret {
+        Err(ActError::Validation(errors)) => {
+            assert_eq!(
+                errors.iter().map(|e| e.key.as_str()).collect::<Vec<_>>(),
+                ["a", "b", "c"]
+            );
+        }
+        _ => panic!("should be validation error"),
+    }
+}
+
 #[tokio::test]
 async fn export_manager_tasks_count() {
     let engine = Engine::new();
@@ -1112,10 +1834,14 @@ async fn export_manager_packages_count() {
             id: utils::longid(),
             name: i.to_string(),
             size: i as u32,
-            data: [0x0, 0x1].to_vec(),
+            data: b"let a = 1;".to_vec(),
             create_time: utils::time::time_millis(),
             update_time: 0,
             timestamp: utils::time::timestamp(),
+            limits: Limits::default(),
+            manifest: Manifest::default(),
+            pack_id: String::new(),
+            code: Vec::new(),
         };
         manager.pack().publish(&package).unwrap();
     }
@@ -1140,10 +1866,14 @@ async fn export_manager_packages_order() {
             id: utils::longid(),
             name: format!("test-{}", i + 1),
             size: i as u32,
-            data: [0x0, 0x1].to_vec(),
+            data: b"let a = 1;".to_vec(),
             create_time: utils::time::time_millis(),
             update_time: 0,
             timestamp: utils::time::timestamp(),
+            limits: Limits::default(),
+            manifest: Manifest::default(),
+            pack_id: String::new(),
+            code: Vec::new(),
         };
         manager.pack().publish(&package).unwrap();
     }
@@ -1171,10 +1901,14 @@ async fn export_manager_packages_query() {
             id: utils::longid(),
             name: format!("test-{}", i + 1),
             size: i as u32,
-            data: [0x0, 0x1].to_vec(),
+            data: b"let a = 1;".to_vec(),
             create_time: utils::time::time_millis(),
             update_time: 0,
             timestamp: utils::time::timestamp(),
+            limits: Limits::default(),
+            manifest: Manifest::default(),
+            pack_id: String::new(),
+            code: Vec::new(),
         };
         manager.pack().publish(&package).unwrap();
     }
@@ -1198,10 +1932,14 @@ async fn export_manager_packages_offset_in_range() {
             id: utils::longid(),
             name: i.to_string(),
             size: i as u32,
-            data: [0x0, 0x1].to_vec(),
+            data: b"let a = 1;".to_vec(),
             create_time: utils::time::time_millis(),
             update_time: 0,
             timestamp: utils::time::timestamp(),
+            limits: Limits::default(),
+            manifest: Manifest::default(),
+            pack_id: String::new(),
+            code: Vec::new(),
         };
         manager.pack().publish(&package).unwrap();
     }
@@ -1227,10 +1965,14 @@ async fn export_manager_packages_offset_out_range() {
             id: utils::longid(),
             name: i.to_string(),
             size: i as u32,
-            data: [0x0, 0x1].to_vec(),
+            data: b"let a = 1;".to_vec(),
             create_time: utils::time::time_millis(),
             update_time: 0,
             timestamp: utils::time::timestamp(),
+            limits: Limits::default(),
+            manifest: Manifest::default(),
+            pack_id: String::new(),
+            code: Vec::new(),
         };
         manager.pack().publish(&package).unwrap();
     }
@@ -1254,10 +1996,14 @@ async fn export_manager_package_rm() {
         id: utils::longid(),
         name: "name".to_string(),
         size: 10,
-        data: [0x0, 0x1].to_vec(),
+        data: b"let a = 1;".to_vec(),
         create_time: utils::time::time_millis(),
         update_time: 0,
         timestamp: utils::time::timestamp(),
+        limits: Limits::default(),
+        manifest: Manifest::default(),
+        pack_id: String::new(),
+        code: Vec::new(),
     };
     manager.pack().publish(&package).unwrap();
     assert!(manager.pack().rm(&package.id).unwrap());
@@ -1708,6 +2454,54 @@ async fn export_extender_register_module() {
     assert_eq!(count, before_count + 1);
 }
 
+#[tokio::test]
+async fn export_extender_register_act() {
+    #[derive(Clone)]
+    struct TestHandler;
+    impl ActHandler for TestHandler {
+        fn run(&self, _ctx: &ActContext) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    let engine = Engine::new();
+    let extender = engine.extender();
+    assert!(extender
+        .register_act("test_export_act", &TestHandler)
+        .is_ok());
+    let text = r#"
+id: m1
+steps:
+  - id: step1
+    acts:
+      - act: test_export_act
+"#;
+    assert!(engine.executor().model().check(text).unwrap().is_empty());
+    let names = engine.executor().model().schema()["definitions"]["act"]["properties"]["act"]
+        ["enum"]
+        .clone();
+    assert!(names
+        .as_array()
+        .unwrap()
+        .contains(&json!("test_export_act")));
+
+    // the other engines do not know the act
+    let other = Engine::new();
+    let diagnostics = other.executor().model().check(text).unwrap();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].code, "unknown-act");
+    assert!(
+        !Workflow::json_schema()["definitions"]["act"]["properties"]["act"]["enum"]
+            .as_array()
+            .unwrap()
+            .contains(&json!("test_export_act"))
+    );
+
+    // the builtin acts can not be overridden
+    let result = extender.register_act("irq", &TestHandler);
+    assert!(matches!(result, Err(ActError::Action(message)) if message.contains("builtin")));
+}
+
 #[tokio::test]
 async fn export_emitter_default() {
     let engine = Engine::new();
@@ -5,6 +5,7 @@
 mod adapter;
 mod builder;
 mod cache;
+mod clock;
 mod config;
 mod engine;
 mod env;
@@ -25,14 +26,18 @@ use std::sync::Arc;
 use std::sync::RwLock;
 
 pub use builder::Builder;
+pub use clock::{Clock, ManualClock, SystemClock};
 pub use config::Config;
 pub use engine::Engine;
-pub use env::ActModule;
-pub use error::{ActError, Error};
+pub use env::ScriptEngine;
+#[cfg(feature = "quickjs")]
+pub use env::{ActModule, AsyncFn};
+pub use error::{ActError, Error, FieldError};
 pub use event::{Action, Event, Message, MessageState};
 pub use export::{Channel, ChannelOptions, Executor, ExecutorQuery, Extender};
 pub use model::*;
 pub use plugin::ActPlugin;
+pub use sch::{ActContext, ActHandler, Graph, GraphEdge, GraphNode};
 pub use signal::Signal;
 pub use store::{data, DbSet, Query, StoreAdapter};
 pub type Result<T> = std::result::Result<T, ActError>;
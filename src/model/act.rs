@@ -10,6 +10,7 @@ mod msg;
 mod pack;
 mod timeout;
 
+use super::fragment::INCLUDE_ACT;
 use crate::{ModelBase, StmtBuild, Vars};
 pub use block::Block;
 pub use call::Call;
@@ -44,6 +45,14 @@ pub struct Act {
     #[serde(default)]
     pub tag: String,
 
+    /// model version for 'call', uses the latest version if not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ver: Option<u32>,
+
+    /// waits for the sub workflow completing for 'call', the default is true
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wait: Option<bool>,
+
     /// in expression for 'each' and 'chain'
     #[serde(default)]
     pub r#in: String,
@@ -141,6 +150,10 @@ pub enum ActFn {
 
     #[serde(rename = "on_catch")]
     OnErrorCatch(Vec<Catch>),
+
+    /// the act which is handled by the registered act handler
+    #[serde(rename = "custom")]
+    Custom(Box<Act>),
 }
 
 impl ModelBase for Act {
@@ -322,7 +335,10 @@ impl Act {
         let call = build(Call::default());
         Act {
             key: call.key,
+            ver: call.ver,
+            wait: call.wait,
             inputs: call.inputs,
+            outputs: call.outputs,
             rets: call.rets,
             act: "call".to_string(),
             ..Default::default()
@@ -372,6 +388,16 @@ impl Act {
         }
     }
 
+    /// includes the fragment acts, the params are the fragment params
+    pub fn include(fragment: &str, params: Vars) -> Self {
+        Act {
+            act: INCLUDE_ACT.to_string(),
+            key: fragment.to_string(),
+            inputs: params,
+            ..Default::default()
+        }
+    }
+
     pub fn catch<F: Fn(Catch) -> Catch>(build: F) -> Self {
         let c = build(Catch::default());
 
@@ -526,7 +552,10 @@ impl From<&Act> for ActFn {
             "call" => {
                 let call = Call {
                     key: act.key.clone(),
+                    ver: act.ver,
+                    wait: act.wait,
                     inputs: act.inputs.clone(),
+                    outputs: act.outputs.clone(),
                     rets: act.rets.clone(),
                 };
                 ActFn::Call(call)
@@ -6,9 +6,23 @@ pub struct Call {
     #[serde(default)]
     pub key: String,
 
+    /// the model version to start, uses the latest version if not set
+    #[serde(default)]
+    pub ver: Option<u32>,
+
+    /// waits for the sub workflow completing, the default is true
+    #[serde(default)]
+    pub wait: Option<bool>,
+
     #[serde(default)]
     pub inputs: Vars,
 
+    /// maps the sub workflow outputs to the act
+    /// the key is the act var name and the value is the sub workflow output name
+    /// or null to use the same name
+    #[serde(default)]
+    pub outputs: Vars,
+
     #[serde(default)]
     pub rets: Vars,
 }
@@ -23,6 +37,44 @@ impl Call {
         self
     }
 
+    pub fn with_ver(mut self, ver: u32) -> Self {
+        self.ver = Some(ver);
+        self
+    }
+
+    pub fn with_wait(mut self, wait: bool) -> Self {
+        self.wait = Some(wait);
+        self
+    }
+
+    pub fn with_output(mut self, name: &str, from: Option<&str>) -> Self {
+        self.outputs.set(name, from);
+        self
+    }
+
+    pub fn is_wait(&self) -> bool {
+        self.wait.unwrap_or(true)
+    }
+
+    /// maps the sub workflow outputs by the outputs defination
+    /// returns all of the outputs if there is no defination
+    pub fn map_outputs(&self, outputs: &Vars) -> Vars {
+        if self.outputs.is_empty() {
+            return outputs.clone();
+        }
+
+        let mut ret = Vars::new();
+        for (ref name, value) in &self.outputs {
+            let from = value.as_str().unwrap_or(name);
+            if let Some(v) = outputs.get_value(from) {
+                ret.set(name, v.clone());
+            } else {
+                ret.set(name, serde_json::Value::Null);
+            }
+        }
+        ret
+    }
+
     pub fn with_input<T>(mut self, name: &str, value: T) -> Self
     where
         T: Serialize + Clone,
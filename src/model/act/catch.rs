@@ -9,6 +9,10 @@ pub struct Catch {
     pub inputs: Vars,
     #[serde(default)]
     pub then: Vec<Act>,
+
+    /// includes the fragment catches, the inputs are the fragment params
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub include: Option<String>,
 }
 
 impl Catch {
@@ -21,6 +25,11 @@ impl Catch {
         self
     }
 
+    pub fn with_include(mut self, fragment: &str) -> Self {
+        self.include = Some(fragment.to_string());
+        self
+    }
+
     pub fn with_error(mut self, err: &str) -> Self {
         self.inputs.set("error", err.to_string());
         self
@@ -0,0 +1,342 @@
+use super::{xml::escape, ACTS_NS, BPMN_NS};
+use crate::{
+    model::act::{TimeoutLimit, TimeoutUnit},
+    Branch, Catch, Result, Step, Timeout, Workflow,
+};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+/// writes the workflow as bpmn 2.0 xml
+/// the acts data which can not be expressed by bpmn is kept in the extension elements
+pub fn to_bpmn(workflow: &Workflow) -> Result<String> {
+    let mut writer = Writer::default();
+    writer.assign(&workflow.steps);
+
+    let pid = writer.unique(if workflow.id.is_empty() {
+        "process"
+    } else {
+        &workflow.id
+    });
+    let start = writer.unique(&format!("{pid}_start"));
+    let end = writer.unique(&format!("{pid}_end"));
+
+    let mut data = workflow.clone();
+    data.steps.clear();
+
+    writer
+        .elements
+        .push(format!("    <bpmn:startEvent id=\"{}\" />", escape(&start)));
+    writer.steps(&workflow.steps, &start, &end, None)?;
+    writer
+        .elements
+        .push(format!("    <bpmn:endEvent id=\"{}\" />", escape(&end)));
+
+    let mut lines = vec![
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>".to_string(),
+        format!(
+            "<bpmn:definitions xmlns:bpmn=\"{BPMN_NS}\" xmlns:acts=\"{ACTS_NS}\" id=\"{}\" targetNamespace=\"{ACTS_NS}\">",
+            escape(&format!("{pid}_definitions"))
+        ),
+    ];
+    for (id, code) in &writer.errors {
+        lines.push(format!(
+            "  <bpmn:error id=\"{}\" errorCode=\"{}\" />",
+            escape(id),
+            escape(code)
+        ));
+    }
+    lines.push(format!(
+        "  <bpmn:process id=\"{}\"{} isExecutable=\"true\">",
+        escape(&pid),
+        name_attr(&workflow.name)
+    ));
+    lines.push(extension("    ", "workflow", &data)?);
+    lines.append(&mut writer.elements);
+    lines.append(&mut writer.flows);
+    lines.push("  </bpmn:process>".to_string());
+    lines.push("</bpmn:definitions>".to_string());
+
+    Ok(lines.join("\n"))
+}
+
+#[derive(Default)]
+struct Writer {
+    elements: Vec<String>,
+    flows: Vec<String>,
+    errors: Vec<(String, String)>,
+    ids: HashSet<String>,
+    /// the element id of each step by the step address
+    elements_of: HashMap<*const Step, String>,
+    /// the element id by the step model id
+    steps_of: HashMap<String, String>,
+}
+
+impl Writer {
+    /// assigns the element ids before writing to resolve the next jumps
+    fn assign(&mut self, steps: &[Step]) {
+        for step in steps {
+            let id = self.unique(if step.id.is_empty() { "step" } else { &step.id });
+            if !step.id.is_empty() {
+                self.steps_of.insert(step.id.clone(), id.clone());
+            }
+            self.elements_of.insert(step as *const Step, id);
+            for branch in &step.branches {
+                self.assign(&branch.steps);
+            }
+        }
+    }
+
+    fn unique(&mut self, id: &str) -> String {
+        let mut base: String = id
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        if !base.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+            base.insert(0, '_');
+        }
+
+        let mut ret = base.clone();
+        let mut index = 1;
+        while self.ids.contains(&ret) {
+            ret = format!("{base}_{index}");
+            index += 1;
+        }
+        self.ids.insert(ret.clone());
+        ret
+    }
+
+    /// writes the step list from the entry element to the exit element
+    /// the first flow carries the branch if the list is in a branch
+    fn steps(
+        &mut self,
+        steps: &[Step],
+        entry: &str,
+        exit: &str,
+        branch: Option<(&str, &Branch)>,
+    ) -> Result<()> {
+        let first = steps
+            .first()
+            .map(|s| self.elements_of[&(s as *const Step)].clone())
+            .unwrap_or(exit.to_string());
+        self.flow(entry, &first, branch)?;
+
+        for (index, step) in steps.iter().enumerate() {
+            let id = self.elements_of[&(step as *const Step)].clone();
+            self.step(&id, step)?;
+
+            let mut out = id.clone();
+            if !step.branches.is_empty() {
+                out = self.branches(&id, step)?;
+            }
+
+            let target = match steps.get(index + 1) {
+                Some(next) => self.elements_of[&(next as *const Step)].clone(),
+                None => step
+                    .next
+                    .as_ref()
+                    .and_then(|next| self.steps_of.get(next).cloned())
+                    .unwrap_or(exit.to_string()),
+            };
+            self.flow(&out, &target, None)?;
+        }
+
+        Ok(())
+    }
+
+    fn step(&mut self, id: &str, step: &Step) -> Result<()> {
+        let mut data = step.clone();
+        data.branches.clear();
+        data.catches.clear();
+        data.timeout.clear();
+
+        let (tag, attrs) = match step.acts.as_slice() {
+            [act] if act.act == "irq" => ("userTask", String::new()),
+            [act] if act.act == "msg" => ("sendTask", String::new()),
+            [act] if act.act == "call" => (
+                "callActivity",
+                format!(" calledElement=\"{}\"", escape(&act.key)),
+            ),
+            [] if step.run.is_some() => ("scriptTask", " scriptFormat=\"javascript\"".to_string()),
+            _ => ("task", String::new()),
+        };
+
+        self.elements.push(format!(
+            "    <bpmn:{tag} id=\"{}\"{}{attrs}>",
+            escape(id),
+            name_attr(&step.name)
+        ));
+        self.elements.push(extension("      ", "step", &data)?);
+        if tag == "scriptTask" {
+            let script = step.run.clone().unwrap_or_default();
+            self.elements.push(format!(
+                "      <bpmn:script>{}</bpmn:script>",
+                escape(&script)
+            ));
+        }
+        self.elements.push(format!("    </bpmn:{tag}>"));
+
+        for (index, catch) in step.catches.iter().enumerate() {
+            self.catch(id, index, catch)?;
+        }
+        for (index, timeout) in step.timeout.iter().enumerate() {
+            self.timeout(id, index, timeout)?;
+        }
+
+        Ok(())
+    }
+
+    /// writes the split and join gateways and returns the join gateway id
+    fn branches(&mut self, id: &str, step: &Step) -> Result<String> {
+        let parallel = step
+            .branches
+            .iter()
+            .all(|b| b.r#if.as_ref().is_some_and(|expr| expr.trim() == "true"));
+        let tag = if parallel {
+            "parallelGateway"
+        } else {
+            "exclusiveGateway"
+        };
+
+        let split = self.unique(&format!("{id}_split"));
+        let join = self.unique(&format!("{id}_join"));
+        let flow_ids = step
+            .branches
+            .iter()
+            .map(|b| self.unique(if b.id.is_empty() { "branch" } else { &b.id }))
+            .collect::<Vec<_>>();
+
+        let default = step
+            .branches
+            .iter()
+            .zip(flow_ids.iter())
+            .find(|(b, _)| !parallel && b.r#else)
+            .map(|(_, flow)| format!(" default=\"{}\"", escape(flow)))
+            .unwrap_or_default();
+        self.elements.push(format!(
+            "    <bpmn:{tag} id=\"{}\" gatewayDirection=\"Diverging\"{default} />",
+            escape(&split)
+        ));
+        self.flow(id, &split, None)?;
+
+        for (branch, flow_id) in step.branches.iter().zip(flow_ids.iter()) {
+            self.steps(&branch.steps, &split, &join, Some((flow_id, branch)))?;
+        }
+
+        self.elements.push(format!(
+            "    <bpmn:{tag} id=\"{}\" gatewayDirection=\"Converging\" />",
+            escape(&join)
+        ));
+        Ok(join)
+    }
+
+    fn flow(&mut self, source: &str, target: &str, branch: Option<(&str, &Branch)>) -> Result<()> {
+        let id = match branch {
+            Some((id, _)) => id.to_string(),
+            None => self.unique("flow"),
+        };
+        let name = branch.map(|(_, b)| name_attr(&b.name)).unwrap_or_default();
+        let head = format!(
+            "    <bpmn:sequenceFlow id=\"{}\"{name} sourceRef=\"{}\" targetRef=\"{}\"",
+            escape(&id),
+            escape(source),
+            escape(target)
+        );
+
+        match branch {
+            Some((_, branch)) => {
+                let mut data = branch.clone();
+                data.steps.clear();
+                self.flows.push(format!("{head}>"));
+                self.flows.push(extension("      ", "branch", &data)?);
+                if let Some(expr) = &branch.r#if {
+                    self.flows.push(format!(
+                        "      <bpmn:conditionExpression>{}</bpmn:conditionExpression>",
+                        escape(expr)
+                    ));
+                }
+                self.flows.push("    </bpmn:sequenceFlow>".to_string());
+            }
+            None => self.flows.push(format!("{head} />")),
+        }
+        Ok(())
+    }
+
+    fn catch(&mut self, step_id: &str, index: usize, catch: &Catch) -> Result<()> {
+        let id = self.unique(&format!("{step_id}_catch_{index}"));
+        let error_ref = match &catch.on {
+            Some(code) => {
+                let error_id = match self.errors.iter().find(|(_, c)| c == code) {
+                    Some((id, _)) => id.clone(),
+                    None => {
+                        let id = self.unique(&format!("error_{code}"));
+                        self.errors.push((id.clone(), code.clone()));
+                        id
+                    }
+                };
+                format!(" errorRef=\"{}\"", escape(&error_id))
+            }
+            None => String::new(),
+        };
+
+        self.elements.push(format!(
+            "    <bpmn:boundaryEvent id=\"{}\" attachedToRef=\"{}\">",
+            escape(&id),
+            escape(step_id)
+        ));
+        self.elements.push(extension("      ", "catch", catch)?);
+        self.elements
+            .push(format!("      <bpmn:errorEventDefinition{error_ref} />"));
+        self.elements.push("    </bpmn:boundaryEvent>".to_string());
+        Ok(())
+    }
+
+    fn timeout(&mut self, step_id: &str, index: usize, timeout: &Timeout) -> Result<()> {
+        let id = self.unique(&format!("{step_id}_timeout_{index}"));
+        self.elements.push(format!(
+            "    <bpmn:boundaryEvent id=\"{}\" attachedToRef=\"{}\" cancelActivity=\"false\">",
+            escape(&id),
+            escape(step_id)
+        ));
+        self.elements.push(extension("      ", "timeout", timeout)?);
+        self.elements
+            .push("      <bpmn:timerEventDefinition>".to_string());
+        self.elements.push(format!(
+            "        <bpmn:timeDuration>{}</bpmn:timeDuration>",
+            iso_duration(&timeout.on)
+        ));
+        self.elements
+            .push("      </bpmn:timerEventDefinition>".to_string());
+        self.elements.push("    </bpmn:boundaryEvent>".to_string());
+        Ok(())
+    }
+}
+
+fn name_attr(name: &str) -> String {
+    if name.is_empty() {
+        return String::new();
+    }
+    format!(" name=\"{}\"", escape(name))
+}
+
+fn extension<T: Serialize>(indent: &str, name: &str, data: &T) -> Result<String> {
+    let json = serde_json::to_string(data)?;
+    Ok(format!(
+        "{indent}<bpmn:extensionElements>\n{indent}  <acts:{name}>{}</acts:{name}>\n{indent}</bpmn:extensionElements>",
+        escape(&json)
+    ))
+}
+
+pub fn iso_duration(limit: &TimeoutLimit) -> String {
+    match limit.unit {
+        TimeoutUnit::Second => format!("PT{}S", limit.value),
+        TimeoutUnit::Minute => format!("PT{}M", limit.value),
+        TimeoutUnit::Hour => format!("PT{}H", limit.value),
+        TimeoutUnit::Day => format!("P{}D", limit.value),
+    }
+}
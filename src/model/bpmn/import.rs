@@ -0,0 +1,467 @@
+use super::xml::XmlNode;
+use crate::{
+    model::act::{TimeoutLimit, TimeoutUnit},
+    Act, ActError, Branch, Catch, Result, Step, Timeout, Workflow,
+};
+use regex::Regex;
+use serde::de::DeserializeOwned;
+use std::collections::{HashMap, HashSet};
+
+const TASKS: [&str; 6] = [
+    "task",
+    "userTask",
+    "sendTask",
+    "scriptTask",
+    "manualTask",
+    "callActivity",
+];
+const GATEWAYS: [&str; 2] = ["exclusiveGateway", "parallelGateway"];
+const IGNORED: [&str; 8] = [
+    "extensionElements",
+    "documentation",
+    "laneSet",
+    "textAnnotation",
+    "association",
+    "dataObject",
+    "dataObjectReference",
+    "dataStoreReference",
+];
+
+/// reads the first process of the bpmn 2.0 xml as workflow
+/// the bpmn structure is the source of truth, the acts extension elements only keep the data
+/// which can not be expressed by bpmn, such as the inputs, outputs and setup acts
+pub fn from_bpmn(text: &str) -> Result<Workflow> {
+    let root = XmlNode::parse(text)?;
+    if root.name != "definitions" {
+        return Err(ActError::Model(format!(
+            "the bpmn root element should be 'definitions' instead of '{}'",
+            root.name
+        )));
+    }
+    let process = root
+        .child("process")
+        .ok_or(ActError::Model("cannot find the bpmn process".to_string()))?;
+
+    let errors = root
+        .children
+        .iter()
+        .filter(|n| n.name == "error")
+        .filter_map(|n| {
+            let id = n.attr("id")?;
+            Some((
+                id.to_string(),
+                n.attr("errorCode").unwrap_or(id).to_string(),
+            ))
+        })
+        .collect::<HashMap<_, _>>();
+
+    let mut reader = Reader::new(process, errors)?;
+    let mut workflow: Workflow = match extension(process, "workflow")? {
+        Some(data) => data,
+        None => Workflow::new().with_id(attr(process, "id")),
+    };
+    workflow.name = attr(process, "name").to_string();
+
+    let start = process
+        .children
+        .iter()
+        .find(|n| n.name == "startEvent")
+        .ok_or(ActError::Model(
+            "cannot find the bpmn startEvent".to_string(),
+        ))?;
+    let first = reader.target(attr(start, "id"))?;
+    let (steps, _) = reader.walk(first)?;
+    workflow.steps = steps;
+
+    Ok(workflow)
+}
+
+/// the stop reason of walking the step list
+enum Stop {
+    End,
+    Join(String),
+    Jump(String),
+}
+
+struct Reader<'a> {
+    elements: HashMap<&'a str, &'a XmlNode>,
+    flows: Vec<&'a XmlNode>,
+    boundaries: Vec<&'a XmlNode>,
+    errors: HashMap<String, String>,
+    visited: HashSet<String>,
+    /// the model id of the visited steps by element id
+    steps_of: HashMap<String, String>,
+}
+
+impl<'a> Reader<'a> {
+    fn new(process: &'a XmlNode, errors: HashMap<String, String>) -> Result<Self> {
+        let mut unsupported = Vec::new();
+        let mut elements = HashMap::new();
+        let mut flows = Vec::new();
+        let mut boundaries = Vec::new();
+        for node in &process.children {
+            let name = node.name.as_str();
+            let id = attr(node, "id");
+            match name {
+                "sequenceFlow" => flows.push(node),
+                "boundaryEvent" => {
+                    if node.child("timerEventDefinition").is_none()
+                        && node.child("errorEventDefinition").is_none()
+                    {
+                        let def = node
+                            .children
+                            .iter()
+                            .find(|c| c.name.ends_with("EventDefinition"))
+                            .map(|c| c.name.as_str())
+                            .unwrap_or("none");
+                        unsupported.push(format!("boundaryEvent '{id}' with {def}"));
+                    }
+                    boundaries.push(node);
+                    elements.insert(id, node);
+                }
+                "startEvent" | "endEvent" => {
+                    elements.insert(id, node);
+                }
+                _ if TASKS.contains(&name) || GATEWAYS.contains(&name) => {
+                    elements.insert(id, node);
+                }
+                _ if IGNORED.contains(&name) => {}
+                _ => unsupported.push(format!("{name} '{id}'")),
+            }
+        }
+
+        if !unsupported.is_empty() {
+            return Err(ActError::Model(format!(
+                "unsupported bpmn elements: {}",
+                unsupported.join(", ")
+            )));
+        }
+
+        Ok(Self {
+            elements,
+            flows,
+            boundaries,
+            errors,
+            visited: HashSet::new(),
+            steps_of: HashMap::new(),
+        })
+    }
+
+    fn element(&self, id: &str) -> Result<&'a XmlNode> {
+        self.elements
+            .get(id)
+            .copied()
+            .ok_or(ActError::Model(format!("cannot find bpmn element '{id}'")))
+    }
+
+    fn outgoing(&self, id: &str) -> Vec<&'a XmlNode> {
+        self.flows
+            .iter()
+            .filter(|f| f.attr("sourceRef") == Some(id))
+            .copied()
+            .collect()
+    }
+
+    /// gets the target of the only outgoing flow
+    fn target(&self, id: &str) -> Result<Option<String>> {
+        let flows = self.outgoing(id);
+        if flows.len() > 1 {
+            return Err(ActError::Model(format!(
+                "the bpmn element '{id}' has {} outgoing flows, please use a gateway instead",
+                flows.len()
+            )));
+        }
+        Ok(flows.first().map(|f| attr(f, "targetRef").to_string()))
+    }
+
+    fn is_join(&self, node: &XmlNode) -> bool {
+        if !GATEWAYS.contains(&node.name.as_str()) {
+            return false;
+        }
+        match node.attr("gatewayDirection") {
+            Some("Converging") => true,
+            Some("Diverging") => false,
+            _ => self.outgoing(attr(node, "id")).len() <= 1,
+        }
+    }
+
+    fn walk(&mut self, first: Option<String>) -> Result<(Vec<Step>, Stop)> {
+        let mut steps: Vec<(Step, bool)> = Vec::new();
+        let mut current = first;
+        let mut stop = Stop::End;
+        while let Some(id) = current.take() {
+            let node = self.element(&id)?;
+            if node.name == "endEvent" {
+                break;
+            }
+            if self.is_join(node) {
+                stop = Stop::Join(id);
+                break;
+            }
+            if self.visited.contains(&id) {
+                let target = self.steps_of.get(&id).cloned().unwrap_or(id);
+                match steps.last_mut() {
+                    Some((step, false)) => step.next = Some(target),
+                    Some(_) => {}
+                    None => stop = Stop::Jump(target),
+                }
+                break;
+            }
+            self.visited.insert(id.clone());
+
+            let (mut step, ext) = if GATEWAYS.contains(&node.name.as_str()) {
+                // the split gateway without task is a step only with branches
+                let step = Step::new()
+                    .with_id(&id)
+                    .with_name(node.attr("name").unwrap_or_default());
+                (step, false)
+            } else if TASKS.contains(&node.name.as_str()) {
+                self.step(node)?
+            } else {
+                return Err(ActError::Model(format!(
+                    "the bpmn element '{}' is not allowed in sequence flow",
+                    node.name
+                )));
+            };
+            self.steps_of.insert(id.clone(), step.id.clone());
+
+            let split = if GATEWAYS.contains(&node.name.as_str()) {
+                Some(id.clone())
+            } else {
+                match self.target(&id)? {
+                    Some(target)
+                        if !self.visited.contains(&target)
+                            && GATEWAYS.contains(&self.element(&target)?.name.as_str())
+                            && !self.is_join(self.element(&target)?) =>
+                    {
+                        self.visited.insert(target.clone());
+                        Some(target)
+                    }
+                    target => {
+                        current = target;
+                        None
+                    }
+                }
+            };
+            if let Some(split) = split {
+                current = self.branches(&mut step, &split)?;
+            }
+            steps.push((step, ext));
+        }
+
+        Ok((steps.into_iter().map(|(s, _)| s).collect(), stop))
+    }
+
+    /// reads the branches from the split gateway and returns the target after joining
+    fn branches(&mut self, step: &mut Step, split: &str) -> Result<Option<String>> {
+        let gateway = self.element(split)?;
+        let parallel = gateway.name == "parallelGateway";
+        let default = gateway.attr("default");
+
+        let mut join = None;
+        for flow in self.outgoing(split) {
+            let id = attr(flow, "id");
+            let mut branch = match extension::<Branch>(flow, "branch")? {
+                Some(data) => data,
+                None => Branch::new().with_id(id),
+            };
+            branch.name = attr(flow, "name").to_string();
+            branch.r#else = !parallel && default == Some(id);
+            let expr = if parallel {
+                Some("true".to_string())
+            } else {
+                flow.child("conditionExpression")
+                    .map(|expr| expr.text.trim().to_string())
+            };
+            // the extension keeps the original whitespaces of the same condition
+            if branch.r#if.as_deref().map(str::trim) != expr.as_deref() {
+                branch.r#if = expr;
+            }
+            let ext = flow.child("extensionElements").is_some();
+
+            let target = attr(flow, "targetRef").to_string();
+            let (steps, stop) = self.walk(Some(target))?;
+            branch.steps = steps;
+            match stop {
+                Stop::Join(id) => join = Some(id),
+                Stop::Jump(target) if !ext => branch.next = Some(target),
+                _ => {}
+            }
+            step.branches.push(branch);
+        }
+
+        match join {
+            Some(join) => self.target(&join),
+            None => Ok(None),
+        }
+    }
+
+    fn step(&self, node: &XmlNode) -> Result<(Step, bool)> {
+        let id = attr(node, "id");
+        let (mut step, ext) = match extension::<Step>(node, "step")? {
+            Some(step) => (step, true),
+            None => (Step::new().with_id(id), false),
+        };
+        step.name = attr(node, "name").to_string();
+        match node.name.as_str() {
+            "scriptTask" => {
+                step.run = node.child("script").map(|s| s.text.clone());
+            }
+            _ => {
+                if let Some(act) = self.act(node) {
+                    match step.acts.as_mut_slice() {
+                        // keeps the act data and follows the task type and the called element
+                        [data] => {
+                            data.act = act.act;
+                            if node.name == "callActivity" {
+                                data.key = act.key;
+                            }
+                        }
+                        _ => step.acts = vec![act],
+                    }
+                }
+            }
+        }
+
+        for boundary in self
+            .boundaries
+            .iter()
+            .filter(|b| b.attr("attachedToRef") == Some(id))
+        {
+            if let Some(def) = boundary.child("errorEventDefinition") {
+                let mut catch = match extension::<Catch>(boundary, "catch")? {
+                    Some(data) => data,
+                    None => {
+                        let mut catch = Catch::new();
+                        catch.then = self.then(boundary)?;
+                        catch
+                    }
+                };
+                catch.on = def
+                    .attr("errorRef")
+                    .map(|r| self.errors.get(r).cloned().unwrap_or(r.to_string()));
+                step.catches.push(catch);
+            } else if let Some(def) = boundary.child("timerEventDefinition") {
+                let text = def
+                    .child("timeDuration")
+                    .map(|d| d.text.trim())
+                    .unwrap_or_default();
+                let on = parse_duration(text)?;
+                let timeout = match extension::<Timeout>(boundary, "timeout")? {
+                    Some(data) => Timeout { on, ..data },
+                    None => Timeout {
+                        on,
+                        then: self.then(boundary)?,
+                    },
+                };
+                step.timeout.push(timeout);
+            }
+        }
+
+        Ok((step, ext))
+    }
+
+    fn act(&self, node: &XmlNode) -> Option<Act> {
+        let id = attr(node, "id");
+        match node.name.as_str() {
+            "userTask" => Some(Act::irq(|act| act.with_key(id))),
+            "sendTask" => Some(Act::msg(|act| act.with_key(id))),
+            "callActivity" => {
+                let key = node.attr("calledElement").unwrap_or(id);
+                Some(Act::call(|act| act.with_key(key)))
+            }
+            _ => None,
+        }
+    }
+
+    /// reads the acts from the tasks after the boundary event
+    fn then(&self, boundary: &XmlNode) -> Result<Vec<Act>> {
+        let mut acts = Vec::new();
+        let mut visited = HashSet::new();
+        let mut current = self.target(attr(boundary, "id"))?;
+        while let Some(id) = current.take() {
+            if !visited.insert(id.clone()) {
+                break;
+            }
+            let node = self.element(&id)?;
+            if !TASKS.contains(&node.name.as_str()) {
+                break;
+            }
+            if let Some(act) = self.act(node) {
+                acts.push(act);
+            }
+            current = self.target(&id)?;
+        }
+        Ok(acts)
+    }
+}
+
+fn attr<'a>(node: &'a XmlNode, name: &str) -> &'a str {
+    node.attr(name).unwrap_or_default()
+}
+
+fn extension<T: DeserializeOwned>(node: &XmlNode, name: &str) -> Result<Option<T>> {
+    match node
+        .child("extensionElements")
+        .and_then(|ext| ext.child(name))
+    {
+        Some(data) => {
+            let value = serde_json::from_str::<T>(data.text.trim()).map_err(|err| {
+                ActError::Model(format!(
+                    "the acts {name} data of bpmn element '{}' is invalid: {err}",
+                    attr(node, "id")
+                ))
+            })?;
+            Ok(Some(value))
+        }
+        None => Ok(None),
+    }
+}
+
+/// parses the iso 8601 duration such as 'PT2H' or the acts timeout such as '2h'
+pub fn parse_duration(text: &str) -> Result<TimeoutLimit> {
+    if !text.starts_with('P') {
+        return TimeoutLimit::parse(text);
+    }
+
+    let re = Regex::new(r"^P(?:(\d+)D)?(?:T(?:(\d+)H)?(?:(\d+)M)?(?:(\d+)S)?)?$").unwrap();
+    let caps = re.captures(text).ok_or(ActError::Model(format!(
+        "timeout parse error with '{text}'"
+    )))?;
+    let parts = [
+        TimeoutUnit::Day,
+        TimeoutUnit::Hour,
+        TimeoutUnit::Minute,
+        TimeoutUnit::Second,
+    ]
+    .into_iter()
+    .enumerate()
+    .filter_map(|(index, unit)| {
+        caps.get(index + 1)
+            .map(|m| (m.as_str().parse::<i64>().unwrap_or(0), unit))
+    })
+    .collect::<Vec<_>>();
+
+    match parts.as_slice() {
+        [] => Err(ActError::Model(format!(
+            "timeout parse error with '{text}'"
+        ))),
+        [(value, unit)] => Ok(TimeoutLimit {
+            value: *value,
+            unit: unit.clone(),
+        }),
+        _ => Ok(TimeoutLimit {
+            value: parts
+                .iter()
+                .map(|(value, unit)| {
+                    TimeoutLimit {
+                        value: *value,
+                        unit: unit.clone(),
+                    }
+                    .as_secs()
+                })
+                .sum(),
+            unit: TimeoutUnit::Second,
+        }),
+    }
+}
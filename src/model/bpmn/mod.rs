@@ -0,0 +1,9 @@
+mod export;
+mod import;
+mod xml;
+
+pub use export::to_bpmn;
+pub use import::from_bpmn;
+
+const BPMN_NS: &str = "http://www.omg.org/spec/BPMN/20100524/MODEL";
+const ACTS_NS: &str = "https://github.com/yaojianpin/acts";
@@ -0,0 +1,105 @@
+use crate::{ActError, Result};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+/// a simple xml element tree, the names are the local names without namespace prefix
+#[derive(Debug, Default, Clone)]
+pub struct XmlNode {
+    pub name: String,
+    pub attrs: Vec<(String, String)>,
+    pub children: Vec<XmlNode>,
+    pub text: String,
+}
+
+impl XmlNode {
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut reader = Reader::from_str(text);
+        let mut stack: Vec<XmlNode> = vec![XmlNode::default()];
+        loop {
+            let event = reader.read_event().map_err(|err| {
+                ActError::Model(format!(
+                    "xml error at position {}: {err}",
+                    reader.error_position()
+                ))
+            })?;
+            match event {
+                Event::Start(e) => stack.push(XmlNode::from_start(&e)?),
+                Event::Empty(e) => {
+                    let node = XmlNode::from_start(&e)?;
+                    stack.last_mut().unwrap().children.push(node);
+                }
+                Event::End(_) => {
+                    let node = stack.pop().unwrap();
+                    match stack.last_mut() {
+                        Some(parent) => parent.children.push(node),
+                        None => {
+                            return Err(ActError::Model("xml error: unexpected end".to_string()))
+                        }
+                    }
+                }
+                Event::Text(e) => {
+                    let text = e
+                        .unescape()
+                        .map_err(|err| ActError::Model(err.to_string()))?;
+                    stack.last_mut().unwrap().text.push_str(&text);
+                }
+                Event::CData(e) => {
+                    let text = String::from_utf8_lossy(&e.into_inner()).to_string();
+                    stack.last_mut().unwrap().text.push_str(&text);
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+
+        let mut root = stack.pop().unwrap();
+        if !stack.is_empty() || root.children.is_empty() {
+            return Err(ActError::Model(
+                "xml error: missing root element".to_string(),
+            ));
+        }
+        Ok(root.children.remove(0))
+    }
+
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn child(&self, name: &str) -> Option<&XmlNode> {
+        self.children.iter().find(|c| c.name == name)
+    }
+
+    fn from_start(e: &BytesStart) -> Result<Self> {
+        let name = String::from_utf8_lossy(e.local_name().as_ref()).to_string();
+        let mut attrs = Vec::new();
+        for attr in e.attributes() {
+            let attr = attr.map_err(|err| ActError::Model(err.to_string()))?;
+            // skips the namespace declarations
+            if attr.key.as_ref().starts_with(b"xmlns") {
+                continue;
+            }
+            let key = String::from_utf8_lossy(attr.key.local_name().as_ref()).to_string();
+            let value = attr
+                .unescape_value()
+                .map_err(|err| ActError::Model(err.to_string()))?;
+            attrs.push((key, value.to_string()));
+        }
+
+        Ok(Self {
+            name,
+            attrs,
+            ..Default::default()
+        })
+    }
+}
+
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
@@ -0,0 +1,351 @@
+use crate::{Act, ActError, Catch, Result, Step, Vars};
+use regex::Regex;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::collections::{HashMap, HashSet};
+
+/// the act name to include the fragment acts
+pub const INCLUDE_ACT: &str = "include";
+
+/// the vars which are not the model nodes
+const VAR_KEYS: [&str; 5] = ["inputs", "outputs", "rets", "env", "params"];
+
+/// the reusable model fragment
+/// it is included by `include` in steps and catches or by the `include` act in act list
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Fragment {
+    #[serde(default)]
+    pub id: String,
+
+    #[serde(default)]
+    pub name: String,
+
+    /// the fragment params with default values, which can be used by `{{ name }}`
+    #[serde(default)]
+    pub params: Vars,
+
+    #[serde(default)]
+    pub steps: Vec<Step>,
+
+    #[serde(default)]
+    pub acts: Vec<Act>,
+
+    #[serde(default)]
+    pub catches: Vec<Catch>,
+}
+
+impl Fragment {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn from_yml(s: &str) -> Result<Self> {
+        serde_yaml::from_str::<Fragment>(s).map_err(|err| ActError::Model(err.to_string()))
+    }
+
+    pub fn to_yml(&self) -> Result<String> {
+        serde_yaml::to_string(self).map_err(|err| ActError::Model(err.to_string()))
+    }
+
+    pub fn with_id(mut self, id: &str) -> Self {
+        self.id = id.to_string();
+        self
+    }
+
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
+    pub fn with_param<T: Serialize + Clone>(mut self, name: &str, value: T) -> Self {
+        self.params.set(name, value);
+        self
+    }
+
+    pub fn with_step(mut self, build: fn(Step) -> Step) -> Self {
+        self.steps.push(build(Step::default()));
+        self
+    }
+
+    pub fn with_act(mut self, act: Act) -> Self {
+        self.acts.push(act);
+        self
+    }
+
+    pub fn with_catch(mut self, build: fn(Catch) -> Catch) -> Self {
+        self.catches.push(build(Catch::default()));
+        self
+    }
+
+    /// gets the fragment ids which are included by the fragment
+    pub fn includes(&self) -> Vec<String> {
+        includes(self)
+    }
+}
+
+/// expands the includes by the fragments
+pub(crate) struct Expander<'a> {
+    fragments: &'a HashMap<String, Fragment>,
+    prefixes: HashSet<String>,
+    stack: Vec<String>,
+}
+
+impl<'a> Expander<'a> {
+    pub fn new(fragments: &'a HashMap<String, Fragment>) -> Self {
+        Self {
+            fragments,
+            prefixes: HashSet::new(),
+            stack: Vec::new(),
+        }
+    }
+
+    pub fn steps(&mut self, steps: Vec<Step>) -> Result<Vec<Step>> {
+        let mut ret = Vec::new();
+        for mut step in steps {
+            if let Some(id) = &step.include {
+                let fragment = self.enter(id)?;
+                let steps = self.steps(fragment.steps.clone())?;
+                self.stack.pop();
+                let prefix = self.prefix(&step.id, &fragment.id);
+                ret.extend(instance(&fragment, &steps, &step.inputs, &prefix)?);
+                continue;
+            }
+
+            for branch in step.branches.iter_mut() {
+                branch.steps = self.steps(std::mem::take(&mut branch.steps))?;
+            }
+            step.acts = self.acts(std::mem::take(&mut step.acts))?;
+            step.setup = self.acts(std::mem::take(&mut step.setup))?;
+            step.catches = self.catches(std::mem::take(&mut step.catches))?;
+            for timeout in step.timeout.iter_mut() {
+                timeout.then = self.acts(std::mem::take(&mut timeout.then))?;
+            }
+            ret.push(step);
+        }
+
+        Ok(ret)
+    }
+
+    pub fn acts(&mut self, acts: Vec<Act>) -> Result<Vec<Act>> {
+        let mut ret = Vec::new();
+        for mut act in acts {
+            if act.act == INCLUDE_ACT {
+                let fragment = self.enter(&act.key)?;
+                let acts = self.acts(fragment.acts.clone())?;
+                self.stack.pop();
+                let prefix = self.prefix(&act.id, &fragment.id);
+                ret.extend(instance(&fragment, &acts, &act.inputs, &prefix)?);
+                continue;
+            }
+
+            self.act(&mut act)?;
+            ret.push(act);
+        }
+
+        Ok(ret)
+    }
+
+    fn act(&mut self, act: &mut Act) -> Result<()> {
+        act.then = self.acts(std::mem::take(&mut act.then))?;
+        act.r#else = self.acts(std::mem::take(&mut act.r#else))?;
+        act.setup = self.acts(std::mem::take(&mut act.setup))?;
+        act.catches = self.catches(std::mem::take(&mut act.catches))?;
+        for timeout in act.timeout.iter_mut() {
+            timeout.then = self.acts(std::mem::take(&mut timeout.then))?;
+        }
+        if let Some(next) = act.next.as_mut() {
+            self.act(next)?;
+        }
+        Ok(())
+    }
+
+    pub fn catches(&mut self, catches: Vec<Catch>) -> Result<Vec<Catch>> {
+        let mut ret = Vec::new();
+        for mut catch in catches {
+            if let Some(id) = &catch.include {
+                let fragment = self.enter(id)?;
+                let catches = self.catches(fragment.catches.clone())?;
+                self.stack.pop();
+                let prefix = self.prefix("", &fragment.id);
+                ret.extend(instance(&fragment, &catches, &catch.inputs, &prefix)?);
+                continue;
+            }
+
+            catch.then = self.acts(std::mem::take(&mut catch.then))?;
+            ret.push(catch);
+        }
+
+        Ok(ret)
+    }
+
+    fn enter(&mut self, id: &str) -> Result<Fragment> {
+        if self.stack.iter().any(|s| s == id) {
+            return Err(ActError::Model(format!(
+                "found fragment cycle '{} -> {id}'",
+                self.stack.join(" -> ")
+            )));
+        }
+        let fragment = self
+            .fragments
+            .get(id)
+            .ok_or(ActError::Model(format!("cannot find fragment '{id}'")))?;
+        self.stack.push(id.to_string());
+        Ok(fragment.clone())
+    }
+
+    /// gets the unique id prefix, uses the fragment id if the include id is empty
+    fn prefix(&mut self, id: &str, fragment: &str) -> String {
+        let base = if id.is_empty() { fragment } else { id };
+        let mut prefix = base.to_string();
+        let mut index = 2;
+        while self.prefixes.contains(&prefix) {
+            prefix = format!("{base}{index}");
+            index += 1;
+        }
+        self.prefixes.insert(prefix.clone());
+        prefix
+    }
+}
+
+/// gets the referenced fragment ids of the model part
+pub(crate) fn includes<T: Serialize>(data: &T) -> Vec<String> {
+    fn walk(value: &JsonValue, ret: &mut Vec<String>) {
+        match value {
+            JsonValue::Object(map) => {
+                if let Some(JsonValue::String(id)) = map.get("include") {
+                    ret.push(id.clone());
+                }
+                if map.get("act").and_then(|v| v.as_str()) == Some(INCLUDE_ACT) {
+                    if let Some(JsonValue::String(id)) = map.get("key") {
+                        ret.push(id.clone());
+                    }
+                }
+                for (key, v) in map {
+                    if !VAR_KEYS.contains(&key.as_str()) {
+                        walk(v, ret);
+                    }
+                }
+            }
+            JsonValue::Array(items) => items.iter().for_each(|v| walk(v, ret)),
+            _ => {}
+        }
+    }
+
+    let mut ret = Vec::new();
+    if let Ok(value) = serde_json::to_value(data) {
+        walk(&value, &mut ret);
+    }
+    ret.dedup();
+    ret
+}
+
+/// creates the fragment part instance by replacing the params and prefixing the ids
+/// the nested includes are expanded before, so the outer params can still be passed in
+fn instance<T: Serialize + DeserializeOwned>(
+    fragment: &Fragment,
+    part: &T,
+    params: &Vars,
+    prefix: &str,
+) -> Result<T> {
+    let mut vars = fragment.params.clone();
+    for (name, value) in params {
+        vars.insert(name, value);
+    }
+
+    let mut value = serde_json::to_value(part)?;
+    replace(&mut value, &vars);
+
+    let mut ids = HashSet::new();
+    collect_ids(&value, &mut ids);
+    prefix_ids(&mut value, &ids, prefix);
+
+    serde_json::from_value::<T>(value).map_err(|err| ActError::Model(err.to_string()))
+}
+
+/// replaces the declared params, the other `{{ name }}` text such as the mustache
+/// templates in the scripts and messages is kept as it is
+fn replace(value: &mut JsonValue, params: &Vars) {
+    let re = Regex::new(r"\{\{\s*([A-Za-z_][A-Za-z0-9_]*)\s*\}\}").unwrap();
+    match value {
+        JsonValue::String(text) => {
+            let get = |name: &str| params.get_value(name).cloned();
+
+            // keeps the param type if the whole string is the param
+            if let Some(caps) = re.captures(text) {
+                if caps.get(0).unwrap().as_str() == text.trim() {
+                    if let Some(v) = get(caps.get(1).unwrap().as_str()) {
+                        *value = v;
+                        return;
+                    }
+                }
+            }
+
+            let ret = re.replace_all(text, |caps: &regex::Captures| {
+                match get(caps.get(1).unwrap().as_str()) {
+                    Some(JsonValue::String(s)) => s,
+                    Some(v) => v.to_string(),
+                    None => caps.get(0).unwrap().as_str().to_string(),
+                }
+            });
+            *text = ret.to_string();
+        }
+        JsonValue::Array(items) => {
+            for v in items.iter_mut() {
+                replace(v, params);
+            }
+        }
+        JsonValue::Object(map) => {
+            for (_, v) in map.iter_mut() {
+                replace(v, params);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn collect_ids(value: &JsonValue, ids: &mut HashSet<String>) {
+    match value {
+        JsonValue::Object(map) => {
+            if let Some(JsonValue::String(id)) = map.get("id") {
+                if !id.is_empty() {
+                    ids.insert(id.clone());
+                }
+            }
+            for (key, v) in map {
+                if !VAR_KEYS.contains(&key.as_str()) {
+                    collect_ids(v, ids);
+                }
+            }
+        }
+        JsonValue::Array(items) => items.iter().for_each(|v| collect_ids(v, ids)),
+        _ => {}
+    }
+}
+
+fn prefix_ids(value: &mut JsonValue, ids: &HashSet<String>, prefix: &str) {
+    let rename = |id: &mut String| {
+        if ids.contains(id.as_str()) {
+            *id = format!("{prefix}.{id}");
+        }
+    };
+    match value {
+        JsonValue::Object(map) => {
+            for (key, v) in map.iter_mut() {
+                match (key.as_str(), v) {
+                    ("id" | "next", JsonValue::String(id)) => rename(id),
+                    ("needs", JsonValue::Array(items)) => {
+                        for item in items.iter_mut() {
+                            if let JsonValue::String(id) = item {
+                                rename(id);
+                            }
+                        }
+                    }
+                    (key, v) if !VAR_KEYS.contains(&key) => prefix_ids(v, ids, prefix),
+                    _ => {}
+                }
+            }
+        }
+        JsonValue::Array(items) => items.iter_mut().for_each(|v| prefix_ids(v, ids, prefix)),
+        _ => {}
+    }
+}
@@ -1,7 +1,7 @@
 use crate::{
-    sch::{self, NodeData},
+    sch::{self, NodeData, NodeRef},
     store::data,
-    ActError, Result, Workflow,
+    ActError, Manifest, Result, Workflow,
 };
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
@@ -15,6 +15,7 @@ pub struct PackageInfo {
     pub update_time: i64,
     pub timestamp: i64,
     pub data: String,
+    pub manifest: Manifest,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -26,6 +27,9 @@ pub struct ProcInfo {
     pub start_time: i64,
     pub end_time: i64,
     pub timestamp: i64,
+    /// the parent proc id which starts the proc by `call` act, it is empty for the root proc
+    #[serde(default)]
+    pub parent_id: String,
     pub tasks: Vec<TaskInfo>,
 }
 
@@ -46,6 +50,15 @@ pub struct TaskInfo {
     pub timestamp: i64,
 }
 
+/// the captured script log of a task
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct LogInfo {
+    /// the log level, such as `log`, `info`, `warn` and `error`
+    pub level: String,
+    pub message: String,
+    pub time: i64,
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct ModelInfo {
     pub id: String,
@@ -87,6 +100,7 @@ impl From<&data::Package> for PackageInfo {
             create_time: m.create_time,
             update_time: m.update_time,
             data: String::from_utf8(m.data.clone()).unwrap(),
+            manifest: m.manifest.clone(),
         }
     }
 }
@@ -134,6 +148,7 @@ impl From<&data::Proc> for ProcInfo {
             start_time: p.start_time,
             end_time: p.end_time,
             timestamp: p.timestamp,
+            parent_id: p.parent_id.clone(),
             tasks: Vec::new(),
         }
     }
@@ -141,21 +156,27 @@ impl From<&data::Proc> for ProcInfo {
 
 impl From<data::Task> for TaskInfo {
     fn from(t: data::Task) -> Self {
-        let node_data: NodeData = serde_json::from_str(&t.node_data).unwrap();
+        let (nid, key, tag) = match serde_json::from_str::<NodeData>(&t.node_data) {
+            Ok(data) => (data.id, data.content.key(), data.content.tag()),
+            Err(_) => {
+                let data: NodeRef = serde_json::from_str(&t.node_data).unwrap_or_default();
+                (data.id, data.key, data.tag)
+            }
+        };
         Self {
             id: t.tid,
             prev: t.prev,
             name: t.name,
             pid: t.pid,
-            nid: node_data.id,
+            nid,
             r#type: t.kind,
             state: t.state,
             data: t.data,
             start_time: t.start_time,
             end_time: t.end_time,
             timestamp: t.timestamp,
-            key: node_data.content.key(),
-            tag: node_data.content.tag(),
+            key,
+            tag,
         }
     }
 }
@@ -239,3 +260,9 @@ impl From<MessageInfo> for serde_json::Value {
         serde_json::to_value(val).unwrap()
     }
 }
+
+impl From<LogInfo> for serde_json::Value {
+    fn from(val: LogInfo) -> Self {
+        serde_json::to_value(val).unwrap()
+    }
+}
@@ -0,0 +1,125 @@
+use crate::{Act, ActError, Result, Step, Vars, Workflow};
+
+/// splits the `extends` value to the base model id and the pinned version
+/// the format is `{id}` or `{id}@{ver}`
+pub fn parse_extends(value: &str) -> (&str, Option<u32>) {
+    match value.rsplit_once('@') {
+        Some((id, ver)) => match ver.parse::<u32>() {
+            Ok(ver) => (id, Some(ver)),
+            Err(_) => (value, None),
+        },
+        None => (value, None),
+    }
+}
+
+/// merges the child model to the resolved base model
+/// the child steps override the base steps with the same id, the new steps are inserted
+/// after the previous child step, and the `removes` steps are removed from the base
+pub fn merge(base: &Workflow, child: &Workflow) -> Result<Workflow> {
+    let mut ret = base.clone();
+    ret.id = child.id.clone();
+    ret.set_ver(child.ver());
+    ret.extends = None;
+    ret.removes.clear();
+    if !child.name.is_empty() {
+        ret.name = child.name.clone();
+    }
+    if !child.tag.is_empty() {
+        ret.tag = child.tag.clone();
+    }
+    if !child.lang.is_empty() {
+        ret.lang = child.lang.clone();
+    }
+
+    merge_vars(&mut ret.env, &child.env);
+    merge_vars(&mut ret.inputs, &child.inputs);
+    merge_vars(&mut ret.outputs, &child.outputs);
+    for (name, output) in child.schema.iter() {
+        ret.schema.push(name, output);
+    }
+    merge_acts(&mut ret.setup, &child.setup);
+    for fragment in &child.fragments {
+        ret.fragments.retain(|f| f.id != fragment.id);
+        ret.fragments.push(fragment.clone());
+    }
+
+    for id in &child.removes {
+        if let Some((steps, index)) = locate(&mut ret.steps, id) {
+            steps.remove(index);
+        }
+    }
+    merge_steps(&mut ret.steps, &child.steps)?;
+
+    Ok(ret)
+}
+
+fn merge_vars(base: &mut Vars, child: &Vars) {
+    for (name, value) in child {
+        base.insert(name, value);
+    }
+}
+
+fn merge_acts(base: &mut Vec<Act>, child: &[Act]) {
+    for act in child {
+        match base
+            .iter_mut()
+            .find(|a| !act.id.is_empty() && a.id == act.id)
+        {
+            Some(a) => *a = act.clone(),
+            None => base.push(act.clone()),
+        }
+    }
+}
+
+fn merge_steps(base: &mut Vec<Step>, child: &[Step]) -> Result<()> {
+    // the new steps before the first overridden step are inserted before it
+    let mut pending = Vec::new();
+    // the anchor is the previous child step id and the count of steps inserted after it
+    let mut anchor: Option<(String, usize)> = None;
+    for step in child {
+        if !step.id.is_empty() {
+            if let Some((steps, index)) = locate(base, &step.id) {
+                steps[index] = step.clone();
+                for (i, s) in pending.drain(..).enumerate() {
+                    steps.insert(index + i, s);
+                }
+                anchor = Some((step.id.clone(), 0));
+                continue;
+            }
+        }
+
+        match anchor.as_mut() {
+            Some((id, count)) => {
+                let (steps, index) = locate(base, id).ok_or_else(|| {
+                    ActError::Model(format!(
+                        "cannot find step '{id}' to insert '{}' after",
+                        step.id
+                    ))
+                })?;
+                steps.insert(index + 1 + *count, step.clone());
+                *count += 1;
+                if !step.id.is_empty() {
+                    anchor = Some((step.id.clone(), 0));
+                }
+            }
+            None => pending.push(step.clone()),
+        }
+    }
+    base.extend(pending);
+    Ok(())
+}
+
+/// finds the step list and the index of the step in the steps and the branch steps
+fn locate<'a>(steps: &'a mut Vec<Step>, id: &str) -> Option<(&'a mut Vec<Step>, usize)> {
+    if let Some(index) = steps.iter().position(|s| s.id == id) {
+        return Some((steps, index));
+    }
+    for step in steps.iter_mut() {
+        for branch in step.branches.iter_mut() {
+            if let Some(ret) = locate(&mut branch.steps, id) {
+                return Some(ret);
+            }
+        }
+    }
+    None
+}
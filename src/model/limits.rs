@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+
+/// the sandbox limits for each script evaluation
+/// the limits in the model and package override the limits in the config
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Limits {
+    /// the max memory in bytes which can be allocated by the script
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory: Option<usize>,
+
+    /// the max stack size in bytes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stack: Option<usize>,
+
+    /// the max execution time in milliseconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+}
+
+impl Limits {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.memory.is_none() && self.stack.is_none() && self.timeout.is_none()
+    }
+
+    /// returns the limits which are overridden by the other limits
+    pub fn merge(&self, other: &Limits) -> Limits {
+        Limits {
+            memory: other.memory.or(self.memory),
+            stack: other.stack.or(self.stack),
+            timeout: other.timeout.or(self.timeout),
+        }
+    }
+
+    pub fn with_memory(mut self, memory: usize) -> Self {
+        self.memory = Some(memory);
+        self
+    }
+
+    pub fn with_stack(mut self, stack: usize) -> Self {
+        self.stack = Some(stack);
+        self
+    }
+
+    pub fn with_timeout(mut self, millis: u64) -> Self {
+        self.timeout = Some(millis);
+        self
+    }
+}
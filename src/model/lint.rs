@@ -0,0 +1,557 @@
+use super::schema;
+use crate::{env::Enviroment, utils, Act, Branch, Catch, Step, Timeout, Vars, Workflow};
+use core::fmt;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use yaml_rust2::{
+    parser::{Event, MarkedEventReceiver, Parser},
+    scanner::Marker,
+};
+
+/// the act names which can be recognized by the act parser
+pub(crate) const ACT_NAMES: [&str; 19] = [
+    "set",
+    "expose",
+    "irq",
+    "msg",
+    "cmd",
+    "each",
+    "chain",
+    "block",
+    "if",
+    "call",
+    "pack",
+    "include",
+    "on_created",
+    "on_timeout",
+    "on_updated",
+    "on_before_update",
+    "on_step",
+    "on_completed",
+    "on_catch",
+];
+
+/// the builtin act names and the names of the registered act handlers
+pub(crate) fn act_names(acts: &[String]) -> Vec<String> {
+    let mut names = ACT_NAMES
+        .iter()
+        .map(|name| name.to_string())
+        .collect::<Vec<_>>();
+    names.extend(acts.iter().cloned());
+    names
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    #[default]
+    Error,
+    Warning,
+}
+
+/// the lint result of the model
+/// the line and column are started from 1, it is 0 if the position is unknown
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    pub code: String,
+    pub severity: Severity,
+    pub message: String,
+    pub path: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{} [{}] {} ({})",
+            self.line, self.column, self.code, self.message, self.path
+        )
+    }
+}
+
+impl Diagnostic {
+    pub fn new(code: &str, path: &str, message: &str) -> Self {
+        Self {
+            code: code.to_string(),
+            path: path.to_string(),
+            message: message.to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn warning(code: &str, path: &str, message: &str) -> Self {
+        Self {
+            severity: Severity::Warning,
+            ..Self::new(code, path, message)
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+
+    pub(crate) fn locate(&mut self, locator: &YamlLocator) {
+        if let Some((line, column)) = locator.find(&self.path) {
+            self.line = line;
+            self.column = column;
+        }
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct Linter {
+    diagnostics: Vec<Diagnostic>,
+    ids: HashMap<String, String>,
+    node_ids: HashSet<String>,
+    nexts: Vec<(String, String)>,
+    packages: Vec<(String, String)>,
+    scripts: Vec<(String, String)>,
+    /// the script lang of the model
+    lang: String,
+    /// the names of the registered act handlers
+    acts: Vec<String>,
+}
+
+impl Linter {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// recognizes the acts of the registered act handlers
+    pub fn with_acts(mut self, acts: &[String]) -> Self {
+        self.acts = acts.to_vec();
+        self
+    }
+
+    /// the referenced package ids with the paths
+    pub fn packages(&self) -> &Vec<(String, String)> {
+        &self.packages
+    }
+
+    /// the scripts which are evaluated at runtime with the paths
+    pub fn scripts(&self) -> &Vec<(String, String)> {
+        &self.scripts
+    }
+
+    pub fn lint(mut self, workflow: &Workflow) -> Self {
+        self.lang = workflow.lang.clone();
+        self.check_vars(&workflow.env, "env");
+        self.check_vars(&workflow.inputs, "inputs");
+        self.check_vars(&workflow.outputs, "outputs");
+        self.check_acts(&workflow.setup, "setup");
+        self.check_steps(&workflow.steps, "steps");
+
+        for (next, path) in &self.nexts {
+            if !self.node_ids.contains(next) {
+                self.diagnostics.push(Diagnostic::new(
+                    "dangling-next",
+                    path,
+                    &format!("cannot find the next node '{next}'"),
+                ));
+            }
+        }
+        self.check_unreachable(&workflow.steps, "steps");
+
+        self
+    }
+
+    pub fn into_diagnostics(self) -> Vec<Diagnostic> {
+        self.diagnostics
+    }
+
+    fn check_id(&mut self, id: &str, path: &str, is_node: bool) {
+        if id.is_empty() {
+            return;
+        }
+        if is_node {
+            self.node_ids.insert(id.to_string());
+        }
+        match self.ids.get(id) {
+            Some(first) => {
+                let message = format!("dup id '{id}' which is already defined in '{first}'");
+                self.diagnostics
+                    .push(Diagnostic::new("dup-id", &format!("{path}.id"), &message));
+            }
+            None => {
+                self.ids.insert(id.to_string(), path.to_string());
+            }
+        }
+    }
+
+    fn check_steps(&mut self, steps: &[Step], path: &str) {
+        for (index, step) in steps.iter().enumerate() {
+            let path = format!("{path}[{index}]");
+            self.check_id(&step.id, &path, true);
+            self.check_vars(&step.inputs, &format!("{path}.inputs"));
+            self.check_vars(&step.outputs, &format!("{path}.outputs"));
+            self.check_script(step.run.as_deref(), &format!("{path}.run"));
+            self.check_script(step.r#if.as_deref(), &format!("{path}.if"));
+            if let Some(next) = &step.next {
+                self.nexts.push((next.clone(), format!("{path}.next")));
+            }
+            if let Some(uses) = &step.uses {
+                self.packages.push((uses.clone(), format!("{path}.uses")));
+            }
+
+            self.check_acts(&step.setup, &format!("{path}.setup"));
+            self.check_node_acts(&step.acts, &format!("{path}.acts"));
+            self.check_catches(&step.catches, &format!("{path}.catches"));
+            self.check_timeout(&step.timeout, &format!("{path}.timeout"));
+            self.check_branches(&step.branches, &format!("{path}.branches"));
+        }
+    }
+
+    fn check_branches(&mut self, branches: &[Branch], path: &str) {
+        for (index, branch) in branches.iter().enumerate() {
+            let path = format!("{path}[{index}]");
+            self.check_id(&branch.id, &path, true);
+            self.check_vars(&branch.inputs, &format!("{path}.inputs"));
+            self.check_vars(&branch.outputs, &format!("{path}.outputs"));
+            self.check_script(branch.run.as_deref(), &format!("{path}.run"));
+            self.check_script(branch.r#if.as_deref(), &format!("{path}.if"));
+            if branch.r#if.is_none() && !branch.r#else && branch.needs.is_empty() {
+                self.diagnostics.push(Diagnostic::new(
+                    "branch-no-condition",
+                    &path,
+                    "the branch has neither 'if' nor 'else', it will always be skipped",
+                ));
+            }
+            if let Some(next) = &branch.next {
+                self.nexts.push((next.clone(), format!("{path}.next")));
+            }
+            self.check_steps(&branch.steps, &format!("{path}.steps"));
+        }
+    }
+
+    /// the acts in step are the nodes of the workflow tree
+    fn check_node_acts(&mut self, acts: &[Act], path: &str) {
+        for (index, act) in acts.iter().enumerate() {
+            let path = format!("{path}[{index}]");
+            self.check_id(&act.id, &path, true);
+            self.check_act(act, &path);
+        }
+    }
+
+    fn check_acts(&mut self, acts: &[Act], path: &str) {
+        for (index, act) in acts.iter().enumerate() {
+            let path = format!("{path}[{index}]");
+            self.check_id(&act.id, &path, false);
+            self.check_act(act, &path);
+        }
+    }
+
+    fn check_act(&mut self, act: &Act, path: &str) {
+        let name = act.act.as_str();
+        if !ACT_NAMES.contains(&name) && !self.acts.iter().any(|act| act == name) {
+            self.diagnostics.push(Diagnostic::new(
+                "unknown-act",
+                &format!("{path}.act"),
+                &format!("cannot recognize the act '{name}'"),
+            ));
+        }
+
+        if schema::required(name).contains(&"key") && act.key.is_empty() {
+            self.diagnostics.push(Diagnostic::new(
+                "missing-key",
+                path,
+                &format!("the 'key' is required in act '{name}'"),
+            ));
+        }
+
+        if name == "pack" && !act.key.is_empty() {
+            self.packages.push((act.key.clone(), format!("{path}.key")));
+        }
+
+        if name == "if" {
+            self.check_script(Some(&act.on), &format!("{path}.on"));
+        }
+        if matches!(name, "each" | "chain") {
+            self.check_script(Some(&act.r#in), &format!("{path}.in"));
+        }
+        self.check_vars(&act.inputs, &format!("{path}.inputs"));
+        self.check_vars(&act.outputs, &format!("{path}.outputs"));
+
+        self.check_acts(&act.setup, &format!("{path}.setup"));
+        self.check_acts(&act.then, &format!("{path}.then"));
+        self.check_acts(&act.r#else, &format!("{path}.else"));
+        if let Some(next) = &act.next {
+            let path = format!("{path}.next");
+            self.check_id(&next.id, &path, false);
+            self.check_act(next, &path);
+        }
+        self.check_catches(&act.catches, &format!("{path}.catches"));
+        self.check_timeout(&act.timeout, &format!("{path}.timeout"));
+    }
+
+    fn check_catches(&mut self, catches: &[Catch], path: &str) {
+        for (index, catch) in catches.iter().enumerate() {
+            let path = format!("{path}[{index}]");
+            self.check_vars(&catch.inputs, &format!("{path}.inputs"));
+            self.check_acts(&catch.then, &format!("{path}.then"));
+        }
+    }
+
+    fn check_timeout(&mut self, timeout: &[Timeout], path: &str) {
+        for (index, t) in timeout.iter().enumerate() {
+            let path = format!("{path}[{index}]");
+            self.check_acts(&t.then, &format!("{path}.then"));
+        }
+    }
+
+    /// checks the expressions in the vars, such as `${ $("a") }` and `Order ${ $("id") | upper }`
+    fn check_vars(&mut self, vars: &Vars, path: &str) {
+        for (key, value) in vars {
+            self.check_value(&value, &format!("{path}.{key}"));
+        }
+    }
+
+    fn check_value(&mut self, value: &serde_json::Value, path: &str) {
+        match value {
+            serde_json::Value::String(text) => {
+                if let Some(template) = utils::Template::parse(text) {
+                    self.check_script(Some(&template.script()), path);
+                }
+            }
+            serde_json::Value::Array(items) => {
+                for (index, item) in items.iter().enumerate() {
+                    self.check_value(item, &format!("{path}[{index}]"));
+                }
+            }
+            serde_json::Value::Object(map) => {
+                for (key, item) in map {
+                    self.check_value(item, &format!("{path}.{key}"));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn check_script(&mut self, script: Option<&str>, path: &str) {
+        if let Some(script) = script {
+            if script.trim().is_empty() {
+                return;
+            }
+            let script = utils::get_expr(script).unwrap_or(script.to_string());
+            self.scripts.push((script.clone(), path.to_string()));
+            if let Err(err) = Enviroment::check_syntax_with(&self.lang, &script) {
+                self.diagnostics.push(Diagnostic::new(
+                    "invalid-script",
+                    path,
+                    &format!("the script is invalid: {err}"),
+                ));
+            }
+        }
+    }
+
+    /// the step after the step with `next` is unreachable if no other `next` targets it
+    fn check_unreachable(&mut self, steps: &[Step], path: &str) {
+        for (index, step) in steps.iter().enumerate() {
+            let step_path = format!("{path}[{index}]");
+            if index > 0 && steps[index - 1].next.is_some() {
+                let is_target =
+                    !step.id.is_empty() && self.nexts.iter().any(|(n, _)| n == &step.id);
+                if !is_target {
+                    self.diagnostics.push(Diagnostic::warning(
+                        "unreachable-step",
+                        &step_path,
+                        &format!("the step '{}' is unreachable", step.id),
+                    ));
+                }
+            }
+
+            for (b, branch) in step.branches.iter().enumerate() {
+                self.check_unreachable(&branch.steps, &format!("{step_path}.branches[{b}].steps"));
+            }
+        }
+    }
+}
+
+/// finds the line and column of the yaml path, such as `steps[0].acts[1].key`
+/// the positions are read from the yaml parser events, so the flow style and the aliases are supported
+#[derive(Default)]
+pub(crate) struct YamlLocator {
+    positions: HashMap<String, (usize, usize)>,
+}
+
+enum Frame {
+    /// the mapping path and the key path which is waiting for its value
+    Map {
+        path: String,
+        key: Option<String>,
+    },
+    Seq {
+        path: String,
+        index: usize,
+    },
+}
+
+/// walks the parser events and records the position of each key and sequence item
+struct YamlWalker<'a> {
+    text: &'a str,
+    locator: YamlLocator,
+    stack: Vec<Frame>,
+    /// the node paths by the anchor id
+    anchors: HashMap<usize, String>,
+    /// the mapping item whose position is read from its first key, because the
+    /// block mapping start is marked at the first `:`
+    item: Option<(String, Marker)>,
+}
+
+impl YamlLocator {
+    pub fn new(text: &str) -> Self {
+        let mut walker = YamlWalker {
+            text,
+            locator: Self::default(),
+            stack: Vec::new(),
+            anchors: HashMap::new(),
+            item: None,
+        };
+        // the positions before the yaml error are kept
+        let _ = Parser::new_from_str(text).load(&mut walker, false);
+        walker.locator
+    }
+
+    fn insert(&mut self, path: &str, line: usize, column: usize) {
+        self.positions
+            .entry(path.to_string())
+            .or_insert((line, column));
+    }
+
+    /// finds the position of the path, or the nearest parent if not found
+    pub fn find(&self, path: &str) -> Option<(usize, usize)> {
+        let mut path = path;
+        loop {
+            if let Some(pos) = self.positions.get(path) {
+                return Some(*pos);
+            }
+            match path.rfind(['.', '[']) {
+                Some(i) => path = &path[..i],
+                None => return None,
+            }
+        }
+    }
+}
+
+impl YamlWalker<'_> {
+    /// the path of the node which starts at the mark, the mapping key node is
+    /// recorded as the key path, which is used by the following value node
+    /// the scalar value is the key name, and the mapping is positioned by its first node
+    fn node_path(&mut self, mark: &Marker, value: Option<&str>, is_map: bool) -> String {
+        self.flush_item(mark);
+        let (line, column) = self.position(mark);
+        let item_column = self.item_column(mark, column);
+        match self.stack.last_mut() {
+            None => String::new(),
+            Some(Frame::Map { path, key }) => match key.take() {
+                Some(key) => key,
+                None => {
+                    let name = value.unwrap_or("?");
+                    let ret = if path.is_empty() {
+                        name.to_string()
+                    } else {
+                        format!("{path}.{name}")
+                    };
+                    self.locator.insert(&ret, line, column);
+                    *key = Some(ret.clone());
+                    ret
+                }
+            },
+            Some(Frame::Seq { path, index }) => {
+                let ret = format!("{path}[{index}]");
+                *index += 1;
+                if is_map {
+                    self.item = Some((ret.clone(), *mark));
+                } else {
+                    self.locator.insert(&ret, line, item_column);
+                }
+                ret
+            }
+        }
+    }
+
+    /// records the position of the pending mapping item by the mark of its first node
+    fn flush_item(&mut self, mark: &Marker) {
+        if let Some((path, _)) = self.item.take() {
+            let (line, column) = self.position(mark);
+            let column = self.item_column(mark, column);
+            self.locator.insert(&path, line, column);
+        }
+    }
+
+    /// the 1-based line and column of the mark
+    fn position(&self, mark: &Marker) -> (usize, usize) {
+        (mark.line(), mark.col() + 1)
+    }
+
+    /// the column of the `-` indicator for the block sequence item
+    fn item_column(&self, mark: &Marker, column: usize) -> usize {
+        let before = self.text.get(..mark.index()).unwrap_or_default();
+        let line = &before[before.rfind('\n').map_or(0, |i| i + 1)..];
+        let trimmed = line.trim_end();
+        if trimmed.ends_with('-') {
+            return trimmed.chars().count();
+        }
+        column
+    }
+
+    /// copies the positions of the anchored node to the alias path
+    fn alias(&mut self, id: usize, path: &str) {
+        let Some(anchor) = self.anchors.get(&id) else {
+            return;
+        };
+        let positions = self
+            .locator
+            .positions
+            .iter()
+            .filter_map(|(p, pos)| {
+                let rest = p.strip_prefix(anchor.as_str())?;
+                if rest.is_empty() || rest.starts_with(['.', '[']) {
+                    return Some((format!("{path}{rest}"), *pos));
+                }
+                None
+            })
+            .collect::<Vec<_>>();
+        for (p, (line, column)) in positions {
+            self.locator.insert(&p, line, column);
+        }
+    }
+}
+
+impl MarkedEventReceiver for YamlWalker<'_> {
+    fn on_event(&mut self, ev: Event, mark: Marker) {
+        match ev {
+            Event::Scalar(value, _, anchor, _) => {
+                let path = self.node_path(&mark, Some(&value), false);
+                if anchor > 0 {
+                    self.anchors.insert(anchor, path);
+                }
+            }
+            Event::Alias(id) => {
+                let path = self.node_path(&mark, None, false);
+                self.alias(id, &path);
+            }
+            Event::MappingStart(anchor, _) | Event::SequenceStart(anchor, _) => {
+                let is_map = matches!(ev, Event::MappingStart(..));
+                let path = self.node_path(&mark, None, is_map);
+                if anchor > 0 {
+                    self.anchors.insert(anchor, path.clone());
+                }
+                if is_map {
+                    self.stack.push(Frame::Map { path, key: None });
+                } else {
+                    self.stack.push(Frame::Seq { path, index: 0 });
+                }
+            }
+            Event::MappingEnd | Event::SequenceEnd => {
+                // the empty mapping item is positioned by its start
+                if let Some(start) = self.item.as_ref().map(|(_, mark)| *mark) {
+                    self.flush_item(&start);
+                }
+                self.stack.pop();
+            }
+            _ => {}
+        }
+    }
+}
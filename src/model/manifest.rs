@@ -0,0 +1,112 @@
+use crate::{ActError, Output, Outputs, Result};
+use semver::{Version, VersionReq};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// the package manifest with the version, dependencies and the inputs and outputs schema
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Manifest {
+    /// the semver version, such as `1.0.0`
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub version: String,
+
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+
+    /// the script lang of the package, the model lang is used if empty
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub lang: String,
+
+    /// the dependent package ids with the semver requirements, such as `utils: ^1.0`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub dependencies: HashMap<String, String>,
+
+    /// the inputs schema to validate the act inputs before running
+    #[serde(default, skip_serializing_if = "Outputs::is_empty")]
+    pub inputs: Outputs,
+
+    /// the outputs schema which is exposed by the package
+    #[serde(default, skip_serializing_if = "Outputs::is_empty")]
+    pub outputs: Outputs,
+}
+
+/// splits the package key to the package id and the pinned version
+/// the format is `{id}` or `{id}@{ver}`, the ver can be the exact version or the requirement
+pub fn parse_package_key(key: &str) -> (&str, Option<&str>) {
+    match key.rsplit_once('@') {
+        Some((id, ver)) if !id.is_empty() && VersionReq::parse(ver).is_ok() => (id, Some(ver)),
+        _ => (key, None),
+    }
+}
+
+/// checks the version is matched with the exact version or the requirement
+pub fn is_version_match(version: &str, ver: &str) -> bool {
+    let version = match Version::parse(version) {
+        Ok(version) => version,
+        Err(_) => return false,
+    };
+    if let Ok(exact) = Version::parse(ver) {
+        return version == exact;
+    }
+    match VersionReq::parse(ver) {
+        Ok(req) => req.matches(&version),
+        Err(_) => false,
+    }
+}
+
+impl Manifest {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn with_version(mut self, version: &str) -> Self {
+        self.version = version.to_string();
+        self
+    }
+
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = description.to_string();
+        self
+    }
+
+    pub fn with_lang(mut self, lang: &str) -> Self {
+        self.lang = lang.to_string();
+        self
+    }
+
+    pub fn with_dependency(mut self, id: &str, req: &str) -> Self {
+        self.dependencies.insert(id.to_string(), req.to_string());
+        self
+    }
+
+    pub fn with_input(mut self, name: &str, input: Output) -> Self {
+        self.inputs.push(name, &input);
+        self
+    }
+
+    pub fn with_output(mut self, name: &str, output: Output) -> Self {
+        self.outputs.push(name, &output);
+        self
+    }
+
+    /// checks the version and the dependency requirements are valid semver
+    pub fn check(&self) -> Result<()> {
+        if !self.version.is_empty() {
+            Version::parse(&self.version).map_err(|err| {
+                ActError::Action(format!(
+                    "the package version '{}' is invalid: {err}",
+                    self.version
+                ))
+            })?;
+        }
+
+        for (id, req) in &self.dependencies {
+            VersionReq::parse(req).map_err(|err| {
+                ActError::Action(format!(
+                    "the dependency version '{id}@{req}' is invalid: {err}"
+                ))
+            })?;
+        }
+        Ok(())
+    }
+}
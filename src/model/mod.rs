@@ -1,7 +1,14 @@
 mod act;
+mod bpmn;
 mod branch;
+mod fragment;
 mod info;
+mod inherit;
+mod limits;
+mod lint;
+mod manifest;
 mod output;
+mod schema;
 mod step;
 mod vars;
 mod workflow;
@@ -11,8 +18,18 @@ mod tests;
 
 pub use act::{Act, ActFn, Block, Call, Catch, Chain, Do, Each, If, Irq, Msg, Pack, Timeout};
 pub use branch::Branch;
-pub use info::{MessageInfo, ModelInfo, PackageInfo, ProcInfo, TaskInfo};
+pub use fragment::Fragment;
+pub use info::{LogInfo, MessageInfo, ModelInfo, PackageInfo, ProcInfo, TaskInfo};
+pub(crate) use inherit::parse_extends;
+pub use limits::Limits;
+pub use lint::{Diagnostic, Severity};
+pub(crate) use lint::{Linter, YamlLocator, ACT_NAMES};
+pub use manifest::Manifest;
+pub(crate) use manifest::{is_version_match, parse_package_key};
+#[cfg(test)]
+pub(crate) use output::is_pattern_cached;
 pub use output::{Output, OutputType, Outputs};
+pub(crate) use schema::json_schema;
 pub use step::Step;
 pub use vars::Vars;
 pub use workflow::Workflow;
@@ -1,17 +1,48 @@
-use crate::{ActError, Vars};
+use crate::{ActError, FieldError, Vars};
 use core::fmt;
+use moka::sync::Cache;
+use once_cell::sync::Lazy;
+use regex::Regex;
 use serde::{de, Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
 
+/// the max compiled patterns kept in the cache
+const PATTERN_CACHE_CAP: u64 = 256;
+
+/// the compiled regex by the output pattern, so the pattern is compiled once for the validations
+static PATTERNS: Lazy<Cache<String, Regex>> = Lazy::new(|| Cache::new(PATTERN_CACHE_CAP));
+
+/// gets the compiled regex of the pattern from the cache
+fn pattern_regex(pattern: &str) -> Result<Regex, regex::Error> {
+    if let Some(re) = PATTERNS.get(pattern) {
+        return Ok(re);
+    }
+    let re = Regex::new(pattern)?;
+    PATTERNS.insert(pattern.to_string(), re.clone());
+    Ok(re)
+}
+
+#[cfg(test)]
+pub(crate) fn is_pattern_cached(pattern: &str) -> bool {
+    PATTERNS.contains_key(pattern)
+}
+
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub enum OutputType {
     #[default]
+    #[serde(alias = "string")]
     String,
+    #[serde(alias = "bool")]
     Bool,
+    #[serde(alias = "number")]
     Number,
+    #[serde(alias = "array")]
     Array,
+    #[serde(alias = "object")]
     Object,
+    #[serde(alias = "any")]
+    Any,
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
@@ -19,6 +50,14 @@ pub struct Output {
     pub required: bool,
     pub default: Value,
     pub r#type: OutputType,
+
+    /// the allowed values
+    #[serde(default, rename = "enum", skip_serializing_if = "Vec::is_empty")]
+    pub r#enum: Vec<Value>,
+
+    /// the regex pattern to match the string value
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
 }
 
 #[derive(Debug, Default, Clone)]
@@ -47,6 +86,7 @@ impl fmt::Display for OutputType {
             OutputType::Number => "Number",
             OutputType::Array => "Array",
             OutputType::Object => "Object",
+            OutputType::Any => "Any",
         })
     }
 }
@@ -56,11 +96,15 @@ impl From<Value> for Output {
         let required = get::<bool>("required", &value).unwrap_or_default();
         let default = get::<Value>("default", &value).unwrap_or_default();
         let r#type = get::<OutputType>("type", &value).unwrap_or_default();
+        let r#enum = get::<Vec<Value>>("enum", &value).unwrap_or_default();
+        let pattern = get::<String>("pattern", &value);
 
         Self {
             required,
             default,
             r#type,
+            r#enum,
+            pattern,
         }
     }
 }
@@ -123,35 +167,120 @@ impl<'de> Deserialize<'de> for Outputs {
     }
 }
 
+impl Output {
+    /// creates the rule from the act `rets` entry
+    /// the entry is nullable unless it is `required` and its type is not checked if not set
+    pub fn from_ret(value: &Value) -> Self {
+        let mut output: Output = value.clone().into();
+        output.required = get::<bool>("required", value).unwrap_or(false);
+        if value.get("type").is_none() {
+            output.r#type = OutputType::Any;
+        }
+        output
+    }
+
+    /// validates the value and returns the error message if it is invalid
+    pub fn validate(&self, key: &str, value: Option<&Value>) -> Option<String> {
+        let v = match value {
+            Some(v) if !v.is_null() => v,
+            _ => {
+                if self.required {
+                    return Some(format!("the key '{key}' is required"));
+                }
+                return None;
+            }
+        };
+
+        let is_type_match = match self.r#type {
+            OutputType::String => v.is_string(),
+            OutputType::Bool => v.is_boolean(),
+            OutputType::Number => v.is_number(),
+            OutputType::Array => v.is_array(),
+            OutputType::Object => v.is_object(),
+            OutputType::Any => true,
+        };
+        if !is_type_match {
+            return Some(format!(
+                "the value {key}({v}) is not matched the type '{}'",
+                self.r#type
+            ));
+        }
+
+        if !self.r#enum.is_empty() && !self.r#enum.contains(v) {
+            let values = self
+                .r#enum
+                .iter()
+                .map(|v| v.to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+            return Some(format!("the value {key}({v}) is not one of [{values}]"));
+        }
+
+        if let (Some(pattern), Some(text)) = (&self.pattern, v.as_str()) {
+            match pattern_regex(pattern) {
+                Ok(re) => {
+                    if !re.is_match(text) {
+                        return Some(format!(
+                            "the value {key}({v}) is not matched the pattern '{pattern}'"
+                        ));
+                    }
+                }
+                Err(err) => return Some(format!("the pattern '{pattern}' is invalid: {err}")),
+            }
+        }
+
+        None
+    }
+}
+
 impl Outputs {
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Output> {
+        self.inner.get(name)
+    }
+
     pub fn push(&mut self, name: &str, output: &Output) {
         self.inner.insert(name.to_string(), output.clone());
     }
 
-    pub fn check(&self, vars: &Vars) -> crate::Result<()> {
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Output)> {
+        self.inner.iter()
+    }
+
+    /// sets the default values to the vars if the keys are not exists
+    pub fn fill_defaults(&self, vars: &mut Vars) {
         for (k, output) in &self.inner {
-            let v = vars.get::<Value>(k);
-            if v.is_none() && output.required {
-                return Err(ActError::Runtime(format!("the key '{k}' is required",)));
+            let is_missing = vars.get_value(k).map(|v| v.is_null()).unwrap_or(true);
+            if is_missing && !output.default.is_null() {
+                vars.set(k, output.default.clone());
             }
+        }
+    }
 
-            if let Some(v) = &v {
-                let is_type_match = match output.r#type {
-                    OutputType::String => v.is_string(),
-                    OutputType::Bool => v.is_boolean(),
-                    OutputType::Number => v.is_number(),
-                    OutputType::Array => v.is_array(),
-                    OutputType::Object => v.is_object(),
-                };
-
-                if !is_type_match {
-                    return Err(ActError::Runtime(format!(
-                        "the value {k}({v}) is not matched the type '{}'",
-                        output.r#type
-                    )));
-                }
+    /// validates the vars and collects all of the errors sorted by key
+    pub fn validate(&self, vars: &Vars) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        for (k, output) in &self.inner {
+            if let Some(message) = output.validate(k, vars.get_value(k)) {
+                errors.push(FieldError::new(k, &message));
             }
         }
+        errors.sort_by(|a, b| a.key.cmp(&b.key));
+        errors
+    }
+
+    pub fn check(&self, vars: &Vars) -> crate::Result<()> {
+        let errors = self.validate(vars);
+        if !errors.is_empty() {
+            return Err(ActError::Validation(errors));
+        }
         Ok(())
     }
 }
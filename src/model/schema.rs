@@ -0,0 +1,235 @@
+use super::lint::{act_names, ACT_NAMES};
+use serde_json::{json, Value};
+
+/// the required fields for each act, which are checked by the linter too
+pub(super) fn required(name: &str) -> Vec<&'static str> {
+    match name {
+        "irq" | "cmd" | "call" | "pack" | "include" => vec!["key"],
+        "each" | "chain" => vec!["in", "then"],
+        "if" => vec!["on", "then"],
+        "block" => vec!["then"],
+        "set" | "expose" => vec!["inputs"],
+        "on_timeout" => vec!["timeout"],
+        "on_catch" => vec!["catches"],
+        "on_created" | "on_updated" | "on_before_update" | "on_step" | "on_completed" => {
+            vec!["then"]
+        }
+        _ => vec![],
+    }
+}
+
+/// generates the json schema (draft-07) of the workflow yml or json
+/// the acts are the names of the registered act handlers
+pub fn json_schema(acts: &[String]) -> Value {
+    let rules = ACT_NAMES
+        .iter()
+        .filter(|name| !required(name).is_empty())
+        .map(|name| {
+            json!({
+                "if": { "properties": { "act": { "const": name } }, "required": ["act"] },
+                "then": { "required": required(name) }
+            })
+        })
+        .collect::<Vec<_>>();
+
+    json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "$id": "https://github.com/yaojianpin/acts/workflow.schema.json",
+        "title": "Workflow",
+        "description": "the acts workflow model",
+        "type": "object",
+        "additionalProperties": false,
+        "properties": {
+            "id": { "type": "string" },
+            "name": { "type": "string" },
+            "tag": { "type": "string" },
+            "ver": { "type": "integer", "minimum": 0 },
+            "extends": { "type": "string", "description": "the base model id to inherit, such as base or base@2" },
+            "removes": {
+                "type": "array",
+                "items": { "type": "string" },
+                "description": "the step ids to remove from the base model"
+            },
+            "env": { "$ref": "#/definitions/vars" },
+            "inputs": { "$ref": "#/definitions/vars" },
+            "outputs": { "$ref": "#/definitions/vars" },
+            "schema": {
+                "type": "object",
+                "additionalProperties": { "$ref": "#/definitions/output" }
+            },
+            "setup": { "$ref": "#/definitions/acts" },
+            "steps": { "$ref": "#/definitions/steps" },
+            "lang": { "type": "string", "description": "the script lang of the model, such as js or rhai" },
+            "limits": {
+                "type": "object",
+                "description": "the sandbox limits of the scripts in the model",
+                "additionalProperties": false,
+                "properties": {
+                    "memory": { "type": "integer", "minimum": 0, "description": "the max memory in bytes" },
+                    "stack": { "type": "integer", "minimum": 0, "description": "the max stack size in bytes" },
+                    "timeout": { "type": "integer", "minimum": 0, "description": "the max execution time in milliseconds" }
+                }
+            },
+            "fragments": {
+                "type": "array",
+                "items": { "$ref": "#/definitions/fragment" }
+            }
+        },
+        "definitions": {
+            "vars": { "type": ["object", "null"] },
+            "script": { "type": "string" },
+            "steps": {
+                "type": "array",
+                "items": { "$ref": "#/definitions/step" }
+            },
+            "acts": {
+                "type": "array",
+                "items": { "$ref": "#/definitions/act" }
+            },
+            "step": {
+                "type": "object",
+                "additionalProperties": false,
+                "properties": {
+                    "id": { "type": "string" },
+                    "name": { "type": "string" },
+                    "tag": { "type": "string" },
+                    "inputs": { "$ref": "#/definitions/vars" },
+                    "outputs": { "$ref": "#/definitions/vars" },
+                    "run": { "$ref": "#/definitions/script" },
+                    "uses": { "type": "string", "description": "the package id to run in step, such as pack1 or pack1@1.0.0" },
+                    "include": { "type": "string", "description": "the fragment id to include" },
+                    "if": { "$ref": "#/definitions/script" },
+                    "next": { "type": "string", "description": "the step id to jump to" },
+                    "branches": {
+                        "type": "array",
+                        "items": { "$ref": "#/definitions/branch" }
+                    },
+                    "acts": { "$ref": "#/definitions/acts" },
+                    "catches": {
+                        "type": "array",
+                        "items": { "$ref": "#/definitions/catch" }
+                    },
+                    "timeout": {
+                        "type": "array",
+                        "items": { "$ref": "#/definitions/timeout" }
+                    },
+                    "setup": { "$ref": "#/definitions/acts" }
+                }
+            },
+            "branch": {
+                "type": "object",
+                "additionalProperties": false,
+                "properties": {
+                    "id": { "type": "string" },
+                    "name": { "type": "string" },
+                    "tag": { "type": "string" },
+                    "inputs": { "$ref": "#/definitions/vars" },
+                    "outputs": { "$ref": "#/definitions/vars" },
+                    "run": { "$ref": "#/definitions/script" },
+                    "if": { "$ref": "#/definitions/script" },
+                    "else": { "type": "boolean" },
+                    "needs": {
+                        "type": "array",
+                        "items": { "type": "string" }
+                    },
+                    "next": { "type": "string" },
+                    "steps": { "$ref": "#/definitions/steps" }
+                },
+                "anyOf": [
+                    { "required": ["if"] },
+                    { "required": ["else"] },
+                    { "required": ["needs"] }
+                ]
+            },
+            "act": {
+                "type": "object",
+                "additionalProperties": false,
+                "properties": {
+                    "id": { "type": "string" },
+                    "name": { "type": "string" },
+                    "act": { "type": "string", "enum": act_names(acts) },
+                    "key": { "type": "string", "minLength": 1 },
+                    "tag": { "type": "string" },
+                    "ver": { "type": "integer", "minimum": 1 },
+                    "wait": { "type": "boolean" },
+                    "in": { "$ref": "#/definitions/script" },
+                    "on": { "$ref": "#/definitions/script" },
+                    "inputs": { "$ref": "#/definitions/vars" },
+                    "rets": { "$ref": "#/definitions/vars" },
+                    "outputs": { "$ref": "#/definitions/vars" },
+                    "setup": { "$ref": "#/definitions/acts" },
+                    "then": { "$ref": "#/definitions/acts" },
+                    "else": { "$ref": "#/definitions/acts" },
+                    "next": { "$ref": "#/definitions/act" },
+                    "catches": {
+                        "type": "array",
+                        "items": { "$ref": "#/definitions/catch" }
+                    },
+                    "timeout": {
+                        "type": "array",
+                        "items": { "$ref": "#/definitions/timeout" }
+                    }
+                },
+                "required": ["act"],
+                "allOf": rules
+            },
+            "catch": {
+                "type": "object",
+                "additionalProperties": false,
+                "properties": {
+                    "id": { "type": "string", "description": "the catch name, which is only used to read the model" },
+                    "on": { "type": "string", "description": "the error code to catch, catches all errors if not set" },
+                    "inputs": { "$ref": "#/definitions/vars" },
+                    "then": { "$ref": "#/definitions/acts" },
+                    "include": { "type": "string", "description": "the fragment id to include" }
+                }
+            },
+            "fragment": {
+                "type": "object",
+                "additionalProperties": false,
+                "properties": {
+                    "id": { "type": "string" },
+                    "name": { "type": "string" },
+                    "params": { "$ref": "#/definitions/vars" },
+                    "steps": { "$ref": "#/definitions/steps" },
+                    "acts": { "$ref": "#/definitions/acts" },
+                    "catches": {
+                        "type": "array",
+                        "items": { "$ref": "#/definitions/catch" }
+                    }
+                },
+                "required": ["id"]
+            },
+            "timeout": {
+                "type": "object",
+                "additionalProperties": false,
+                "properties": {
+                    "on": {
+                        "type": "string",
+                        "pattern": "^[0-9]+(s|m|h|d)$",
+                        "description": "the timeout value with unit s, m, h or d, such as 2h"
+                    },
+                    "then": { "$ref": "#/definitions/acts" }
+                },
+                "required": ["on"]
+            },
+            "output": {
+                "type": "object",
+                "additionalProperties": false,
+                "properties": {
+                    "type": {
+                        "type": "string",
+                        "enum": [
+                            "string", "bool", "number", "array", "object", "any",
+                            "String", "Bool", "Number", "Array", "Object", "Any"
+                        ]
+                    },
+                    "required": { "type": "boolean" },
+                    "default": {},
+                    "enum": { "type": "array" },
+                    "pattern": { "type": "string", "format": "regex" }
+                }
+            }
+        }
+    })
+}
@@ -26,6 +26,10 @@ pub struct Step {
     #[serde(default)]
     pub uses: Option<String>,
 
+    /// includes the fragment steps, the inputs are the fragment params
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub include: Option<String>,
+
     #[serde(default)]
     pub r#if: Option<String>,
 
@@ -98,6 +102,11 @@ impl Step {
         self
     }
 
+    pub fn with_include(mut self, fragment: &str) -> Self {
+        self.include = Some(fragment.to_string());
+        self
+    }
+
     pub fn with_input(mut self, name: &str, value: JsonValue) -> Self {
         self.inputs.insert(name.to_string(), value);
         self
@@ -1,4 +1,4 @@
-use crate::{Act, Call};
+use crate::{Act, ActFn, Call, Vars};
 use serde_json::json;
 
 #[test]
@@ -47,3 +47,68 @@ fn model_act_call_rets() {
     assert_eq!(act.rets.len(), 1);
     assert!(act.rets.get_value("p1").is_some());
 }
+
+#[test]
+fn model_act_call_parse_ver_and_wait() {
+    let text = r#"
+    act: call
+    key: m1
+    ver: 2
+    wait: false
+    outputs:
+        total: sum
+        count:
+    "#;
+    let act: Act = serde_yaml::from_str(text).unwrap();
+    assert_eq!(act.ver, Some(2));
+    assert_eq!(act.wait, Some(false));
+
+    let call = match ActFn::from(&act) {
+        ActFn::Call(call) => call,
+        _ => panic!("should be call act"),
+    };
+    assert_eq!(call.ver, Some(2));
+    assert!(!call.is_wait());
+    assert_eq!(call.outputs.len(), 2);
+}
+
+#[test]
+fn model_act_call_ver() {
+    let act = Call::new().with_ver(3);
+    assert_eq!(act.ver, Some(3));
+}
+
+#[test]
+fn model_act_call_wait() {
+    let act = Call::new();
+    assert!(act.is_wait());
+
+    let act = Call::new().with_wait(false);
+    assert!(!act.is_wait());
+}
+
+#[test]
+fn model_act_call_map_outputs() {
+    let act = Call::new()
+        .with_output("total", Some("sum"))
+        .with_output("count", None)
+        .with_output("not_exists", None);
+    let outputs = Vars::new()
+        .with("sum", 10)
+        .with("count", 2)
+        .with("other", 5);
+    let ret = act.map_outputs(&outputs);
+    assert_eq!(ret.len(), 3);
+    assert_eq!(ret.get::<i32>("total").unwrap(), 10);
+    assert_eq!(ret.get::<i32>("count").unwrap(), 2);
+    assert_eq!(ret.get_value("not_exists").unwrap(), &json!(null));
+    assert!(ret.get_value("other").is_none());
+}
+
+#[test]
+fn model_act_call_map_outputs_all() {
+    let act = Call::new();
+    let outputs = Vars::new().with("sum", 10).with("count", 2);
+    let ret = act.map_outputs(&outputs);
+    assert_eq!(ret.len(), 2);
+}
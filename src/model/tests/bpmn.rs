@@ -0,0 +1,429 @@
+use crate::{Act, Step, Workflow};
+use regex::Regex;
+use serde_json::json;
+
+fn round_trip(text: &str) {
+    let workflow = Workflow::from_yml(text).unwrap();
+    let xml = workflow.to_bpmn().unwrap();
+    let result = Workflow::from_bpmn(&xml).unwrap();
+    assert_eq!(
+        serde_json::to_value(&workflow).unwrap(),
+        serde_json::to_value(&result).unwrap()
+    );
+}
+
+const BPMN_HEAD: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<bpmn:definitions xmlns:bpmn="http://www.omg.org/spec/BPMN/20100524/MODEL" id="d1">"#;
+
+#[test]
+fn model_bpmn_round_trip_examples() {
+    round_trip(include_str!("../../../examples/actions/model.yml"));
+    round_trip(include_str!("../../../examples/approve/model.yml"));
+    round_trip(include_str!("../../../examples/catches/model.yml"));
+    round_trip(include_str!("../../../examples/message/model.yml"));
+    round_trip(include_str!("../../../examples/package/model.yml"));
+    round_trip(include_str!("../../../examples/simple/model.yml"));
+    round_trip(include_str!(
+        "../../../examples/sub_workflow/model/main.yml"
+    ));
+    round_trip(include_str!("../../../examples/sub_workflow/model/sub.yml"));
+    round_trip(include_str!("../../../examples/timeout/model.yml"));
+}
+
+#[test]
+fn model_bpmn_round_trip_builder() {
+    let workflow = Workflow::new()
+        .with_id("m1")
+        .with_step(|step| {
+            step.with_id("step1")
+                .with_branch(|b| b.with_id("b1").with_if("true"))
+                .with_branch(|b| {
+                    b.with_id("b2")
+                        .with_if("true")
+                        .with_step(|step| step.with_id("step11"))
+                })
+        })
+        .with_step(|step| step.with_id("step2").with_next("step1"));
+    let xml = workflow.to_bpmn().unwrap();
+    let result = Workflow::from_bpmn(&xml).unwrap();
+    assert_eq!(
+        serde_json::to_value(&workflow).unwrap(),
+        serde_json::to_value(&result).unwrap()
+    );
+}
+
+#[test]
+fn model_bpmn_export_elements() {
+    let text = r#"
+    id: m1
+    steps:
+      - id: step1
+        acts:
+          - act: irq
+            key: act1
+        catches:
+          - on: err1
+        timeout:
+          - on: 2h
+      - id: step2
+        acts:
+          - act: msg
+            key: msg1
+      - id: step3
+        acts:
+          - act: call
+            key: sub
+      - id: step4
+        branches:
+          - id: b1
+            if: $("v") > 0
+          - id: b2
+            else: true
+    "#;
+    let workflow = Workflow::from_yml(text).unwrap();
+    let xml = workflow.to_bpmn().unwrap();
+    assert!(xml.contains(r#"<bpmn:userTask id="step1">"#));
+    assert!(xml.contains(r#"<bpmn:sendTask id="step2">"#));
+    assert!(xml.contains(r#"<bpmn:callActivity id="step3" calledElement="sub">"#));
+    assert!(xml.contains(r#"<bpmn:error id="error_err1" errorCode="err1" />"#));
+    assert!(xml.contains(r#"<bpmn:errorEventDefinition errorRef="error_err1" />"#));
+    assert!(xml.contains("<bpmn:timeDuration>PT2H</bpmn:timeDuration>"));
+    assert!(xml.contains(
+        r#"<bpmn:exclusiveGateway id="step4_split" gatewayDirection="Diverging" default="b2" />"#
+    ));
+    assert!(xml
+        .contains("<bpmn:conditionExpression>$(&quot;v&quot;) &gt; 0</bpmn:conditionExpression>"));
+}
+
+#[test]
+fn model_bpmn_import_tasks() {
+    let text = format!(
+        r#"{BPMN_HEAD}
+  <bpmn:process id="p1" name="process 1">
+    <bpmn:startEvent id="start" />
+    <bpmn:userTask id="approve" name="approve" />
+    <bpmn:sendTask id="notify" />
+    <bpmn:callActivity id="call1" calledElement="sub" />
+    <bpmn:scriptTask id="script1"><bpmn:script>console.log(1)</bpmn:script></bpmn:scriptTask>
+    <bpmn:endEvent id="end" />
+    <bpmn:sequenceFlow id="f1" sourceRef="start" targetRef="approve" />
+    <bpmn:sequenceFlow id="f2" sourceRef="approve" targetRef="notify" />
+    <bpmn:sequenceFlow id="f3" sourceRef="notify" targetRef="call1" />
+    <bpmn:sequenceFlow id="f4" sourceRef="call1" targetRef="script1" />
+    <bpmn:sequenceFlow id="f5" sourceRef="script1" targetRef="end" />
+  </bpmn:process>
+</bpmn:definitions>"#
+    );
+    let workflow = Workflow::from_bpmn(&text).unwrap();
+    assert_eq!(workflow.id, "p1");
+    assert_eq!(workflow.name, "process 1");
+    assert_eq!(workflow.steps.len(), 4);
+
+    let act = workflow.steps[0].acts.first().unwrap();
+    assert_eq!(act.act, "irq");
+    assert_eq!(act.key, "approve");
+    let act = workflow.steps[1].acts.first().unwrap();
+    assert_eq!(act.act, "msg");
+    assert_eq!(act.key, "notify");
+    let act = workflow.steps[2].acts.first().unwrap();
+    assert_eq!(act.act, "call");
+    assert_eq!(act.key, "sub");
+    assert_eq!(workflow.steps[3].run, Some("console.log(1)".to_string()));
+    assert!(workflow.valid().is_ok());
+}
+
+#[test]
+fn model_bpmn_import_exclusive_gateway() {
+    let text = format!(
+        r#"{BPMN_HEAD}
+  <bpmn:process id="p1">
+    <bpmn:startEvent id="start" />
+    <bpmn:task id="step1" />
+    <bpmn:exclusiveGateway id="split" default="f3" />
+    <bpmn:userTask id="step2" />
+    <bpmn:userTask id="step3" />
+    <bpmn:exclusiveGateway id="join" />
+    <bpmn:task id="step4" />
+    <bpmn:endEvent id="end" />
+    <bpmn:sequenceFlow id="f1" sourceRef="start" targetRef="step1" />
+    <bpmn:sequenceFlow id="f0" sourceRef="step1" targetRef="split" />
+    <bpmn:sequenceFlow id="f2" name="big" sourceRef="split" targetRef="step2">
+      <bpmn:conditionExpression>$("v") &gt; 10</bpmn:conditionExpression>
+    </bpmn:sequenceFlow>
+    <bpmn:sequenceFlow id="f3" sourceRef="split" targetRef="step3" />
+    <bpmn:sequenceFlow id="f4" sourceRef="step2" targetRef="join" />
+    <bpmn:sequenceFlow id="f5" sourceRef="step3" targetRef="join" />
+    <bpmn:sequenceFlow id="f6" sourceRef="join" targetRef="step4" />
+    <bpmn:sequenceFlow id="f7" sourceRef="step4" targetRef="end" />
+  </bpmn:process>
+</bpmn:definitions>"#
+    );
+    let workflow = Workflow::from_bpmn(&text).unwrap();
+    assert_eq!(workflow.steps.len(), 2);
+
+    let branches = &workflow.steps[0].branches;
+    assert_eq!(branches.len(), 2);
+    assert_eq!(branches[0].id, "f2");
+    assert_eq!(branches[0].name, "big");
+    assert_eq!(branches[0].r#if, Some(r#"$("v") > 10"#.to_string()));
+    assert_eq!(branches[0].steps[0].id, "step2");
+    assert!(branches[1].r#else);
+    assert_eq!(branches[1].steps[0].id, "step3");
+    assert_eq!(workflow.steps[1].id, "step4");
+}
+
+#[test]
+fn model_bpmn_import_parallel_gateway() {
+    let text = format!(
+        r#"{BPMN_HEAD}
+  <bpmn:process id="p1">
+    <bpmn:startEvent id="start" />
+    <bpmn:parallelGateway id="split" />
+    <bpmn:userTask id="step1" />
+    <bpmn:userTask id="step2" />
+    <bpmn:parallelGateway id="join" />
+    <bpmn:endEvent id="end" />
+    <bpmn:sequenceFlow id="f1" sourceRef="start" targetRef="split" />
+    <bpmn:sequenceFlow id="f2" sourceRef="split" targetRef="step1" />
+    <bpmn:sequenceFlow id="f3" sourceRef="split" targetRef="step2" />
+    <bpmn:sequenceFlow id="f4" sourceRef="step1" targetRef="join" />
+    <bpmn:sequenceFlow id="f5" sourceRef="step2" targetRef="join" />
+    <bpmn:sequenceFlow id="f6" sourceRef="join" targetRef="end" />
+  </bpmn:process>
+</bpmn:definitions>"#
+    );
+    let workflow = Workflow::from_bpmn(&text).unwrap();
+    assert_eq!(workflow.steps.len(), 1);
+    assert_eq!(workflow.steps[0].id, "split");
+
+    let branches = &workflow.steps[0].branches;
+    assert_eq!(branches.len(), 2);
+    assert!(branches.iter().all(|b| b.r#if == Some("true".to_string())));
+}
+
+#[test]
+fn model_bpmn_import_loop() {
+    let text = format!(
+        r#"{BPMN_HEAD}
+  <bpmn:process id="p1">
+    <bpmn:startEvent id="start" />
+    <bpmn:task id="step1" />
+    <bpmn:exclusiveGateway id="split" />
+    <bpmn:task id="step2" />
+    <bpmn:endEvent id="end" />
+    <bpmn:sequenceFlow id="f1" sourceRef="start" targetRef="step1" />
+    <bpmn:sequenceFlow id="f2" sourceRef="step1" targetRef="split" />
+    <bpmn:sequenceFlow id="f3" sourceRef="split" targetRef="step2">
+      <bpmn:conditionExpression>$("i") &lt; 10</bpmn:conditionExpression>
+    </bpmn:sequenceFlow>
+    <bpmn:sequenceFlow id="f4" sourceRef="split" targetRef="end">
+      <bpmn:conditionExpression>$("i") &gt;= 10</bpmn:conditionExpression>
+    </bpmn:sequenceFlow>
+    <bpmn:sequenceFlow id="f5" sourceRef="step2" targetRef="step1" />
+  </bpmn:process>
+</bpmn:definitions>"#
+    );
+    let workflow = Workflow::from_bpmn(&text).unwrap();
+    let branches = &workflow.steps[0].branches;
+    assert_eq!(branches.len(), 2);
+    assert_eq!(branches[0].steps[0].next, Some("step1".to_string()));
+    assert!(branches[1].steps.is_empty());
+}
+
+#[test]
+fn model_bpmn_import_boundary_events() {
+    let text = format!(
+        r#"{BPMN_HEAD}
+  <bpmn:error id="e1" errorCode="err1" />
+  <bpmn:process id="p1">
+    <bpmn:startEvent id="start" />
+    <bpmn:userTask id="step1" />
+    <bpmn:boundaryEvent id="b1" attachedToRef="step1">
+      <bpmn:errorEventDefinition errorRef="e1" />
+    </bpmn:boundaryEvent>
+    <bpmn:boundaryEvent id="b2" attachedToRef="step1" cancelActivity="false">
+      <bpmn:timerEventDefinition>
+        <bpmn:timeDuration>PT30M</bpmn:timeDuration>
+      </bpmn:timerEventDefinition>
+    </bpmn:boundaryEvent>
+    <bpmn:sendTask id="notify" />
+    <bpmn:endEvent id="end" />
+    <bpmn:endEvent id="end2" />
+    <bpmn:sequenceFlow id="f1" sourceRef="start" targetRef="step1" />
+    <bpmn:sequenceFlow id="f2" sourceRef="step1" targetRef="end" />
+    <bpmn:sequenceFlow id="f3" sourceRef="b2" targetRef="notify" />
+    <bpmn:sequenceFlow id="f4" sourceRef="notify" targetRef="end2" />
+  </bpmn:process>
+</bpmn:definitions>"#
+    );
+    let workflow = Workflow::from_bpmn(&text).unwrap();
+    let step = &workflow.steps[0];
+    assert_eq!(step.catches.len(), 1);
+    assert_eq!(step.catches[0].on, Some("err1".to_string()));
+    assert_eq!(step.timeout.len(), 1);
+    assert_eq!(step.timeout[0].on.to_string(), "30m");
+    assert_eq!(step.timeout[0].then.len(), 1);
+    assert_eq!(step.timeout[0].then[0].act, "msg");
+    assert_eq!(step.timeout[0].then[0].key, "notify");
+}
+
+#[test]
+fn model_bpmn_import_duration() {
+    let text = format!(
+        r#"{BPMN_HEAD}
+  <bpmn:process id="p1">
+    <bpmn:startEvent id="start" />
+    <bpmn:userTask id="step1" />
+    <bpmn:boundaryEvent id="b1" attachedToRef="step1">
+      <bpmn:timerEventDefinition>
+        <bpmn:timeDuration>PT1H30M</bpmn:timeDuration>
+      </bpmn:timerEventDefinition>
+    </bpmn:boundaryEvent>
+    <bpmn:sequenceFlow id="f1" sourceRef="start" targetRef="step1" />
+  </bpmn:process>
+</bpmn:definitions>"#
+    );
+    let workflow = Workflow::from_bpmn(&text).unwrap();
+    assert_eq!(workflow.steps[0].timeout[0].on.to_string(), "5400s");
+}
+
+#[test]
+fn model_bpmn_import_unsupported() {
+    let text = format!(
+        r#"{BPMN_HEAD}
+  <bpmn:process id="p1">
+    <bpmn:startEvent id="start" />
+    <bpmn:subProcess id="sub1" />
+    <bpmn:inclusiveGateway id="g1" />
+    <bpmn:boundaryEvent id="b1" attachedToRef="sub1">
+      <bpmn:messageEventDefinition />
+    </bpmn:boundaryEvent>
+    <bpmn:sequenceFlow id="f1" sourceRef="start" targetRef="sub1" />
+  </bpmn:process>
+</bpmn:definitions>"#
+    );
+    let err = Workflow::from_bpmn(&text).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "unsupported bpmn elements: subProcess 'sub1', inclusiveGateway 'g1', boundaryEvent 'b1' with messageEventDefinition"
+    );
+}
+
+#[test]
+fn model_bpmn_import_invalid_xml() {
+    assert!(Workflow::from_bpmn("<definitions><process></definitions>").is_err());
+    assert!(Workflow::from_bpmn("<process id=\"p1\" />").is_err());
+}
+
+#[test]
+fn model_bpmn_export_extension() {
+    let workflow = Workflow::new().with_id("m1").with_step(|step| {
+        step.with_id("step1")
+            .with_act(Act::irq(|act| act.with_key("act1")))
+    });
+    let xml = workflow.to_bpmn().unwrap();
+    assert!(xml.contains("<acts:step>"));
+}
+
+/// the fields which are expressed by the bpmn structure
+fn structure(steps: &[Step]) -> serde_json::Value {
+    steps
+        .iter()
+        .map(|step| {
+            let acts = match step.acts.as_slice() {
+                [act] if ["irq", "msg", "call"].contains(&act.act.as_str()) => {
+                    vec![act.act.clone()]
+                }
+                _ => Vec::new(),
+            };
+            json!({
+                "name": step.name,
+                "run": step.run,
+                "acts": acts,
+                "catches": step.catches.iter().map(|c| c.on.clone()).collect::<Vec<_>>(),
+                "timeout": step.timeout.iter().map(|t| t.on.as_secs()).collect::<Vec<_>>(),
+                "branches": step.branches.iter().map(|b| json!({
+                    "name": b.name,
+                    "if": b.r#if.as_ref().map(|expr| expr.trim()),
+                    "else": b.r#else,
+                    "steps": structure(&b.steps),
+                })).collect::<Vec<_>>(),
+            })
+        })
+        .collect()
+}
+
+#[test]
+fn model_bpmn_round_trip_without_extensions() {
+    let re = Regex::new(r"\s*<bpmn:extensionElements>[\s\S]*?</bpmn:extensionElements>").unwrap();
+    for text in [
+        include_str!("../../../examples/approve/model.yml"),
+        include_str!("../../../examples/catches/model.yml"),
+        include_str!("../../../examples/simple/model.yml"),
+        include_str!("../../../examples/timeout/model.yml"),
+    ] {
+        let workflow = Workflow::from_yml(text).unwrap();
+        let xml = workflow.to_bpmn().unwrap();
+        let xml = re.replace_all(&xml, "");
+        assert!(!xml.contains("acts:"));
+
+        let result = Workflow::from_bpmn(&xml).unwrap();
+        assert_eq!(result.name, workflow.name);
+        assert_eq!(structure(&result.steps), structure(&workflow.steps));
+    }
+}
+
+#[test]
+fn model_bpmn_import_edited_bpmn() {
+    let text = r#"
+    id: m1
+    name: model 1
+    steps:
+      - id: step1
+        name: step 1
+        inputs:
+          a: 1
+        acts:
+          - act: call
+            key: sub1
+            inputs:
+              b: 2
+        catches:
+          - on: err1
+        timeout:
+          - on: 2h
+      - id: step2
+        branches:
+          - id: b1
+            if: $("v") > 0
+          - id: b2
+            else: true
+    "#;
+    let workflow = Workflow::from_yml(text).unwrap();
+    let xml = workflow
+        .to_bpmn()
+        .unwrap()
+        .replace(r#"name="model 1""#, r#"name="model 2""#)
+        .replace(r#"name="step 1""#, r#"name="step 2""#)
+        .replace(r#"calledElement="sub1""#, r#"calledElement="sub2""#)
+        .replace(r#"errorCode="err1""#, r#"errorCode="err2""#)
+        .replace("PT2H", "PT30M")
+        .replace("$(&quot;v&quot;) &gt; 0", "$(&quot;v&quot;) &gt; 10");
+
+    let result = Workflow::from_bpmn(&xml).unwrap();
+    assert_eq!(result.name, "model 2");
+    let step = &result.steps[0];
+    assert_eq!(step.name, "step 2");
+    assert_eq!(step.acts[0].key, "sub2");
+    assert_eq!(step.catches[0].on, Some("err2".to_string()));
+    assert_eq!(step.timeout[0].on.to_string(), "30m");
+    assert_eq!(
+        result.steps[1].branches[0].r#if,
+        Some(r#"$("v") > 10"#.to_string())
+    );
+    assert!(result.steps[1].branches[1].r#else);
+
+    // the data which can not be expressed by bpmn is kept
+    assert_eq!(step.inputs.get::<i32>("a"), Some(1));
+    assert_eq!(step.acts[0].inputs.get::<i32>("b"), Some(2));
+}
@@ -0,0 +1,280 @@
+use crate::{Act, Catch, Fragment, Vars, Workflow};
+use serde_json::json;
+
+#[test]
+fn model_fragment_from_yml() {
+    let text = r#"
+    id: approve
+    name: approve fragment
+    params:
+      role: admin
+    steps:
+      - id: step1
+        acts:
+          - act: irq
+            key: "{{ role }}"
+    "#;
+    let fragment = Fragment::from_yml(text).unwrap();
+    assert_eq!(fragment.id, "approve");
+    assert_eq!(fragment.params.get::<String>("role").unwrap(), "admin");
+    assert_eq!(fragment.steps.len(), 1);
+}
+
+#[test]
+fn model_fragment_include_steps() {
+    let text = r#"
+    id: m1
+    fragments:
+      - id: approve
+        steps:
+          - id: step1
+          - id: step2
+    steps:
+      - id: start
+      - include: approve
+      - id: end
+    "#;
+    let mut workflow = Workflow::from_yml(text).unwrap();
+    workflow.expand(&[]).unwrap();
+    let ids = workflow
+        .steps
+        .iter()
+        .map(|s| s.id.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(ids, ["start", "approve.step1", "approve.step2", "end"]);
+}
+
+#[test]
+fn model_fragment_include_prefix_by_include_id() {
+    let text = r#"
+    id: m1
+    fragments:
+      - id: approve
+        steps:
+          - id: step1
+    steps:
+      - id: a
+        include: approve
+      - id: b
+        include: approve
+    "#;
+    let mut workflow = Workflow::from_yml(text).unwrap();
+    workflow.expand(&[]).unwrap();
+    assert_eq!(workflow.steps[0].id, "a.step1");
+    assert_eq!(workflow.steps[1].id, "b.step1");
+    assert!(workflow.valid().is_ok());
+}
+
+#[test]
+fn model_fragment_include_twice_without_id() {
+    let text = r#"
+    id: m1
+    fragments:
+      - id: approve
+        steps:
+          - id: step1
+    steps:
+      - include: approve
+      - include: approve
+    "#;
+    let mut workflow = Workflow::from_yml(text).unwrap();
+    workflow.expand(&[]).unwrap();
+    assert_eq!(workflow.steps[0].id, "approve.step1");
+    assert_eq!(workflow.steps[1].id, "approve2.step1");
+}
+
+#[test]
+fn model_fragment_include_prefix_refs() {
+    let text = r#"
+    id: m1
+    fragments:
+      - id: review
+        steps:
+          - id: step1
+            branches:
+              - id: b1
+                if: "true"
+                next: step3
+              - id: b2
+                else: true
+          - id: step2
+            next: step1
+          - id: step3
+    steps:
+      - include: review
+    "#;
+    let mut workflow = Workflow::from_yml(text).unwrap();
+    workflow.expand(&[]).unwrap();
+    assert_eq!(workflow.steps[0].branches[0].id, "review.b1");
+    assert_eq!(
+        workflow.steps[0].branches[0].next.as_deref(),
+        Some("review.step3")
+    );
+    assert_eq!(workflow.steps[1].next.as_deref(), Some("review.step1"));
+}
+
+#[test]
+fn model_fragment_include_params() {
+    let text = r#"
+    id: m1
+    fragments:
+      - id: approve
+        params:
+          role: admin
+          count: 1
+        steps:
+          - id: step1
+            name: "approve by {{ role }}"
+            inputs:
+              count: "{{ count }}"
+    steps:
+      - include: approve
+        inputs:
+          count: 3
+    "#;
+    let mut workflow = Workflow::from_yml(text).unwrap();
+    workflow.expand(&[]).unwrap();
+    assert_eq!(workflow.steps[0].name, "approve by admin");
+    assert_eq!(workflow.steps[0].inputs.get_value("count"), Some(&json!(3)));
+}
+
+#[test]
+fn model_fragment_include_param_not_defined() {
+    let text = r#"
+    id: m1
+    fragments:
+      - id: approve
+        params:
+          role: admin
+        steps:
+          - id: step1
+            name: "{{ owner }} by {{ role }}"
+            inputs:
+              owner: "{{ owner }}"
+    steps:
+      - include: approve
+    "#;
+    let mut workflow = Workflow::from_yml(text).unwrap();
+    workflow.expand(&[]).unwrap();
+    assert_eq!(workflow.steps[0].name, "{{ owner }} by admin");
+    assert_eq!(
+        workflow.steps[0].inputs.get_value("owner"),
+        Some(&json!("{{ owner }}"))
+    );
+}
+
+#[test]
+fn model_fragment_include_script_braces() {
+    let text = r#"
+    id: m1
+    fragments:
+      - id: render
+        params:
+          name: world
+        steps:
+          - id: step1
+            run: |
+              const tpl = "{{ greeting }}, {{ name }}";
+              const obj = {{a: 1}}.a;
+              $("text", tpl.replace("{{ greeting }}", "hello"));
+    steps:
+      - include: render
+    "#;
+    let mut workflow = Workflow::from_yml(text).unwrap();
+    workflow.expand(&[]).unwrap();
+    let run = workflow.steps[0].run.as_deref().unwrap();
+    assert!(run.contains(r#"const tpl = "{{ greeting }}, world";"#));
+    assert!(run.contains("const obj = {{a: 1}}.a;"));
+    assert!(run.contains(r#"tpl.replace("{{ greeting }}", "hello")"#));
+}
+
+#[test]
+fn model_fragment_include_acts() {
+    let fragment = Fragment::new()
+        .with_id("notify")
+        .with_act(Act::msg(|msg| msg.with_key("{{ key }}")));
+    let mut workflow = Workflow::new().with_id("m1").with_step(|step| {
+        step.with_id("step1")
+            .with_act(Act::include("notify", Vars::new().with("key", "msg1")))
+    });
+    workflow.expand(&[fragment]).unwrap();
+    assert_eq!(workflow.steps[0].acts.len(), 1);
+    assert_eq!(workflow.steps[0].acts[0].act, "msg");
+    assert_eq!(workflow.steps[0].acts[0].key, "msg1");
+}
+
+#[test]
+fn model_fragment_include_catches() {
+    let fragment = Fragment::new()
+        .with_id("errors")
+        .with_catch(|c| c.with_on("err1"))
+        .with_catch(|c| c.with_on("err2"));
+    let mut workflow = Workflow::new().with_id("m1").with_step(|step| {
+        step.with_id("step1")
+            .with_catch(|c| c.with_include("errors"))
+            .with_catch(|c| c.with_on("err3"))
+    });
+    workflow.expand(&[fragment]).unwrap();
+    let ons = workflow.steps[0]
+        .catches
+        .iter()
+        .map(|c| c.on.clone().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(ons, ["err1", "err2", "err3"]);
+}
+
+#[test]
+fn model_fragment_include_nested() {
+    let inner = Fragment::new()
+        .with_id("inner")
+        .with_step(|step| step.with_id("step1"));
+    let outer = Fragment::new()
+        .with_id("outer")
+        .with_step(|step| step.with_include("inner"));
+    let mut workflow = Workflow::new()
+        .with_id("m1")
+        .with_step(|step| step.with_include("outer"));
+    workflow.expand(&[inner, outer]).unwrap();
+    assert_eq!(workflow.steps[0].id, "outer.inner.step1");
+}
+
+#[test]
+fn model_fragment_include_cycle() {
+    let a = Fragment::new()
+        .with_id("a")
+        .with_step(|step| step.with_include("b"));
+    let b = Fragment::new()
+        .with_id("b")
+        .with_step(|step| step.with_include("a"));
+    let mut workflow = Workflow::new()
+        .with_id("m1")
+        .with_step(|step| step.with_include("a"));
+    let err = workflow.expand(&[a, b]).unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("found fragment cycle 'a -> b -> a'"));
+}
+
+#[test]
+fn model_fragment_include_missing() {
+    let mut workflow = Workflow::new()
+        .with_id("m1")
+        .with_step(|step| step.with_include("not_exists"));
+    let err = workflow.expand(&[]).unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("cannot find fragment 'not_exists'"));
+    assert!(workflow.valid().is_err());
+}
+
+#[test]
+fn model_fragment_includes() {
+    let workflow = Workflow::new()
+        .with_id("m1")
+        .with_step(|step| {
+            step.with_include("f1")
+                .with_catch(|c: Catch| c.with_include("f2"))
+        })
+        .with_step(|step| step.with_act(Act::include("f3", Vars::new())));
+    assert_eq!(workflow.includes(), ["f1", "f2", "f3"]);
+}
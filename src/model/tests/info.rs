@@ -1,8 +1,8 @@
 use serde_json::Value;
 
 use crate::{
-    data, sch::NodeData, utils, ModelInfo, NodeKind, PackageInfo, ProcInfo, TaskInfo, TaskState,
-    Workflow,
+    data, sch::NodeData, utils, Limits, Manifest, ModelInfo, NodeKind, PackageInfo, ProcInfo,
+    TaskInfo, TaskState, Workflow,
 };
 
 #[test]
@@ -15,6 +15,10 @@ fn model_info_package() {
         create_time: 0,
         update_time: 0,
         timestamp: 0,
+        limits: Limits::default(),
+        manifest: Manifest::default(),
+        pack_id: String::new(),
+        code: Vec::new(),
     };
     let info: PackageInfo = package.into();
     assert_eq!(info.id, package.id);
@@ -36,8 +40,11 @@ fn model_info_proc() {
         end_time: 2345,
         timestamp: 11111,
         model: "{}".to_string(),
+        ver: 0,
+        inputs: "{}".to_string(),
         env_local: "".to_string(),
         err: None,
+        parent_id: String::new(),
     };
     let info: ProcInfo = proc.into();
     assert_eq!(info.id, proc.id);
@@ -96,6 +103,7 @@ fn model_info_model() {
         update_time: 0,
         data: "{}".to_string(),
         timestamp: 0,
+        code: Vec::new(),
     };
     let info: ModelInfo = model.clone().into();
     assert_eq!(info.id, model.id);
@@ -117,6 +125,10 @@ fn model_info_package_arr_to_value() {
         create_time: 0,
         update_time: 0,
         timestamp: 0,
+        limits: Limits::default(),
+        manifest: Manifest::default(),
+        pack_id: String::new(),
+        code: Vec::new(),
     };
     let info: PackageInfo = package.into();
 
@@ -155,8 +167,11 @@ fn model_info_proc_arr_to_value() {
         end_time: 2345,
         timestamp: 11111,
         model: "{}".to_string(),
+        ver: 0,
+        inputs: "{}".to_string(),
         env_local: "".to_string(),
         err: None,
+        parent_id: String::new(),
     };
     let info: ProcInfo = proc.into();
 
@@ -247,6 +262,7 @@ fn model_info_model_arr_to_value() {
         update_time: 0,
         data: "{}".to_string(),
         timestamp: 0,
+        code: Vec::new(),
     };
 
     let mut arr: Vec<ModelInfo> = Vec::new();
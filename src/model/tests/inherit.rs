@@ -0,0 +1,149 @@
+use crate::{model::parse_extends, Act, Vars, Workflow};
+use serde_json::json;
+
+fn base() -> Workflow {
+    let text = r#"
+    id: base
+    name: purchase
+    env:
+      region: global
+    inputs:
+      a: 1
+      b: 2
+    setup:
+      - id: init
+        act: msg
+        key: init
+    steps:
+      - id: apply
+      - id: approve
+        branches:
+          - id: b1
+            if: "true"
+            steps:
+              - id: manager
+              - id: finance
+      - id: pay
+    "#;
+    Workflow::from_yml(text).unwrap()
+}
+
+fn ids(steps: &[crate::Step]) -> Vec<String> {
+    steps.iter().map(|s| s.id.clone()).collect()
+}
+
+#[test]
+fn model_inherit_parse_extends() {
+    assert_eq!(parse_extends("base"), ("base", None));
+    assert_eq!(parse_extends("base@2"), ("base", Some(2)));
+    assert_eq!(parse_extends("a@b"), ("a@b", None));
+}
+
+#[test]
+fn model_inherit_override_step() {
+    let child = Workflow::new()
+        .with_id("child")
+        .with_step(|step| step.with_id("pay").with_name("pay by card"));
+    let workflow = child.inherit(&base()).unwrap();
+    assert_eq!(workflow.id, "child");
+    assert_eq!(workflow.name, "purchase");
+    assert_eq!(ids(&workflow.steps), ["apply", "approve", "pay"]);
+    assert_eq!(workflow.steps[2].name, "pay by card");
+}
+
+#[test]
+fn model_inherit_insert_after_step() {
+    let child = Workflow::new()
+        .with_id("child")
+        .with_step(|step| step.with_id("apply"))
+        .with_step(|step| step.with_id("check"))
+        .with_step(|step| step.with_id("audit"));
+    let workflow = child.inherit(&base()).unwrap();
+    assert_eq!(
+        ids(&workflow.steps),
+        ["apply", "check", "audit", "approve", "pay"]
+    );
+}
+
+#[test]
+fn model_inherit_insert_before_step() {
+    let child = Workflow::new()
+        .with_id("child")
+        .with_step(|step| step.with_id("check"))
+        .with_step(|step| step.with_id("pay"));
+    let workflow = child.inherit(&base()).unwrap();
+    assert_eq!(ids(&workflow.steps), ["apply", "approve", "check", "pay"]);
+}
+
+#[test]
+fn model_inherit_append_step() {
+    let child = Workflow::new()
+        .with_id("child")
+        .with_step(|step| step.with_id("notify"));
+    let workflow = child.inherit(&base()).unwrap();
+    assert_eq!(ids(&workflow.steps), ["apply", "approve", "pay", "notify"]);
+}
+
+#[test]
+fn model_inherit_branch_step() {
+    let child = Workflow::new()
+        .with_id("child")
+        .with_step(|step| step.with_id("manager").with_name("regional manager"))
+        .with_step(|step| step.with_id("director"));
+    let workflow = child.inherit(&base()).unwrap();
+    let steps = &workflow.steps[1].branches[0].steps;
+    assert_eq!(ids(steps), ["manager", "director", "finance"]);
+    assert_eq!(steps[0].name, "regional manager");
+}
+
+#[test]
+fn model_inherit_remove_step() {
+    let child = Workflow::new()
+        .with_id("child")
+        .with_remove("apply")
+        .with_remove("finance");
+    let workflow = child.inherit(&base()).unwrap();
+    assert_eq!(ids(&workflow.steps), ["approve", "pay"]);
+    assert_eq!(ids(&workflow.steps[0].branches[0].steps), ["manager"]);
+}
+
+#[test]
+fn model_inherit_merge_vars() {
+    let child = Workflow::new()
+        .with_id("child")
+        .with_env("region", json!("eu"))
+        .with_input("b", json!(20))
+        .with_input("c", json!(3))
+        .with_output("total", json!(null));
+    let workflow = child.inherit(&base()).unwrap();
+    assert_eq!(workflow.env.get_value("region"), Some(&json!("eu")));
+    assert_eq!(workflow.inputs.get_value("a"), Some(&json!(1)));
+    assert_eq!(workflow.inputs.get_value("b"), Some(&json!(20)));
+    assert_eq!(workflow.inputs.get_value("c"), Some(&json!(3)));
+    assert!(workflow.outputs.contains_key("total"));
+}
+
+#[test]
+fn model_inherit_merge_setup() {
+    let mut child = Workflow::new().with_id("child");
+    child
+        .setup
+        .push(Act::msg(|msg| msg.with_key("init2")).with_id("init"));
+    child.setup.push(Act::set(Vars::new().with("a", 10)));
+    let workflow = child.inherit(&base()).unwrap();
+    assert_eq!(workflow.setup.len(), 2);
+    assert_eq!(workflow.setup[0].key, "init2");
+    assert_eq!(workflow.setup[1].act, "set");
+}
+
+#[test]
+fn model_inherit_valid() {
+    let child = Workflow::new()
+        .with_id("child")
+        .with_extends("base")
+        .with_step(|step| step.with_id("apply"))
+        .with_step(|step| step.with_id("apply2"));
+    let workflow = child.inherit(&base()).unwrap();
+    assert!(workflow.extends.is_none());
+    assert!(workflow.valid().is_ok());
+}
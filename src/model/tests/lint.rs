@@ -0,0 +1,302 @@
+use crate::{Diagnostic, Severity, Workflow};
+
+fn codes(diagnostics: &[Diagnostic]) -> Vec<&str> {
+    diagnostics.iter().map(|d| d.code.as_str()).collect()
+}
+
+#[test]
+fn model_lint_ok() {
+    let text = r#"
+id: m1
+steps:
+  - id: step1
+    run: |
+      let a = 1;
+      console.log(a);
+    acts:
+      - act: irq
+        key: act1
+  - id: step2
+    branches:
+      - id: b1
+        if: $("v") > 0
+      - id: b2
+        else: true
+"#;
+    let diagnostics = Workflow::lint_yml(text);
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+}
+
+#[test]
+fn model_lint_yaml_error() {
+    let text = r#"
+id: m1
+steps:
+  - id: step1
+   name: error indent
+"#;
+    let diagnostics = Workflow::lint_yml(text);
+    assert_eq!(codes(&diagnostics), ["yaml"]);
+    assert!(diagnostics[0].line > 0);
+}
+
+#[test]
+fn model_lint_dup_id() {
+    let text = r#"
+id: m1
+steps:
+  - id: step1
+  - id: step1
+"#;
+    let diagnostics = Workflow::lint_yml(text);
+    assert_eq!(codes(&diagnostics), ["dup-id"]);
+    assert_eq!(diagnostics[0].path, "steps[1].id");
+    assert_eq!((diagnostics[0].line, diagnostics[0].column), (5, 5));
+}
+
+#[test]
+fn model_lint_dangling_next() {
+    let text = r#"
+id: m1
+steps:
+  - id: step1
+    next: not_exists
+  - id: step2
+    next: step1
+"#;
+    let diagnostics = Workflow::lint_yml(text);
+    assert!(codes(&diagnostics).contains(&"dangling-next"));
+    let d = diagnostics
+        .iter()
+        .find(|d| d.code == "dangling-next")
+        .unwrap();
+    assert_eq!(d.path, "steps[0].next");
+    assert_eq!((d.line, d.column), (5, 5));
+}
+
+#[test]
+fn model_lint_unknown_act() {
+    let text = r#"
+id: m1
+steps:
+  - id: step1
+    acts:
+      - act: irq
+        key: act1
+      - act: not_exists
+"#;
+    let diagnostics = Workflow::lint_yml(text);
+    assert_eq!(codes(&diagnostics), ["unknown-act"]);
+    assert_eq!(diagnostics[0].path, "steps[0].acts[1].act");
+    assert_eq!((diagnostics[0].line, diagnostics[0].column), (8, 9));
+}
+
+#[test]
+fn model_lint_registered_act() {
+    let text = r#"
+id: m1
+steps:
+  - id: step1
+    acts:
+      - act: test_lint_act
+"#;
+    assert_eq!(codes(&Workflow::lint_yml(text)), ["unknown-act"]);
+    let acts = vec!["test_lint_act".to_string()];
+    assert!(Workflow::lint_yml_with(text, &acts).is_empty());
+}
+
+#[test]
+fn model_lint_missing_key() {
+    let text = r#"
+id: m1
+setup:
+  - act: on_created
+    then:
+      - act: call
+steps:
+  - id: step1
+    acts:
+      - act: irq
+      - act: pack
+"#;
+    let diagnostics = Workflow::lint_yml(text);
+    assert_eq!(
+        codes(&diagnostics),
+        ["missing-key", "missing-key", "missing-key"]
+    );
+    assert_eq!(diagnostics[0].path, "setup[0].then[0]");
+    assert_eq!((diagnostics[0].line, diagnostics[0].column), (6, 7));
+    assert_eq!((diagnostics[1].line, diagnostics[1].column), (10, 7));
+    assert_eq!((diagnostics[2].line, diagnostics[2].column), (11, 7));
+}
+
+#[test]
+fn model_lint_cmd_missing_key() {
+    let text = r#"
+id: m1
+steps:
+  - id: step1
+    acts:
+      - act: cmd
+"#;
+    let diagnostics = Workflow::lint_yml(text);
+    assert_eq!(codes(&diagnostics), ["missing-key"]);
+    assert_eq!(diagnostics[0].path, "steps[0].acts[0]");
+    assert_eq!((diagnostics[0].line, diagnostics[0].column), (6, 7));
+}
+
+#[test]
+fn model_lint_flow_style() {
+    let text = r#"
+id: m1
+steps: [{ id: step1 }, { id: step2, next: not_exists }]
+"#;
+    let diagnostics = Workflow::lint_yml(text);
+    assert_eq!(codes(&diagnostics), ["dangling-next"]);
+    assert_eq!(diagnostics[0].path, "steps[1].next");
+    assert_eq!((diagnostics[0].line, diagnostics[0].column), (3, 37));
+}
+
+#[test]
+fn model_lint_alias() {
+    let text = r#"
+id: m1
+steps:
+  - id: step1
+    acts: &acts
+      - act: not_exists
+  - id: step2
+    acts: *acts
+"#;
+    let diagnostics = Workflow::lint_yml(text);
+    assert_eq!(codes(&diagnostics), ["unknown-act", "unknown-act"]);
+    assert_eq!(diagnostics[1].path, "steps[1].acts[0].act");
+    // the aliased act is located at the anchored node
+    assert_eq!((diagnostics[1].line, diagnostics[1].column), (6, 9));
+}
+
+#[test]
+fn model_lint_unreachable_step() {
+    let text = r#"
+id: m1
+steps:
+  - id: step1
+  - id: step2
+    next: step1
+  - id: step3
+"#;
+    let diagnostics = Workflow::lint_yml(text);
+    assert_eq!(codes(&diagnostics), ["unreachable-step"]);
+    assert_eq!(diagnostics[0].severity, Severity::Warning);
+    assert!(!diagnostics[0].is_error());
+    assert_eq!(diagnostics[0].line, 7);
+}
+
+#[test]
+fn model_lint_branch_no_condition() {
+    let text = r#"
+id: m1
+steps:
+  - id: step1
+    branches:
+      - id: b1
+        if: $("v") > 0
+      - id: b2
+      - id: b3
+        needs: [b1]
+"#;
+    let diagnostics = Workflow::lint_yml(text);
+    assert_eq!(codes(&diagnostics), ["branch-no-condition"]);
+    assert_eq!(diagnostics[0].path, "steps[0].branches[1]");
+    assert_eq!(diagnostics[0].line, 8);
+}
+
+#[test]
+fn model_lint_template_script() {
+    let text = r#"
+id: m1
+inputs:
+  a: Order ${ $("id") | upper } approved by ${ $("uid") | default("n/a") }
+steps:
+  - id: step1
+    acts:
+      - act: msg
+        inputs:
+          order:
+            tags:
+              - id-${ $("id") + }
+"#;
+    let diagnostics = Workflow::lint_yml(text);
+    assert_eq!(codes(&diagnostics), ["invalid-script"]);
+    assert_eq!(diagnostics[0].path, "steps[0].acts[0].inputs.order.tags[0]");
+}
+
+#[cfg(feature = "rhai")]
+#[test]
+fn model_lint_script_lang() {
+    let text = r#"
+id: m1
+lang: rhai
+steps:
+  - id: step1
+    if: get("v") > 100
+    run: |
+      let a = [1, 2];
+      set("b", a.len());
+  - id: step2
+    run: |
+      let a = ;
+"#;
+    let diagnostics = Workflow::lint_yml(text);
+    assert_eq!(codes(&diagnostics), ["invalid-script"]);
+    assert_eq!(diagnostics[0].path, "steps[1].run");
+}
+
+#[test]
+fn model_lint_invalid_script() {
+    let text = r#"
+id: m1
+inputs:
+  a: ${ $("v") + }
+steps:
+  - id: step1
+    if: ${ $("v") > }
+    run: |
+      let a = ;
+    acts:
+      - act: if
+        on: $("v") >
+"#;
+    let diagnostics = Workflow::lint_yml(text);
+    assert_eq!(
+        codes(&diagnostics),
+        [
+            "invalid-script",
+            "invalid-script",
+            "invalid-script",
+            "invalid-script"
+        ]
+    );
+    let paths: Vec<_> = diagnostics.iter().map(|d| d.path.as_str()).collect();
+    assert_eq!(
+        paths,
+        [
+            "inputs.a",
+            "steps[0].run",
+            "steps[0].if",
+            "steps[0].acts[0].on"
+        ]
+    );
+    let lines: Vec<_> = diagnostics.iter().map(|d| d.line).collect();
+    assert_eq!(lines, [4, 8, 7, 12]);
+}
+
+#[test]
+fn model_lint_many() {
+    let model = Workflow::new()
+        .with_step(|step| step.with_id("step1"))
+        .with_step(|step| step.with_id("step1").with_next("not_exists"));
+    let diagnostics = model.lint();
+    assert_eq!(codes(&diagnostics), ["dup-id", "dangling-next"]);
+    assert_eq!(diagnostics[0].line, 0);
+}
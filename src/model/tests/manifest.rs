@@ -0,0 +1,89 @@
+use crate::{
+    model::{is_version_match, parse_package_key},
+    ActError, Manifest, Output, OutputType, Vars,
+};
+use serde_json::json;
+
+#[test]
+fn model_manifest_parse_key() {
+    assert_eq!(parse_package_key("pack1"), ("pack1", None));
+    assert_eq!(parse_package_key("pack1@1.0.0"), ("pack1", Some("1.0.0")));
+    assert_eq!(parse_package_key("pack1@^1.2"), ("pack1", Some("^1.2")));
+    assert_eq!(parse_package_key("pack1@"), ("pack1@", None));
+    assert_eq!(parse_package_key("@scope/pack1"), ("@scope/pack1", None));
+    assert_eq!(
+        parse_package_key("@scope/pack1@1.0.0"),
+        ("@scope/pack1", Some("1.0.0"))
+    );
+}
+
+#[test]
+fn model_manifest_version_match() {
+    assert!(is_version_match("1.0.0", "1.0.0"));
+    assert!(!is_version_match("1.0.1", "1.0.0"));
+    assert!(is_version_match("1.2.3", "^1.0"));
+    assert!(!is_version_match("2.0.0", "^1.0"));
+    assert!(is_version_match("1.2.3", ">=1.2, <1.3"));
+    assert!(!is_version_match("", "^1.0"));
+    assert!(!is_version_match("1.0.0", "abc"));
+}
+
+#[test]
+fn model_manifest_yml() {
+    let text = r#"
+    version: 1.2.0
+    description: the test package
+    dependencies:
+      utils: ^1.0
+    inputs:
+      name:
+        type: string
+        required: true
+    outputs:
+      result:
+        type: number
+    "#;
+    let manifest: Manifest = serde_yaml::from_str(text).unwrap();
+    assert_eq!(manifest.version, "1.2.0");
+    assert_eq!(manifest.description, "the test package");
+    assert_eq!(manifest.dependencies.get("utils").unwrap(), "^1.0");
+    assert!(manifest.inputs.get("name").unwrap().required);
+    assert!(matches!(
+        manifest.outputs.get("result").unwrap().r#type,
+        OutputType::Number
+    ));
+    assert!(manifest.check().is_ok());
+}
+
+#[test]
+fn model_manifest_check_inputs() {
+    let manifest = Manifest::new().with_input(
+        "name",
+        Output {
+            required: true,
+            r#type: OutputType::String,
+            ..Default::default()
+        },
+    );
+    let vars: Vars = json!({ "name": 10 }).into();
+    assert!(matches!(
+        manifest.inputs.check(&vars),
+        Err(ActError::Validation(_))
+    ));
+    let vars: Vars = json!({ "name": "a" }).into();
+    assert!(manifest.inputs.check(&vars).is_ok());
+}
+
+#[test]
+fn model_manifest_invalid_version() {
+    let manifest = Manifest::new().with_version("1.0");
+    assert!(matches!(manifest.check(), Err(ActError::Action(_))));
+}
+
+#[test]
+fn model_manifest_invalid_dependency() {
+    let manifest = Manifest::new()
+        .with_version("1.0.0")
+        .with_dependency("utils", "abc");
+    assert!(matches!(manifest.check(), Err(ActError::Action(_))));
+}
@@ -1,7 +1,13 @@
 mod act;
+mod bpmn;
 mod branch;
+mod fragment;
 mod info;
+mod inherit;
+mod lint;
+mod manifest;
 mod output;
+mod schema;
 mod step;
 mod valid;
 mod vars;
@@ -1,4 +1,4 @@
-use crate::{Output, OutputType, Outputs};
+use crate::{Output, OutputType, Outputs, Vars};
 use serde_json::json;
 
 #[test]
@@ -104,6 +104,7 @@ fn model_outputs_one() {
             default: "abc".into(),
             required: true,
             r#type: OutputType::String,
+            ..Default::default()
         },
     );
 
@@ -124,6 +125,7 @@ fn model_outputs_many() {
             default: "abc".into(),
             required: true,
             r#type: OutputType::String,
+            ..Default::default()
         },
     );
 
@@ -133,6 +135,7 @@ fn model_outputs_many() {
             default: json!(0),
             required: false,
             r#type: OutputType::Number,
+            ..Default::default()
         },
     );
 
@@ -142,6 +145,7 @@ fn model_outputs_many() {
             default: json!([]),
             required: true,
             r#type: OutputType::Array,
+            ..Default::default()
         },
     );
 
@@ -151,6 +155,7 @@ fn model_outputs_many() {
             default: json!(null),
             required: true,
             r#type: OutputType::Object,
+            ..Default::default()
         },
     );
 
@@ -176,3 +181,117 @@ fn model_outputs_many() {
     assert_eq!(output.get("required").unwrap(), true);
     assert_eq!(output.get("type").unwrap(), "Object");
 }
+
+#[test]
+fn model_output_parse_enum_and_pattern() {
+    let text = r#"
+    a:
+      type: string
+      enum: ["u1", "u2"]
+      pattern: ^u[0-9]+$
+    "#;
+    let outputs: Outputs = serde_yaml::from_str(text).unwrap();
+    let output = outputs.get("a").unwrap();
+    assert!(matches!(output.r#type, OutputType::String));
+    assert_eq!(output.r#enum, vec![json!("u1"), json!("u2")]);
+    assert_eq!(output.pattern.as_deref(), Some("^u[0-9]+$"));
+
+    let value = serde_json::to_value(&outputs).unwrap();
+    assert_eq!(value["a"]["enum"], json!(["u1", "u2"]));
+}
+
+#[test]
+fn model_outputs_validate_all() {
+    let text = r#"
+    a:
+      required: true
+    b:
+      type: Number
+    c:
+      enum: ["x", "y"]
+    d:
+      pattern: ^[a-z]+$
+    e:
+      type: Bool
+    "#;
+    let outputs: Outputs = serde_yaml::from_str(text).unwrap();
+    let vars = Vars::new()
+        .with("b", "abc")
+        .with("c", "z")
+        .with("d", "ABC")
+        .with("e", true);
+    let errors = outputs.validate(&vars);
+    assert_eq!(
+        errors.iter().map(|e| e.key.as_str()).collect::<Vec<_>>(),
+        ["a", "b", "c", "d"]
+    );
+    assert!(outputs.check(&vars).is_err());
+}
+
+#[test]
+fn model_outputs_validate_ok() {
+    let text = r#"
+    a:
+      required: true
+      type: Number
+      enum: [1, 2]
+    b:
+    "#;
+    let outputs: Outputs = serde_yaml::from_str(text).unwrap();
+    let vars = Vars::new().with("a", 2);
+    assert!(outputs.validate(&vars).is_empty());
+    assert!(outputs.check(&vars).is_ok());
+}
+
+#[test]
+fn model_outputs_fill_defaults() {
+    let text = r#"
+    a:
+      default: 10
+      type: Number
+    b:
+      default: abc
+    "#;
+    let outputs: Outputs = serde_yaml::from_str(text).unwrap();
+    let mut vars = Vars::new().with("b", "value");
+    outputs.fill_defaults(&mut vars);
+    assert_eq!(vars.get::<i32>("a"), Some(10));
+    assert_eq!(vars.get::<String>("b"), Some("value".to_string()));
+}
+
+#[test]
+fn model_output_from_ret() {
+    let output = Output::from_ret(&json!(null));
+    assert!(!output.required);
+    assert!(matches!(output.r#type, OutputType::Any));
+    assert!(output.validate("a", Some(&json!(null))).is_none());
+
+    let output = Output::from_ret(&json!({ "required": true }));
+    assert!(output.validate("a", Some(&json!(null))).is_some());
+
+    let output = Output::from_ret(&json!({ "type": "bool", "required": false }));
+    assert!(!output.required);
+    assert!(matches!(output.r#type, OutputType::Bool));
+    assert!(output.validate("a", Some(&json!("yes"))).is_some());
+    assert!(output.validate("a", Some(&json!(true))).is_none());
+}
+
+#[test]
+fn model_output_pattern_cached() {
+    let output = Output {
+        pattern: Some("^cached[0-9]+$".to_string()),
+        ..Default::default()
+    };
+    assert!(!crate::model::is_pattern_cached("^cached[0-9]+$"));
+    assert_eq!(output.validate("name", Some(&json!("cached1"))), None);
+    assert!(crate::model::is_pattern_cached("^cached[0-9]+$"));
+    assert!(output.validate("name", Some(&json!("abc"))).is_some());
+
+    // the invalid pattern is not cached
+    let output = Output {
+        pattern: Some("[a-".to_string()),
+        ..Default::default()
+    };
+    assert!(output.validate("name", Some(&json!("abc"))).is_some());
+    assert!(!crate::model::is_pattern_cached("[a-"));
+}
@@ -0,0 +1,245 @@
+use crate::{
+    model::{Branch, Catch, Fragment, Limits, Output, Step, Timeout},
+    Act, Workflow,
+};
+use serde::{de, Deserialize};
+use serde_json::{json, Value};
+
+fn check(text: &str) -> Vec<String> {
+    let schema = Workflow::json_schema();
+    let validator = jsonschema::validator_for(&schema).unwrap();
+    let value: Value = serde_yaml::from_str(text).unwrap();
+    validator
+        .iter_errors(&value)
+        .map(|err| format!("{}: {}", err.instance_path, err))
+        .collect()
+}
+
+/// the deserializer which only captures the struct field names
+struct Fields(&'static [&'static str]);
+
+impl<'de> de::Deserializer<'de> for &mut Fields {
+    type Error = de::value::Error;
+
+    fn deserialize_any<V: de::Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(de::Error::custom("not a struct"))
+    }
+
+    fn deserialize_struct<V: de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.0 = fields;
+        Err(de::Error::custom("fields captured"))
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map enum identifier ignored_any
+    }
+}
+
+/// the serde field names of the struct
+fn fields<'de, T: Deserialize<'de>>() -> Vec<&'static str> {
+    let mut capture = Fields(&[]);
+    let _ = T::deserialize(&mut capture);
+    let mut ret = capture.0.to_vec();
+    ret.sort();
+    ret
+}
+
+fn keys(schema: &Value) -> Vec<&str> {
+    let mut ret = schema["properties"]
+        .as_object()
+        .unwrap()
+        .keys()
+        .map(|key| key.as_str())
+        .collect::<Vec<_>>();
+    ret.sort();
+    ret
+}
+
+#[test]
+fn model_schema_meta() {
+    let schema = Workflow::json_schema();
+    assert_eq!(schema["$schema"], "http://json-schema.org/draft-07/schema#");
+    assert_eq!(schema["title"], "Workflow");
+    assert!(schema["definitions"]["act"]["properties"]["act"]["enum"]
+        .as_array()
+        .unwrap()
+        .contains(&json!("irq")));
+}
+
+#[test]
+fn model_schema_serde_fields() {
+    let schema = Workflow::json_schema();
+    let definitions = &schema["definitions"];
+    assert_eq!(keys(&schema), fields::<Workflow>());
+    assert_eq!(keys(&schema["properties"]["limits"]), fields::<Limits>());
+    assert_eq!(keys(&definitions["step"]), fields::<Step>());
+    assert_eq!(keys(&definitions["branch"]), fields::<Branch>());
+    assert_eq!(keys(&definitions["act"]), fields::<Act>());
+    // the catch `id` is accepted for the existing models but is not kept
+    let mut catch = fields::<Catch>();
+    catch.push("id");
+    catch.sort();
+    assert_eq!(keys(&definitions["catch"]), catch);
+    assert_eq!(keys(&definitions["fragment"]), fields::<Fragment>());
+    assert_eq!(keys(&definitions["timeout"]), fields::<Timeout>());
+    assert_eq!(keys(&definitions["output"]), fields::<Output>());
+}
+
+#[test]
+fn model_schema_no_additional_properties() {
+    let schema = Workflow::json_schema();
+    assert_eq!(schema["additionalProperties"], json!(false));
+    for (name, definition) in schema["definitions"].as_object().unwrap() {
+        if definition.get("properties").is_some() {
+            assert_eq!(
+                definition["additionalProperties"],
+                json!(false),
+                "definition '{name}'"
+            );
+        }
+    }
+}
+
+#[test]
+fn model_schema_validate_unknown_field() {
+    let text = r#"
+    id: m1
+    steps:
+      - id: step1
+        acts:
+          - act: irq
+            key: act1
+            keys: act2
+    "#;
+    assert_eq!(
+        check(text),
+        vec!["/steps/0/acts/0: Additional properties are not allowed ('keys' was unexpected)"]
+    );
+}
+
+#[test]
+fn model_schema_act_required() {
+    let schema = Workflow::json_schema();
+    let rules = schema["definitions"]["act"]["allOf"].as_array().unwrap();
+    let rule = |name: &str| {
+        rules
+            .iter()
+            .find(|r| r["if"]["properties"]["act"]["const"] == name)
+            .unwrap()["then"]["required"]
+            .clone()
+    };
+    assert_eq!(rule("irq"), json!(["key"]));
+    assert_eq!(rule("call"), json!(["key"]));
+    assert_eq!(rule("each"), json!(["in", "then"]));
+    assert_eq!(rule("if"), json!(["on", "then"]));
+    assert_eq!(rule("on_catch"), json!(["catches"]));
+}
+
+#[test]
+fn model_schema_validate_examples() {
+    for text in [
+        include_str!("../../../examples/actions/model.yml"),
+        include_str!("../../../examples/approve/model.yml"),
+        include_str!("../../../examples/catches/model.yml"),
+        include_str!("../../../examples/message/model.yml"),
+        include_str!("../../../examples/package/model.yml"),
+        include_str!("../../../examples/simple/model.yml"),
+        include_str!("../../../examples/sub_workflow/model/main.yml"),
+        include_str!("../../../examples/sub_workflow/model/sub.yml"),
+    ] {
+        assert_eq!(check(text), Vec::<String>::new());
+    }
+}
+
+#[test]
+fn model_schema_validate_missing_key() {
+    let text = r#"
+    id: m1
+    steps:
+      - id: step1
+        acts:
+          - act: irq
+    "#;
+    assert_eq!(
+        check(text),
+        vec!["/steps/0/acts/0: \"key\" is a required property"]
+    );
+}
+
+#[test]
+fn model_schema_validate_unknown_act() {
+    let text = r#"
+    id: m1
+    steps:
+      - id: step1
+        acts:
+          - act: abc
+    "#;
+    let errors = check(text);
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].starts_with("/steps/0/acts/0/act: \"abc\" is not one of"));
+}
+
+#[test]
+fn model_schema_validate_each_required() {
+    let text = r#"
+    id: m1
+    steps:
+      - id: step1
+        acts:
+          - act: each
+            then:
+              - act: irq
+                key: act1
+    "#;
+    assert_eq!(
+        check(text),
+        vec!["/steps/0/acts/0: \"in\" is a required property"]
+    );
+}
+
+#[test]
+fn model_schema_validate_timeout() {
+    let text = r#"
+    id: m1
+    steps:
+      - id: step1
+        timeout:
+          - on: 2x
+    "#;
+    assert_eq!(
+        check(text),
+        vec!["/steps/0/timeout/0/on: \"2x\" does not match \"^[0-9]+(s|m|h|d)$\""]
+    );
+}
+
+#[test]
+fn model_schema_validate_branch_condition() {
+    let text = r#"
+    id: m1
+    steps:
+      - id: step1
+        branches:
+          - id: b1
+    "#;
+    assert_eq!(check(text), vec!["/steps/0/branches/0: {\"id\":\"b1\"} is not valid under any of the schemas listed in the 'anyOf' keyword"]);
+}
+
+#[test]
+fn model_schema_validate_type() {
+    let text = r#"
+    id: m1
+    steps: abc
+    "#;
+    assert_eq!(
+        check(text),
+        vec!["/steps: \"abc\" is not of type \"array\""]
+    );
+}
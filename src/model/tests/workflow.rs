@@ -1,4 +1,4 @@
-use crate::{Act, StmtBuild, Vars, Workflow};
+use crate::{Act, Limits, Output, OutputType, StmtBuild, Vars, Workflow};
 use serde_json::json;
 
 #[test]
@@ -55,6 +55,41 @@ fn model_workflow_set_input() {
     assert_eq!(m.inputs.get_value("v1"), Some(&json!(5)));
 }
 
+#[test]
+fn model_workflow_schema_parse() {
+    let text = r#"
+    id: m1
+    schema:
+      a:
+        type: Number
+        required: true
+      b:
+        enum: [x, y]
+        default: x
+    "#;
+    let m = Workflow::from_yml(text).unwrap();
+    assert_eq!(m.schema.len(), 2);
+    assert!(m.schema.get("a").unwrap().required);
+    assert_eq!(m.schema.get("b").unwrap().default, json!("x"));
+}
+
+#[test]
+fn model_workflow_schema_build() {
+    let m = Workflow::new().with_schema(
+        "a",
+        Output {
+            r#type: OutputType::Number,
+            required: true,
+            ..Default::default()
+        },
+    );
+    assert_eq!(m.schema.len(), 1);
+
+    let text = m.to_yml().unwrap();
+    assert!(text.contains("schema"));
+    assert!(!Workflow::new().to_yml().unwrap().contains("schema"));
+}
+
 #[test]
 fn model_workflow_set_env() {
     let mut m = Workflow::new();
@@ -142,3 +177,28 @@ fn model_workflow_setup_parse() {
     let m = Workflow::from_yml(text).unwrap();
     assert_eq!(m.setup.len(), 9);
 }
+
+#[test]
+fn model_workflow_limits() {
+    let text = r#"
+    id: m1
+    limits:
+      memory: 1024
+      timeout: 100
+    steps:
+      - id: step1
+    "#;
+    let workflow = Workflow::from_yml(text).unwrap();
+    assert_eq!(workflow.limits.memory, Some(1024));
+    assert_eq!(workflow.limits.stack, None);
+    assert_eq!(workflow.limits.timeout, Some(100));
+}
+
+#[test]
+fn model_workflow_limits_merge() {
+    let base = Limits::new().with_memory(1024).with_timeout(100);
+    let limits = base.merge(&Limits::new().with_timeout(200).with_stack(64));
+    assert_eq!(limits.memory, Some(1024));
+    assert_eq!(limits.stack, Some(64));
+    assert_eq!(limits.timeout, Some(200));
+}
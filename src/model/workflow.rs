@@ -1,6 +1,15 @@
-use crate::{sch::NodeTree, Act, ActError, ModelBase, Result, Step, Vars};
+use crate::{
+    model::{
+        bpmn,
+        fragment::{self, Expander},
+        inherit, schema, Fragment, Limits, Linter, YamlLocator,
+    },
+    sch::NodeTree,
+    Act, ActError, Diagnostic, Graph, ModelBase, Output, Outputs, Result, Step, Vars,
+};
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
+use std::collections::HashMap;
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Workflow {
@@ -22,12 +31,37 @@ pub struct Workflow {
     #[serde(default)]
     pub inputs: Vars,
 
+    /// the inputs schema to validate the inputs when starting
+    #[serde(default, skip_serializing_if = "Outputs::is_empty")]
+    pub schema: Outputs,
+
     #[serde(default)]
     pub outputs: Vars,
 
     #[serde(default)]
     pub setup: Vec<Act>,
 
+    /// the base model id to inherit, the format is `{id}` or `{id}@{ver}`
+    /// the version is pinned to the latest base model when deploying
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extends: Option<String>,
+
+    /// the step ids to remove from the base model
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub removes: Vec<String>,
+
+    /// the sandbox limits of the scripts in the model
+    #[serde(default, skip_serializing_if = "Limits::is_empty")]
+    pub limits: Limits,
+
+    /// the script lang of the model, such as `js` or `rhai`, the default lang is used if empty
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub lang: String,
+
+    /// the local fragments which can be included by steps, acts and catches
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fragments: Vec<Fragment>,
+
     #[serde(default)]
     ver: u32,
 }
@@ -49,6 +83,54 @@ impl Workflow {
         }
     }
 
+    /// reads the workflow from bpmn 2.0 xml
+    /// returns error with all of the unsupported elements
+    pub fn from_bpmn(s: &str) -> Result<Self> {
+        bpmn::from_bpmn(s)
+    }
+
+    /// writes the workflow as bpmn 2.0 xml
+    pub fn to_bpmn(&self) -> Result<String> {
+        bpmn::to_bpmn(self)
+    }
+
+    /// expands the includes by the local fragments and the given fragments
+    /// the ids in the fragment are prefixed by the include id or the fragment id
+    pub fn expand(&mut self, fragments: &[Fragment]) -> Result<()> {
+        let fragments = self
+            .fragments
+            .iter()
+            .chain(fragments.iter())
+            .map(|f| (f.id.clone(), f.clone()))
+            .collect::<HashMap<_, _>>();
+        let mut expander = Expander::new(&fragments);
+        let steps = expander.steps(self.steps.clone())?;
+        let setup = expander.acts(self.setup.clone())?;
+        self.steps = steps;
+        self.setup = setup;
+        Ok(())
+    }
+
+    /// merges the model to the resolved base model and returns the effective model
+    pub fn inherit(&self, base: &Workflow) -> Result<Workflow> {
+        inherit::merge(base, self)
+    }
+
+    /// gets the fragment ids which are included by the model
+    pub fn includes(&self) -> Vec<String> {
+        let mut ret = fragment::includes(&self.steps);
+        ret.extend(fragment::includes(&self.setup));
+        ret.sort();
+        ret.dedup();
+        ret
+    }
+
+    /// generates the json schema of the workflow model with the builtin acts
+    /// it can be used by the editors and ci to validate the yml models
+    pub fn json_schema() -> JsonValue {
+        schema::json_schema(&[])
+    }
+
     pub fn set_env(&mut self, vars: &Vars) {
         for (name, value) in vars {
             self.env
@@ -79,6 +161,17 @@ impl Workflow {
         root.tree_output()
     }
 
+    /// builds the graph to render as 'mermaid', 'dot' or 'json-graph'
+    /// the model error is returned when the model can not be built, such as a missing `next` step
+    pub fn graph(&self) -> Result<Graph> {
+        let mut root = NodeTree::new();
+        root.load(self)?;
+        if let Some(err) = root.error.take() {
+            return Err(err);
+        }
+        Ok(root.graph())
+    }
+
     pub fn step(&self, id: &str) -> Option<&Step> {
         match self.steps.iter().find(|s| s.id == id) {
             Some(s) => Some(s),
@@ -89,6 +182,10 @@ impl Workflow {
         self.id = id.to_string();
     }
 
+    pub fn ver(&self) -> u32 {
+        self.ver
+    }
+
     pub fn set_ver(&mut self, ver: u32) {
         self.ver = ver;
     }
@@ -107,6 +204,41 @@ impl Workflow {
         }
     }
 
+    /// checks the model with the builtin acts and returns all of the diagnostics without the positions
+    pub fn lint(&self) -> Vec<Diagnostic> {
+        Linter::new().lint(self).into_diagnostics()
+    }
+
+    /// checks the yml model with the builtin acts and returns all of the diagnostics with line and column
+    pub fn lint_yml(text: &str) -> Vec<Diagnostic> {
+        Self::lint_yml_with(text, &[])
+    }
+
+    /// checks the yml model, the acts are the names of the registered act handlers
+    pub(crate) fn lint_yml_with(text: &str, acts: &[String]) -> Vec<Diagnostic> {
+        match serde_yaml::from_str::<Workflow>(text) {
+            Ok(workflow) => {
+                let locator = YamlLocator::new(text);
+                let mut diagnostics = Linter::new()
+                    .with_acts(acts)
+                    .lint(&workflow)
+                    .into_diagnostics();
+                for d in diagnostics.iter_mut() {
+                    d.locate(&locator);
+                }
+                diagnostics
+            }
+            Err(err) => {
+                let mut d = Diagnostic::new("yaml", "", &err.to_string());
+                if let Some(location) = err.location() {
+                    d.line = location.line();
+                    d.column = location.column();
+                }
+                vec![d]
+            }
+        }
+    }
+
     pub fn valid(&self) -> Result<()> {
         let mut root = NodeTree::new();
         root.load(self)?;
@@ -146,6 +278,11 @@ impl Workflow {
         self
     }
 
+    pub fn with_schema(mut self, name: &str, schema: Output) -> Self {
+        self.schema.push(name, &schema);
+        self
+    }
+
     pub fn with_env(mut self, name: &str, value: JsonValue) -> Self {
         self.env.insert(name.to_string(), value);
         self
@@ -162,6 +299,31 @@ impl Workflow {
         self
     }
 
+    pub fn with_extends(mut self, base: &str) -> Self {
+        self.extends = Some(base.to_string());
+        self
+    }
+
+    pub fn with_remove(mut self, step: &str) -> Self {
+        self.removes.push(step.to_string());
+        self
+    }
+
+    pub fn with_limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    pub fn with_lang(mut self, lang: &str) -> Self {
+        self.lang = lang.to_string();
+        self
+    }
+
+    pub fn with_fragment(mut self, fragment: Fragment) -> Self {
+        self.fragments.push(fragment);
+        self
+    }
+
     pub fn with_setup(mut self, build: fn(Vec<Act>) -> Vec<Act>) -> Self {
         let stmts = Vec::new();
         self.setup = build(stmts);
@@ -1,13 +1,16 @@
 use super::{ActTask, Runtime};
+#[cfg(feature = "quickjs")]
+use crate::env::{JsEval, Wake};
 use crate::{
+    data::Package,
     event::{Action, Model},
     sch::{tree::NodeContent, Node, Proc, Task},
     utils::{self, consts, shortid},
-    Act, ActError, Message, MessageState, NodeKind, Result, TaskState, Vars,
+    Act, ActError, Limits, LogInfo, Message, MessageState, NodeKind, Result, TaskState, Vars,
 };
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::{cell::RefCell, sync::Arc};
-use tracing::debug;
+use tracing::{debug, error, info, warn};
 
 tokio::task_local! {
     static CONTEXT: Context;
@@ -22,6 +25,11 @@ pub struct Context {
     task: RefCell<Arc<Task>>,
     action: RefCell<Option<Action>>,
     vars: RefCell<Vars>,
+    // the order of the next suspendable eval in the task run
+    #[cfg(feature = "quickjs")]
+    runs: RefCell<usize>,
+    // the task run is suspended by the js eval
+    suspended: RefCell<bool>,
 }
 
 impl std::fmt::Debug for Context {
@@ -52,12 +60,15 @@ impl Context {
             action: RefCell::new(None),
             task: RefCell::new(task.clone()),
             vars: RefCell::new(Vars::new()),
+            #[cfg(feature = "quickjs")]
+            runs: RefCell::new(0),
+            suspended: RefCell::new(false),
         };
 
         ctx
     }
 
-    pub fn scope<T, F: Fn() -> T>(ctx: Context, f: F) -> T {
+    pub fn scope<T, F: FnOnce() -> T>(ctx: Context, f: F) -> T {
         if Context::current().is_ok() {
             f()
         } else {
@@ -75,6 +86,46 @@ impl Context {
             .map_err(|e| ActError::Runtime(e.to_string()))
     }
 
+    /// writes the script console log by tracing with the pid, tid and nid fields
+    /// the log is also buffered in the task when `script_log_limit` is greater than 0
+    pub fn console(level: &str, message: &str) {
+        let level = match level {
+            "wran" => "warn",
+            level => level,
+        };
+        let ctx = Context::current().ok();
+        let (pid, tid, nid) = match &ctx {
+            Some(ctx) => {
+                let task = ctx.task();
+                (
+                    task.pid.clone(),
+                    task.id.clone(),
+                    task.node().id().to_string(),
+                )
+            }
+            None => Default::default(),
+        };
+        match level {
+            "error" => error!(target: "acts::console", pid, tid, nid, "{message}"),
+            "warn" => warn!(target: "acts::console", pid, tid, nid, "{message}"),
+            "debug" => debug!(target: "acts::console", pid, tid, nid, "{message}"),
+            _ => info!(target: "acts::console", pid, tid, nid, "{message}"),
+        }
+
+        if let Some(ctx) = ctx {
+            let limit = ctx.runtime.config().script_log_limit;
+            if limit == 0 {
+                return;
+            }
+            let log = LogInfo {
+                level: level.to_string(),
+                message: message.to_string(),
+                time: ctx.runtime.clock().now(),
+            };
+            ctx.task().push_log(log, limit);
+        }
+    }
+
     pub fn set_task(&self, task: &Arc<Task>) {
         if self.task.borrow().id != task.id {
             *self.task.borrow_mut() = task.clone();
@@ -149,7 +200,151 @@ impl Context {
     }
 
     pub fn eval<T: DeserializeOwned + Serialize>(&self, expr: &str) -> Result<T> {
-        Context::scope(self.clone(), || self.runtime.env().eval::<T>(expr))
+        self.eval_with_limits(expr, &Limits::default())
+    }
+
+    /// evaluates the expression with the limits which override the config and model limits
+    pub fn eval_with_limits<T: DeserializeOwned + Serialize>(
+        &self,
+        expr: &str,
+        limits: &Limits,
+    ) -> Result<T> {
+        let limits = self.limits(limits);
+        let lang = self.lang();
+        Context::scope(self.clone(), || {
+            self.runtime.env().eval_with::<T>(&lang, expr, &limits)
+        })
+    }
+
+    /// evaluates the expression by the model lang, the vars are bound as the variables by name
+    pub fn eval_with_vars<T: DeserializeOwned + Serialize>(
+        &self,
+        expr: &str,
+        vars: &Vars,
+    ) -> Result<T> {
+        let limits = self.limits(&Limits::default());
+        let lang = self.lang();
+        Context::scope(self.clone(), || {
+            self.runtime
+                .env()
+                .eval_with_vars::<T>(&lang, expr, vars, &limits)
+        })
+    }
+
+    /// runs the task script by the model lang, the js script waiting for the async host functions
+    /// suspends the task instead of blocking the scheduler, which is checked by `is_suspended`
+    pub fn run_script(&self, script: &str) -> Result<()> {
+        let limits = self.limits(&Limits::default());
+        self.run_with(&self.lang(), script, &limits)
+    }
+
+    /// the task run is suspended by the js eval, so the task should not go on
+    pub fn is_suspended(&self) -> bool {
+        *self.suspended.borrow()
+    }
+
+    /// the script lang of the model
+    pub fn lang(&self) -> String {
+        self.proc.tree().model.lang.clone()
+    }
+
+    /// runs the package script with the package limits after validating the inputs by the manifest
+    /// the package is evaluated by the manifest `lang` or the model lang,
+    /// and the js es module package can import the other packages by `@pack/{id}`
+    pub fn eval_package(&self, pack: &Package) -> Result<()> {
+        pack.manifest.inputs.check(&self.task().inputs())?;
+        let script = String::from_utf8(pack.data.clone()).map_err(ActError::from)?;
+        let lang = if pack.manifest.lang.is_empty() {
+            self.lang()
+        } else {
+            pack.manifest.lang.clone()
+        };
+        if !Self::is_module(&lang, &script) {
+            let limits = self.limits(&pack.limits);
+            let env = self.runtime.env();
+            env.load_code(&lang, &script, &pack.code);
+            return self.run_with(&lang, &script, &limits);
+        }
+        self.eval_module(pack, &script)
+    }
+
+    #[cfg(feature = "quickjs")]
+    fn run_with(&self, lang: &str, script: &str, limits: &Limits) -> Result<()> {
+        let env = self.runtime.env();
+        self.suspend(|wake| env.start(lang, script, limits, wake))
+    }
+
+    #[cfg(not(feature = "quickjs"))]
+    fn run_with(&self, lang: &str, script: &str, limits: &Limits) -> Result<()> {
+        let env = self.runtime.env();
+        Context::scope(self.clone(), || env.eval_with::<()>(lang, script, limits))
+    }
+
+    /// runs the js eval which is suspended when it waits for the async host functions,
+    /// the suspended eval is kept by the task, and the task is pushed back to the scheduler
+    /// when it is woken, then the task runs again to resume it and skips the evals before it
+    #[cfg(feature = "quickjs")]
+    fn suspend(&self, start: impl FnOnce(Wake) -> Result<JsEval>) -> Result<()> {
+        let seq = self.runs.replace_with(|seq| *seq + 1);
+        let task = self.task();
+        let suspended = match task.suspended_seq() {
+            Some(s) if s > seq => return Ok(()),
+            Some(s) if s == seq => task.take_suspended(),
+            _ => None,
+        };
+        let wake: Wake = {
+            let scher = self.runtime.scher().clone();
+            let task = task.clone();
+            Box::new(move || scher.push(&task))
+        };
+        let env = self.runtime.env();
+        let ret = Context::scope(self.clone(), || match suspended {
+            Some(suspended) => env.resume(suspended, wake),
+            None => start(wake),
+        })?;
+        match ret {
+            JsEval::Ready(value) => serde_json::from_value::<()>(value).map_err(ActError::from),
+            JsEval::Suspended(suspended) => {
+                task.set_suspended(seq, suspended);
+                *self.suspended.borrow_mut() = true;
+                Ok(())
+            }
+        }
+    }
+
+    #[cfg(feature = "quickjs")]
+    fn is_module(lang: &str, script: &str) -> bool {
+        matches!(lang, "" | "js") && crate::env::is_module(script)
+    }
+
+    #[cfg(not(feature = "quickjs"))]
+    fn is_module(_lang: &str, _script: &str) -> bool {
+        false
+    }
+
+    #[cfg(feature = "quickjs")]
+    fn eval_module(&self, pack: &Package, script: &str) -> Result<()> {
+        let store = self.runtime.cache().store();
+        crate::env::check_imports(pack, script, &|id, req| store.find_package(id, req))?;
+        let name = crate::env::module_name(pack);
+        let limits = self.limits(&pack.limits);
+        let env = self.runtime.env();
+        env.load_module(&name, script, &pack.code);
+        self.suspend(|wake| env.start_module(&name, script, &limits, wake))
+    }
+
+    #[cfg(not(feature = "quickjs"))]
+    fn eval_module(&self, _pack: &Package, _script: &str) -> Result<()> {
+        Ok(())
+    }
+
+    /// merges the limits with the config and model limits
+    fn limits(&self, limits: &Limits) -> Limits {
+        self.runtime
+            .config()
+            .limits()
+            .merge(&self.proc.tree().model.limits)
+            .merge(limits)
     }
 
     #[allow(unused)]
@@ -376,6 +571,9 @@ impl Context {
         if let Some(err) = task.err() {
             inputs.set(consts::ACT_ERR_MESSAGE, err.message);
             inputs.set(consts::ACT_ERR_CODE, err.ecode);
+            if !err.fields.is_empty() {
+                inputs.set(consts::ACT_ERR_FIELDS, err.fields);
+            }
         }
 
         let state: MessageState = task.state().into();
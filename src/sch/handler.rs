@@ -0,0 +1,144 @@
+use super::Context;
+use crate::{model::ACT_NAMES, utils::consts, Act, ActError, Outputs, Result, Vars};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+/// the native act handler which is used by `act: {name}` in models
+///
+/// # Example
+/// ```rust
+///   use acts::{ActContext, ActHandler, Result};
+///   #[derive(Clone)]
+///   pub struct SumHandler;
+///   impl ActHandler for SumHandler {
+///     fn run(&self, ctx: &ActContext) -> Result<()> {
+///         let a = ctx.get::<i64>("a").unwrap_or_default();
+///         let b = ctx.get::<i64>("b").unwrap_or_default();
+///         ctx.expose("sum", a + b);
+///         Ok(())
+///     }
+///   }
+/// ```
+pub trait ActHandler: Send + Sync {
+    /// the inputs schema to validate the act inputs before running
+    fn inputs(&self) -> Outputs {
+        Outputs::default()
+    }
+
+    fn init(&self, _ctx: &ActContext) -> Result<()> {
+        Ok(())
+    }
+
+    fn run(&self, ctx: &ActContext) -> Result<()>;
+
+    fn review(&self, _ctx: &ActContext) -> Result<bool> {
+        Ok(true)
+    }
+}
+
+/// the act context for the act handler hooks
+pub struct ActContext<'a> {
+    ctx: &'a Context,
+    act: &'a Act,
+}
+
+impl<'a> ActContext<'a> {
+    pub(crate) fn new(ctx: &'a Context, act: &'a Act) -> Self {
+        Self { ctx, act }
+    }
+
+    pub fn pid(&self) -> String {
+        self.ctx.proc.id().to_string()
+    }
+
+    pub fn tid(&self) -> String {
+        self.ctx.task().id.clone()
+    }
+
+    /// the act model
+    pub fn act(&self) -> &Act {
+        self.act
+    }
+
+    /// the act inputs which are filled by the context vars
+    pub fn inputs(&self) -> Vars {
+        self.ctx.task().inputs()
+    }
+
+    /// gets the var from the task and the parent tasks
+    pub fn get<T: DeserializeOwned + Clone + std::fmt::Debug>(&self, name: &str) -> Option<T> {
+        self.ctx.task().find::<T>(name)
+    }
+
+    /// sets the var to the task data
+    pub fn set<T: Serialize + Clone>(&self, name: &str, value: T) {
+        self.ctx.task().update_data(&Vars::new().with(name, value));
+    }
+
+    /// exposes the value to the act outputs
+    pub fn expose<T: Serialize + Clone>(&self, key: &str, value: T) {
+        let value = serde_json::to_value(value).unwrap_or_default();
+        self.ctx.task().set_data_with(|data| {
+            let outputs = data.get::<Vars>(consts::ACT_OUTPUTS).unwrap_or_default();
+            data.set(consts::ACT_OUTPUTS, outputs.with(key, &value));
+        });
+    }
+}
+
+/// the registered act handlers of the engine by the act name
+#[derive(Default)]
+pub struct ActHandlers {
+    handlers: RwLock<HashMap<String, Arc<dyn ActHandler>>>,
+}
+
+impl std::fmt::Debug for ActHandlers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ActHandlers")
+            .field("names", &self.names())
+            .finish()
+    }
+}
+
+impl ActHandlers {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// registers the act handler, the builtin act names can not be overridden
+    pub fn register(&self, name: &str, handler: Arc<dyn ActHandler>) -> Result<()> {
+        if name.is_empty() || ACT_NAMES.contains(&name) {
+            return Err(ActError::Action(format!(
+                "the act name '{name}' is empty or used by the builtin act"
+            )));
+        }
+        self.handlers
+            .write()
+            .unwrap()
+            .insert(name.to_string(), handler);
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<dyn ActHandler>> {
+        self.handlers.read().unwrap().get(name).cloned()
+    }
+
+    pub fn is_registered(&self, name: &str) -> bool {
+        self.handlers.read().unwrap().contains_key(name)
+    }
+
+    /// the registered act names
+    pub fn names(&self) -> Vec<String> {
+        let mut names = self
+            .handlers
+            .read()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+}
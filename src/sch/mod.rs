@@ -1,4 +1,5 @@
 mod context;
+pub(crate) mod handler;
 mod proc;
 mod queue;
 mod runtime;
@@ -13,11 +14,14 @@ use async_trait::async_trait;
 
 pub use crate::Result;
 pub use context::Context;
+pub use handler::{ActContext, ActHandler};
 pub use proc::{Proc, StatementBatch, Task, TaskLifeCycle};
 pub use runtime::Runtime;
 pub use scher::Scheduler;
 pub use state::TaskState;
-pub use tree::{Node, NodeContent, NodeData, NodeKind, NodeTree};
+pub use tree::{
+    Graph, GraphEdge, GraphNode, Node, NodeContent, NodeData, NodeKind, NodeRef, NodeTree,
+};
 
 #[async_trait]
 pub trait ActTask: Clone + Send {
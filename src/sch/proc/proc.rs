@@ -6,7 +6,7 @@ use crate::{
         Context, Runtime, Task, TaskLifeCycle, TaskState,
     },
     utils::{self, consts},
-    ActError, Error, NodeKind, ProcInfo, Result, ShareLock, Vars, Workflow,
+    ActError, Error, FieldError, NodeKind, Output, ProcInfo, Result, ShareLock, Vars, Workflow,
 };
 use serde::Deserialize;
 use std::{
@@ -19,7 +19,8 @@ use tracing::{debug, error, instrument};
 #[derive(Clone)]
 pub struct Proc {
     id: String,
-    tree: ShareLock<NodeTree>,
+    tree: ShareLock<Arc<NodeTree>>,
+    inputs: ShareLock<Vars>,
     tasks: ShareLock<TaskTree>,
     state: ShareLock<TaskState>,
     start_time: ShareLock<i64>,
@@ -46,14 +47,15 @@ impl std::fmt::Debug for Proc {
 
 impl Proc {
     pub fn new(pid: &str, rt: &Arc<Runtime>) -> Arc<Self> {
-        Self::new_with_timestamp(pid, utils::time::timestamp(), rt)
+        Self::new_with_timestamp(pid, rt.clock().timestamp(), rt)
     }
 
     pub fn new_with_timestamp(pid: &str, timestamp: i64, rt: &Arc<Runtime>) -> Arc<Self> {
         let tree = NodeTree::new();
         Arc::new(Proc {
             id: pid.to_string(),
-            tree: Arc::new(RwLock::new(tree)),
+            tree: Arc::new(RwLock::new(Arc::new(tree))),
+            inputs: Arc::new(RwLock::new(Vars::new())),
             state: Arc::new(RwLock::new(TaskState::None)),
             start_time: Arc::new(RwLock::new(0)),
             end_time: Arc::new(RwLock::new(0)),
@@ -85,17 +87,28 @@ impl Proc {
         }
     }
 
+    /// loads the compiled node tree from cache, the model inputs are kept in proc
+    /// because the tree is shared by the procs with the same model version
     pub fn load(&self, model: &Workflow) -> Result<()> {
-        let tree = &mut self.tree.write().unwrap();
-        tree.load(model)
+        let tree = self.runtime.cache().tree(model)?;
+        *self.tree.write().unwrap() = tree;
+        *self.inputs.write().unwrap() = model.inputs.clone();
+        Ok(())
     }
 
-    pub fn tree(&self) -> std::sync::RwLockReadGuard<'_, NodeTree> {
-        self.tree.read().unwrap()
+    pub fn tree(&self) -> Arc<NodeTree> {
+        self.tree.read().unwrap().clone()
     }
 
     pub fn model(&self) -> Box<Workflow> {
-        self.tree().model.clone()
+        let mut model = self.tree().model.clone();
+        model.inputs = self.model_inputs();
+        model
+    }
+
+    /// the workflow inputs with the proc start options
+    pub fn model_inputs(&self) -> Vars {
+        self.inputs.read().unwrap().clone()
     }
 
     pub fn state(&self) -> TaskState {
@@ -148,10 +161,10 @@ impl Proc {
     }
 
     pub fn inputs(&self) -> Vars {
-        let inputs = &self.model().inputs;
+        let inputs = self.model_inputs();
         if let Some(task) = self.root() {
             let ctx = task.create_context();
-            let vars = utils::fill_proc_vars(&task, inputs, &ctx);
+            let vars = utils::fill_proc_vars(&task, &inputs, &ctx);
             return vars;
         }
         Vars::new()
@@ -179,6 +192,7 @@ impl Proc {
             start_time: self.start_time(),
             end_time: self.end_time(),
             timestamp: self.timestamp,
+            parent_id: self.parent_id().unwrap_or_default(),
             tasks: Vec::new(),
         }
     }
@@ -194,7 +208,8 @@ impl Proc {
     pub fn find_tasks(&self, predicate: impl Fn(&Arc<Task>) -> bool) -> Vec<Arc<Task>> {
         let tasks = self.tasks.read().unwrap();
         let mut ret = tasks.find_tasks(predicate);
-        ret.sort_by_key(|a| a.start_time());
+        // the tasks may start in the same millis, so the creation timestamp keeps the order
+        ret.sort_by_key(|a| (a.start_time(), a.timestamp));
 
         ret
     }
@@ -229,9 +244,9 @@ impl Proc {
 
     pub fn set_state(&self, state: TaskState) {
         if state.is_completed() {
-            self.set_end_time(utils::time::time_millis());
+            self.set_end_time(self.runtime.clock().now());
         } else if state.is_running() {
-            self.set_start_time(utils::time::time_millis());
+            self.set_start_time(self.runtime.clock().now());
         }
         *self.state.write().unwrap() = state;
     }
@@ -296,15 +311,40 @@ impl Proc {
         let rets = task.node().content.rets();
         if rets.len() > 0 {
             let mut options = Vars::new();
-            for (ref key, _) in &rets {
-                if !action.options.contains_key(key) {
-                    return Err(ActError::Action(format!(
-                        "the options is not satisfied with act's rets '{}' in task({})",
-                        key, action.tid
-                    )));
+            let mut errors = Vec::new();
+            for (ref key, rule) in &rets {
+                let output = Output::from_ret(&rule);
+                match action.options.get_value(key) {
+                    Some(value) => {
+                        // the explicit null is only rejected by the `required` ret
+                        if let Some(message) = output.validate(key, Some(value)) {
+                            errors.push(FieldError::new(key, &message));
+                            continue;
+                        }
+                        if !value.is_null() {
+                            if let Some(expr) = rule.get("validate").and_then(|v| v.as_str()) {
+                                if let Some(message) = task.check_ret(key, value, expr) {
+                                    errors.push(FieldError::new(key, &message));
+                                    continue;
+                                }
+                            }
+                        }
+                        options.set(key, value.clone());
+                    }
+                    None => {
+                        // the ret key must be passed unless it is `required: false`
+                        if rule.get("required").and_then(|v| v.as_bool()) != Some(false) {
+                            errors.push(FieldError::new(
+                                key,
+                                &format!("the options is not satisfied with act's rets '{key}'"),
+                            ));
+                        }
+                    }
                 }
-                let value = action.options.get_value(key).unwrap();
-                options.set(key, value.clone());
+            }
+
+            if !errors.is_empty() {
+                return Err(ActError::Validation(errors));
             }
 
             // retset the options by rets defination
@@ -370,6 +410,13 @@ impl Proc {
         tasks.push(task);
     }
 
+    /// the parent proc id which starts the proc by `call` act, whether it waits or not
+    pub fn parent_id(&self) -> Option<String> {
+        self.model_inputs()
+            .get::<String>(consts::ACT_CALL_PARENT_PROC_ID)
+            .or_else(|| self.parent().map(|(ppid, _)| ppid))
+    }
+
     pub fn parent(&self) -> Option<(String, String)> {
         if let Some(root) = &self.root() {
             let use_data = root.with_data(|data| {
@@ -463,9 +510,20 @@ impl Proc {
 
     pub fn into_data(self: &Arc<Self>) -> Result<data::Proc> {
         let model = self.model();
+        // the deployed model is resolved from the model store by id and ver when loading
+        // only the model which is not deployed keeps the full copy
+        let ver = model.ver();
+        let inputs = serde_json::to_string(&self.model_inputs())
+            .map_err(|err| ActError::Runtime(err.to_string()))?;
         Ok(data::Proc {
             id: self.id.clone(),
-            model: model.to_json()?,
+            model: if ver > 0 {
+                String::new()
+            } else {
+                model.to_json()?
+            },
+            ver,
+            inputs,
             mid: model.id,
             name: model.name,
             state: self.state().into(),
@@ -474,6 +532,7 @@ impl Proc {
             timestamp: self.timestamp(),
             env_local: self.env_local().to_string(),
             err: self.err().map(|err| err.to_string()),
+            parent_id: self.parent_id().unwrap_or_default(),
         })
     }
 }
@@ -4,6 +4,8 @@ mod hook;
 mod step;
 mod workflow;
 
+#[cfg(feature = "quickjs")]
+use crate::env::JsSuspended;
 use crate::{
     data::{self, MessageStatus},
     event::{EventAction, Model},
@@ -12,8 +14,8 @@ use crate::{
         Context, Proc, Runtime, TaskState,
     },
     utils::{self, consts},
-    Act, ActError, ActTask, Catch, Error, Message, MessageState, NodeKind, Result, ShareLock,
-    Timeout, Vars,
+    Act, ActError, ActFn, ActTask, Catch, Error, LogInfo, Message, MessageState, NodeKind, Result,
+    ShareLock, Timeout, Vars,
 };
 use async_trait::async_trait;
 pub use hook::{StatementBatch, TaskLifeCycle};
@@ -23,7 +25,7 @@ use std::{
     collections::HashMap,
     sync::{Arc, RwLock},
 };
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 #[derive(Clone)]
 pub struct Task {
@@ -44,6 +46,9 @@ pub struct Task {
     /// task error
     err: ShareLock<Option<Error>>,
 
+    /// the captured script logs, which are saved to store when the task is completed
+    logs: ShareLock<Vec<LogInfo>>,
+
     start_time: ShareLock<i64>,
     end_time: ShareLock<i64>,
 
@@ -59,6 +64,9 @@ pub struct Task {
 
     runtime: Arc<Runtime>,
     // sync: Arc<std::sync::Mutex<usize>>,
+    /// the js eval suspended by the async host functions, with its order in the task run
+    #[cfg(feature = "quickjs")]
+    suspended: Arc<std::sync::Mutex<Option<(usize, JsSuspended)>>>,
 }
 
 impl Task {
@@ -70,20 +78,52 @@ impl Task {
             data: Arc::new(RwLock::new(Vars::new())),
             state: Arc::new(RwLock::new(TaskState::None)),
             err: Arc::new(RwLock::new(None)),
+            logs: Arc::new(RwLock::new(Vec::new())),
             start_time: Arc::new(RwLock::new(0)),
             end_time: Arc::new(RwLock::new(0)),
             prev: Arc::new(RwLock::new(None)),
-            timestamp: utils::time::timestamp(),
+            timestamp: rt.clock().timestamp(),
             proc: proc.clone(),
 
             hooks: Arc::new(RwLock::new(HashMap::new())),
             runtime: rt.clone(),
             // sync: Arc::new(std::sync::Mutex::new(0)),
+            #[cfg(feature = "quickjs")]
+            suspended: Arc::new(std::sync::Mutex::new(None)),
         };
 
         task
     }
 
+    /// the task is suspended by the js eval which waits for the async host functions
+    pub fn is_suspended(&self) -> bool {
+        #[cfg(feature = "quickjs")]
+        let suspended = self.suspended.lock().unwrap().is_some();
+        #[cfg(not(feature = "quickjs"))]
+        let suspended = false;
+        suspended
+    }
+
+    /// the order of the suspended eval in the task run
+    #[cfg(feature = "quickjs")]
+    pub(crate) fn suspended_seq(&self) -> Option<usize> {
+        self.suspended.lock().unwrap().as_ref().map(|(seq, _)| *seq)
+    }
+
+    #[cfg(feature = "quickjs")]
+    pub(crate) fn set_suspended(&self, seq: usize, suspended: JsSuspended) {
+        *self.suspended.lock().unwrap() = Some((seq, suspended));
+    }
+
+    #[cfg(feature = "quickjs")]
+    pub(crate) fn take_suspended(&self) -> Option<JsSuspended> {
+        self.suspended
+            .lock()
+            .unwrap()
+            .take()
+            .map(|(_, suspended)| suspended)
+    }
+
     pub fn unique_id(&self) -> String {
         format!("{}:{}", self.pid, self.id)
     }
@@ -159,10 +199,17 @@ impl Task {
             }
         }
 
-        // add error to inputs
+        // add error to inputs, the captured script logs are sent with the error
         if let Some(err) = self.err() {
             inputs.set(consts::ACT_ERR_CODE, err.ecode);
             inputs.set(consts::ACT_ERR_MESSAGE, err.message);
+            if !err.fields.is_empty() {
+                inputs.set(consts::ACT_ERR_FIELDS, err.fields);
+            }
+            let logs = self.logs();
+            if !logs.is_empty() {
+                inputs.set(consts::ACT_ERR_LOGS, logs);
+            }
         }
 
         let mut key = self.node.key();
@@ -239,7 +286,12 @@ impl Task {
 
     pub fn inputs(self: &Arc<Self>) -> Vars {
         let ctx = self.create_context();
-        utils::fill_inputs(&self.node.content.inputs(), &ctx)
+        let inputs = match &self.node.content {
+            // the workflow node is shared by the procs, uses the proc inputs instead
+            NodeContent::Workflow(_) => self.proc.model_inputs(),
+            content => content.inputs(),
+        };
+        utils::fill_inputs(&inputs, &ctx)
     }
 
     pub fn outputs(self: &Arc<Self>) -> Vars {
@@ -274,9 +326,9 @@ impl Task {
 
     pub fn set_state(&self, state: TaskState) {
         if state.is_completed() {
-            self.set_end_time(utils::time::time_millis());
+            self.set_end_time(self.runtime.clock().now());
         } else if state.is_created() {
-            self.set_start_time(utils::time::time_millis());
+            self.set_start_time(self.runtime.clock().now());
         }
         *self.state.write().unwrap() = state.clone();
 
@@ -284,6 +336,10 @@ impl Task {
         if state != TaskState::Error {
             *self.err.write().unwrap() = None;
         }
+
+        if state.is_completed() {
+            self.save_logs();
+        }
     }
 
     pub fn set_err(&self, err: &Error) {
@@ -291,6 +347,36 @@ impl Task {
         self.set_state(TaskState::Error);
     }
 
+    /// the captured script logs of the task
+    pub fn logs(&self) -> Vec<LogInfo> {
+        self.logs.read().unwrap().clone()
+    }
+
+    /// buffers the script log, the oldest logs are dropped when exceeding the limit
+    pub fn push_log(&self, log: LogInfo, limit: usize) {
+        let mut logs = self.logs.write().unwrap();
+        logs.push(log);
+        if logs.len() > limit {
+            let count = logs.len() - limit;
+            logs.drain(..count);
+        }
+    }
+
+    fn save_logs(&self) {
+        let logs = self.logs();
+        if logs.is_empty() {
+            return;
+        }
+        if let Err(err) =
+            self.runtime
+                .cache()
+                .store()
+                .save_logs(&self.pid, &self.id, self.node.id(), &logs)
+        {
+            warn!("failed to save the logs of task {}: {err}", self.id);
+        }
+    }
+
     pub(crate) fn set_pure_err(&self, err: &Error) {
         *self.err.write().unwrap() = Some(err.clone());
     }
@@ -321,9 +407,45 @@ impl Task {
         false
     }
 
+    /// the sub proc id started by a `call` act which is waiting for the sub proc
+    pub fn sub_proc_id(&self) -> Option<String> {
+        if let NodeContent::Act(act) = &self.node.content {
+            if let ActFn::Call(call) = act.into() {
+                if call.is_wait() {
+                    return self.with_data(|data| data.get::<String>(consts::ACT_CALL_PROC_ID));
+                }
+            }
+        }
+        None
+    }
+
+    /// checks the act ret value by the validation expression with the model lang
+    /// the value can be accessed by `value` in the expression
+    pub fn check_ret(
+        self: &Arc<Self>,
+        key: &str,
+        value: &serde_json::Value,
+        expr: &str,
+    ) -> Option<String> {
+        let expr = utils::get_expr(expr).unwrap_or(expr.to_string());
+        let ctx = self.create_context();
+        match ctx.eval_with_vars::<bool>(&expr, &Vars::new().with("value", value)) {
+            Ok(true) => None,
+            Ok(false) => Some(format!(
+                "the value {key}({value}) is not passed the validation '{expr}'"
+            )),
+            Err(err) => Some(format!("the validation of '{key}' is failed: {err}")),
+        }
+    }
+
     pub fn exec(self: &Arc<Self>, ctx: &Context) -> Result<()> {
         // let _lock = self.sync.lock().unwrap();
         debug!("exec task={:?}", ctx.task());
+        #[cfg(feature = "quickjs")]
+        if self.state().is_completed() && self.take_suspended().is_some() {
+            // the task is completed by the other actions when the eval is suspended
+            return Ok(());
+        }
         if self.state().is_completed() {
             return Err(ActError::Runtime(format!(
                 "task({}:{}) is already completed",
@@ -332,6 +454,9 @@ impl Task {
         }
         self.init(ctx)?;
         self.run(ctx)?;
+        if ctx.is_suspended() {
+            return Ok(());
+        }
         self.next(ctx)?;
         Ok(())
     }
@@ -710,7 +835,7 @@ impl Task {
             kind: self.node.typ(),
             pid: self.pid.clone(),
             tid: self.id.clone(),
-            node_data: self.node.to_string(),
+            node_data: self.node_data(),
             state: self.state().into(),
             data: self.data().to_string(),
             start_time: self.start_time(),
@@ -721,6 +846,15 @@ impl Task {
         })
     }
 
+    /// the node in the model tree is stored as reference
+    /// the dynamic node which is not in the tree keeps the full node data
+    fn node_data(&self) -> String {
+        match self.proc.tree().node(self.node.id()) {
+            Some(node) if Arc::ptr_eq(&node, &self.node) => self.node.to_ref(),
+            _ => self.node.to_string(),
+        }
+    }
+
     /// check if the task includes act
     fn is_acts(&self) -> bool {
         self.children()
@@ -805,7 +939,8 @@ impl ActTask for Arc<Task> {
 
     fn run(&self, ctx: &Context) -> Result<()> {
         let task = ctx.task();
-        if task.state().is_ready() {
+        // the suspended task runs again to resume the eval
+        if task.state().is_ready() || task.is_suspended() {
             task.set_state(TaskState::Running);
             match &self.node.content {
                 NodeContent::Workflow(workflow) => workflow.run(ctx),
@@ -813,6 +948,9 @@ impl ActTask for Arc<Task> {
                 NodeContent::Step(step) => step.run(ctx),
                 NodeContent::Act(act) => act.run(ctx),
             }?;
+            if ctx.is_suspended() {
+                return Ok(());
+            }
             ctx.emit_task(&ctx.task())?;
         }
 
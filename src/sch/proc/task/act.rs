@@ -1,6 +1,7 @@
 mod block;
 mod call;
 mod cmd;
+mod custom;
 mod irq;
 mod pack;
 
@@ -11,6 +12,7 @@ use crate::{
     Act, ActError, ActFn, ActTask, Result, TaskState, Vars,
 };
 use async_trait::async_trait;
+use custom::Custom;
 use std::{cell::RefCell, rc::Rc};
 use tracing::debug;
 
@@ -35,29 +37,31 @@ impl ActTask for Act {
             }
         }
 
-        let func: ActFn = self.into();
+        let func = self.act_fn(ctx);
         match func {
             ActFn::Irq(irq) => irq.init(ctx),
             ActFn::Call(u) => u.init(ctx),
             ActFn::Block(b) => b.init(ctx),
             ActFn::Pack(p) => p.init(ctx),
+            ActFn::Custom(act) => Custom(*act).init(ctx),
             _ => Ok(()),
         }
     }
 
     fn run(&self, ctx: &Context) -> Result<()> {
-        let func: ActFn = self.into();
+        let func = self.act_fn(ctx);
         match func {
             ActFn::Irq(req) => req.run(ctx),
             ActFn::Call(u) => u.run(ctx),
             ActFn::Block(b) => b.run(ctx),
             ActFn::Pack(p) => p.run(ctx),
+            ActFn::Custom(act) => Custom(*act).run(ctx),
             _ => Ok(()),
         }
     }
 
     fn next(&self, ctx: &Context) -> Result<bool> {
-        let func: ActFn = self.into();
+        let func = self.act_fn(ctx);
         match func {
             ActFn::Irq(req) => req.next(ctx),
             ActFn::Call(u) => u.next(ctx),
@@ -68,23 +72,33 @@ impl ActTask for Act {
     }
 
     fn review(&self, ctx: &Context) -> Result<bool> {
-        let func: ActFn = self.into();
+        let func = self.act_fn(ctx);
         match func {
             ActFn::Irq(req) => req.review(ctx),
             ActFn::Call(u) => u.review(ctx),
             ActFn::Block(b) => b.review(ctx),
             ActFn::Pack(p) => p.review(ctx),
+            ActFn::Custom(act) => Custom(*act).review(ctx),
             _ => Ok(true),
         }
     }
 }
 
 impl Act {
+    /// parses the act function, the act which is not builtin is dispatched to the registered handler
+    fn act_fn(&self, ctx: &Context) -> ActFn {
+        match self.into() {
+            ActFn::None if ctx.runtime.act_handlers().is_registered(&self.act) => {
+                ActFn::Custom(Box::new(self.clone()))
+            }
+            func => func,
+        }
+    }
+
     pub fn exec(&self, ctx: &Context) -> Result<()> {
         let task = ctx.task();
         debug!("act.exec task={}", task.id);
-        let act_fn = self.into();
-        match act_fn {
+        match self.act_fn(ctx) {
             ActFn::Set(vars) => {
                 let inputs = utils::fill_inputs(&vars, ctx);
                 task.update_data(&inputs);
@@ -203,6 +217,9 @@ impl Act {
             ActFn::Call(_) => {
                 ctx.append_act(self)?;
             }
+            ActFn::Custom(_) => {
+                ctx.append_act(self)?;
+            }
             ActFn::OnCreated(stmts) => {
                 let task = ctx.task();
                 for s in stmts {
@@ -1,4 +1,4 @@
-use crate::{sch::ActTask, utils::consts, Call, Context, Executor, Result};
+use crate::{sch::ActTask, utils::consts, Call, Context, Executor, Result, TaskState};
 
 impl ActTask for Call {
     fn init(&self, ctx: &Context) -> Result<()> {
@@ -18,9 +18,23 @@ impl ActTask for Call {
         let executor = Executor::new(&ctx.runtime);
 
         let mut inputs = task.inputs();
-        inputs.set(consts::ACT_USE_PARENT_PROC_ID, &ctx.proc.id());
-        inputs.set(consts::ACT_USE_PARENT_TASK_ID, &task.id);
-        executor.proc().start(&self.key, &inputs)?;
+        // records the parent proc id for the detached sub workflow too
+        inputs.set(consts::ACT_CALL_PARENT_PROC_ID, &ctx.proc.id());
+        if self.is_wait() {
+            inputs.set(consts::ACT_USE_PARENT_PROC_ID, &ctx.proc.id());
+            inputs.set(consts::ACT_USE_PARENT_TASK_ID, &task.id);
+        }
+        let pid = executor
+            .proc()
+            .start_with_ver(&self.key, self.ver, &inputs)?;
+
+        // records the sub proc id to navigate from the act
+        task.set_data_with(move |data| data.set(consts::ACT_CALL_PROC_ID, &pid));
+
+        // the detached sub workflow does not return to the act
+        if !self.is_wait() && task.state().is_running() {
+            task.set_state(TaskState::Completed);
+        }
 
         Ok(())
     }
@@ -0,0 +1,49 @@
+use crate::{
+    sch::{ActContext, ActHandler, Context},
+    Act, ActError, ActTask, Result, TaskState,
+};
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// the act which is dispatched to the registered act handler
+#[derive(Clone)]
+pub struct Custom(pub Act);
+
+impl Custom {
+    fn handler(&self, ctx: &Context) -> Result<Arc<dyn ActHandler>> {
+        ctx.runtime
+            .act_handlers()
+            .get(&self.0.act)
+            .ok_or(ActError::Action(format!(
+                "cannot find the act handler '{}'",
+                self.0.act
+            )))
+    }
+}
+
+#[async_trait]
+impl ActTask for Custom {
+    fn init(&self, ctx: &Context) -> Result<()> {
+        let handler = self.handler(ctx)?;
+        let task = ctx.task();
+        task.set_emit_disabled(true);
+        handler.inputs().check(&task.inputs())?;
+        handler.init(&ActContext::new(ctx, &self.0))
+    }
+
+    fn run(&self, ctx: &Context) -> Result<()> {
+        let handler = self.handler(ctx)?;
+        let task = ctx.task();
+        handler.run(&ActContext::new(ctx, &self.0))?;
+
+        if task.state().is_running() {
+            task.set_state(TaskState::Completed);
+        }
+        Ok(())
+    }
+
+    fn review(&self, ctx: &Context) -> Result<bool> {
+        let handler = self.handler(ctx)?;
+        handler.review(&ActContext::new(ctx, &self.0))
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{sch::Context, ActError, ActTask, Pack, Result, StoreAdapter, TaskState};
+use crate::{model::parse_package_key, sch::Context, ActTask, Pack, Result, TaskState};
 use async_trait::async_trait;
 
 #[async_trait]
@@ -17,9 +17,12 @@ impl ActTask for Pack {
 
     fn run(&self, ctx: &Context) -> Result<()> {
         let task = ctx.task();
-        let pack = ctx.runtime.cache().store().packages().find(&self.key)?;
-        let script: String = String::from_utf8(pack.data).map_err(ActError::from)?;
-        ctx.eval::<()>(&script)?;
+        let (id, ver) = parse_package_key(&self.key);
+        let pack = ctx.runtime.cache().store().find_package(id, ver)?;
+        ctx.eval_package(&pack)?;
+        if ctx.is_suspended() {
+            return Ok(());
+        }
 
         if task.state().is_running() {
             task.set_state(TaskState::Completed);
@@ -1,7 +1,4 @@
-use crate::{
-    utils::{self, consts},
-    Act, ActTask, Catch, Context, Result, TaskState, Timeout,
-};
+use crate::{utils::consts, Act, ActTask, Catch, Context, Result, TaskState, Timeout};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Eq, Hash, PartialEq, Serialize, Deserialize)]
@@ -61,7 +58,7 @@ impl StatementBatch {
                     return Ok(());
                 }
 
-                let millis = utils::time::time_millis() - task.start_time();
+                let millis = ctx.runtime.clock().now() - task.start_time();
                 if millis >= t.on.as_secs() * 1000 {
                     task.set_data_with(|data| data.set(&key, true));
                     for s in &t.then {
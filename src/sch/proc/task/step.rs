@@ -1,8 +1,8 @@
 use super::hook::TaskLifeCycle;
 use crate::{
-    model::Step,
+    model::{parse_package_key, Step},
     sch::{Context, NodeContent, TaskState},
-    ActError, ActTask, Result, StoreAdapter,
+    ActTask, Result,
 };
 use async_trait::async_trait;
 
@@ -45,13 +45,19 @@ impl ActTask for Step {
     fn run(&self, ctx: &Context) -> Result<()> {
         let task = ctx.task();
         if let Some(script) = &self.run {
-            ctx.eval::<()>(script)?;
+            ctx.run_script(script)?;
+            if ctx.is_suspended() {
+                return Ok(());
+            }
         }
 
         if let Some(pack_id) = &self.uses {
-            let pack = ctx.runtime.cache().store().packages().find(pack_id)?;
-            let script: String = String::from_utf8(pack.data).map_err(ActError::from)?;
-            ctx.eval::<()>(&script)?;
+            let (id, ver) = parse_package_key(pack_id);
+            let pack = ctx.runtime.cache().store().find_package(id, ver)?;
+            ctx.eval_package(&pack)?;
+            if ctx.is_suspended() {
+                return Ok(());
+            }
         }
         let children = task.node.children();
         if !children.is_empty() {
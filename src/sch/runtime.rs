@@ -1,16 +1,16 @@
-use tokio::{runtime::Handle, time};
+use tokio::runtime::Handle;
 use tracing::{debug, error};
 
-use super::{Proc, Scheduler, Task};
+use super::{handler::ActHandlers, NodeContent, Proc, Scheduler, Task, TaskState};
 use crate::{
     cache::Cache,
     data,
     env::Enviroment,
     event::Emitter,
     utils::{self, consts},
-    ActError, Action, Config, Engine, Result, Vars, Workflow,
+    ActError, ActFn, Action, Clock, Config, Engine, Result, Vars, Workflow,
 };
-use std::{sync::Arc, time::Duration};
+use std::sync::Arc;
 
 #[derive(Debug, Clone)]
 pub struct Runtime {
@@ -19,11 +19,13 @@ pub struct Runtime {
     env: Arc<Enviroment>,
     cache: Arc<Cache>,
     emitter: Arc<Emitter>,
+    clock: Arc<dyn Clock>,
+    act_handlers: Arc<ActHandlers>,
 }
 
 impl Runtime {
-    pub fn new(config: &Config) -> Arc<Self> {
-        let runtime = Self::create(config);
+    pub fn new(config: &Config, clock: &Arc<dyn Clock>) -> Arc<Self> {
+        let runtime = Self::create(config, clock);
         runtime.event_loop();
 
         runtime
@@ -47,6 +49,15 @@ impl Runtime {
         &self.emitter
     }
 
+    pub fn clock(&self) -> &Arc<dyn Clock> {
+        &self.clock
+    }
+
+    /// the act handlers which are registered to the engine
+    pub fn act_handlers(&self) -> &Arc<ActHandlers> {
+        &self.act_handlers
+    }
+
     #[allow(unused)]
     pub fn config(&self) -> &Arc<Config> {
         &self.config
@@ -57,10 +68,11 @@ impl Runtime {
         !self.scher.is_closed()
     }
 
-    pub fn init(&self, engine: &Engine) {
+    pub fn init(&self, engine: &Engine) -> Result<()> {
         self.scher.init(engine);
-        self.cache.init(engine);
+        self.cache.init(engine)?;
         self.emitter.init(&engine.runtime());
+        Ok(())
     }
 
     pub fn start(self: &Arc<Self>, model: &Workflow, options: &Vars) -> Result<Arc<Proc>> {
@@ -81,6 +93,10 @@ impl Runtime {
         let mut w = model.clone();
         w.set_inputs(options);
 
+        // validates the inputs by the workflow schema
+        w.schema.fill_defaults(&mut w.inputs);
+        w.schema.check(&w.inputs)?;
+
         let proc = Proc::new(&proc_id, self);
         proc.load(&w)?;
         self.launch(&proc);
@@ -146,10 +162,11 @@ impl Runtime {
         });
     }
 
-    fn create(config: &Config) -> Arc<Runtime> {
+    fn create(config: &Config, clock: &Arc<dyn Clock>) -> Arc<Runtime> {
         let scher = Scheduler::new_with(config);
         let env = Arc::new(Enviroment::new());
         let cache = Arc::new(Cache::new(config.cache_cap));
+        cache.store().set_clock(clock);
         let emitter = Arc::new(Emitter::new());
         let runtime = Arc::new(Runtime {
             config: Arc::new(config.clone()),
@@ -157,6 +174,8 @@ impl Runtime {
             scher,
             env,
             cache,
+            clock: clock.clone(),
+            act_handlers: Arc::new(ActHandlers::new()),
         });
 
         runtime.initialize(config);
@@ -222,6 +241,14 @@ impl Runtime {
                 e.run_hooks(&ctx)
                     .unwrap_or_else(|err| error!("scher.initialize hooks={}", err));
 
+                // cascades the abort to the sub proc when the call act is ended
+                // by aborting, cancelling or backing in the parent proc
+                if e.state().is_completed() {
+                    if let Some(sub_pid) = e.sub_proc_id() {
+                        rt.abort_sub_proc(&sub_pid);
+                    }
+                }
+
                 // check task is allowed to emit message to client
                 if e.extra().emit_message
                     && !e.state().is_pending()
@@ -236,18 +263,10 @@ impl Runtime {
         }
         {
             // start tick interval
-            #[allow(unused_assignments)]
             let mut default_interval_millis = 15;
             let max_message_retry_times = options.max_message_retry_times;
             if options.tick_interval_secs > 0 {
-                #[allow(unused_assignments)]
-                {
-                    default_interval_millis = options.tick_interval_secs * 1000;
-                }
-            }
-            #[cfg(test)]
-            {
-                default_interval_millis = 900;
+                default_interval_millis = options.tick_interval_secs * 1000;
             }
 
             let evt = self.emitter().clone();
@@ -271,23 +290,93 @@ impl Runtime {
             });
 
             let evt = self.emitter().clone();
+            let clock = self.clock.clone();
+            // the first deadline is taken before spawning, so that the clock
+            // advancing can not be missed by the tick loop
+            let mut deadline = clock.now() + default_interval_millis as i64;
             Handle::current().spawn(async move {
-                let mut intv = time::interval(Duration::from_millis(default_interval_millis));
                 loop {
-                    intv.tick().await;
-                    evt.emit_tick();
+                    clock.sleep_until(deadline).await;
+                    let now = clock.now();
+                    evt.emit_tick(now);
+                    deadline = now + default_interval_millis as i64;
                 }
             });
         }
     }
 
+    /// aborts the running proc with all of its tasks
+    /// the sub procs of the aborted call acts will be aborted by cascading
+    pub fn abort_proc(self: &Arc<Self>, pid: &str) -> Result<()> {
+        debug!("sch::abort_proc({pid})");
+        let proc = self.proc(pid).ok_or(ActError::Runtime(format!(
+            "cannot find proc '{pid}' when aborting"
+        )))?;
+        if proc.state().is_completed() {
+            return Err(ActError::Action(format!(
+                "proc '{pid}' is already completed"
+            )));
+        }
+        let root = proc.root().ok_or(ActError::Runtime(format!(
+            "cannot find root task in proc '{pid}'"
+        )))?;
+
+        for task in proc.tasks().iter().rev() {
+            if task.id == root.id || task.state().is_completed() {
+                continue;
+            }
+            if task.state().is_running() {
+                task.set_state(TaskState::Aborted);
+            } else {
+                task.set_state(TaskState::Skipped);
+            }
+            task.create_context().emit_task(task)?;
+        }
+
+        root.set_state(TaskState::Aborted);
+        root.create_context().emit_task(&root)?;
+
+        Ok(())
+    }
+
+    fn abort_sub_proc(self: &Arc<Self>, pid: &str) {
+        let rt = self.clone();
+        let pid = pid.to_string();
+        tokio::spawn(async move {
+            if let Some(proc) = rt.proc(&pid) {
+                if proc.state().is_completed() {
+                    return;
+                }
+                rt.abort_proc(&pid)
+                    .unwrap_or_else(|err| error!("scher::abort_sub_proc {}", err));
+            }
+        });
+    }
+
     fn return_to_act(self: &Arc<Self>, pid: &str, tid: &str, proc: &Proc) {
         debug!("scher.return_to_act");
         let state = proc.state();
+        // the act is already ended by the parent proc, such as the sub proc is aborted by cascading
+        if let Some(task) = self.proc(pid).and_then(|p| p.task(tid)) {
+            if task.state().is_completed() {
+                debug!("scher.return_to_act: task '{pid}:{tid}' is already completed");
+                return;
+            }
+        }
         // proc.print();
         let mut vars = proc.outputs();
         debug!("sub outputs: {vars}");
         let mut event = consts::EVT_NEXT;
+        if state.is_completed() {
+            // maps the outputs by the call act's outputs defination
+            if let Some(task) = self.proc(pid).and_then(|p| p.task(tid)) {
+                if let NodeContent::Act(act) = &task.node().content {
+                    if let ActFn::Call(call) = act.into() {
+                        vars = call.map_outputs(&vars);
+                    }
+                }
+            }
+        }
         if state.is_abort() {
             event = consts::EVT_ABORT;
         } else if state.is_skip() {
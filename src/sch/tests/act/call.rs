@@ -1,7 +1,7 @@
 use crate::{
     sch::{tests::create_proc_signal, TaskState},
     utils::{self, consts},
-    Act, Executor, Vars, Workflow,
+    Act, Executor, Signal, Vars, Workflow,
 };
 use serde_json::json;
 
@@ -234,3 +234,345 @@ async fn sch_act_call_act_error() {
         .state()
         .is_error());
 }
+
+#[tokio::test]
+async fn sch_act_call_ver() {
+    let mut main = Workflow::new().with_id("main").with_step(|step| {
+        step.with_id("step1")
+            .with_act(Act::call(|act| act.with_key("w2").with_ver(1)))
+    });
+
+    let w2 = Workflow::new()
+        .with_id("w2")
+        .with_name("v1")
+        .with_step(|step| step.with_id("step1"));
+
+    main.print();
+    let (proc, scher, emitter, tx, rx) = create_proc_signal::<String>(&mut main, &utils::longid());
+    let executor = Executor::new(&scher);
+    executor.model().deploy(&w2).unwrap();
+    executor
+        .model()
+        .deploy(&w2.clone().with_name("v2"))
+        .unwrap();
+
+    emitter.on_start(move |e| {
+        if e.model.id == "w2" {
+            rx.send(e.model.name.clone());
+        }
+    });
+
+    scher.launch(&proc);
+    let ret = tx.recv().await;
+    proc.print();
+    assert_eq!(ret, "v1");
+}
+
+#[tokio::test]
+async fn sch_act_call_ver_not_found_error() {
+    let mut main = Workflow::new().with_id("main").with_step(|step| {
+        step.with_id("step1")
+            .with_act(Act::call(|act| act.with_key("w2").with_ver(10)))
+    });
+
+    let w2 = Workflow::new()
+        .with_id("w2")
+        .with_step(|step| step.with_id("step1"));
+
+    main.print();
+    let (proc, scher, _, tx, _) = create_proc_signal::<()>(&mut main, &utils::longid());
+    Executor::new(&scher).model().deploy(&w2).unwrap();
+    scher.launch(&proc);
+    tx.recv().await;
+    proc.print();
+    assert!(proc.state().is_error())
+}
+
+#[tokio::test]
+async fn sch_act_call_no_wait() {
+    let mut main = Workflow::new().with_id("main").with_step(|step| {
+        step.with_id("step1")
+            .with_act(Act::call(|act| act.with_key("w2").with_wait(false)).with_id("call1"))
+    });
+
+    let w2 = Workflow::new().with_id("w2").with_step(|step| {
+        step.with_id("s1")
+            .with_act(Act::irq(|act| act.with_key("act1")).with_id("act1"))
+    });
+
+    main.print();
+    let main_pid = utils::longid();
+    let (proc, scher, emitter, tx, rx) = create_proc_signal::<()>(&mut main, &main_pid);
+    Executor::new(&scher).model().deploy(&w2).unwrap();
+    let pid = main_pid.clone();
+    emitter.on_complete(move |e| {
+        if e.pid == pid {
+            rx.close();
+        }
+    });
+
+    scher.launch(&proc);
+    tx.recv().await;
+    proc.print();
+
+    // the main proc is completed without waiting for the sub proc
+    assert!(proc.state().is_completed());
+    let call = proc.task_by_nid("call1").first().cloned().unwrap();
+    assert_eq!(call.state(), TaskState::Completed);
+
+    let sub_pid = call
+        .with_data(|data| data.get::<String>(consts::ACT_CALL_PROC_ID))
+        .unwrap();
+    let sub = scher.proc(&sub_pid).unwrap();
+    assert!(sub.state().is_running());
+    assert_eq!(sub.parent(), None);
+    // the detached sub proc still records the parent proc id
+    assert_eq!(sub.parent_id(), Some(main_pid));
+}
+
+#[tokio::test]
+async fn sch_act_call_outputs() {
+    let mut main = Workflow::new().with_id("main").with_step(|step| {
+        step.with_id("step1").with_act(
+            Act::call(|act| {
+                act.with_key("w2")
+                    .with_output("total", Some("sum"))
+                    .with_output("count", None)
+            })
+            .with_id("call1"),
+        )
+    });
+
+    let w2 = Workflow::new()
+        .with_id("w2")
+        .with_output("sum", json!(10))
+        .with_output("count", json!(2))
+        .with_output("other", json!(5))
+        .with_step(|step| step.with_id("s1"));
+
+    main.print();
+    let main_pid = utils::longid();
+    let (proc, scher, emitter, _, _) = create_proc_signal::<()>(&mut main, &main_pid);
+    Executor::new(&scher).model().deploy(&w2).unwrap();
+
+    // waits for the main proc completing
+    let (sig, s) = Signal::new(()).double();
+    emitter.on_complete(move |e| {
+        if e.model.id == "main" {
+            s.close();
+        }
+    });
+    scher.launch(&proc);
+    sig.recv().await;
+    proc.print();
+
+    let call = proc.task_by_nid("call1").first().cloned().unwrap();
+    assert_eq!(call.state(), TaskState::Completed);
+    assert_eq!(call.find::<i32>("total"), Some(10));
+    assert_eq!(call.find::<i32>("count"), Some(2));
+    assert_eq!(call.find::<i32>("other"), None);
+}
+
+#[tokio::test]
+async fn sch_act_call_record_sub_pid() {
+    let mut main = Workflow::new().with_id("main").with_step(|step| {
+        step.with_id("step1").with_act(
+            Act::call(|act| act.with_key("w2").with_input("pid", json!("sub_pid1")))
+                .with_id("call1"),
+        )
+    });
+
+    let w2 = Workflow::new()
+        .with_id("w2")
+        .with_step(|step| step.with_id("s1"));
+
+    main.print();
+    let (proc, scher, emitter, _, _) = create_proc_signal::<()>(&mut main, &utils::longid());
+    Executor::new(&scher).model().deploy(&w2).unwrap();
+
+    let (sig, s) = Signal::new(()).double();
+    emitter.on_complete(move |e| {
+        if e.model.id == "main" {
+            s.close();
+        }
+    });
+    scher.launch(&proc);
+    sig.recv().await;
+    proc.print();
+
+    let call = proc.task_by_nid("call1").first().cloned().unwrap();
+    assert_eq!(
+        call.with_data(|data| data.get::<String>(consts::ACT_CALL_PROC_ID)),
+        Some("sub_pid1".to_string())
+    );
+}
+
+#[tokio::test]
+async fn sch_act_call_cascade_abort() {
+    let mut main = Workflow::new().with_id("main").with_step(|step| {
+        step.with_id("step1").with_act(
+            Act::call(|act| {
+                act.with_key("w2")
+                    .with_input("pid", json!("cascade_abort_sub1"))
+            })
+            .with_id("call1"),
+        )
+    });
+
+    let w2 = Workflow::new().with_id("w2").with_step(|step| {
+        step.with_id("s1")
+            .with_act(Act::irq(|act| act.with_key("act1")).with_id("act1"))
+    });
+
+    main.print();
+    let main_pid = utils::longid();
+    let (proc, scher, emitter, _, _) = create_proc_signal::<()>(&mut main, &main_pid);
+    Executor::new(&scher).model().deploy(&w2).unwrap();
+
+    // waits for the sub proc completing
+    let (sig, s) = Signal::new(String::new()).double();
+    emitter.on_complete(move |e| {
+        if e.model.id == "w2" {
+            s.send(e.state.clone());
+        }
+    });
+
+    let p = proc.clone();
+    emitter.on_message(move |e| {
+        if e.is_key("act1") && e.is_state("created") {
+            // aborts the call act in the parent proc
+            let call = p.task_by_nid("call1").first().cloned().unwrap();
+            e.do_action(p.id(), &call.id, consts::EVT_ABORT, &Vars::new())
+                .unwrap();
+        }
+    });
+    scher.launch(&proc);
+    let state = sig.recv().await;
+    proc.print();
+
+    assert_eq!(state, TaskState::Aborted.to_string());
+    assert!(proc.state().is_abort());
+    assert_eq!(
+        proc.task_by_nid("call1").first().unwrap().state(),
+        TaskState::Aborted
+    );
+}
+
+#[tokio::test]
+async fn sch_act_call_cascade_back() {
+    let mut main = Workflow::new()
+        .with_id("main")
+        .with_step(|step| {
+            step.with_id("step1")
+                .with_act(Act::irq(|act| act.with_key("act0")).with_id("act0"))
+        })
+        .with_step(|step| {
+            step.with_id("step2").with_act(
+                Act::call(|act| {
+                    act.with_key("w2")
+                        .with_input("pid", json!("cascade_back_sub1"))
+                })
+                .with_id("call1"),
+            )
+        });
+
+    let w2 = Workflow::new().with_id("w2").with_step(|step| {
+        step.with_id("s1")
+            .with_act(Act::irq(|act| act.with_key("act1")).with_id("act1"))
+    });
+
+    main.print();
+    let main_pid = utils::longid();
+    let (proc, scher, emitter, _, _) = create_proc_signal::<()>(&mut main, &main_pid);
+    Executor::new(&scher).model().deploy(&w2).unwrap();
+
+    let (sig, s) = Signal::new(String::new()).double();
+    emitter.on_complete(move |e| {
+        if e.model.id == "w2" {
+            s.send(e.state.clone());
+        }
+    });
+
+    let p = proc.clone();
+    emitter.on_message(move |e| {
+        if e.is_key("act0") && e.is_state("created") {
+            e.do_action(&e.pid, &e.tid, consts::EVT_NEXT, &Vars::new())
+                .unwrap();
+        }
+        if e.is_key("act1") && e.is_state("created") {
+            // backs the call act to step1 in the parent proc
+            let call = p.task_by_nid("call1").first().cloned().unwrap();
+            let options = Vars::new().with(consts::ACT_TO, "step1");
+            e.do_action(p.id(), &call.id, consts::EVT_BACK, &options)
+                .unwrap();
+        }
+    });
+    scher.launch(&proc);
+    let state = sig.recv().await;
+    proc.print();
+
+    assert_eq!(state, TaskState::Aborted.to_string());
+    assert_eq!(
+        proc.task_by_nid("call1").first().unwrap().state(),
+        TaskState::Backed
+    );
+}
+
+#[tokio::test]
+async fn sch_act_call_cascade_cancel() {
+    let mut main = Workflow::new()
+        .with_id("main")
+        .with_step(|step| {
+            step.with_id("step1")
+                .with_act(Act::irq(|act| act.with_key("act0")).with_id("act0"))
+        })
+        .with_step(|step| {
+            step.with_id("step2").with_act(
+                Act::call(|act| {
+                    act.with_key("w2")
+                        .with_input("pid", json!("cascade_cancel_sub1"))
+                })
+                .with_id("call1"),
+            )
+        });
+
+    let w2 = Workflow::new().with_id("w2").with_step(|step| {
+        step.with_id("s1")
+            .with_act(Act::irq(|act| act.with_key("act1")).with_id("act1"))
+    });
+
+    main.print();
+    let main_pid = utils::longid();
+    let (proc, scher, emitter, _, _) = create_proc_signal::<()>(&mut main, &main_pid);
+    Executor::new(&scher).model().deploy(&w2).unwrap();
+
+    let (sig, s) = Signal::new(String::new()).double();
+    emitter.on_complete(move |e| {
+        if e.model.id == "w2" {
+            s.send(e.state.clone());
+        }
+    });
+
+    let p = proc.clone();
+    emitter.on_message(move |e| {
+        if e.is_key("act0") && e.is_state("created") {
+            e.do_action(&e.pid, &e.tid, consts::EVT_NEXT, &Vars::new())
+                .unwrap();
+        }
+        if e.is_key("act1") && e.is_state("created") {
+            // cancels the completed act0, which undoes the call act in step2
+            let act0 = p.task_by_nid("act0").first().cloned().unwrap();
+            e.do_action(p.id(), &act0.id, consts::EVT_CANCEL, &Vars::new())
+                .unwrap();
+        }
+    });
+    scher.launch(&proc);
+    let state = sig.recv().await;
+    proc.print();
+
+    assert_eq!(state, TaskState::Aborted.to_string());
+    assert_eq!(
+        proc.task_by_nid("call1").first().unwrap().state(),
+        TaskState::Cancelled
+    );
+}
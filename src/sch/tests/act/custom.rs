@@ -0,0 +1,108 @@
+use crate::{
+    sch::tests::create_proc_signal2, utils, utils::consts, Act, ActContext, ActHandler, Output,
+    OutputType, Outputs, Result, StmtBuild, Workflow,
+};
+use serde_json::json;
+
+#[derive(Clone)]
+struct SumHandler;
+impl ActHandler for SumHandler {
+    fn inputs(&self) -> Outputs {
+        let number = Output {
+            required: true,
+            r#type: OutputType::Number,
+            ..Default::default()
+        };
+        let mut inputs = Outputs::default();
+        inputs.push("a", &number);
+        inputs.push("b", &number);
+        inputs
+    }
+
+    fn run(&self, ctx: &ActContext) -> Result<()> {
+        let inputs = ctx.inputs();
+        let sum =
+            inputs.get::<i64>("a").unwrap_or_default() + inputs.get::<i64>("b").unwrap_or_default();
+        ctx.expose("sum", sum);
+        ctx.set("sum", sum);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn sch_act_custom_run() {
+    let workflow = Workflow::new().with_step(|step| {
+        step.with_id("step1").with_act(
+            Act::new()
+                .with_act("test_sum")
+                .with_id("act1")
+                .with_input("a", 1)
+                .with_input("b", 2),
+        )
+    });
+    let (engine, proc, tx, _) = create_proc_signal2::<()>(&workflow, &utils::longid());
+    engine
+        .extender()
+        .register_act("test_sum", &SumHandler)
+        .unwrap();
+    engine.runtime().launch(&proc);
+    tx.recv().await;
+    proc.print();
+
+    let task = proc.task_by_nid("act1").first().unwrap().clone();
+    assert!(task.state().is_completed());
+    assert_eq!(task.outputs().get::<i64>("sum").unwrap(), 3);
+}
+
+#[tokio::test]
+async fn sch_act_custom_setup() {
+    let workflow = Workflow::new().with_step(|step| {
+        step.with_id("step1").with_setup(|stmts| {
+            stmts.add(
+                Act::new()
+                    .with_act("test_sum_setup")
+                    .with_id("act1")
+                    .with_input("a", 5)
+                    .with_input("b", 5),
+            )
+        })
+    });
+    let (engine, proc, tx, _) = create_proc_signal2::<()>(&workflow, &utils::longid());
+    engine
+        .extender()
+        .register_act("test_sum_setup", &SumHandler)
+        .unwrap();
+    engine.runtime().launch(&proc);
+    tx.recv().await;
+    proc.print();
+
+    let task = proc.task_by_nid("act1").first().unwrap().clone();
+    assert!(task.state().is_completed());
+    assert_eq!(task.data().get::<i64>("sum").unwrap(), 10);
+}
+
+#[tokio::test]
+async fn sch_act_custom_inputs_invalid() {
+    let workflow = Workflow::new().with_step(|step| {
+        step.with_id("step1").with_act(
+            Act::new()
+                .with_act("test_sum_invalid")
+                .with_id("act1")
+                .with_input("a", json!("1")),
+        )
+    });
+    let (engine, proc, tx, rx) = create_proc_signal2::<String>(&workflow, &utils::longid());
+    engine
+        .extender()
+        .register_act("test_sum_invalid", &SumHandler)
+        .unwrap();
+    engine.channel().on_message(move |e| {
+        if e.is_key("step1") && e.is_state("error") {
+            rx.send(e.inputs.get::<String>(consts::ACT_ERR_MESSAGE).unwrap());
+        }
+    });
+    engine.runtime().launch(&proc);
+    let ret = tx.recv().await;
+    assert!(ret.contains("the key 'b' is required"));
+    assert!(ret.contains("is not matched the type 'Number'"));
+}
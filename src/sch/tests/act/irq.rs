@@ -2,11 +2,12 @@ use crate::{
     event::{Action, MessageState},
     sch::{tests::*, TaskState},
     utils::{self, consts},
-    Act, Message, StmtBuild, Vars, Workflow,
+    Act, ActError, FieldError, ManualClock, Message, StmtBuild, Vars, Workflow,
 };
 use serde_json::{json, Value};
 use std::sync::Arc;
 use std::sync::Mutex;
+use std::time::Duration;
 
 #[tokio::test]
 async fn sch_act_irq_one() {
@@ -918,6 +919,189 @@ async fn sch_act_irq_do_action_ret_key_check() {
     assert!(ret);
 }
 
+#[tokio::test]
+async fn sch_act_irq_do_action_rets_typed_error() {
+    let text = r#"
+    steps:
+      - id: step1
+        acts:
+          - act: irq
+            id: fn1
+            key: fn1
+            rets:
+              approved:
+                type: bool
+              amount:
+                type: number
+                validate: ${ value > 0 }
+              level:
+                enum: [low, high]
+              comment:
+                required: false
+    "#;
+    let mut workflow = Workflow::from_yml(text).unwrap();
+
+    let pid = utils::longid();
+    let (proc, scher, emitter, tx, rx) = create_proc_signal::<Vec<FieldError>>(&mut workflow, &pid);
+
+    let s = scher.clone();
+    emitter.on_message(move |e| {
+        if e.is_key("fn1") && e.is_state("created") {
+            let options = Vars::new()
+                .with("approved", "yes")
+                .with("amount", -1)
+                .with("level", "middle");
+            let action = Action::new(&e.pid, &e.tid, consts::EVT_NEXT, &options);
+            match s.do_action(&action) {
+                Err(ActError::Validation(errors)) => rx.send(errors),
+                _ => rx.close(),
+            }
+        }
+    });
+
+    scher.launch(&proc);
+    let errors = tx.recv().await;
+    proc.print();
+    let keys = errors.iter().map(|e| e.key.as_str()).collect::<Vec<_>>();
+    assert_eq!(keys, ["amount", "approved", "level"]);
+    assert!(errors[0].message.contains("amount(-1)"));
+    assert!(errors[1].message.contains("approved(\"yes\")"));
+    assert!(errors[2].message.contains("level(\"middle\")"));
+
+    // the task state is not changed
+    let task = proc.task_by_nid("fn1").first().cloned().unwrap();
+    assert_eq!(task.state(), TaskState::Interrupt);
+    assert_eq!(task.data().get_value("approved"), None);
+}
+
+#[tokio::test]
+async fn sch_act_irq_do_action_rets_typed_ok() {
+    let text = r#"
+    steps:
+      - id: step1
+        acts:
+          - act: irq
+            id: fn1
+            key: fn1
+            rets:
+              approved:
+                type: bool
+              amount:
+                type: number
+                validate: ${ value > 0 }
+              comment:
+                required: false
+    "#;
+    let mut workflow = Workflow::from_yml(text).unwrap();
+
+    let pid = utils::longid();
+    let (proc, scher, emitter, tx, _) = create_proc_signal::<()>(&mut workflow, &pid);
+
+    let s = scher.clone();
+    emitter.on_message(move |e| {
+        if e.is_key("fn1") && e.is_state("created") {
+            let options = Vars::new().with("approved", true).with("amount", 10);
+            let action = Action::new(&e.pid, &e.tid, consts::EVT_NEXT, &options);
+            s.do_action(&action).unwrap();
+        }
+    });
+
+    scher.launch(&proc);
+    tx.recv().await;
+    proc.print();
+    let task = proc.task_by_nid("fn1").first().cloned().unwrap();
+    assert_eq!(task.state(), TaskState::Completed);
+    assert_eq!(task.data().get::<bool>("approved"), Some(true));
+    assert_eq!(task.data().get::<i32>("amount"), Some(10));
+}
+
+#[tokio::test]
+async fn sch_act_irq_do_action_rets_nullable() {
+    let text = r#"
+    steps:
+      - id: step1
+        acts:
+          - act: irq
+            id: fn1
+            key: fn1
+            rets:
+              a:
+              amount:
+                type: number
+              comment:
+                required: false
+    "#;
+    let mut workflow = Workflow::from_yml(text).unwrap();
+
+    let pid = utils::longid();
+    let (proc, scher, emitter, tx, _) = create_proc_signal::<()>(&mut workflow, &pid);
+
+    let s = scher.clone();
+    emitter.on_message(move |e| {
+        if e.is_key("fn1") && e.is_state("created") {
+            // the rets are nullable and the `required: false` ret can be omitted
+            let options = Vars::new()
+                .with("a", json!(null))
+                .with("amount", json!(null));
+            let action = Action::new(&e.pid, &e.tid, consts::EVT_NEXT, &options);
+            s.do_action(&action).unwrap();
+        }
+    });
+
+    scher.launch(&proc);
+    tx.recv().await;
+    proc.print();
+    let task = proc.task_by_nid("fn1").first().cloned().unwrap();
+    assert_eq!(task.state(), TaskState::Completed);
+    assert_eq!(task.data().get_value("amount"), Some(&json!(null)));
+}
+
+#[tokio::test]
+async fn sch_act_irq_do_action_rets_null_error() {
+    let text = r#"
+    steps:
+      - id: step1
+        acts:
+          - act: irq
+            id: fn1
+            key: fn1
+            rets:
+              amount:
+                type: number
+                required: true
+                validate: ${ value > 0 }
+              comment:
+                required: false
+                validate: ${ value.length > 0 }
+    "#;
+    let mut workflow = Workflow::from_yml(text).unwrap();
+
+    let pid = utils::longid();
+    let (proc, scher, emitter, tx, rx) = create_proc_signal::<Vec<FieldError>>(&mut workflow, &pid);
+
+    let s = scher.clone();
+    emitter.on_message(move |e| {
+        if e.is_key("fn1") && e.is_state("created") {
+            let options = Vars::new()
+                .with("amount", json!(null))
+                .with("comment", json!(null));
+            let action = Action::new(&e.pid, &e.tid, consts::EVT_NEXT, &options);
+            match s.do_action(&action) {
+                Err(ActError::Validation(errors)) => rx.send(errors),
+                _ => rx.close(),
+            }
+        }
+    });
+
+    scher.launch(&proc);
+    let errors = tx.recv().await;
+    proc.print();
+    // the optional null is not validated by the expression
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].key, "amount");
+    assert!(errors[0].message.contains("the key 'amount' is required"));
+}
+
 #[tokio::test]
 async fn sch_act_irq_do_action_proc_id_error() {
     let mut workflow = Workflow::new().with_step(|step| {
@@ -1508,9 +1692,14 @@ async fn sch_act_irq_on_timeout() {
         )
     });
 
-    let (proc, scher, emitter, tx, rx) = create_proc_signal::<()>(&mut workflow, &utils::longid());
+    let clock = ManualClock::new();
+    let (proc, scher, emitter, tx, rx) =
+        create_proc_signal_with_clock::<()>(&mut workflow, &utils::longid(), &clock);
     emitter.on_message(move |e| {
         println!("message: {e:?}");
+        if e.is_key("act1") && e.is_state("created") {
+            clock.advance(Duration::from_secs(16));
+        }
         if e.is_key("act2") {
             rx.close();
         }
@@ -3,6 +3,7 @@ mod call;
 mod catch;
 mod chain;
 mod cmd;
+mod custom;
 mod each;
 mod expose;
 mod r#if;
@@ -1,8 +1,12 @@
 use crate::{
     data,
-    sch::{tests::create_proc_signal2, Proc},
+    sch::{
+        tests::{create_proc_signal2, create_proc_signal_config},
+        Proc,
+    },
     utils::{self, consts},
-    Act, Event, Message, Signal, StmtBuild, TaskState, Workflow,
+    Act, ActModule, AsyncFn, Config, Event, FieldError, Limits, Manifest, ManualClock, Message,
+    Output, OutputType, Signal, StmtBuild, TaskState, Workflow,
 };
 use serde_json::json;
 use std::sync::Arc;
@@ -515,3 +519,508 @@ async fn run_test_dep<T: Clone + Send + 'static + Default>(
     engine.runtime().launch(&proc);
     tx.recv().await
 }
+
+#[tokio::test]
+async fn sch_act_pack_timeout_limit() {
+    let workflow = Workflow::new().with_step(|step| {
+        step.with_id("step1")
+            .with_act(Act::pack(|p| p.with_key("pack1")))
+    });
+    let pack = data::Package {
+        id: "pack1".to_string(),
+        name: "package 1".to_string(),
+        data: b"while(true) {}".to_vec(),
+        limits: Limits::new().with_timeout(100),
+        ..Default::default()
+    };
+    let ret: String = run_test(&workflow, &pack, |e, s| {
+        if e.is_key("step1") && e.is_state("error") {
+            s.send(e.inputs.get::<String>(consts::ACT_ERR_CODE).unwrap());
+        }
+    })
+    .await;
+    assert_eq!(ret, consts::ACT_LIMIT_ECODE);
+}
+
+#[tokio::test]
+async fn sch_act_pack_import() {
+    let workflow = Workflow::new().with_step(|step| {
+        step.with_id("step1")
+            .with_act(Act::pack(|p| p.with_key("pack1")))
+    });
+    let utils = data::Package {
+        id: "utils".to_string(),
+        name: "utils".to_string(),
+        data: br#"export function key(name) { return `msg_${name}`; }"#.to_vec(),
+        ..Default::default()
+    };
+    let pack = data::Package {
+        id: "pack1".to_string(),
+        name: "package 1".to_string(),
+        data: br#"
+        import { key } from '@pack/utils';
+        act.msg({ key: key("a") });
+        "#
+        .to_vec(),
+        ..Default::default()
+    };
+    let ret = run_test_packs(&workflow, &[utils, pack], |e, s| {
+        if e.is_key("msg_a") {
+            s.send(true);
+        }
+    })
+    .await;
+    assert!(ret);
+}
+
+#[tokio::test]
+async fn sch_act_pack_import_republish() {
+    let workflow = Workflow::new().with_step(|step| step.with_id("step1").with_uses("pack1"));
+    let utils = |prefix: &str| data::Package {
+        id: "utils".to_string(),
+        name: "utils".to_string(),
+        data: format!(r#"export function key(name) {{ return "{prefix}" + name; }}"#).into_bytes(),
+        ..Default::default()
+    };
+    let pack = data::Package {
+        id: "pack1".to_string(),
+        name: "package 1".to_string(),
+        data: br#"
+        import { key } from '@pack/utils';
+        act.msg({ key: key("a") });
+        "#
+        .to_vec(),
+        ..Default::default()
+    };
+    let (engine, proc, _, _) = create_proc_signal2::<()>(&workflow, &utils::longid());
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    engine.channel().on_message(move |e| {
+        if e.is_type("msg") {
+            tx.send(e.key.clone()).unwrap();
+        }
+    });
+    engine.executor().pack().publish(&utils("msg_")).unwrap();
+    engine.executor().pack().publish(&pack).unwrap();
+    engine.runtime().launch(&proc);
+    assert_eq!(rx.recv().await.unwrap(), "msg_a");
+
+    // the republished module is loaded by the next run
+    engine.executor().pack().publish(&utils("new_")).unwrap();
+    let proc = engine.runtime().create_proc(&utils::longid(), &workflow);
+    engine.runtime().launch(&proc);
+    assert_eq!(rx.recv().await.unwrap(), "new_a");
+}
+
+#[tokio::test]
+async fn sch_act_pack_import_std() {
+    let workflow = Workflow::new().with_step(|step| step.with_id("step1").with_uses("pack1"));
+    let pack = data::Package {
+        id: "pack1".to_string(),
+        name: "package 1".to_string(),
+        data: br#"
+        import { add } from '@acts/time';
+        import { base64Encode } from '@acts/crypto';
+        import { get } from '@acts/json';
+        const date = add("2024-01-05T10:00:00Z", 1, "bd").slice(0, 10);
+        act.msg({ key: `msg_${date}_${base64Encode(get({ a: ["b"] }, "a[0]"))}` });
+        "#
+        .to_vec(),
+        ..Default::default()
+    };
+    let ret = run_test_packs(&workflow, &[pack], |e, s| {
+        if e.is_key("msg_2024-01-08_Yg==") {
+            s.send(true);
+        }
+    })
+    .await;
+    assert!(ret);
+}
+
+#[tokio::test]
+async fn sch_act_pack_time_now_by_clock() {
+    let workflow = Workflow::new().with_step(|step| {
+        step.with_id("step1")
+            .with_act(Act::pack(|p| p.with_key("pack1")).with_id("pack1"))
+    });
+    let pack = data::Package {
+        id: "pack1".to_string(),
+        name: "package 1".to_string(),
+        data: br#"
+        import { now } from '@acts/time';
+        act.expose("now", now());
+        "#
+        .to_vec(),
+        ..Default::default()
+    };
+    let clock = ManualClock::at(1_700_000_000_000);
+    let (engine, proc, sig) =
+        create_proc_signal_config::<()>(&Config::default(), &workflow, &utils::longid(), &clock);
+    engine.executor().pack().publish(&pack).unwrap();
+    engine.runtime().launch(&proc);
+    sig.recv().await;
+
+    let task = proc.task_by_nid("pack1").first().unwrap().clone();
+    assert_eq!(
+        task.outputs().get::<String>("now").unwrap(),
+        "2023-11-14T22:13:20.000Z"
+    );
+}
+
+#[tokio::test]
+async fn sch_act_pack_import_nested() {
+    let workflow = Workflow::new().with_step(|step| step.with_id("step1").with_uses("pack1"));
+    let base = data::Package {
+        id: "base".to_string(),
+        name: "base".to_string(),
+        data: br#"export const prefix = "msg_";"#.to_vec(),
+        ..Default::default()
+    };
+    let utils = data::Package {
+        id: "utils".to_string(),
+        name: "utils".to_string(),
+        data: br#"
+        import { prefix } from "@pack/base";
+        export function key(name) { return prefix + name; }
+        "#
+        .to_vec(),
+        ..Default::default()
+    };
+    let pack = data::Package {
+        id: "pack1".to_string(),
+        name: "package 1".to_string(),
+        data: br#"
+        import { key } from '@pack/utils';
+        act.msg({ key: key("b") });
+        "#
+        .to_vec(),
+        ..Default::default()
+    };
+    let ret = run_test_packs(&workflow, &[base, utils, pack], |e, s| {
+        if e.is_key("msg_b") {
+            s.send(true);
+        }
+    })
+    .await;
+    assert!(ret);
+}
+
+#[tokio::test]
+async fn sch_act_pack_import_dependency_version() {
+    let workflow = Workflow::new().with_step(|step| step.with_id("step1").with_uses("pack1"));
+    let mut packages = ["1.0.0", "2.0.0"]
+        .iter()
+        .map(|ver| data::Package {
+            id: "utils".to_string(),
+            name: "utils".to_string(),
+            data: format!(r#"export function key(name) {{ return `msg_${{name}}_{ver}`; }}"#)
+                .into_bytes(),
+            manifest: Manifest::new().with_version(ver),
+            ..Default::default()
+        })
+        .collect::<Vec<_>>();
+    packages.push(data::Package {
+        id: "pack1".to_string(),
+        name: "package 1".to_string(),
+        data: br#"
+        import { key } from '@pack/utils';
+        act.msg({ key: key("a") });
+        "#
+        .to_vec(),
+        manifest: Manifest::new()
+            .with_version("1.0.0")
+            .with_dependency("utils", "^1.0"),
+        ..Default::default()
+    });
+    let ret: String = run_test_packs(&workflow, &packages, |e, s| {
+        if e.is_key("step1") && e.is_state("error") {
+            s.send(e.inputs.get::<String>(consts::ACT_ERR_MESSAGE).unwrap());
+        }
+        if e.is_type("msg") {
+            s.send(e.key.clone());
+        }
+    })
+    .await;
+    // the latest utils is 2.0.0, but the dependency requires ^1.0
+    assert_eq!(ret, "msg_a_1.0.0");
+}
+
+#[tokio::test]
+async fn sch_act_pack_import_not_found() {
+    let workflow = Workflow::new().with_step(|step| {
+        step.with_id("step1")
+            .with_act(Act::pack(|p| p.with_key("pack1")))
+    });
+    let pack = data::Package {
+        id: "pack1".to_string(),
+        name: "package 1".to_string(),
+        data: br#"
+        import { key } from '@pack/not_exists';
+        act.msg({ key: key("a") });
+        "#
+        .to_vec(),
+        ..Default::default()
+    };
+    let ret: String = run_test_packs(&workflow, &[pack], |e, s| {
+        if e.is_key("step1") && e.is_state("error") {
+            s.send(e.inputs.get::<String>(consts::ACT_ERR_MESSAGE).unwrap());
+        }
+    })
+    .await;
+    assert!(ret.contains("cannot find module '@pack/not_exists' imported by 'pack1'"));
+}
+
+#[tokio::test]
+async fn sch_act_pack_import_cycle() {
+    let workflow = Workflow::new().with_step(|step| {
+        step.with_id("step1")
+            .with_act(Act::pack(|p| p.with_key("pack1")))
+    });
+    let a = data::Package {
+        id: "a".to_string(),
+        name: "a".to_string(),
+        data: br#"
+        import { b } from '@pack/b';
+        export const a = 1;
+        "#
+        .to_vec(),
+        ..Default::default()
+    };
+    let b = data::Package {
+        id: "b".to_string(),
+        name: "b".to_string(),
+        data: br#"
+        import { a } from '@pack/a';
+        export const b = 1;
+        "#
+        .to_vec(),
+        ..Default::default()
+    };
+    let pack = data::Package {
+        id: "pack1".to_string(),
+        name: "package 1".to_string(),
+        data: br#"import { a } from '@pack/a';"#.to_vec(),
+        ..Default::default()
+    };
+    let ret: String = run_test_packs(&workflow, &[a, b, pack], |e, s| {
+        if e.is_key("step1") && e.is_state("error") {
+            s.send(e.inputs.get::<String>(consts::ACT_ERR_MESSAGE).unwrap());
+        }
+    })
+    .await;
+    assert!(ret.contains("@pack/pack1 -> @pack/a -> @pack/b -> @pack/a"));
+}
+
+async fn run_test_packs<T: Clone + Send + 'static + Default>(
+    workflow: &Workflow,
+    packages: &[data::Package],
+    exit_if: fn(&Event<Message>, sig: Signal<T>),
+) -> T {
+    let (engine, proc, tx, rx) = create_proc_signal2::<T>(workflow, &utils::longid());
+    for package in packages {
+        engine.executor().pack().publish(package).unwrap();
+    }
+    engine.channel().on_message(move |e| {
+        println!("message: {:?}", e);
+        exit_if(e, rx.clone());
+    });
+    engine.runtime().launch(&proc);
+    tx.recv().await
+}
+
+#[tokio::test]
+async fn sch_act_pack_await() {
+    let workflow = Workflow::new().with_step(|step| step.with_id("step1").with_uses("pack1"));
+    let pack = data::Package {
+        id: "pack1".to_string(),
+        name: "package 1".to_string(),
+        data: br#"
+        let ret = await lookup("a");
+        act.msg({ key: ret.key });
+        "#
+        .to_vec(),
+        ..Default::default()
+    };
+    let ret = run_test_lookup(&workflow, &[pack]).await;
+    assert!(ret);
+}
+
+#[tokio::test]
+async fn sch_act_pack_await_module() {
+    let workflow = Workflow::new().with_step(|step| step.with_id("step1").with_uses("pack1"));
+    let utils = data::Package {
+        id: "utils".to_string(),
+        name: "utils".to_string(),
+        data: br#"export async function key(name) { return (await lookup(name)).key; }"#.to_vec(),
+        ..Default::default()
+    };
+    let pack = data::Package {
+        id: "pack1".to_string(),
+        name: "package 1".to_string(),
+        data: br#"
+        import { key } from '@pack/utils';
+        act.msg({ key: await key("a") });
+        "#
+        .to_vec(),
+        ..Default::default()
+    };
+    let ret = run_test_lookup(&workflow, &[utils, pack]).await;
+    assert!(ret);
+}
+
+/// runs the packages with the async `lookup` host function
+async fn run_test_lookup(workflow: &Workflow, packages: &[data::Package]) -> bool {
+    #[derive(Clone)]
+    struct LookupModule;
+    impl ActModule for LookupModule {
+        fn init(&self, ctx: &rquickjs::Ctx<'_>) -> crate::Result<()> {
+            let lookup = AsyncFn::new(|args| async move {
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+                let name = args.first().and_then(|v| v.as_str()).unwrap_or_default();
+                Ok(json!({ "key": format!("msg_{name}") }))
+            });
+            ctx.globals().set("lookup", lookup)?;
+            Ok(())
+        }
+    }
+
+    let (engine, proc, tx, rx) = create_proc_signal2::<bool>(workflow, &utils::longid());
+    engine.extender().register_module(&LookupModule);
+    for package in packages {
+        engine.executor().pack().publish(package).unwrap();
+    }
+    engine.channel().on_message(move |e| {
+        if e.is_key("msg_a") {
+            rx.send(true);
+        }
+    });
+    engine.runtime().launch(&proc);
+    tx.recv().await
+}
+
+#[tokio::test]
+async fn sch_act_pack_version() {
+    let workflow = Workflow::new().with_step(|step| {
+        step.with_id("step1")
+            .with_act(Act::pack(|p| p.with_key("pack1@1.0.0")))
+    });
+    let packages = ["1.0.0", "1.1.0"]
+        .iter()
+        .map(|ver| data::Package {
+            id: "pack1".to_string(),
+            name: "package 1".to_string(),
+            data: format!(r#"act.msg({{ key: "msg_{ver}" }})"#).into_bytes(),
+            manifest: Manifest::new().with_version(ver),
+            ..Default::default()
+        })
+        .collect::<Vec<_>>();
+    let ret: String = run_test_packs(&workflow, &packages, |e, s| {
+        if e.is_type("msg") {
+            s.send(e.key.clone());
+        }
+    })
+    .await;
+    assert_eq!(ret, "msg_1.0.0");
+}
+
+#[tokio::test]
+async fn sch_act_pack_version_not_found() {
+    let workflow = Workflow::new().with_step(|step| step.with_id("step1").with_uses("pack1@2.0.0"));
+    let pack = data::Package {
+        id: "pack1".to_string(),
+        name: "package 1".to_string(),
+        data: br#"act.msg({ key: "msg1" })"#.to_vec(),
+        manifest: Manifest::new().with_version("1.0.0"),
+        ..Default::default()
+    };
+    let ret: String = run_test_packs(&workflow, &[pack], |e, s| {
+        if e.is_key("step1") && e.is_state("error") {
+            s.send(e.inputs.get::<String>(consts::ACT_ERR_MESSAGE).unwrap());
+        }
+    })
+    .await;
+    assert!(ret.contains("cannot find package 'pack1' with version 2.0.0"));
+}
+
+#[tokio::test]
+async fn sch_act_pack_inputs_valid() {
+    let workflow = Workflow::new().with_step(|step| {
+        step.with_id("step1").with_act(Act::pack(|p| {
+            p.with_key("pack1").with_input("name", json!("a"))
+        }))
+    });
+    let pack = data::Package {
+        id: "pack1".to_string(),
+        name: "package 1".to_string(),
+        data: br#"act.msg({ key: `msg_${act.inputs().name}` })"#.to_vec(),
+        manifest: Manifest::new().with_input(
+            "name",
+            Output {
+                required: true,
+                r#type: OutputType::String,
+                ..Default::default()
+            },
+        ),
+        ..Default::default()
+    };
+    let ret = run_test_packs(&workflow, &[pack], |e, s| {
+        if e.is_key("msg_a") {
+            s.send(true);
+        }
+    })
+    .await;
+    assert!(ret);
+}
+
+#[tokio::test]
+async fn sch_act_pack_inputs_invalid() {
+    let workflow = Workflow::new().with_step(|step| {
+        step.with_id("step1").with_act(Act::pack(|p| {
+            p.with_key("pack1").with_input("name", json!(10))
+        }))
+    });
+    let pack = data::Package {
+        id: "pack1".to_string(),
+        name: "package 1".to_string(),
+        data: br#"act.msg({ key: "msg1" })"#.to_vec(),
+        manifest: Manifest::new()
+            .with_input(
+                "name",
+                Output {
+                    required: true,
+                    r#type: OutputType::String,
+                    ..Default::default()
+                },
+            )
+            .with_input(
+                "age",
+                Output {
+                    required: true,
+                    r#type: OutputType::Number,
+                    ..Default::default()
+                },
+            ),
+        ..Default::default()
+    };
+    let ret: String = run_test_packs(&workflow, &[pack], |e, s| {
+        if e.is_key("msg1") {
+            s.send("".to_string());
+        }
+        if e.is_key("step1") && e.is_state("error") {
+            // the field errors are sent with the error message
+            let mut keys = e
+                .inputs
+                .get::<Vec<FieldError>>(consts::ACT_ERR_FIELDS)
+                .unwrap()
+                .iter()
+                .map(|field| field.key.clone())
+                .collect::<Vec<_>>();
+            keys.sort();
+            let message = e.inputs.get::<String>(consts::ACT_ERR_MESSAGE).unwrap();
+            s.send(format!("{message} [{}]", keys.join(", ")));
+        }
+    })
+    .await;
+    assert!(ret.contains("the key 'age' is required"));
+    assert!(ret.contains("the value name(10) is not matched the type 'String'"));
+    assert!(ret.ends_with(" [age, name]"));
+}
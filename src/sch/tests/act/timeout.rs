@@ -1,4 +1,15 @@
-use crate::{sch::tests::create_proc_signal, utils, Act, Message, StmtBuild, Workflow};
+use crate::{
+    sch::tests::create_proc_signal_with_clock, utils, Act, Clock, ManualClock, Message, StmtBuild,
+    Workflow,
+};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::sync::Notify;
 
 #[tokio::test]
 async fn sch_act_timeout_one() {
@@ -15,9 +26,13 @@ async fn sch_act_timeout_one() {
             )
     });
     workflow.print();
+    let clock = ManualClock::new();
     let (proc, scher, emitter, tx, rx) =
-        create_proc_signal::<bool>(&mut workflow, &utils::longid());
+        create_proc_signal_with_clock::<bool>(&mut workflow, &utils::longid(), &clock);
     emitter.on_message(move |e| {
+        if e.is_key("act1") && e.is_state("created") {
+            clock.advance(Duration::from_secs(16));
+        }
         if e.is_key("msg1") {
             rx.send(true);
         }
@@ -47,9 +62,13 @@ async fn sch_act_timeout_many() {
         )
     });
     workflow.print();
+    let clock = ManualClock::new();
     let (proc, scher, emitter, tx, rx) =
-        create_proc_signal::<Vec<Message>>(&mut workflow, &utils::longid());
+        create_proc_signal_with_clock::<Vec<Message>>(&mut workflow, &utils::longid(), &clock);
     emitter.on_message(move |e| {
+        if e.is_key("act1") && e.is_state("created") {
+            clock.advance(Duration::from_secs(16));
+        }
         if e.is_key("msg1") {
             rx.update(|data| data.push(e.inner().clone()));
         }
@@ -65,3 +84,50 @@ async fn sch_act_timeout_many() {
     proc.print();
     assert_eq!(ret.len(), 2)
 }
+
+#[tokio::test]
+async fn sch_act_timeout_days() {
+    let mut workflow = Workflow::new().with_step(|step| {
+        step.with_id("step1")
+            .with_act(
+                Act::new()
+                    .with_act("irq")
+                    .with_key("act1")
+                    .with_timeout(|t| {
+                        t.with_on("3d")
+                            .with_then(|stmts| stmts.add(Act::msg(|msg| msg.with_key("msg1"))))
+                    }),
+            )
+    });
+    workflow.print();
+    let clock = ManualClock::new();
+    let (proc, scher, emitter, tx, rx) =
+        create_proc_signal_with_clock::<bool>(&mut workflow, &utils::longid(), &clock);
+    let created = Arc::new(Notify::new());
+    let fired = Arc::new(AtomicBool::new(false));
+    let (created2, fired2) = (created.clone(), fired.clone());
+    emitter.on_message(move |e| {
+        if e.is_key("act1") && e.is_state("created") {
+            created2.notify_one();
+        }
+        if e.is_key("msg1") {
+            fired2.store(true, Ordering::SeqCst);
+            rx.send(true);
+        }
+    });
+
+    scher.launch(&proc);
+    created.notified().await;
+
+    // not timeout before the 3 days
+    clock.advance(Duration::from_secs(2 * 24 * 60 * 60));
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(!fired.load(Ordering::SeqCst));
+
+    clock.advance(Duration::from_secs(24 * 60 * 60));
+    let ret = tx.recv().await;
+    proc.print();
+    assert!(ret);
+    let task = proc.task_by_nid("step1").first().cloned().unwrap();
+    assert!(clock.now() - task.start_time() >= 3 * 24 * 60 * 60 * 1000);
+}
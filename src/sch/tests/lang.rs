@@ -0,0 +1,135 @@
+use crate::{
+    data,
+    event::Action,
+    sch::tests::{create_proc_signal, create_proc_signal2},
+    utils::{self, consts},
+    Act, ActError, Manifest, StmtBuild, Vars, Workflow,
+};
+use serde_json::json;
+
+#[tokio::test]
+async fn sch_lang_rhai_run() {
+    let mut workflow = Workflow::new()
+        .with_lang("rhai")
+        .with_input("v", json!(10))
+        .with_step(|step| {
+            step.with_id("step1")
+                .with_setup(|setup| {
+                    setup.add(Act::set(Vars::new().with("a", r#"${ get("v") + 1 }"#)))
+                })
+                .with_run(r#"set("b", get("a") * 2)"#)
+        });
+    let (proc, scher, _, tx, _) = create_proc_signal::<()>(&mut workflow, &utils::longid());
+    scher.launch(&proc);
+    tx.recv().await;
+    proc.print();
+
+    let task = proc.task_by_nid("step1").first().unwrap().clone();
+    assert_eq!(task.data().get::<i64>("a").unwrap(), 11);
+    assert_eq!(task.data().get::<i64>("b").unwrap(), 22);
+}
+
+#[tokio::test]
+async fn sch_lang_rhai_step_if() {
+    let mut workflow = Workflow::new()
+        .with_lang("rhai")
+        .with_input("v", json!(150))
+        .with_step(|step| step.with_id("step1").with_if(r#"get("v") > 100"#))
+        .with_step(|step| step.with_id("step2").with_if(r#"get("v") <= 100"#));
+    let (proc, scher, _, tx, _) = create_proc_signal::<()>(&mut workflow, &utils::longid());
+    scher.launch(&proc);
+    tx.recv().await;
+    proc.print();
+
+    assert!(proc
+        .task_by_nid("step1")
+        .first()
+        .unwrap()
+        .state()
+        .is_success());
+    assert!(proc.task_by_nid("step2").first().unwrap().state().is_skip());
+}
+
+#[tokio::test]
+async fn sch_lang_rhai_act_if() {
+    let mut workflow = Workflow::new()
+        .with_lang("rhai")
+        .with_input("v", json!(5))
+        .with_step(|step| {
+            step.with_id("step1").with_setup(|setup| {
+                setup.add(Act::r#if(|act| {
+                    act.with_on(r#"get("v") == 5"#)
+                        .with_then(|stmts| stmts.add(Act::set(Vars::new().with("matched", true))))
+                }))
+            })
+        });
+    let (proc, scher, _, tx, _) = create_proc_signal::<()>(&mut workflow, &utils::longid());
+    scher.launch(&proc);
+    tx.recv().await;
+    proc.print();
+
+    let task = proc.task_by_nid("step1").first().unwrap().clone();
+    assert!(task.data().get::<bool>("matched").unwrap());
+}
+
+#[tokio::test]
+async fn sch_lang_rhai_pack() {
+    let workflow = Workflow::new().with_step(|step| {
+        step.with_id("step1")
+            .with_act(Act::pack(|p| p.with_key("pack1").with_input("a", 1)).with_id("act1"))
+    });
+    let pack = data::Package {
+        id: "pack1".to_string(),
+        name: "package 1".to_string(),
+        data: br#"expose("sum", inputs().a + 1)"#.to_vec(),
+        manifest: Manifest::new().with_lang("rhai"),
+        ..Default::default()
+    };
+    let (engine, proc, tx, _) = create_proc_signal2::<()>(&workflow, &utils::longid());
+    engine.executor().pack().publish(&pack).unwrap();
+    engine.runtime().launch(&proc);
+    tx.recv().await;
+    proc.print();
+
+    let task = proc.task_by_nid("act1").first().unwrap().clone();
+    assert_eq!(task.outputs().get::<i64>("sum").unwrap(), 2);
+}
+
+#[tokio::test]
+async fn sch_lang_rhai_rets_validate() {
+    let text = r#"
+    lang: rhai
+    steps:
+      - id: step1
+        acts:
+          - act: irq
+            id: fn1
+            key: fn1
+            rets:
+              amount:
+                type: number
+                validate: ${ value > 0 && value < get("max") }
+    "#;
+    let mut workflow = Workflow::from_yml(text)
+        .unwrap()
+        .with_input("max", json!(100));
+    let (proc, scher, emitter, tx, rx) =
+        create_proc_signal::<Vec<bool>>(&mut workflow, &utils::longid());
+
+    let s = scher.clone();
+    emitter.on_message(move |e| {
+        if e.is_key("fn1") && e.is_state("created") {
+            let mut ret = Vec::new();
+            for amount in [-1, 200, 10] {
+                let options = Vars::new().with("amount", amount);
+                let action = Action::new(&e.pid, &e.tid, consts::EVT_NEXT, &options);
+                ret.push(matches!(s.do_action(&action), Err(ActError::Validation(_))));
+            }
+            rx.send(ret);
+        }
+    });
+    scher.launch(&proc);
+    let ret = tx.recv().await;
+    proc.print();
+    assert_eq!(ret, [true, true, false]);
+}
@@ -0,0 +1,203 @@
+use crate::{
+    sch::tests::{create_proc_signal, create_proc_signal_config},
+    utils::{self, consts},
+    Act, Config, LogInfo, ManualClock, TaskState, Vars, Workflow,
+};
+
+fn config(limit: usize) -> Config {
+    Config {
+        script_log_limit: limit,
+        ..Config::default()
+    }
+}
+
+#[tokio::test]
+async fn sch_log_console_capture() {
+    let workflow = Workflow::new()
+        .with_step(|step| {
+            step.with_id("step1").with_run(
+                r#"
+            console.log("a");
+            console.info("b");
+            console.wran("c");
+            console.error("d");
+            "#,
+            )
+        })
+        .with_step(|step| step.with_act(Act::irq(|act| act.with_key("act1"))));
+    let (engine, proc, sig) = create_proc_signal_config::<()>(
+        &config(10),
+        &workflow,
+        &utils::longid(),
+        &ManualClock::new(),
+    );
+    let s = sig.clone();
+    engine.channel().on_message(move |e| {
+        if e.is_key("act1") {
+            s.close();
+        }
+    });
+    engine.runtime().launch(&proc);
+    sig.recv().await;
+
+    // the logs are removed with the proc, so read them before the proc completes
+    let task = proc.task_by_nid("step1").first().unwrap().clone();
+    let logs = engine.executor().task().logs(proc.id(), &task.id).unwrap();
+    assert_eq!(
+        logs.iter()
+            .map(|log| (log.level.as_str(), log.message.as_str()))
+            .collect::<Vec<_>>(),
+        [("log", "a"), ("info", "b"), ("warn", "c"), ("error", "d")]
+    );
+}
+
+#[tokio::test]
+async fn sch_log_console_limit() {
+    let workflow = Workflow::new()
+        .with_step(|step| {
+            step.with_id("step1")
+                .with_run(r#"for (let i = 0; i < 5; i++) { console.log(`${i}`); }"#)
+        })
+        .with_step(|step| step.with_act(Act::irq(|act| act.with_key("act1"))));
+    let (engine, proc, sig) = create_proc_signal_config::<()>(
+        &config(2),
+        &workflow,
+        &utils::longid(),
+        &ManualClock::new(),
+    );
+    let s = sig.clone();
+    engine.channel().on_message(move |e| {
+        if e.is_key("act1") {
+            s.close();
+        }
+    });
+    engine.runtime().launch(&proc);
+    sig.recv().await;
+
+    let task = proc.task_by_nid("step1").first().unwrap().clone();
+    let logs = engine.executor().task().logs(proc.id(), &task.id).unwrap();
+    assert_eq!(
+        logs.iter()
+            .map(|log| log.message.as_str())
+            .collect::<Vec<_>>(),
+        ["3", "4"]
+    );
+}
+
+#[tokio::test]
+async fn sch_log_console_no_capture() {
+    let mut workflow =
+        Workflow::new().with_step(|step| step.with_id("step1").with_run(r#"console.log("a");"#));
+    let (proc, scher, _, tx, _) = create_proc_signal::<()>(&mut workflow, &utils::longid());
+    scher.launch(&proc);
+    tx.recv().await;
+
+    let task = proc.task_by_nid("step1").first().unwrap().clone();
+    let logs = scher.cache().store().logs_of(proc.id(), &task.id).unwrap();
+    assert!(logs.is_empty());
+}
+
+#[tokio::test]
+async fn sch_log_error_message() {
+    let workflow = Workflow::new().with_step(|step| {
+        step.with_id("step1").with_run(
+            r#"
+            console.log("before");
+            throw new Error("failed");
+            "#,
+        )
+    });
+    let (engine, proc, sig) = create_proc_signal_config::<Vars>(
+        &config(10),
+        &workflow,
+        &utils::longid(),
+        &ManualClock::new(),
+    );
+    let s = sig.clone();
+    engine.channel().on_message(move |e| {
+        if e.is_key("step1") && e.is_state("error") {
+            s.send(e.inputs.clone());
+        }
+    });
+    engine.runtime().launch(&proc);
+    let inputs = sig.recv().await;
+
+    // the logs are sent in the message and not appended to the error
+    let task = proc.task_by_nid("step1").first().unwrap().clone();
+    let err = task.err().unwrap();
+    assert!(err.message.contains("failed"));
+    assert!(!err.message.contains("before"));
+    assert_eq!(
+        inputs.get::<String>(consts::ACT_ERR_MESSAGE).unwrap(),
+        err.message
+    );
+    let logs = inputs.get::<Vec<LogInfo>>(consts::ACT_ERR_LOGS).unwrap();
+    assert_eq!(logs.len(), 1);
+    assert_eq!(logs[0].message, "before");
+}
+
+#[tokio::test]
+async fn sch_log_save_on_complete() {
+    let workflow = Workflow::new().with_step(|step| {
+        step.with_id("step1")
+            .with_run(r#"console.log("a");"#)
+            .with_act(Act::irq(|act| act.with_key("act1")))
+    });
+    let (engine, proc, sig) = create_proc_signal_config::<()>(
+        &config(10),
+        &workflow,
+        &utils::longid(),
+        &ManualClock::new(),
+    );
+    let s = sig.clone();
+    engine.channel().on_message(move |e| {
+        if e.is_key("act1") {
+            s.close();
+        }
+    });
+    engine.runtime().launch(&proc);
+    sig.recv().await;
+
+    // the logs are buffered in the task until it is completed
+    let task = proc.task_by_nid("step1").first().unwrap().clone();
+    assert_eq!(task.logs().len(), 1);
+    let store = engine.runtime().cache().store();
+    assert!(store.logs_of(proc.id(), &task.id).unwrap().is_empty());
+
+    task.set_state(TaskState::Completed);
+    let logs = store.logs_of(proc.id(), &task.id).unwrap();
+    assert_eq!(logs.len(), 1);
+    assert_eq!(logs[0].message, "a");
+}
+
+#[cfg(feature = "rhai")]
+#[tokio::test]
+async fn sch_log_rhai_print() {
+    let workflow = Workflow::new()
+        .with_lang("rhai")
+        .with_step(|step| step.with_id("step1").with_run(r#"print("a"); debug("b");"#))
+        .with_step(|step| step.with_act(Act::irq(|act| act.with_key("act1"))));
+    let (engine, proc, sig) = create_proc_signal_config::<()>(
+        &config(10),
+        &workflow,
+        &utils::longid(),
+        &ManualClock::new(),
+    );
+    let s = sig.clone();
+    engine.channel().on_message(move |e| {
+        if e.is_key("act1") {
+            s.close();
+        }
+    });
+    engine.runtime().launch(&proc);
+    sig.recv().await;
+
+    let task = proc.task_by_nid("step1").first().unwrap().clone();
+    let logs = engine.executor().task().logs(proc.id(), &task.id).unwrap();
+    assert_eq!(
+        logs.iter()
+            .map(|log| (log.level.as_str(), log.message.as_str()))
+            .collect::<Vec<_>>(),
+        [("log", "a"), ("debug", "\"b\"")]
+    );
+}
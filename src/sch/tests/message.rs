@@ -4,10 +4,13 @@ use crate::{
     sch::tests::{create_proc_signal, create_proc_signal2, create_proc_signal_config},
     store::{Cond, Expr},
     utils::{self, consts},
-    Act, Action, ChannelOptions, Config, Message, Query, StoreAdapter, Vars, Workflow,
+    Act, Action, ChannelOptions, Config, ManualClock, Message, Query, StoreAdapter, Vars, Workflow,
 };
 use serde_json::json;
-use std::sync::{Arc, Mutex};
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
 #[tokio::test]
 async fn sch_message_workflow_created() {
@@ -845,8 +848,10 @@ async fn sch_message_re_sent_if_not_ack() {
     let workflow =
         Workflow::new().with_step(|step| step.with_act(Act::irq(|act| act.with_key("act1"))));
     let id = utils::longid();
-    let (engine, proc, tx, rx) = create_proc_signal2::<Vec<Message>>(&workflow, &id);
-
+    let clock = ManualClock::new();
+    let (engine, proc, tx) =
+        create_proc_signal_config::<Vec<Message>>(&Config::default(), &workflow, &id, &clock);
+    let rx = tx.clone();
     let options = ChannelOptions {
         id: "e1".to_string(),
         ack: true,
@@ -860,6 +865,9 @@ async fn sch_message_re_sent_if_not_ack() {
             if rx.data().len() > 1 {
                 rx.close();
             }
+
+            // let the next tick to re-send the message
+            clock.advance(Duration::from_secs(16));
         }
     });
     engine.runtime().launch(&proc);
@@ -893,7 +901,9 @@ async fn sch_message_error_if_not_ack_and_exceed_max_reties() {
         max_message_retry_times: 2,
         ..Config::default()
     };
-    let (engine, proc, sig) = create_proc_signal_config::<Vec<Message>>(&config, &workflow, &id);
+    let clock = ManualClock::new();
+    let (engine, proc, sig) =
+        create_proc_signal_config::<Vec<Message>>(&config, &workflow, &id, &clock);
     let rx = sig.clone();
     let options = ChannelOptions {
         id: "e1".to_string(),
@@ -905,6 +915,7 @@ async fn sch_message_error_if_not_ack_and_exceed_max_reties() {
         if e.r#type == "workflow" && e.state() == MessageState::Created {
             // not ack the message
             rx.update(|data| data.push(e.inner().clone()));
+            clock.advance(Duration::from_secs(16));
         } else {
             engine.executor().msg().ack(&e.id).unwrap();
         }
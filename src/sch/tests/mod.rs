@@ -1,4 +1,7 @@
 mod act;
+#[cfg(feature = "rhai")]
+mod lang;
+mod log;
 mod message;
 mod proc;
 mod scher;
@@ -10,7 +13,7 @@ mod vars;
 mod workflow;
 
 use super::{Proc, Runtime};
-use crate::{export::Channel, Builder, Config, Engine, Signal, Workflow};
+use crate::{export::Channel, Builder, Config, Engine, ManualClock, Signal, Workflow};
 use std::sync::Arc;
 
 fn create_proc(workflow: &mut Workflow, pid: &str) -> (Arc<Proc>, Arc<Runtime>, Arc<Channel>) {
@@ -70,6 +73,41 @@ fn create_proc_signal<R: Clone + Default + Sync + Send + 'static>(
     (proc, rt, emitter, sig.clone(), sig.clone())
 }
 
+#[allow(clippy::type_complexity)]
+fn create_proc_signal_with_clock<R: Clone + Default + Sync + Send + 'static>(
+    workflow: &mut Workflow,
+    pid: &str,
+    clock: &ManualClock,
+) -> (
+    Arc<Proc>,
+    Arc<Runtime>,
+    Arc<crate::export::Channel>,
+    Signal<R>,
+    Signal<R>,
+) {
+    let engine = Builder::new().clock(clock).build();
+    let rt = engine.runtime();
+
+    let proc = rt.create_proc(pid, workflow);
+
+    let emitter = engine.channel().clone();
+    let sig = engine.signal(R::default());
+    let rx2 = sig.clone();
+    let rx3 = sig.clone();
+    emitter.on_complete(move |p| {
+        if p.state().is_completed() {
+            rx2.close();
+        }
+    });
+
+    emitter.on_error(move |p| {
+        println!("error in '{}', error={}", p.inner().pid, p.inner().state);
+        rx3.close();
+    });
+
+    (proc, rt, emitter, sig.clone(), sig.clone())
+}
+
 #[allow(clippy::type_complexity)]
 fn create_proc_signal2<R: Clone + Default + Send + 'static>(
     workflow: &Workflow,
@@ -102,8 +140,9 @@ fn create_proc_signal_config<R: Clone + Default + Send + 'static>(
     config: &Config,
     workflow: &Workflow,
     pid: &str,
+    clock: &ManualClock,
 ) -> (Engine, Arc<Proc>, Signal<R>) {
-    let mut builder = Builder::new();
+    let mut builder = Builder::new().clock(clock);
     builder.set_config(config);
     let engine = builder.build();
     let rt = engine.runtime();
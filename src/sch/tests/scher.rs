@@ -1,8 +1,10 @@
 use crate::{
     utils::{self, consts},
-    Act, Action, Engine, TaskState, Vars, Workflow,
+    Act, ActModule, Action, AsyncFn, Engine, TaskState, Vars, Workflow,
 };
 use serde_json::json;
+use std::{sync::Arc, time::Duration};
+use tokio::sync::{mpsc, Notify};
 
 #[tokio::test]
 async fn sch_scher_next() {
@@ -93,3 +95,67 @@ async fn sch_scher_do_action() {
 
     assert!(proc.state().is_success());
 }
+
+#[tokio::test]
+async fn sch_scher_async_host_not_blocking() {
+    #[derive(Clone)]
+    struct GateModule {
+        entered: Arc<Notify>,
+        gate: Arc<Notify>,
+    }
+    impl ActModule for GateModule {
+        fn init(&self, ctx: &rquickjs::Ctx<'_>) -> crate::Result<()> {
+            let module = self.clone();
+            let wait = AsyncFn::new(move |_| {
+                let module = module.clone();
+                async move {
+                    module.entered.notify_one();
+                    module.gate.notified().await;
+                    Ok(json!("opened"))
+                }
+            });
+            ctx.globals().set("wait_gate", wait)?;
+            Ok(())
+        }
+    }
+
+    let engine = Engine::new();
+    let module = GateModule {
+        entered: Arc::new(Notify::new()),
+        gate: Arc::new(Notify::new()),
+    };
+    engine.extender().register_module(&module);
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    engine.channel().on_complete(move |e| {
+        tx.send(e.pid.clone()).unwrap();
+    });
+
+    let rt = engine.runtime();
+    let workflow1 = Workflow::new().with_step(|step| {
+        step.with_id("step1").with_run(
+            r#"let ret = await wait_gate(); if (ret !== "opened") throw new Error("err1");"#,
+        )
+    });
+    let proc1 = rt.create_proc("async_host_proc1", &workflow1);
+    rt.launch(&proc1);
+    module.entered.notified().await;
+
+    // the second proc runs to the end when the first one is waiting for the host function
+    let workflow2 = Workflow::new().with_step(|step| step.with_id("step1").with_run("let a = 1;"));
+    let proc2 = rt.create_proc("async_host_proc2", &workflow2);
+    rt.launch(&proc2);
+    let pid = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(pid, "async_host_proc2");
+    assert!(proc1.state().is_running());
+
+    module.gate.notify_one();
+    let pid = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(pid, "async_host_proc1");
+    assert!(proc1.state().is_success());
+}
@@ -1,7 +1,7 @@
 use crate::{
     sch::tests::create_proc_signal2,
     utils::{self, consts},
-    Act, Event, Message, Signal, StmtBuild, Workflow,
+    Act, Event, Limits, Message, Signal, StmtBuild, Workflow,
 };
 use serde_json::json;
 
@@ -314,3 +314,38 @@ async fn run_test_dep<T: Clone + Send + 'static + Default>(
     engine.runtime().launch(&proc);
     tx.recv().await
 }
+
+#[tokio::test]
+async fn sch_step_run_timeout_limit() {
+    let workflow = Workflow::new()
+        .with_limits(Limits::new().with_timeout(100))
+        .with_step(|step| step.with_id("step1").with_run("while(true) {}"));
+    let ret = run_test(&workflow, |e, s| {
+        if e.is_key("step1") && e.is_state("error") {
+            s.send(e.inputs.get::<String>(consts::ACT_ERR_CODE).unwrap());
+        }
+    })
+    .await;
+    assert_eq!(ret, consts::ACT_LIMIT_ECODE);
+}
+
+#[tokio::test]
+async fn sch_step_run_timeout_limit_catch() {
+    let workflow = Workflow::new()
+        .with_limits(Limits::new().with_timeout(100))
+        .with_step(|step| {
+            step.with_id("step1")
+                .with_run("while(true) {}")
+                .with_catch(|c| {
+                    c.with_on(consts::ACT_LIMIT_ECODE)
+                        .with_then(|stmts| stmts.add(Act::msg(|act| act.with_key("msg1"))))
+                })
+        });
+    let ret = run_test(&workflow, |e, s| {
+        if e.is_key("msg1") {
+            s.send(true);
+        }
+    })
+    .await;
+    assert!(ret);
+}
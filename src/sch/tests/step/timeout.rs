@@ -1,4 +1,8 @@
-use crate::{sch::tests::create_proc_signal, utils, Act, Message, StmtBuild, Workflow};
+use crate::{
+    sch::tests::create_proc_signal_with_clock, utils, Act, ManualClock, Message, StmtBuild,
+    Workflow,
+};
+use std::time::Duration;
 
 #[tokio::test]
 async fn sch_step_timeout_one() {
@@ -11,9 +15,13 @@ async fn sch_step_timeout_one() {
             .with_act(Act::irq(|act| act.with_key("act1")))
     });
     workflow.print();
+    let clock = ManualClock::new();
     let (proc, scher, emitter, tx, rx) =
-        create_proc_signal::<bool>(&mut workflow, &utils::longid());
+        create_proc_signal_with_clock::<bool>(&mut workflow, &utils::longid(), &clock);
     emitter.on_message(move |e| {
+        if e.is_key("act1") && e.is_state("created") {
+            clock.advance(Duration::from_secs(16));
+        }
         if e.is_key("msg1") {
             rx.send(true);
         }
@@ -40,9 +48,13 @@ async fn sch_step_timeout_many() {
             .with_act(Act::irq(|act| act.with_key("act1")))
     });
     workflow.print();
+    let clock = ManualClock::new();
     let (proc, scher, emitter, tx, rx) =
-        create_proc_signal::<Vec<Message>>(&mut workflow, &utils::longid());
+        create_proc_signal_with_clock::<Vec<Message>>(&mut workflow, &utils::longid(), &clock);
     emitter.on_message(move |e| {
+        if e.is_key("act1") && e.is_state("created") {
+            clock.advance(Duration::from_secs(16));
+        }
         if e.is_key("msg1") {
             rx.update(|data| data.push(e.inner().clone()));
         }
@@ -316,3 +316,269 @@ async fn sch_tree_node_act2_ser_de() {
     // not care the parent for act when resume data from string
     assert!(act.parent().is_none());
 }
+
+#[tokio::test]
+async fn sch_tree_graph_steps() {
+    let mut workflow = Workflow::new()
+        .with_id("w1")
+        .with_step(|step| step.with_id("step1"))
+        .with_step(|step| step.with_id("step2"));
+    let tree = NodeTree::build(&mut workflow).unwrap();
+    let graph = tree.graph();
+
+    assert_eq!(graph.id, "w1");
+    assert_eq!(graph.nodes.len(), 3);
+    assert!(graph
+        .edges
+        .iter()
+        .any(|e| e.from == "w1" && e.to == "step1" && e.kind == "flow"));
+    assert!(graph
+        .edges
+        .iter()
+        .any(|e| e.from == "step1" && e.to == "step2" && e.kind == "flow"));
+}
+
+#[tokio::test]
+async fn sch_tree_graph_branches() {
+    let text = r#"
+    id: m1
+    steps:
+      - id: step1
+        branches:
+          - id: b1
+            if: ${ v > 0 }
+            steps:
+              - id: step11
+          - id: b2
+            else: true
+            steps:
+              - id: step21
+      - id: step2
+    "#;
+    let mut workflow = Workflow::from_yml(text).unwrap();
+    let tree = NodeTree::build(&mut workflow).unwrap();
+    let graph = tree.graph();
+
+    let b1 = graph.edges.iter().find(|e| e.to == "b1").unwrap();
+    assert_eq!(b1.kind, "branch");
+    assert_eq!(b1.label, "${ v > 0 }");
+    let b2 = graph.edges.iter().find(|e| e.to == "b2").unwrap();
+    assert_eq!(b2.label, "else");
+    assert!(graph
+        .edges
+        .iter()
+        .any(|e| e.from == "b1" && e.to == "step11"));
+}
+
+#[tokio::test]
+async fn sch_tree_graph_next_jump() {
+    let text = r#"
+    id: m1
+    steps:
+      - id: step1
+        next: step3
+      - id: step2
+      - id: step3
+    "#;
+    let mut workflow = Workflow::from_yml(text).unwrap();
+    let tree = NodeTree::build(&mut workflow).unwrap();
+    let graph = tree.graph();
+
+    assert!(graph
+        .edges
+        .iter()
+        .any(|e| e.from == "step1" && e.to == "step3" && e.kind == "next"));
+}
+
+#[tokio::test]
+async fn sch_tree_graph_acts_catches_timeout() {
+    let text = r#"
+    id: m1
+    steps:
+      - id: step1
+        acts:
+          - act: irq
+            id: act1
+            key: act1
+        catches:
+          - on: err1
+            then:
+              - act: irq
+                key: fix
+        timeout:
+          - on: 2h
+            then:
+              - act: msg
+                key: notify
+    "#;
+    let mut workflow = Workflow::from_yml(text).unwrap();
+    let tree = NodeTree::build(&mut workflow).unwrap();
+    let graph = tree.graph();
+
+    let act = graph.nodes.iter().find(|n| n.id == "act1").unwrap();
+    assert_eq!(act.kind, "act");
+    assert_eq!(act.label, "irq: act1");
+    let catch = graph.nodes.iter().find(|n| n.kind == "catch").unwrap();
+    assert_eq!(catch.label, "catch: err1 => irq fix");
+    let timeout = graph.nodes.iter().find(|n| n.kind == "timeout").unwrap();
+    assert_eq!(timeout.label, "timeout: 2h => msg notify");
+}
+
+#[tokio::test]
+async fn sch_tree_graph_nested_acts() {
+    let text = r#"
+    id: m1
+    setup:
+      - act: set
+        key: init
+    steps:
+      - id: step1
+        setup:
+          - act: msg
+            key: prepare
+        acts:
+          - act: if
+            id: act1
+            on: $("a") > 0
+            then:
+              - act: irq
+                key: yes
+            else:
+              - act: irq
+                key: no
+                next:
+                  act: msg
+                  key: done
+    "#;
+    let mut workflow = Workflow::from_yml(text).unwrap();
+    let tree = NodeTree::build(&mut workflow).unwrap();
+    let graph = tree.graph();
+
+    let has_edge = |from: &str, to: &str, label: &str| {
+        graph
+            .edges
+            .iter()
+            .any(|e| e.from == from && e.to == to && e.kind == "act" && e.label == label)
+    };
+    assert!(has_edge("m1", "m1.setup[0]", "setup"));
+    assert!(has_edge("step1", "step1.setup[0]", "setup"));
+    assert!(has_edge("act1", "act1.then[0]", "then"));
+    assert!(has_edge("act1", "act1.else[0]", "else"));
+    assert!(has_edge("act1.else[0]", "act1.else[0].next", "next"));
+
+    let node = graph.nodes.iter().find(|n| n.id == "act1.then[0]").unwrap();
+    assert_eq!(node.kind, "act");
+    assert_eq!(node.label, "irq: yes");
+    let node = graph.nodes.iter().find(|n| n.id == "m1.setup[0]").unwrap();
+    assert_eq!(node.label, "set: init");
+}
+
+#[tokio::test]
+async fn sch_tree_graph_workflow_error() {
+    let workflow = Workflow::new()
+        .with_id("m1")
+        .with_step(|step| step.with_id("step1").with_next("not_exists"));
+    assert!(workflow.graph().is_err());
+
+    let workflow = Workflow::new()
+        .with_id("m1")
+        .with_step(|step| step.with_id("step1"));
+    assert_eq!(workflow.graph().unwrap().nodes.len(), 2);
+}
+
+#[tokio::test]
+async fn sch_tree_graph_render_mermaid() {
+    let mut workflow = Workflow::new()
+        .with_id("w1")
+        .with_step(|step| step.with_id("step1").with_name("say \"hi\""));
+    let tree = NodeTree::build(&mut workflow).unwrap();
+    let text = tree.graph().render("mermaid").unwrap();
+
+    assert!(text.starts_with("flowchart TD"));
+    assert!(text.contains("n1[\"say #quot;hi#quot;\"]"));
+    assert!(text.contains("n0 --> n1"));
+}
+
+#[tokio::test]
+async fn sch_tree_graph_render_dot() {
+    let mut workflow = Workflow::new()
+        .with_id("w1")
+        .with_step(|step| step.with_id("step1"));
+    let tree = NodeTree::build(&mut workflow).unwrap();
+    let text = tree.graph().render("dot").unwrap();
+
+    assert!(text.starts_with("digraph \"w1\" {"));
+    assert!(text.contains("\"w1\" -> \"step1\";"));
+    assert!(text.ends_with('}'));
+}
+
+#[tokio::test]
+async fn sch_tree_graph_render_json() {
+    let mut workflow = Workflow::new()
+        .with_id("w1")
+        .with_step(|step| step.with_id("step1"));
+    let tree = NodeTree::build(&mut workflow).unwrap();
+    let text = tree.graph().render("json-graph").unwrap();
+
+    let value: serde_json::Value = serde_json::from_str(&text).unwrap();
+    assert_eq!(value["nodes"].as_array().unwrap().len(), 2);
+    assert_eq!(value["edges"][0]["to"], "step1");
+}
+
+#[tokio::test]
+async fn sch_tree_graph_render_unknown() {
+    let mut workflow = Workflow::new()
+        .with_id("w1")
+        .with_step(|step| step.with_id("step1"));
+    let tree = NodeTree::build(&mut workflow).unwrap();
+    assert!(tree.graph().render("svg").is_err());
+}
+
+#[tokio::test]
+async fn sch_tree_build_stable_ids() {
+    let workflow = Workflow::new().with_id("m1").with_step(|step| {
+        step.with_name("step1")
+            .with_act(Act::irq(|act| act.with_key("act1")))
+    });
+
+    let tree1 = NodeTree::build(&mut workflow.clone()).unwrap();
+    let tree2 = NodeTree::build(&mut workflow.clone()).unwrap();
+    let ids1 = tree1
+        .model
+        .steps
+        .iter()
+        .map(|s| s.id.clone())
+        .collect::<Vec<_>>();
+    let ids2 = tree2
+        .model
+        .steps
+        .iter()
+        .map(|s| s.id.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(ids1, ids2);
+    assert_eq!(
+        tree1.model.steps[0].acts[0].id,
+        tree2.model.steps[0].acts[0].id
+    );
+}
+
+#[tokio::test]
+async fn sch_tree_node_load_ref() {
+    let mut workflow = Workflow::from_yml(SIMPLE_WORKFLOW).unwrap();
+    let tr = NodeTree::build(&mut workflow).unwrap();
+
+    let step1 = tr.node("step1").unwrap();
+    let node = Node::load(&step1.to_ref(), &tr).unwrap();
+    assert!(Arc::ptr_eq(&node, &step1));
+
+    let node = Node::load(&step1.to_string(), &tr).unwrap();
+    assert!(Arc::ptr_eq(&node, &step1));
+}
+
+#[tokio::test]
+async fn sch_tree_node_load_ref_not_found() {
+    let mut workflow = Workflow::from_yml(SIMPLE_WORKFLOW).unwrap();
+    let tr = NodeTree::build(&mut workflow).unwrap();
+    let ret = Node::load(r#"{"id":"not_exists"}"#, &tr);
+    assert!(ret.is_err());
+}
@@ -606,3 +606,155 @@ async fn sch_vars_override_step_vars() {
         json!(10)
     );
 }
+
+#[tokio::test]
+async fn sch_vars_template_inputs() {
+    let mut workflow = Workflow::new()
+        .with_input("id", json!(100))
+        .with_input("uid", json!("u1"))
+        .with_step(|step| {
+            step.with_id("step1").with_input(
+                "text",
+                r#"Order ${ $("id") } approved by ${ $("uid") }"#.into(),
+            )
+        });
+    let (proc, scher, _, tx, _) = create_proc_signal::<()>(&mut workflow, &utils::longid());
+    scher.launch(&proc);
+    tx.recv().await;
+    assert_eq!(
+        proc.task_by_nid("step1")
+            .first()
+            .unwrap()
+            .inputs()
+            .get::<String>("text")
+            .unwrap(),
+        "Order 100 approved by u1"
+    );
+}
+
+#[tokio::test]
+async fn sch_vars_template_nested() {
+    let mut workflow = Workflow::new()
+        .with_input("id", json!(100))
+        .with_step(|step| {
+            step.with_id("step1").with_input(
+                "order",
+                json!({
+                    "id": r#"${ $("id") }"#,
+                    "tags": [r#"id-${ $("id") }"#, "plain"],
+                    "items": [{ "count": r#"${ $("id") + 1 }"# }]
+                }),
+            )
+        });
+    let (proc, scher, _, tx, _) = create_proc_signal::<()>(&mut workflow, &utils::longid());
+    scher.launch(&proc);
+    tx.recv().await;
+    assert_eq!(
+        proc.task_by_nid("step1")
+            .first()
+            .unwrap()
+            .inputs()
+            .get_value("order")
+            .unwrap(),
+        &json!({
+            "id": 100,
+            "tags": ["id-100", "plain"],
+            "items": [{ "count": 101 }]
+        })
+    );
+}
+
+#[tokio::test]
+async fn sch_vars_template_filters() {
+    let mut workflow = Workflow::new()
+        .with_input("name", json!("tom"))
+        .with_input("time", json!("2024-01-02T03:04:05Z"))
+        .with_input("obj", json!({ "a": 1 }))
+        .with_step(|step| {
+            step.with_id("step1")
+                .with_input("upper", r#"${ $("name") | upper }"#.into())
+                .with_input("default", r#"${ $("none") | default("n/a") }"#.into())
+                .with_input("value", r#"${ $("none") | default(10) }"#.into())
+                .with_input("date", r#"${ $("time") | date("%Y/%m/%d") }"#.into())
+                .with_input("json", r#"${ $("obj") | json }"#.into())
+                .with_input(
+                    "text",
+                    r#"${ $("name") | upper } at ${ $("time") | date("%H:%M") }: ${ $("a") || $("obj").a | default(0) }"#.into(),
+                )
+        });
+    let (proc, scher, _, tx, _) = create_proc_signal::<()>(&mut workflow, &utils::longid());
+    scher.launch(&proc);
+    tx.recv().await;
+    let inputs = proc.task_by_nid("step1").first().unwrap().inputs();
+    assert_eq!(inputs.get::<String>("upper").unwrap(), "TOM");
+    assert_eq!(inputs.get::<String>("default").unwrap(), "n/a");
+    assert_eq!(inputs.get::<i64>("value").unwrap(), 10);
+    assert_eq!(inputs.get::<String>("date").unwrap(), "2024/01/02");
+    assert_eq!(inputs.get::<String>("json").unwrap(), r#"{"a":1}"#);
+    assert_eq!(inputs.get::<String>("text").unwrap(), "TOM at 03:04: 1");
+}
+
+#[tokio::test]
+async fn sch_vars_template_filter_error() {
+    let mut workflow = Workflow::new()
+        .with_input("name", json!("tom"))
+        .with_step(|step| {
+            step.with_id("step1")
+                .with_input("date", r#"${ $("name") | date }"#.into())
+        });
+    let (proc, scher, _, tx, _) = create_proc_signal::<()>(&mut workflow, &utils::longid());
+    scher.launch(&proc);
+    tx.recv().await;
+    let inputs = proc.task_by_nid("step1").first().unwrap().inputs();
+    assert_eq!(
+        inputs.get_value("date").unwrap(),
+        &json!(r#"${ $("name") | date }"#)
+    );
+}
+
+#[tokio::test]
+async fn sch_vars_template_eval_error_keep_text() {
+    let mut workflow = Workflow::new().with_step(|step| {
+        step.with_id("step1")
+            .with_input("cmd", json!({ "run": "echo ${HOME}" }))
+    });
+    let (proc, scher, _, tx, _) = create_proc_signal::<()>(&mut workflow, &utils::longid());
+    scher.launch(&proc);
+    tx.recv().await;
+    let inputs = proc.task_by_nid("step1").first().unwrap().inputs();
+    assert_eq!(
+        inputs.get_value("cmd").unwrap(),
+        &json!({ "run": "echo ${HOME}" })
+    );
+}
+
+#[tokio::test]
+async fn sch_vars_template_escape() {
+    let mut workflow = Workflow::new()
+        .with_input("id", json!(100))
+        .with_step(|step| {
+            step.with_id("step1")
+                .with_input("a", json!("echo $${HOME}"))
+                .with_input("b", json!(r#"$${HOME}/${ $("id") }"#))
+        });
+    let (proc, scher, _, tx, _) = create_proc_signal::<()>(&mut workflow, &utils::longid());
+    scher.launch(&proc);
+    tx.recv().await;
+    let inputs = proc.task_by_nid("step1").first().unwrap().inputs();
+    assert_eq!(inputs.get::<String>("a").unwrap(), "echo ${HOME}");
+    assert_eq!(inputs.get::<String>("b").unwrap(), "${HOME}/100");
+}
+
+#[tokio::test]
+async fn sch_vars_template_outputs() {
+    let mut workflow = Workflow::new()
+        .with_input("id", json!(100))
+        .with_output("text", json!(r#"order-${ $("id") }"#));
+    let (proc, scher, emiter, tx, rx) = create_proc_signal::<Vars>(&mut workflow, &utils::longid());
+    emiter.on_complete(move |e| {
+        rx.send(e.outputs.clone());
+    });
+    scher.launch(&proc);
+    let ret = tx.recv().await;
+    assert_eq!(ret.get::<String>("text").unwrap(), "order-100");
+}
@@ -1,9 +1,10 @@
 use crate::{
-    sch::tests::create_proc_signal,
+    sch::tests::{create_proc_signal, create_proc_signal_with_clock},
     utils::{self, consts},
-    Act, Catch, Message, StmtBuild, TaskState, Timeout, Vars, Workflow,
+    Act, Catch, ManualClock, Message, StmtBuild, TaskState, Timeout, Vars, Workflow,
 };
 use serde_json::json;
+use std::time::Duration;
 
 #[tokio::test]
 async fn sch_workflow_setup_set() {
@@ -300,9 +301,14 @@ async fn sch_workflow_setup_on_timeout() {
         });
 
     workflow.print();
-    let (proc, scher, emitter, tx, rx) = create_proc_signal(&mut workflow, &utils::longid());
+    let clock = ManualClock::new();
+    let (proc, scher, emitter, tx, rx) =
+        create_proc_signal_with_clock(&mut workflow, &utils::longid(), &clock);
     emitter.on_message(move |e| {
         println!("message: {:?}", e);
+        if e.is_key("act1") && e.is_state("created") {
+            clock.advance(Duration::from_secs(16));
+        }
         if e.is_key("msg1") {
             rx.send(true);
         }
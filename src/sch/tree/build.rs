@@ -3,13 +3,14 @@ use super::{
     node_tree::NodeTree,
 };
 use crate::{
-    utils::{longid, shortid},
+    utils::{hashid, longid},
     Act, ActError, Branch, Result, Step, Workflow,
 };
 use std::sync::Arc;
 
 pub fn build_workflow(workflow: &mut Workflow, tree: &mut NodeTree) -> Result<()> {
     let level = 0;
+    workflow.expand(&[])?;
     if workflow.id.is_empty() {
         workflow.id = longid();
     }
@@ -36,7 +37,7 @@ pub fn build_step(
     level: usize,
 ) -> Result<()> {
     if step.id.is_empty() {
-        step.id = shortid();
+        step.id = node_id(tree, parent);
     }
     let data = NodeContent::Step(step.clone());
     let node = tree.make(&data.id(), data, level)?;
@@ -86,7 +87,7 @@ pub fn build_branch(
     level: usize,
 ) -> Result<()> {
     if branch.id.is_empty() {
-        branch.id = shortid();
+        branch.id = node_id(tree, parent);
     }
     let data = NodeContent::Branch(branch.clone());
     let node = tree.make(&data.id(), data, level)?;
@@ -111,7 +112,7 @@ pub fn build_act(
     level: usize,
 ) -> Result<()> {
     if act.id.is_empty() {
-        act.id = shortid();
+        act.id = node_id(tree, parent);
     }
 
     let data = NodeContent::Act(act.clone());
@@ -122,3 +123,10 @@ pub fn build_act(
 
     Ok(())
 }
+
+/// generates the node id by the parent and the node count
+/// the id is stable when the tree is rebuilt from the same model, so the stored tasks can find the node again
+fn node_id(tree: &NodeTree, parent: &Arc<Node>) -> String {
+    let count = tree.node_map.read().unwrap().len();
+    hashid(&format!("{}:{count}", parent.id()))
+}
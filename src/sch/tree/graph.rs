@@ -0,0 +1,407 @@
+use super::{Node, NodeContent, NodeTree};
+use crate::{Act, ActError, Catch, Result, TaskState, Timeout};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+/// the graph of the workflow model to render as mermaid, dot or json
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Graph {
+    pub id: String,
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct GraphNode {
+    pub id: String,
+    /// workflow, step, branch, act, catch or timeout
+    pub kind: String,
+    pub label: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct GraphEdge {
+    pub from: String,
+    pub to: String,
+    /// flow, next, branch, act, catch or timeout
+    pub kind: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub label: String,
+}
+
+impl NodeTree {
+    pub fn graph(&self) -> Graph {
+        let mut graph = Graph::default();
+        if let Some(root) = &self.root {
+            graph.id = root.id().to_string();
+            graph.push_node(root, "workflow", &label(root));
+            if let NodeContent::Workflow(workflow) = &root.content {
+                graph.push_acts(root.id(), "setup", &workflow.setup);
+            }
+            if let Some(first) = root.children().first() {
+                let mut visited = HashSet::new();
+                graph.walk_steps(first, root.id(), "", &mut visited);
+            }
+
+            // removes the jumps to the nodes which are not in the graph
+            let ids: HashSet<String> = graph.nodes.iter().map(|n| n.id.clone()).collect();
+            graph
+                .edges
+                .retain(|e| ids.contains(&e.from) && ids.contains(&e.to));
+        }
+        graph
+    }
+}
+
+impl Graph {
+    /// sets the node states by node id
+    pub(crate) fn set_states(&mut self, states: &HashMap<String, TaskState>) {
+        for node in self.nodes.iter_mut() {
+            if let Some(state) = states.get(&node.id) {
+                node.state = Some(state.to_string());
+            }
+        }
+    }
+
+    /// renders the graph by the format, which can be 'mermaid', 'dot' or 'json-graph'
+    pub fn render(&self, fmt: &str) -> Result<String> {
+        match fmt {
+            "mermaid" => Ok(self.to_mermaid()),
+            "dot" => Ok(self.to_dot()),
+            "json-graph" => serde_json::to_string_pretty(self).map_err(ActError::from),
+            _ => Err(ActError::Runtime(format!(
+                "cannot render the graph with format '{fmt}'"
+            ))),
+        }
+    }
+
+    pub fn to_mermaid(&self) -> String {
+        let ids: HashMap<&str, String> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(index, n)| (n.id.as_str(), format!("n{index}")))
+            .collect();
+
+        let mut lines = vec!["flowchart TD".to_string()];
+        for node in &self.nodes {
+            let text = node.label.replace('"', "#quot;");
+            let shape = match node.kind.as_str() {
+                "workflow" => format!("([\"{text}\"])"),
+                "branch" => format!("{{\"{text}\"}}"),
+                "act" => format!("(\"{text}\")"),
+                "catch" | "timeout" => format!("{{{{\"{text}\"}}}}"),
+                _ => format!("[\"{text}\"]"),
+            };
+            lines.push(format!("    {}{shape}", ids[node.id.as_str()]));
+        }
+
+        for edge in &self.edges {
+            let arrow = match edge.kind.as_str() {
+                "flow" | "branch" => "-->",
+                _ => "-.->",
+            };
+            let label = if edge.label.is_empty() {
+                String::new()
+            } else {
+                format!("|\"{}\"|", edge.label.replace('"', "#quot;"))
+            };
+            lines.push(format!(
+                "    {} {arrow}{label} {}",
+                ids[edge.from.as_str()],
+                ids[edge.to.as_str()]
+            ));
+        }
+
+        let mut classes: Vec<(&str, Vec<&str>)> = Vec::new();
+        for node in &self.nodes {
+            if let Some(state) = &node.state {
+                let class = state_class(state);
+                match classes.iter_mut().find(|(c, _)| *c == class) {
+                    Some((_, ids_of_class)) => ids_of_class.push(&ids[node.id.as_str()]),
+                    None => classes.push((class, vec![&ids[node.id.as_str()]])),
+                }
+            }
+        }
+        for (class, nodes) in &classes {
+            let (fill, stroke) = state_color(class);
+            lines.push(format!("    classDef {class} fill:{fill},stroke:{stroke}"));
+            lines.push(format!("    class {} {class}", nodes.join(",")));
+        }
+
+        lines.join("\n")
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut lines = vec![format!("digraph \"{}\" {{", escape(&self.id))];
+        lines.push("    rankdir=TB;".to_string());
+        for node in &self.nodes {
+            let shape = match node.kind.as_str() {
+                "workflow" => "oval",
+                "branch" => "diamond",
+                "act" => "box, style=rounded",
+                "catch" | "timeout" => "hexagon",
+                _ => "box",
+            };
+            let mut attrs = format!("label=\"{}\", shape={shape}", escape(&node.label));
+            if let Some(state) = &node.state {
+                let (fill, stroke) = state_color(state_class(state));
+                let style = if node.kind == "act" {
+                    "\"rounded,filled\""
+                } else {
+                    "filled"
+                };
+                attrs.push_str(&format!(
+                    ", style={style}, fillcolor=\"{fill}\", color=\"{stroke}\""
+                ));
+            }
+            lines.push(format!("    \"{}\" [{attrs}];", escape(&node.id)));
+        }
+
+        for edge in &self.edges {
+            let mut attrs = Vec::new();
+            if !edge.label.is_empty() {
+                attrs.push(format!("label=\"{}\"", escape(&edge.label)));
+            }
+            match edge.kind.as_str() {
+                "flow" | "branch" => {}
+                "next" => attrs.push("style=dashed".to_string()),
+                _ => attrs.push("style=dotted".to_string()),
+            }
+            let attrs = if attrs.is_empty() {
+                String::new()
+            } else {
+                format!(" [{}]", attrs.join(", "))
+            };
+            lines.push(format!(
+                "    \"{}\" -> \"{}\"{attrs};",
+                escape(&edge.from),
+                escape(&edge.to)
+            ));
+        }
+        lines.push("}".to_string());
+
+        lines.join("\n")
+    }
+
+    fn push_node(&mut self, node: &Node, kind: &str, label: &str) {
+        self.nodes.push(GraphNode {
+            id: node.id().to_string(),
+            kind: kind.to_string(),
+            label: label.to_string(),
+            state: None,
+        });
+    }
+
+    fn push_edge(&mut self, from: &str, to: &str, kind: &str, label: &str) {
+        if self.edges.iter().any(|e| e.from == from && e.to == to) {
+            return;
+        }
+        self.edges.push(GraphEdge {
+            from: from.to_string(),
+            to: to.to_string(),
+            kind: kind.to_string(),
+            label: label.to_string(),
+        });
+    }
+
+    /// walks the step list from the first step by the next link
+    fn walk_steps(
+        &mut self,
+        first: &Arc<Node>,
+        from: &str,
+        label: &str,
+        visited: &mut HashSet<String>,
+    ) {
+        let mut prev = from.to_string();
+        let mut label = label.to_string();
+        let mut current = Some(first.clone());
+        while let Some(node) = current {
+            self.push_edge(&prev, node.id(), "flow", &label);
+            if !visited.insert(node.id().to_string()) {
+                break;
+            }
+            self.visit_step(&node, visited);
+
+            prev = node.id().to_string();
+            label = String::new();
+            current = node.next().upgrade();
+        }
+    }
+
+    fn visit_step(&mut self, node: &Arc<Node>, visited: &mut HashSet<String>) {
+        self.push_node(node, "step", &label(node));
+        if let NodeContent::Step(step) = &node.content {
+            if let Some(next) = &step.next {
+                self.push_edge(node.id(), next, "next", "next");
+            }
+        }
+
+        for child in node.children() {
+            match &child.content {
+                NodeContent::Branch(branch) => {
+                    self.push_node(&child, "branch", &label(&child));
+                    let cond = match &branch.r#if {
+                        Some(expr) => expr.clone(),
+                        None if branch.r#else => "else".to_string(),
+                        None if !branch.needs.is_empty() => {
+                            format!("needs: {}", branch.needs.join(", "))
+                        }
+                        None => String::new(),
+                    };
+                    self.push_edge(node.id(), child.id(), "branch", &cond);
+                    if let Some(first) = child.children().first() {
+                        self.walk_steps(first, child.id(), "", visited);
+                    }
+                    if let Some(next) = &branch.next {
+                        self.push_edge(child.id(), next, "next", "next");
+                    }
+                }
+                NodeContent::Act(act) => {
+                    self.push_node(&child, "act", &label(&child));
+                    self.push_edge(node.id(), child.id(), "act", "");
+                    self.visit_act(child.id(), act);
+                }
+                _ => {}
+            }
+        }
+
+        if let NodeContent::Step(step) = &node.content {
+            self.push_acts(node.id(), "setup", &step.setup);
+            self.push_hooks(node.id(), &step.catches, &step.timeout);
+        }
+    }
+
+    /// pushes the nested acts and the hooks of the act
+    fn visit_act(&mut self, id: &str, act: &Act) {
+        self.push_acts(id, "setup", &act.setup);
+        self.push_acts(id, "then", &act.then);
+        self.push_acts(id, "else", &act.r#else);
+        if let Some(next) = &act.next {
+            self.push_act(id, &format!("{id}.next"), "next", next);
+        }
+        self.push_hooks(id, &act.catches, &act.timeout);
+    }
+
+    /// pushes the acts under `setup`, `then` or `else`, which are created when running,
+    /// so the node id is the path of the act, such as `act1.then[0]`
+    fn push_acts(&mut self, from: &str, name: &str, acts: &[Act]) {
+        for (index, act) in acts.iter().enumerate() {
+            self.push_act(from, &format!("{from}.{name}[{index}]"), name, act);
+        }
+    }
+
+    fn push_act(&mut self, from: &str, id: &str, name: &str, act: &Act) {
+        self.nodes.push(GraphNode {
+            id: id.to_string(),
+            kind: "act".to_string(),
+            label: act_label(act, &act.name),
+            state: None,
+        });
+        self.push_edge(from, id, "act", name);
+        self.visit_act(id, act);
+    }
+
+    fn push_hooks(&mut self, id: &str, catches: &[Catch], timeout: &[Timeout]) {
+        for (index, catch) in catches.iter().enumerate() {
+            let on = catch.on.clone().unwrap_or("*".to_string());
+            self.push_hook(
+                id,
+                &format!("{id}.catches[{index}]"),
+                "catch",
+                &on,
+                &catch.then,
+            );
+        }
+        for (index, t) in timeout.iter().enumerate() {
+            let on = t.on.to_string();
+            self.push_hook(
+                id,
+                &format!("{id}.timeout[{index}]"),
+                "timeout",
+                &on,
+                &t.then,
+            );
+        }
+    }
+
+    fn push_hook(&mut self, from: &str, id: &str, kind: &str, on: &str, then: &[Act]) {
+        let mut label = format!("{kind}: {on}");
+        let acts = then
+            .iter()
+            .map(|act| {
+                if act.key.is_empty() {
+                    act.act.clone()
+                } else {
+                    format!("{} {}", act.act, act.key)
+                }
+            })
+            .collect::<Vec<_>>();
+        if !acts.is_empty() {
+            label.push_str(&format!(" => {}", acts.join(", ")));
+        }
+        self.nodes.push(GraphNode {
+            id: id.to_string(),
+            kind: kind.to_string(),
+            label,
+            state: None,
+        });
+        self.push_edge(from, id, kind, on);
+    }
+}
+
+fn label(node: &Node) -> String {
+    let name = node.name();
+    let text = if name.is_empty() {
+        node.id().to_string()
+    } else {
+        name
+    };
+    if let NodeContent::Act(act) = &node.content {
+        return act_label(act, &text);
+    }
+    text
+}
+
+fn act_label(act: &Act, text: &str) -> String {
+    if !act.key.is_empty() {
+        return format!("{}: {}", act.act, act.key);
+    }
+    if text.is_empty() {
+        return act.act.clone();
+    }
+    format!("{}: {text}", act.act)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn state_class(state: &str) -> &'static str {
+    match TaskState::from(state) {
+        TaskState::Running | TaskState::Interrupt | TaskState::Pending | TaskState::Ready => {
+            "running"
+        }
+        TaskState::Completed | TaskState::Submitted => "completed",
+        TaskState::Error | TaskState::Aborted => "error",
+        TaskState::Skipped | TaskState::Cancelled | TaskState::Backed | TaskState::Removed => {
+            "skipped"
+        }
+        TaskState::None => "none",
+    }
+}
+
+fn state_color(class: &str) -> (&'static str, &'static str) {
+    match class {
+        "running" => ("#fff3cd", "#f0ad4e"),
+        "completed" => ("#d4edda", "#28a745"),
+        "error" => ("#f8d7da", "#dc3545"),
+        "skipped" => ("#e2e3e5", "#6c757d"),
+        _ => ("#ffffff", "#333333"),
+    }
+}
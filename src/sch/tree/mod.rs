@@ -1,9 +1,11 @@
 mod build;
+mod graph;
 mod node;
 mod node_tree;
 mod task_tree;
 mod visit;
 
-pub use node::{Node, NodeContent, NodeData, NodeKind};
+pub use graph::{Graph, GraphEdge, GraphNode};
+pub use node::{Node, NodeContent, NodeData, NodeKind, NodeRef};
 pub use node_tree::NodeTree;
 pub use task_tree::TaskTree;
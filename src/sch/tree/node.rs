@@ -1,4 +1,4 @@
-use crate::{Act, Branch, Step, Vars, Workflow};
+use crate::{Act, ActError, Branch, Result, Step, Vars, Workflow};
 use serde::{Deserialize, Serialize};
 use std::sync::{Arc, RwLock, Weak};
 
@@ -43,6 +43,17 @@ pub struct NodeData {
     // pub next: Option<String>,
 }
 
+/// the node reference which is stored in the task
+/// the node content is resolved from the node tree of the model version when loading
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NodeRef {
+    pub id: String,
+    #[serde(default)]
+    pub key: String,
+    #[serde(default)]
+    pub tag: String,
+}
+
 impl NodeContent {
     pub fn id(&self) -> String {
         match self {
@@ -221,6 +232,30 @@ impl Node {
         serde_json::to_string(&data).unwrap()
     }
 
+    /// gets the node reference of the node
+    pub fn to_ref(&self) -> String {
+        let data = NodeRef {
+            id: self.id.clone(),
+            key: self.content.key(),
+            tag: self.content.tag(),
+        };
+        serde_json::to_string(&data).unwrap()
+    }
+
+    /// loads the node by the full node data or the node reference
+    pub fn load(s: &str, tree: &node_tree::NodeTree) -> Result<Arc<Self>> {
+        if serde_json::from_str::<NodeData>(s).is_ok() {
+            return Ok(Self::from_str(s, tree));
+        }
+
+        let data: NodeRef =
+            serde_json::from_str(s).map_err(|err| ActError::Store(err.to_string()))?;
+        tree.node(&data.id).ok_or(ActError::Store(format!(
+            "cannot find node '{}' in model '{}'",
+            data.id, tree.model.id
+        )))
+    }
+
     pub fn from_str(s: &str, tree: &node_tree::NodeTree) -> Arc<Self> {
         let data: NodeData = serde_json::from_str(s).unwrap();
         let ret = Arc::new(Self::new(&data.id, data.content, data.level));
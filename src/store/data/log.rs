@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+
+/// the captured script logs of a task, the id is formatted as `{pid}:{tid}`
+#[derive(Default, Deserialize, Serialize, Debug, Clone)]
+pub struct Log {
+    pub id: String,
+    pub pid: String,
+    pub tid: String,
+    pub nid: String,
+    /// the json array of the log entries, which is bounded by `script_log_limit`
+    pub data: String,
+    pub timestamp: i64,
+}
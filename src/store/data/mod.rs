@@ -1,4 +1,5 @@
 mod data;
+mod log;
 mod message;
 mod model;
 mod package;
@@ -6,6 +7,7 @@ mod proc;
 mod task;
 
 pub use data::Data;
+pub use log::Log;
 pub use message::{Message, MessageStatus};
 pub use model::Model;
 pub use package::Package;
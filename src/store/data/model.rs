@@ -10,4 +10,7 @@ pub struct Model {
     pub update_time: i64,
     pub data: String,
     pub timestamp: i64,
+    /// the compiled bytecode of the model scripts, which is encoded by `env::encode_codes`
+    #[serde(default, with = "hex")]
+    pub code: Vec<u8>,
 }
@@ -1,3 +1,4 @@
+use crate::{Limits, Manifest};
 use serde::{Deserialize, Serialize};
 
 #[derive(Default, Deserialize, Serialize, Debug, Clone)]
@@ -10,4 +11,16 @@ pub struct Package {
     pub create_time: i64,
     pub update_time: i64,
     pub timestamp: i64,
+    /// the sandbox limits which override the config and model limits
+    #[serde(default)]
+    pub limits: Limits,
+    /// the manifest with the version, dependencies and the inputs and outputs schema
+    #[serde(default)]
+    pub manifest: Manifest,
+    /// the package id, which is used to query the published versions in history
+    #[serde(default)]
+    pub pack_id: String,
+    /// the compiled bytecode of the package script, it is empty for the engines without bytecode
+    #[serde(default, with = "hex")]
+    pub code: Vec<u8>,
 }
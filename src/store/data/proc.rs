@@ -9,7 +9,15 @@ pub struct Proc {
     pub start_time: i64,
     pub end_time: i64,
     pub timestamp: i64,
+    /// the full model copy, it is empty when the proc references a deployed model version
     pub model: String,
+    /// the deployed model version, 0 means the model is not deployed
+    pub ver: u32,
+    /// the model inputs of the proc
+    pub inputs: String,
     pub env_local: String,
     pub err: Option<String>,
+    /// the parent proc id which starts the proc by the `call` act, it is empty for the root proc
+    #[serde(default)]
+    pub parent_id: String,
 }
@@ -18,13 +18,13 @@ impl<T> Collect<T>
 where
     T: DbSchema,
 {
-    pub fn new(db: &ShareLock<Database>, name: &str) -> Self {
-        db.write().unwrap().init(name, &T::schema().unwrap());
-        Self {
+    pub fn new(db: &ShareLock<Database>, name: &str) -> Result<Self> {
+        db.write().unwrap().init(name, &T::schema()?)?;
+        Ok(Self {
             db: db.clone(),
             name: name.to_string(),
             _t: PhantomData::default(),
-        }
+        })
     }
 }
 
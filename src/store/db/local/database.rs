@@ -1,7 +1,13 @@
-use crate::store::db::local::{DbColumn, DbType};
+use crate::{
+    store::{
+        db::local::{DbColumn, DbType},
+        map_db_err,
+    },
+    ActError, Result,
+};
 #[allow(unused_imports)]
 use r2d2_sqlite::SqliteConnectionManager;
-use rusqlite::params;
+use rusqlite::{params, Connection};
 #[allow(unused_imports)]
 use std::{fs, path::Path};
 use tracing::debug;
@@ -51,8 +57,8 @@ impl Database {
         &self.pool
     }
 
-    pub fn init(&mut self, name: &str, schema: &Vec<(String, DbColumn)>) {
-        let mut conn = self.pool().get().unwrap();
+    pub fn init(&mut self, name: &str, schema: &Vec<(String, DbColumn)>) -> Result<()> {
+        let mut conn = self.pool().get().map_err(map_db_err)?;
         let mut sql = String::new();
         sql.push_str(&format!("create table IF NOT EXISTS {} ", name));
         sql.push_str("(");
@@ -60,20 +66,7 @@ impl Database {
         let len = schema.len();
         let mut idx_sqls = Vec::new();
         for (index, (key, col)) in schema.iter().enumerate() {
-            sql.push_str(&format!(
-                "{key} {}",
-                match col.db_type {
-                    DbType::Boolean => "BOOLEAN".to_string(),
-                    DbType::Double => "DOUBLE".to_string(),
-                    DbType::Decimal(width, scale) => format!("DECIMAL({width},{scale})"),
-                    DbType::Int8 => "TINYINT".to_string(),
-                    DbType::Int16 => "SMALLINT".to_string(),
-                    DbType::Int32 => "INTEGER".to_string(),
-                    DbType::Int64 => "BIGINT".to_string(),
-                    DbType::Text => "VARCHAR".to_string(),
-                    DbType::Binary => "BLOB".to_string(),
-                }
-            ));
+            sql.push_str(&format!("{key} {}", col_type(&col.db_type)));
 
             if col.is_not_null {
                 sql.push_str(" NOT NULL ");
@@ -109,16 +102,63 @@ impl Database {
         }
         sql.push_str(");");
         debug!("sql={}", sql);
-        let tr = conn.transaction().unwrap();
+        let tr = conn.transaction().map_err(map_db_err)?;
         if let Ok(affect_count) = tr.execute(&sql, params![]) {
             if affect_count > 0 {
                 for idx_sql in idx_sqls {
-                    tr.execute(&idx_sql, params![]).unwrap();
+                    tr.execute(&idx_sql, params![]).map_err(map_db_err)?;
+                }
+            }
+        }
+
+        tr.commit().map_err(map_db_err)?;
+
+        Self::migrate(&conn, name, schema)
+    }
+
+    /// adds the missing columns to the table which is created by the previous version
+    /// the new columns should have the default value to fill the existing rows
+    fn migrate(conn: &Connection, name: &str, schema: &[(String, DbColumn)]) -> Result<()> {
+        let columns = Self::columns(conn, name)?;
+        for (key, col) in schema.iter() {
+            if columns.contains(key) {
+                continue;
+            }
+            let mut sql = format!(
+                "ALTER TABLE {name} ADD COLUMN {key} {}",
+                col_type(&col.db_type)
+            );
+            if let Some(default) = &col.default {
+                if col.is_not_null {
+                    sql.push_str(" NOT NULL");
+                }
+                sql.push_str(" DEFAULT ");
+                sql.push_str(default);
+            }
+            debug!("sql={}", sql);
+            if let Err(err) = conn.execute(&sql, params![]) {
+                // the column can be added by the other process after checking
+                if !Self::columns(conn, name)?.contains(key) {
+                    return Err(ActError::Store(format!(
+                        "failed to add column '{key}' to table '{name}': {err}"
+                    )));
                 }
             }
         }
+        Ok(())
+    }
 
-        tr.commit().unwrap();
+    /// the column names of the table
+    fn columns(conn: &Connection, name: &str) -> Result<Vec<String>> {
+        let mut stmt = conn
+            .prepare(&format!("PRAGMA table_info({name})"))
+            .map_err(map_db_err)?;
+        let columns = stmt
+            .query_map(params![], |row| row.get::<usize, String>(1))
+            .map_err(map_db_err)?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(map_db_err)?;
+        Ok(columns)
     }
 
     pub fn close(&self) {
@@ -127,3 +167,17 @@ impl Database {
         }
     }
 }
+
+fn col_type(db_type: &DbType) -> String {
+    match db_type {
+        DbType::Boolean => "BOOLEAN".to_string(),
+        DbType::Double => "DOUBLE".to_string(),
+        DbType::Decimal(width, scale) => format!("DECIMAL({width},{scale})"),
+        DbType::Int8 => "TINYINT".to_string(),
+        DbType::Int16 => "SMALLINT".to_string(),
+        DbType::Int32 => "INTEGER".to_string(),
+        DbType::Int64 => "BIGINT".to_string(),
+        DbType::Text => "VARCHAR".to_string(),
+        DbType::Binary => "BLOB".to_string(),
+    }
+}
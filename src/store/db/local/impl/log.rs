@@ -0,0 +1,90 @@
+use crate::{
+    store::{
+        db::local::{DbColumn, DbRow, DbSchema, DbType},
+        Log,
+    },
+    Result,
+};
+use rusqlite::{types::Value, Error as DbError, Result as DbResult, Row};
+
+impl DbSchema for Log {
+    fn schema() -> Result<Vec<(String, DbColumn)>> {
+        let mut map = Vec::new();
+        map.push((
+            "id".to_string(),
+            DbColumn {
+                db_type: DbType::Text,
+                is_not_null: true,
+                is_primary_key: true,
+                ..Default::default()
+            },
+        ));
+        map.push((
+            "pid".to_string(),
+            DbColumn {
+                db_type: DbType::Text,
+                is_index: true,
+                ..Default::default()
+            },
+        ));
+        map.push((
+            "tid".to_string(),
+            DbColumn {
+                db_type: DbType::Text,
+                is_not_null: true,
+                ..Default::default()
+            },
+        ));
+        map.push((
+            "nid".to_string(),
+            DbColumn {
+                db_type: DbType::Text,
+                ..Default::default()
+            },
+        ));
+        map.push((
+            "data".to_string(),
+            DbColumn {
+                db_type: DbType::Text,
+                ..Default::default()
+            },
+        ));
+        map.push((
+            "timestamp".to_string(),
+            DbColumn {
+                db_type: DbType::Int64,
+                ..Default::default()
+            },
+        ));
+        Ok(map)
+    }
+}
+
+impl DbRow for Log {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn from_row<'a>(row: &Row<'a>) -> DbResult<Log, DbError> {
+        Ok(Log {
+            id: row.get::<usize, String>(0).unwrap(),
+            pid: row.get::<usize, String>(1).unwrap(),
+            tid: row.get::<usize, String>(2).unwrap(),
+            nid: row.get::<usize, String>(3).unwrap(),
+            data: row.get::<usize, String>(4).unwrap(),
+            timestamp: row.get::<usize, i64>(5).unwrap(),
+        })
+    }
+
+    fn to_values(&self) -> Result<Vec<(String, Value)>> {
+        let mut ret = Vec::new();
+
+        ret.push(("id".to_string(), Value::Text(self.id.clone())));
+        ret.push(("pid".to_string(), Value::Text(self.pid.clone())));
+        ret.push(("tid".to_string(), Value::Text(self.tid.clone())));
+        ret.push(("nid".to_string(), Value::Text(self.nid.clone())));
+        ret.push(("data".to_string(), Value::Text(self.data.clone())));
+        ret.push(("timestamp".to_string(), Value::Integer(self.timestamp)));
+        Ok(ret)
+    }
+}
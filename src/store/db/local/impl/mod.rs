@@ -1,3 +1,4 @@
+mod log;
 mod message;
 mod model;
 mod package;
@@ -78,6 +78,14 @@ impl DbSchema for Model {
                 ..Default::default()
             },
         ));
+        map.push((
+            "code".to_string(),
+            DbColumn {
+                db_type: DbType::Binary,
+                default: Some("X''".to_string()),
+                ..Default::default()
+            },
+        ));
         Ok(map)
     }
 }
@@ -96,6 +104,7 @@ impl DbRow for Model {
             update_time: row.get::<usize, i64>(5).unwrap(),
             data: row.get::<usize, String>(6).unwrap(),
             timestamp: row.get::<usize, i64>(7).unwrap(),
+            code: row.get::<usize, Vec<u8>>(8).unwrap_or_default(),
         })
     }
 
@@ -113,6 +122,7 @@ impl DbRow for Model {
             "timestamp".to_string(),
             Value::Integer(self.timestamp as i64),
         ));
+        ret.push(("code".to_string(), Value::Blob(self.code.clone())));
 
         Ok(ret)
     }
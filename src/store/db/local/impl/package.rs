@@ -3,7 +3,7 @@ use crate::{
         db::local::{DbColumn, DbRow, DbSchema, DbType},
         Package,
     },
-    Result,
+    ActError, Result,
 };
 use rusqlite::{types::Value, Error as DbError, Result as DbResult, Row};
 impl DbSchema for Package {
@@ -70,6 +70,40 @@ impl DbSchema for Package {
                 ..Default::default()
             },
         ));
+        map.push((
+            "limits".to_string(),
+            DbColumn {
+                db_type: DbType::Text,
+                default: Some("'{}'".to_string()),
+                ..Default::default()
+            },
+        ));
+        map.push((
+            "manifest".to_string(),
+            DbColumn {
+                db_type: DbType::Text,
+                default: Some("'{}'".to_string()),
+                ..Default::default()
+            },
+        ));
+        map.push((
+            "pack_id".to_string(),
+            DbColumn {
+                db_type: DbType::Text,
+                is_index: true,
+                default: Some("''".to_string()),
+                ..Default::default()
+            },
+        ));
+
+        map.push((
+            "code".to_string(),
+            DbColumn {
+                db_type: DbType::Binary,
+                default: Some("X''".to_string()),
+                ..Default::default()
+            },
+        ));
 
         Ok(map)
     }
@@ -89,6 +123,11 @@ impl DbRow for Package {
             create_time: row.get::<usize, i64>(4).unwrap(),
             update_time: row.get::<usize, i64>(5).unwrap(),
             timestamp: row.get::<usize, i64>(6).unwrap(),
+            limits: serde_json::from_str(&row.get::<usize, String>(7).unwrap()).unwrap_or_default(),
+            manifest: serde_json::from_str(&row.get::<usize, String>(8).unwrap())
+                .unwrap_or_default(),
+            pack_id: row.get::<usize, String>(9).unwrap_or_default(),
+            code: row.get::<usize, Vec<u8>>(10).unwrap_or_default(),
         })
     }
 
@@ -102,6 +141,16 @@ impl DbRow for Package {
         ret.push(("create_time".to_string(), Value::Integer(self.create_time)));
         ret.push(("update_time".to_string(), Value::Integer(self.update_time)));
         ret.push(("timestamp".to_string(), Value::Integer(self.timestamp)));
+        ret.push((
+            "limits".to_string(),
+            Value::Text(serde_json::to_string(&self.limits).map_err(ActError::from)?),
+        ));
+        ret.push((
+            "manifest".to_string(),
+            Value::Text(serde_json::to_string(&self.manifest).map_err(ActError::from)?),
+        ));
+        ret.push(("pack_id".to_string(), Value::Text(self.pack_id.clone())));
+        ret.push(("code".to_string(), Value::Blob(self.code.clone())));
 
         Ok(ret)
     }
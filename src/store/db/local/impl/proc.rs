@@ -95,6 +95,33 @@ impl DbSchema for Proc {
                 ..Default::default()
             },
         ));
+        map.push((
+            "ver".to_string(),
+            DbColumn {
+                db_type: DbType::Int32,
+                is_not_null: true,
+                default: Some("0".to_string()),
+                ..Default::default()
+            },
+        ));
+        map.push((
+            "inputs".to_string(),
+            DbColumn {
+                db_type: DbType::Text,
+                default: Some("'{}'".to_string()),
+                ..Default::default()
+            },
+        ));
+        map.push((
+            "parent_id".to_string(),
+            DbColumn {
+                db_type: DbType::Text,
+                is_not_null: true,
+                is_index: true,
+                default: Some("''".to_string()),
+                ..Default::default()
+            },
+        ));
         Ok(map)
     }
 }
@@ -116,6 +143,9 @@ impl DbRow for Proc {
             model: row.get::<usize, String>(7).unwrap(),
             env_local: row.get::<usize, String>(8).unwrap(),
             err: row.get::<usize, Option<String>>(9).unwrap(),
+            ver: row.get::<usize, u32>(10).unwrap(),
+            inputs: row.get::<usize, String>(11).unwrap(),
+            parent_id: row.get::<usize, String>(12).unwrap(),
         })
     }
 
@@ -138,6 +168,9 @@ impl DbRow for Proc {
                 None => Value::Null,
             },
         ));
+        ret.push(("ver".to_string(), Value::Integer(self.ver as i64)));
+        ret.push(("inputs".to_string(), Value::Text(self.inputs.clone())));
+        ret.push(("parent_id".to_string(), Value::Text(self.parent_id.clone())));
         Ok(ret)
     }
 }
@@ -1,42 +1,68 @@
 use super::{collect::Collect, database::Database};
-use crate::store::{data::*, DbSet, StoreAdapter};
+use crate::{
+    store::{data::*, DbSet, StoreAdapter},
+    Result,
+};
 use std::sync::{Arc, RwLock};
+use tracing::{debug, warn};
 
 #[derive(Debug, Clone)]
 pub struct LocalStore {
     db: Arc<RwLock<Database>>,
     models: Arc<Collect<Model>>,
+    model_versions: Arc<Collect<Model>>,
+    fragments: Arc<Collect<Model>>,
     procs: Arc<Collect<Proc>>,
     tasks: Arc<Collect<Task>>,
     packages: Arc<Collect<Package>>,
+    package_versions: Arc<Collect<Package>>,
     messages: Arc<Collect<Message>>,
+    logs: Arc<Collect<Log>>,
 }
 
 impl LocalStore {
-    pub fn new(path: &str, name: &str) -> Self {
+    /// opens the local store and migrates the tables, the error is returned when migrating fails
+    pub fn new(path: &str, name: &str) -> Result<Self> {
         let db = Arc::new(RwLock::new(Database::new(path, name)));
-        let models = Collect::new(&db, "models");
-        let procs = Collect::new(&db, "procs");
-        let tasks = Collect::new(&db, "tasks");
-        let packages = Collect::new(&db, "packages");
-        let messages = Collect::new(&db, "messages");
+        let models = Collect::new(&db, "models")?;
+        let model_versions = Collect::new(&db, "model_versions")?;
+        let fragments = Collect::new(&db, "fragments")?;
+        let procs = Collect::new(&db, "procs")?;
+        let tasks = Collect::new(&db, "tasks")?;
+        let packages = Collect::new(&db, "packages")?;
+        let package_versions = Collect::new(&db, "package_versions")?;
+        let messages = Collect::new(&db, "messages")?;
+        let logs = Collect::new(&db, "logs")?;
         let store = Self {
             db: db.clone(),
             models: Arc::new(models),
+            model_versions: Arc::new(model_versions),
+            fragments: Arc::new(fragments),
             procs: Arc::new(procs),
             tasks: Arc::new(tasks),
             packages: Arc::new(packages),
+            package_versions: Arc::new(package_versions),
             messages: Arc::new(messages),
+            logs: Arc::new(logs),
         };
 
         store.init();
 
-        store
+        Ok(store)
     }
 }
 
 impl StoreAdapter for LocalStore {
-    fn init(&self) {}
+    /// fills the package id of the versions which are published before the `pack_id` column
+    fn init(&self) {
+        let db = self.db.read().unwrap();
+        let conn = db.pool().get().unwrap();
+        let sql = "update package_versions set pack_id = substr(id, 1, length(id) - length(json_extract(manifest, '$.version')) - 1) where pack_id = '' and json_extract(manifest, '$.version') is not null";
+        debug!("sql={}", sql);
+        if let Err(err) = conn.execute(sql, []) {
+            warn!("failed to fill the package id of the package versions: {err}");
+        }
+    }
     fn close(&self) {
         self.db.write().unwrap().close();
     }
@@ -45,6 +71,14 @@ impl StoreAdapter for LocalStore {
         self.models.clone()
     }
 
+    fn model_versions(&self) -> Arc<dyn DbSet<Item = Model>> {
+        self.model_versions.clone()
+    }
+
+    fn fragments(&self) -> Arc<dyn DbSet<Item = Model>> {
+        self.fragments.clone()
+    }
+
     fn procs(&self) -> Arc<dyn DbSet<Item = Proc>> {
         self.procs.clone()
     }
@@ -57,7 +91,15 @@ impl StoreAdapter for LocalStore {
         self.packages.clone()
     }
 
+    fn package_versions(&self) -> Arc<dyn DbSet<Item = Package>> {
+        self.package_versions.clone()
+    }
+
     fn messages(&self) -> Arc<dyn DbSet<Item = Message>> {
         self.messages.clone()
     }
+
+    fn logs(&self) -> Arc<dyn DbSet<Item = Log>> {
+        self.logs.clone()
+    }
 }
@@ -56,3 +56,6 @@ trait DbRow {
 }
 
 pub use local::LocalStore;
+
+#[cfg(test)]
+pub(crate) use database::Database;
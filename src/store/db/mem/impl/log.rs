@@ -0,0 +1,23 @@
+use crate::{
+    store::{db::mem::DbDocument, Log},
+    Result,
+};
+use serde_json::{json, Value as JsonValue};
+use std::collections::HashMap;
+
+impl DbDocument for Log {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn doc(&self) -> Result<HashMap<String, JsonValue>> {
+        let mut map = HashMap::new();
+        map.insert("id".to_string(), json!(self.id.clone()));
+        map.insert("pid".to_string(), json!(self.pid.clone()));
+        map.insert("tid".to_string(), json!(self.tid.clone()));
+        map.insert("nid".to_string(), json!(self.nid.clone()));
+        map.insert("data".to_string(), json!(self.data.clone()));
+        map.insert("timestamp".to_string(), json!(self.timestamp));
+        Ok(map)
+    }
+}
@@ -20,6 +20,10 @@ impl DbDocument for Model {
         map.insert("update_time".to_string(), json!(self.update_time));
         map.insert("data".to_string(), json!(self.data.clone()));
         map.insert("timestamp".to_string(), json!(self.timestamp));
+        map.insert(
+            "code".to_string(),
+            JsonValue::String(hex::encode(&self.code)),
+        );
         Ok(map)
     }
 }
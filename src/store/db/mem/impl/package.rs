@@ -22,6 +22,13 @@ impl DbDocument for Package {
         map.insert("create_time".to_string(), json!(self.create_time.clone()));
         map.insert("update_time".to_string(), json!(self.update_time.clone()));
         map.insert("timestamp".to_string(), json!(self.timestamp.clone()));
+        map.insert("limits".to_string(), json!(self.limits.clone()));
+        map.insert("manifest".to_string(), json!(self.manifest.clone()));
+        map.insert("pack_id".to_string(), json!(self.pack_id.clone()));
+        map.insert(
+            "code".to_string(),
+            JsonValue::String(hex::encode(&self.code)),
+        );
         Ok(map)
     }
 }
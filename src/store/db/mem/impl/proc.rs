@@ -20,7 +20,10 @@ impl DbDocument for Proc {
         map.insert("end_time".to_string(), json!(self.end_time));
         map.insert("timestamp".to_string(), json!(self.timestamp));
         map.insert("model".to_string(), json!(self.model.clone()));
+        map.insert("ver".to_string(), json!(self.ver));
+        map.insert("inputs".to_string(), json!(self.inputs.clone()));
         map.insert("env_local".to_string(), json!(self.env_local.clone()));
+        map.insert("parent_id".to_string(), json!(self.parent_id.clone()));
         Ok(map)
     }
 }
@@ -13,10 +13,14 @@ use std::{collections::HashMap, sync::Arc};
 #[derive(Debug, Clone)]
 pub struct MemStore {
     models: Arc<Collect<Model>>,
+    model_versions: Arc<Collect<Model>>,
+    fragments: Arc<Collect<Model>>,
     procs: Arc<Collect<Proc>>,
     tasks: Arc<Collect<Task>>,
     packages: Arc<Collect<Package>>,
+    package_versions: Arc<Collect<Package>>,
     messages: Arc<Collect<Message>>,
+    logs: Arc<Collect<Log>>,
 }
 
 trait DbDocument: Serialize + DeserializeOwned {
@@ -27,16 +31,24 @@ trait DbDocument: Serialize + DeserializeOwned {
 impl MemStore {
     pub fn new() -> Self {
         let models = Collect::new("models");
+        let model_versions = Collect::new("model_versions");
+        let fragments = Collect::new("fragments");
         let procs = Collect::new("procs");
         let tasks = Collect::new("tasks");
         let packages = Collect::new("packages");
+        let package_versions = Collect::new("package_versions");
         let messages = Collect::new("messages");
+        let logs = Collect::new("logs");
         let store = Self {
             models: Arc::new(models),
+            model_versions: Arc::new(model_versions),
+            fragments: Arc::new(fragments),
             procs: Arc::new(procs),
             tasks: Arc::new(tasks),
             packages: Arc::new(packages),
+            package_versions: Arc::new(package_versions),
             messages: Arc::new(messages),
+            logs: Arc::new(logs),
         };
 
         store.init();
@@ -53,6 +65,14 @@ impl StoreAdapter for MemStore {
         self.models.clone()
     }
 
+    fn model_versions(&self) -> Arc<dyn DbSet<Item = Model>> {
+        self.model_versions.clone()
+    }
+
+    fn fragments(&self) -> Arc<dyn DbSet<Item = Model>> {
+        self.fragments.clone()
+    }
+
     fn procs(&self) -> Arc<dyn DbSet<Item = Proc>> {
         self.procs.clone()
     }
@@ -65,7 +85,15 @@ impl StoreAdapter for MemStore {
         self.packages.clone()
     }
 
+    fn package_versions(&self) -> Arc<dyn DbSet<Item = Package>> {
+        self.package_versions.clone()
+    }
+
     fn messages(&self) -> Arc<dyn DbSet<Item = Message>> {
         self.messages.clone()
     }
+
+    fn logs(&self) -> Arc<dyn DbSet<Item = Log>> {
+        self.logs.clone()
+    }
 }
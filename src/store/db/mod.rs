@@ -4,4 +4,6 @@ mod mem;
 
 #[cfg(feature = "store")]
 pub use local::LocalStore;
+#[cfg(all(test, feature = "store"))]
+pub(crate) use local::{Database, DbColumn, DbType};
 pub use mem::MemStore;
@@ -13,7 +13,7 @@ use serde::{Deserialize, Serialize};
 pub use store::{Store, StoreKind};
 
 use crate::{ActError, Result};
-use std::{error::Error, sync::Arc};
+use std::{error::Error, marker::PhantomData, sync::Arc};
 
 fn map_db_err(err: impl Error) -> ActError {
     ActError::Store(err.to_string())
@@ -41,6 +41,9 @@ pub trait DbSet: Send + Sync {
 /// Store adapter trait
 /// Used to implement custom storage
 ///
+/// The optional methods have the default implementations which are always empty
+/// and return an error when writing.
+///
 /// # Example
 /// ```no_run
 /// use acts::{data::{Model, Proc, Task, Package, Message}, DbSet, StoreAdapter};
@@ -51,6 +54,9 @@ pub trait DbSet: Send + Sync {
 ///     fn models(&self) -> Arc<dyn DbSet<Item = Model>> {
 ///         todo!()
 ///     }
+///     fn model_versions(&self) -> Arc<dyn DbSet<Item = Model>> {
+///         todo!()
+///     }
 ///     fn procs(&self) -> Arc<dyn DbSet<Item =Proc>> {
 ///         todo!()
 ///     }
@@ -71,9 +77,87 @@ pub trait StoreAdapter: Send + Sync {
     fn init(&self);
 
     fn models(&self) -> Arc<dyn DbSet<Item = Model>>;
+    /// the deployed history of the models, the id is formatted as `{id}@{ver}`
+    ///
+    /// it is required, because the procs only keep the model version and are reloaded
+    /// with the model of that version, even after the model is deployed again
+    fn model_versions(&self) -> Arc<dyn DbSet<Item = Model>>;
+    /// the deployed model fragments, the data is the fragment yml
+    fn fragments(&self) -> Arc<dyn DbSet<Item = Model>> {
+        Arc::new(EmptySet::new("fragments"))
+    }
     fn procs(&self) -> Arc<dyn DbSet<Item = Proc>>;
     fn tasks(&self) -> Arc<dyn DbSet<Item = Task>>;
     fn packages(&self) -> Arc<dyn DbSet<Item = Package>>;
+    /// the published history of the versioned packages, the id is formatted as `{id}@{version}`
+    /// and the versions of a package are queried by the `pack_id` column
+    fn package_versions(&self) -> Arc<dyn DbSet<Item = Package>> {
+        Arc::new(EmptySet::new("package_versions"))
+    }
     fn messages(&self) -> Arc<dyn DbSet<Item = Message>>;
+    /// the captured script logs of the tasks, the id is formatted as `{pid}:{tid}`
+    fn logs(&self) -> Arc<dyn DbSet<Item = Log>> {
+        Arc::new(EmptySet::new("logs"))
+    }
     fn close(&self);
 }
+
+/// the default set of the optional `StoreAdapter` methods, which is always empty
+/// and returns an error when writing, so the data is never dropped silently
+struct EmptySet<T> {
+    name: &'static str,
+    _t: PhantomData<T>,
+}
+
+impl<T> EmptySet<T> {
+    fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            _t: PhantomData,
+        }
+    }
+
+    fn unsupported(&self) -> ActError {
+        ActError::Store(format!(
+            "'{}' is not implemented by the store adapter",
+            self.name
+        ))
+    }
+}
+
+impl<T: Send + Sync> DbSet for EmptySet<T> {
+    type Item = T;
+
+    fn exists(&self, _id: &str) -> Result<bool> {
+        Ok(false)
+    }
+
+    fn find(&self, id: &str) -> Result<Self::Item> {
+        Err(ActError::Store(format!(
+            "cannot find {} by '{}'",
+            self.name, id
+        )))
+    }
+
+    fn query(&self, q: &Query) -> Result<PageData<Self::Item>> {
+        Ok(PageData {
+            count: 0,
+            page_num: q.offset() / q.limit() + 1,
+            page_count: 0,
+            page_size: q.limit(),
+            rows: Vec::new(),
+        })
+    }
+
+    fn create(&self, _data: &Self::Item) -> Result<bool> {
+        Err(self.unsupported())
+    }
+
+    fn update(&self, _data: &Self::Item) -> Result<bool> {
+        Err(self.unsupported())
+    }
+
+    fn delete(&self, _id: &str) -> Result<bool> {
+        Ok(false)
+    }
+}
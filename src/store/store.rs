@@ -1,6 +1,7 @@
 use crate::{
-    store::{Message, Model, Package, Proc, StoreAdapter, Task},
-    utils, ActError, Result, ShareLock, Workflow,
+    model::{is_version_match, parse_extends},
+    store::{Cond, Expr, Log, Message, Model, Package, Proc, Query, StoreAdapter, Task},
+    ActError, Clock, Fragment, Result, ShareLock, SystemClock, Workflow,
 };
 use std::sync::{Arc, Mutex, RwLock};
 use tracing::trace;
@@ -20,6 +21,7 @@ pub enum StoreKind {
 pub struct Store {
     kind: Arc<Mutex<StoreKind>>,
     base: ShareLock<Arc<dyn StoreAdapter>>,
+    clock: ShareLock<Arc<dyn Clock>>,
 }
 
 impl StoreAdapter for Store {
@@ -31,6 +33,14 @@ impl StoreAdapter for Store {
         self.base.read().unwrap().models()
     }
 
+    fn model_versions(&self) -> Arc<dyn super::DbSet<Item = Model>> {
+        self.base.read().unwrap().model_versions()
+    }
+
+    fn fragments(&self) -> Arc<dyn super::DbSet<Item = Model>> {
+        self.base.read().unwrap().fragments()
+    }
+
     fn procs(&self) -> Arc<dyn super::DbSet<Item = Proc>> {
         self.base.read().unwrap().procs()
     }
@@ -43,10 +53,18 @@ impl StoreAdapter for Store {
         self.base.read().unwrap().packages()
     }
 
+    fn package_versions(&self) -> Arc<dyn super::DbSet<Item = Package>> {
+        self.base.read().unwrap().package_versions()
+    }
+
     fn messages(&self) -> Arc<dyn super::DbSet<Item = Message>> {
         self.base.read().unwrap().messages()
     }
 
+    fn logs(&self) -> Arc<dyn super::DbSet<Item = Log>> {
+        self.base.read().unwrap().logs()
+    }
+
     fn close(&self) {
         self.base.read().unwrap().close()
     }
@@ -58,6 +76,7 @@ impl Store {
         Arc::new(Self {
             kind: Arc::new(Mutex::new(StoreKind::Memory)),
             base: Arc::new(RwLock::new(store)),
+            clock: Arc::new(RwLock::new(Arc::new(SystemClock::new()))),
         })
     }
 
@@ -65,16 +84,18 @@ impl Store {
         Self {
             kind: Arc::new(Mutex::new(StoreKind::Extern)),
             base: Arc::new(RwLock::new(store)),
+            clock: Arc::new(RwLock::new(Arc::new(SystemClock::new()))),
         }
     }
 
     #[cfg(feature = "store")]
-    pub fn local(path: &str, name: &str) -> Self {
-        let store = Arc::new(LocalStore::new(path, name));
-        Self {
+    pub fn local(path: &str, name: &str) -> Result<Self> {
+        let store = Arc::new(LocalStore::new(path, name)?);
+        Ok(Self {
             kind: Arc::new(Mutex::new(StoreKind::Local)),
             base: Arc::new(RwLock::new(store)),
-        }
+            clock: Arc::new(RwLock::new(Arc::new(SystemClock::new()))),
+        })
     }
 
     #[cfg(test)]
@@ -87,7 +108,7 @@ impl Store {
         }
         #[cfg(feature = "store")]
         {
-            let store = Self::local("data", "acts.db");
+            let store = Self::local("data", "acts.db").unwrap();
             *self.kind.lock().unwrap() = store.kind();
             *self.base.write().unwrap() = store.base();
         }
@@ -103,32 +124,115 @@ impl Store {
             return Err(ActError::Action("missing file in package".into()));
         }
 
+        pack.manifest.check()?;
+        for (id, req) in &pack.manifest.dependencies {
+            if self.find_package(id, Some(req)).is_err() {
+                return Err(ActError::Action(format!(
+                    "cannot find the dependency '{id}@{req}' of package '{}'",
+                    pack.id
+                )));
+            }
+        }
+
         let packages = self.base().packages();
-        match packages.find(&pack.id) {
+        let (data, ret) = match packages.find(&pack.id) {
             Ok(m) => {
                 let data = Package {
                     create_time: m.create_time,
-                    update_time: utils::time::time_millis(),
+                    update_time: self.clock().now(),
+                    pack_id: pack.id.clone(),
                     ..pack.clone()
                 };
-                packages.update(&data)
+                let ret = packages.update(&data)?;
+                (data, ret)
             }
             Err(_) => {
                 let data = Package {
-                    create_time: utils::time::time_millis(),
+                    create_time: self.clock().now(),
+                    pack_id: pack.id.clone(),
                     ..pack.clone()
                 };
-                packages.create(&data)
+                let ret = packages.create(&data)?;
+                (data, ret)
+            }
+        };
+
+        // keep the published version in history
+        if !data.manifest.version.is_empty() {
+            let versions = self.base().package_versions();
+            let history = Package {
+                id: format!("{}@{}", data.id, data.manifest.version),
+                pack_id: data.id.clone(),
+                ..data
+            };
+            if versions.exists(&history.id)? {
+                versions.update(&history)?;
+            } else {
+                versions.create(&history)?;
             }
         }
+
+        Ok(ret)
+    }
+
+    /// find the package by id, the latest package will be returned if the ver is None
+    /// the ver can be the exact version or the semver requirement, such as `^1.0`
+    pub fn find_package(&self, id: &str, ver: Option<&str>) -> Result<Package> {
+        let ver = match ver {
+            Some(ver) => ver,
+            None => return self.base().packages().find(id),
+        };
+
+        if let Some(pack) = self
+            .package_versions_of(id)?
+            .into_iter()
+            .find(|p| is_version_match(&p.manifest.version, ver))
+        {
+            return Ok(pack);
+        }
+
+        // the packages published without history
+        match self.base().packages().find(id) {
+            Ok(pack) if is_version_match(&pack.manifest.version, ver) => Ok(pack),
+            _ => Err(ActError::Store(format!(
+                "cannot find package '{id}' with version {ver}"
+            ))),
+        }
+    }
+
+    /// the published versions of the package, which are sorted by the version in descending order
+    pub fn package_versions_of(&self, id: &str) -> Result<Vec<Package>> {
+        let q = Query::new().push(Cond::and().push(Expr::eq("pack_id", id.to_string())));
+        let mut versions = self
+            .base()
+            .package_versions()
+            .query(&q)?
+            .rows
+            .into_iter()
+            .map(|p| Package {
+                id: id.to_string(),
+                ..p
+            })
+            .collect::<Vec<_>>();
+        versions.sort_by(|a, b| {
+            let a = semver::Version::parse(&a.manifest.version).ok();
+            let b = semver::Version::parse(&b.manifest.version).ok();
+            b.cmp(&a)
+        });
+        Ok(versions)
     }
     pub fn deploy(&self, model: &Workflow) -> Result<bool> {
+        self.deploy_with_code(model, Vec::new())
+    }
+
+    /// deploys the model with the compiled bytecode of its scripts, which is kept in the history too
+    pub fn deploy_with_code(&self, model: &Workflow, code: Vec<u8>) -> Result<bool> {
         trace!("store::deploy({})", model.id);
         if model.id.is_empty() {
             return Err(ActError::Action("missing id in model".into()));
         }
         let models = self.base().models();
-        match models.find(&model.id) {
+        let (data, ret) = match models.find(&model.id) {
             Ok(m) => {
                 let text = serde_yaml::to_string(model).unwrap();
                 let data = Model {
@@ -138,10 +242,12 @@ impl Store {
                     ver: m.ver + 1,
                     size: text.len() as u32,
                     create_time: m.create_time,
-                    update_time: utils::time::time_millis(),
-                    timestamp: utils::time::timestamp(),
+                    update_time: self.clock().now(),
+                    timestamp: self.clock().timestamp(),
+                    code,
                 };
-                models.update(&data)
+                let ret = models.update(&data)?;
+                (data, ret)
             }
             Err(_) => {
                 let text = serde_yaml::to_string(model).unwrap();
@@ -151,19 +257,125 @@ impl Store {
                     data: text.clone(),
                     ver: 1,
                     size: text.len() as u32,
-                    create_time: utils::time::time_millis(),
+                    create_time: self.clock().now(),
                     update_time: 0,
-                    timestamp: utils::time::timestamp(),
+                    timestamp: self.clock().timestamp(),
+                    code,
                 };
-                models.create(&data)
+                let ret = models.create(&data)?;
+                (data, ret)
             }
+        };
+
+        // keep the deployed version in history
+        let versions = self.base().model_versions();
+        let history = Model {
+            id: format!("{}@{}", data.id, data.ver),
+            ..data
+        };
+        if versions.exists(&history.id)? {
+            versions.update(&history)?;
+        } else {
+            versions.create(&history)?;
         }
+
+        Ok(ret)
+    }
+
+    pub fn deploy_fragment(&self, fragment: &Fragment) -> Result<bool> {
+        trace!("store::deploy_fragment({})", fragment.id);
+        if fragment.id.is_empty() {
+            return Err(ActError::Action("missing id in fragment".into()));
+        }
+        let fragments = self.base().fragments();
+        let text = fragment.to_yml()?;
+        match fragments.find(&fragment.id) {
+            Ok(m) => fragments.update(&Model {
+                id: fragment.id.clone(),
+                name: fragment.name.clone(),
+                data: text.clone(),
+                ver: m.ver + 1,
+                size: text.len() as u32,
+                create_time: m.create_time,
+                update_time: self.clock().now(),
+                timestamp: self.clock().timestamp(),
+                code: Vec::new(),
+            }),
+            Err(_) => fragments.create(&Model {
+                id: fragment.id.clone(),
+                name: fragment.name.clone(),
+                data: text.clone(),
+                ver: 1,
+                size: text.len() as u32,
+                create_time: self.clock().now(),
+                update_time: 0,
+                timestamp: self.clock().timestamp(),
+                code: Vec::new(),
+            }),
+        }
+    }
+
+    /// find the model by id, the latest version will be returned if the ver is None
+    pub fn find_model(&self, id: &str, ver: Option<u32>) -> Result<Model> {
+        let ver = match ver {
+            Some(ver) => ver,
+            None => return self.base().models().find(id),
+        };
+
+        if let Ok(mut model) = self.base().model_versions().find(&format!("{id}@{ver}")) {
+            model.id = id.to_string();
+            return Ok(model);
+        }
+
+        // the models deployed without history
+        match self.base().models().find(id) {
+            Ok(model) if model.ver == ver => Ok(model),
+            _ => Err(ActError::Store(format!(
+                "cannot find model '{id}' with version {ver}"
+            ))),
+        }
+    }
+
+    /// resolves the effective model by merging the `extends` base models
+    pub fn resolve_model(&self, model: &Workflow) -> Result<Workflow> {
+        self.resolve_model_with(model, &mut vec![model.id.clone()])
+    }
+
+    fn resolve_model_with(&self, model: &Workflow, chain: &mut Vec<String>) -> Result<Workflow> {
+        let extends = match &model.extends {
+            Some(extends) => extends,
+            None => return Ok(model.clone()),
+        };
+        let (id, ver) = parse_extends(extends);
+        if chain.iter().any(|m| m == id) {
+            return Err(ActError::Model(format!(
+                "found model extends cycle '{} -> {id}'",
+                chain.join(" -> ")
+            )));
+        }
+        chain.push(id.to_string());
+
+        let data = self
+            .find_model(id, ver)
+            .map_err(|_| ActError::Model(format!("cannot find base model '{extends}'")))?;
+        let mut base = Workflow::from_yml(&data.data)?;
+        base.set_ver(data.ver);
+        let base = self.resolve_model_with(&base, chain)?;
+        model.inherit(&base)
     }
 
     pub(crate) fn base(&self) -> Arc<dyn StoreAdapter> {
         self.base.read().unwrap().clone()
     }
 
+    pub fn clock(&self) -> Arc<dyn Clock> {
+        self.clock.read().unwrap().clone()
+    }
+
+    pub fn set_clock(&self, clock: &Arc<dyn Clock>) {
+        *self.clock.write().unwrap() = clock.clone();
+    }
+
     pub fn kind(&self) -> StoreKind {
         self.kind.lock().unwrap().clone()
     }
@@ -1,14 +1,14 @@
 use crate::{
     sch::NodeKind,
     store::{data::*, db::LocalStore, query::Expr, Cond, Query},
-    utils, StoreAdapter, TaskState, Vars,
+    utils, ActError, Limits, Manifest, Output, StoreAdapter, TaskState, Vars,
 };
 use serde_json::json;
 use tokio::sync::OnceCell;
 
 static STORE: OnceCell<LocalStore> = OnceCell::const_new();
 async fn init() -> LocalStore {
-    let s = LocalStore::new("test_data", "test.db");
+    let s = LocalStore::new("test_data", "test.db").unwrap();
     s
 }
 
@@ -28,6 +28,7 @@ async fn store_mem_model_create() {
         update_time: 0,
         data: "{}".to_string(),
         timestamp: 0,
+        code: Vec::new(),
     };
     store.models().create(&model).unwrap();
     assert_eq!(store.models().exists(&model.id).unwrap(), true);
@@ -46,6 +47,7 @@ async fn store_mem_model_find() {
         update_time: 0,
         data: "{}".to_string(),
         timestamp: 0,
+        code: Vec::new(),
     };
     store.models().create(&model).unwrap();
     assert_eq!(store.models().find(&mid).unwrap().id, mid);
@@ -65,6 +67,7 @@ async fn store_mem_model_query() {
             update_time: 0,
             data: "{}".to_string(),
             timestamp: 0,
+            code: Vec::new(),
         };
         models.create(&model).unwrap();
     }
@@ -89,6 +92,7 @@ async fn store_mem_model_update() {
         update_time: 0,
         data: "{}".to_string(),
         timestamp: 0,
+        code: Vec::new(),
     };
     store.models().create(&model).unwrap();
 
@@ -113,6 +117,7 @@ async fn store_mem_model_delete() {
         update_time: 0,
         data: "{}".to_string(),
         timestamp: 0,
+        code: Vec::new(),
     };
     store.models().create(&model).unwrap();
     store.models().delete(&model.id).unwrap();
@@ -132,8 +137,11 @@ async fn store_local_proc_create() {
         end_time: 0,
         timestamp: 0,
         model: "".to_string(),
+        ver: 0,
+        inputs: "{}".to_string(),
         env_local: "{}".to_string(),
         err: None,
+        parent_id: String::new(),
     };
     store.procs().create(&proc).unwrap();
     assert_eq!(store.procs().exists(&proc.id).unwrap(), true);
@@ -152,8 +160,11 @@ async fn store_local_proc_find() {
         end_time: 0,
         timestamp: 0,
         model: "".to_string(),
+        ver: 0,
+        inputs: "{}".to_string(),
         env_local: "{}".to_string(),
         err: None,
+        parent_id: String::new(),
     };
     store.procs().create(&proc).unwrap();
     assert_eq!(store.procs().find(&pid).unwrap().id, pid);
@@ -174,8 +185,11 @@ async fn store_local_proc_query() {
             end_time: 0,
             timestamp: 0,
             model: "".to_string(),
+            ver: 0,
+            inputs: "{}".to_string(),
             env_local: "{}".to_string(),
             err: None,
+            parent_id: String::new(),
         };
         procs.create(&proc).unwrap();
     }
@@ -203,8 +217,11 @@ async fn store_local_proc_update() {
         end_time: 0,
         timestamp: 0,
         model: "".to_string(),
+        ver: 0,
+        inputs: "{}".to_string(),
         env_local: "{}".to_string(),
         err: None,
+        parent_id: String::new(),
     };
     store.procs().create(&proc).unwrap();
 
@@ -229,8 +246,11 @@ async fn store_local_proc_delete() {
         end_time: 0,
         timestamp: 0,
         model: "".to_string(),
+        ver: 0,
+        inputs: "{}".to_string(),
         env_local: "{}".to_string(),
         err: None,
+        parent_id: String::new(),
     };
     store.procs().create(&proc).unwrap();
     store.procs().delete(&proc.id).unwrap();
@@ -554,6 +574,10 @@ async fn store_local_package_create() {
         create_time: 0,
         update_time: 0,
         timestamp: 0,
+        limits: Limits::default(),
+        manifest: Manifest::default(),
+        pack_id: String::new(),
+        code: Vec::new(),
     };
 
     store.packages().create(&package).unwrap();
@@ -561,6 +585,64 @@ async fn store_local_package_create() {
     assert!(ret.is_ok());
 }
 
+#[tokio::test]
+async fn store_local_package_manifest() {
+    let store = store().await;
+
+    let id = utils::longid();
+    let package = Package {
+        id,
+        name: "test package".to_string(),
+        data: vec![0x01, 0x02],
+        manifest: Manifest::new()
+            .with_version("1.0.0")
+            .with_description("the test package")
+            .with_dependency("utils", "^1.0")
+            .with_input("name", Output::default()),
+        ..Default::default()
+    };
+
+    store.packages().create(&package).unwrap();
+    store.package_versions().create(&package).unwrap();
+    let ret = store.packages().find(&package.id).unwrap();
+    assert_eq!(ret.manifest.version, "1.0.0");
+    assert_eq!(ret.manifest.description, "the test package");
+    assert_eq!(ret.manifest.dependencies.get("utils").unwrap(), "^1.0");
+    assert!(ret.manifest.inputs.get("name").is_some());
+    assert!(store.package_versions().exists(&package.id).unwrap());
+}
+
+#[tokio::test]
+async fn store_local_code() {
+    let store = store().await;
+
+    let package = Package {
+        id: utils::longid(),
+        data: b"let a = 1;".to_vec(),
+        code: vec![0x01, 0x02],
+        ..Default::default()
+    };
+    store.packages().create(&package).unwrap();
+    assert_eq!(
+        store.packages().find(&package.id).unwrap().code,
+        [0x01, 0x02]
+    );
+
+    let model = Model {
+        id: utils::longid(),
+        name: "test".to_string(),
+        ver: 1,
+        size: 0,
+        create_time: 0,
+        update_time: 0,
+        data: "{}".to_string(),
+        timestamp: 0,
+        code: vec![0x03],
+    };
+    store.models().create(&model).unwrap();
+    assert_eq!(store.models().find(&model.id).unwrap().code, [0x03]);
+}
+
 #[tokio::test]
 async fn store_local_package_query() {
     let store = store().await;
@@ -574,6 +656,10 @@ async fn store_local_package_query() {
         create_time: 0,
         update_time: 0,
         timestamp: 0,
+        limits: Limits::default(),
+        manifest: Manifest::default(),
+        pack_id: String::new(),
+        code: Vec::new(),
     };
     store.packages().create(&package).unwrap();
     let q = Query::new().push(Cond::and().push(Expr::eq("id", package.id)));
@@ -594,6 +680,10 @@ async fn store_local_package_update() {
         create_time: 0,
         update_time: 0,
         timestamp: 0,
+        limits: Limits::default(),
+        manifest: Manifest::default(),
+        pack_id: String::new(),
+        code: Vec::new(),
     };
     store.packages().create(&package).unwrap();
     let mut p = store.packages().find(&package.id).unwrap();
@@ -621,6 +711,10 @@ async fn store_local_package_remove() {
         create_time: 0,
         update_time: 0,
         timestamp: 0,
+        limits: Limits::default(),
+        manifest: Manifest::default(),
+        pack_id: String::new(),
+        code: Vec::new(),
     };
     store.packages().create(&package).unwrap();
     store.packages().delete(&package.id).unwrap();
@@ -628,3 +722,129 @@ async fn store_local_package_remove() {
     let ret = store.packages().find(&package.id);
     assert!(ret.is_err());
 }
+
+#[tokio::test]
+async fn store_local_migrate_columns() {
+    use crate::store::db::{Database, DbColumn, DbType};
+    let mut db = Database::new("test_data", "migrate.db");
+    let conn = db.pool().get().unwrap();
+    conn.execute(
+        "create table migrate_procs (id VARCHAR NOT NULL PRIMARY KEY, model VARCHAR)",
+        [],
+    )
+    .unwrap();
+    conn.execute(
+        "insert into migrate_procs (id, model) values ('p1', '{}')",
+        [],
+    )
+    .unwrap();
+    drop(conn);
+
+    let schema = vec![
+        (
+            "id".to_string(),
+            DbColumn {
+                db_type: DbType::Text,
+                is_not_null: true,
+                is_primary_key: true,
+                ..Default::default()
+            },
+        ),
+        (
+            "model".to_string(),
+            DbColumn {
+                db_type: DbType::Text,
+                ..Default::default()
+            },
+        ),
+        (
+            "ver".to_string(),
+            DbColumn {
+                db_type: DbType::Int32,
+                is_not_null: true,
+                default: Some("0".to_string()),
+                ..Default::default()
+            },
+        ),
+        (
+            "inputs".to_string(),
+            DbColumn {
+                db_type: DbType::Text,
+                default: Some("'{}'".to_string()),
+                ..Default::default()
+            },
+        ),
+    ];
+    db.init("migrate_procs", &schema).unwrap();
+    // the existing columns are not added again
+    db.init("migrate_procs", &schema).unwrap();
+
+    let conn = db.pool().get().unwrap();
+    let (ver, inputs) = conn
+        .query_row(
+            "select ver, inputs from migrate_procs where id = 'p1'",
+            [],
+            |row| Ok((row.get::<usize, u32>(0)?, row.get::<usize, String>(1)?)),
+        )
+        .unwrap();
+    assert_eq!(ver, 0);
+    assert_eq!(inputs, "{}");
+}
+
+#[tokio::test]
+async fn store_local_migrate_error() {
+    use crate::store::db::{Database, DbColumn, DbType};
+    let mut db = Database::new("test_data", "migrate.db");
+    let conn = db.pool().get().unwrap();
+    conn.execute(
+        "create table migrate_tasks (id VARCHAR NOT NULL PRIMARY KEY)",
+        [],
+    )
+    .unwrap();
+    drop(conn);
+
+    // the column with the invalid default cannot be added
+    let schema = vec![
+        (
+            "id".to_string(),
+            DbColumn {
+                db_type: DbType::Text,
+                is_not_null: true,
+                is_primary_key: true,
+                ..Default::default()
+            },
+        ),
+        (
+            "time".to_string(),
+            DbColumn {
+                db_type: DbType::Int64,
+                default: Some("'x".to_string()),
+                ..Default::default()
+            },
+        ),
+    ];
+    let result = db.init("migrate_tasks", &schema);
+    assert!(
+        matches!(result, Err(ActError::Store(message)) if message.starts_with("failed to add column 'time' to table 'migrate_tasks'"))
+    );
+}
+
+#[tokio::test]
+async fn store_local_package_versions_fill_pack_id() {
+    let store = store().await;
+
+    let id = utils::longid();
+    // the version which is published before the `pack_id` column
+    let package = Package {
+        id: format!("{id}@1.0.0"),
+        name: "test package".to_string(),
+        data: vec![0x01, 0x02],
+        manifest: Manifest::new().with_version("1.0.0"),
+        ..Default::default()
+    };
+    store.package_versions().create(&package).unwrap();
+    store.init();
+
+    let ret = store.package_versions().find(&package.id).unwrap();
+    assert_eq!(ret.pack_id, id);
+}
@@ -1,7 +1,7 @@
 use crate::{
     sch::NodeKind,
     store::{data::*, db::MemStore, query::Expr, Cond, Query},
-    utils, StoreAdapter, TaskState, Vars,
+    utils, Limits, Manifest, StoreAdapter, TaskState, Vars,
 };
 use serde_json::json;
 use tokio::sync::OnceCell;
@@ -27,6 +27,7 @@ async fn store_mem_model_create() {
         update_time: 0,
         data: "{}".to_string(),
         timestamp: 0,
+        code: Vec::new(),
     };
     store.models().create(&model).unwrap();
     assert!(store.models().exists(&model.id).unwrap());
@@ -45,6 +46,7 @@ async fn store_mem_model_find() {
         data: "{}".to_string(),
         update_time: 0,
         timestamp: 0,
+        code: Vec::new(),
     };
     store.models().create(&model).unwrap();
     assert_eq!(store.models().find(&mid).unwrap().id, mid);
@@ -64,6 +66,7 @@ async fn store_mem_model_query() {
             update_time: 0,
             data: "{}".to_string(),
             timestamp: 0,
+            code: Vec::new(),
         };
         models.create(&model).unwrap();
     }
@@ -88,6 +91,7 @@ async fn store_mem_model_update() {
         update_time: 0,
         data: "{}".to_string(),
         timestamp: 0,
+        code: Vec::new(),
     };
     store.models().create(&model).unwrap();
 
@@ -112,6 +116,7 @@ async fn store_mem_model_delete() {
         update_time: 0,
         data: "{}".to_string(),
         timestamp: 0,
+        code: Vec::new(),
     };
     store.models().create(&model).unwrap();
     store.models().delete(&model.id).unwrap();
@@ -131,8 +136,11 @@ async fn store_mem_proc_create() {
         end_time: 0,
         timestamp: 0,
         model: "".to_string(),
+        ver: 0,
+        inputs: "{}".to_string(),
         env_local: "".to_string(),
         err: None,
+        parent_id: String::new(),
     };
     store.procs().create(&proc).unwrap();
     assert!(store.procs().exists(&proc.id).unwrap());
@@ -151,8 +159,11 @@ async fn store_mem_proc_find() {
         end_time: 0,
         timestamp: 0,
         model: "".to_string(),
+        ver: 0,
+        inputs: "{}".to_string(),
         env_local: "".to_string(),
         err: None,
+        parent_id: String::new(),
     };
     store.procs().create(&proc).unwrap();
     assert_eq!(store.procs().find(&pid).unwrap().id, pid);
@@ -173,8 +184,11 @@ async fn store_mem_proc_query() {
             end_time: 0,
             timestamp: 0,
             model: "".to_string(),
+            ver: 0,
+            inputs: "{}".to_string(),
             env_local: "".to_string(),
             err: None,
+            parent_id: String::new(),
         };
         procs.create(&proc).unwrap();
     }
@@ -202,8 +216,11 @@ async fn store_mem_proc_update() {
         end_time: 0,
         timestamp: 0,
         model: "".to_string(),
+        ver: 0,
+        inputs: "{}".to_string(),
         env_local: "".to_string(),
         err: None,
+        parent_id: String::new(),
     };
     store.procs().create(&proc).unwrap();
 
@@ -226,8 +243,11 @@ async fn store_mem_proc_delete() {
         end_time: 0,
         timestamp: 0,
         model: "".to_string(),
+        ver: 0,
+        inputs: "{}".to_string(),
         env_local: "".to_string(),
         err: None,
+        parent_id: String::new(),
     };
     store.procs().create(&proc).unwrap();
     store.procs().delete(&proc.id).unwrap();
@@ -551,6 +571,10 @@ async fn store_mem_package_create() {
         create_time: 0,
         update_time: 0,
         timestamp: 0,
+        limits: Limits::default(),
+        manifest: Manifest::default(),
+        pack_id: String::new(),
+        code: Vec::new(),
     };
 
     store.packages().create(&package).unwrap();
@@ -571,6 +595,10 @@ async fn store_mem_package_query() {
         create_time: 0,
         update_time: 0,
         timestamp: 0,
+        limits: Limits::default(),
+        manifest: Manifest::default(),
+        pack_id: String::new(),
+        code: Vec::new(),
     };
     store.packages().create(&package).unwrap();
     let q = Query::new().push(Cond::and().push(Expr::eq("id", package.id)));
@@ -591,6 +619,10 @@ async fn store_mem_package_update() {
         create_time: 0,
         update_time: 0,
         timestamp: 0,
+        limits: Limits::default(),
+        manifest: Manifest::default(),
+        pack_id: String::new(),
+        code: Vec::new(),
     };
     store.packages().create(&package).unwrap();
     let mut p = store.packages().find(&package.id).unwrap();
@@ -618,6 +650,10 @@ async fn store_mem_package_remove() {
         create_time: 0,
         update_time: 0,
         timestamp: 0,
+        limits: Limits::default(),
+        manifest: Manifest::default(),
+        pack_id: String::new(),
+        code: Vec::new(),
     };
     store.packages().create(&package).unwrap();
     store.packages().delete(&package.id).unwrap();
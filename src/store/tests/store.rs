@@ -1,8 +1,9 @@
 use crate::{
     data::Model,
     sch::NodeKind,
-    store::{data, query::Expr, Cond, Store, StoreKind},
-    utils, Query, StoreAdapter, TaskState, Workflow,
+    store::{data, db::MemStore, query::Expr, Cond, Store, StoreKind},
+    utils, ActError, DbSet, Fragment, Limits, LogInfo, Manifest, Query, StoreAdapter, TaskState,
+    Workflow,
 };
 use data::{Message, MessageStatus, Package, Proc, Task};
 use serde_json::json;
@@ -13,7 +14,7 @@ static STORE: OnceCell<Arc<Store>> = OnceCell::const_new();
 async fn init() -> Arc<Store> {
     #[cfg(feature = "store")]
     {
-        return Arc::new(Store::local("test_data", "test.db"));
+        return Arc::new(Store::local("test_data", "test.db").unwrap());
     }
 
     #[cfg(not(feature = "store"))]
@@ -119,6 +120,99 @@ async fn store_model_deploy_ver_incr() {
     assert_eq!(model.ver, 2);
 }
 
+#[tokio::test]
+async fn store_model_find_by_ver() {
+    let store = store().await;
+    let mut workflow = create_workflow();
+    workflow.id = utils::longid();
+    workflow.name = "v1".to_string();
+    store.deploy(&workflow).unwrap();
+    workflow.name = "v2".to_string();
+    store.deploy(&workflow).unwrap();
+
+    let model = store.find_model(&workflow.id, Some(1)).unwrap();
+    assert_eq!(model.id, workflow.id);
+    assert_eq!(model.ver, 1);
+    assert_eq!(model.name, "v1");
+
+    let model = store.find_model(&workflow.id, None).unwrap();
+    assert_eq!(model.ver, 2);
+    assert_eq!(model.name, "v2");
+}
+
+#[tokio::test]
+async fn store_model_find_by_ver_not_found() {
+    let store = store().await;
+    let mut workflow = create_workflow();
+    workflow.id = utils::longid();
+    store.deploy(&workflow).unwrap();
+
+    let result = store.find_model(&workflow.id, Some(5));
+    assert!(result.is_err());
+}
+
+/// the adapter which only implements the required methods of `StoreAdapter`
+struct RequiredStore(MemStore);
+
+impl StoreAdapter for RequiredStore {
+    fn init(&self) {}
+    fn close(&self) {}
+
+    fn models(&self) -> Arc<dyn DbSet<Item = Model>> {
+        self.0.models()
+    }
+
+    fn model_versions(&self) -> Arc<dyn DbSet<Item = Model>> {
+        self.0.model_versions()
+    }
+
+    fn procs(&self) -> Arc<dyn DbSet<Item = Proc>> {
+        self.0.procs()
+    }
+
+    fn tasks(&self) -> Arc<dyn DbSet<Item = Task>> {
+        self.0.tasks()
+    }
+
+    fn packages(&self) -> Arc<dyn DbSet<Item = Package>> {
+        self.0.packages()
+    }
+
+    fn messages(&self) -> Arc<dyn DbSet<Item = Message>> {
+        self.0.messages()
+    }
+}
+
+#[tokio::test]
+async fn store_adapter_default_methods() {
+    let store = Store::create(Arc::new(RequiredStore(MemStore::new())));
+    let fragment = Fragment {
+        id: utils::longid(),
+        ..Default::default()
+    };
+    assert!(matches!(
+        store.deploy_fragment(&fragment),
+        Err(ActError::Store(_))
+    ));
+    assert!(store.fragments().find(&fragment.id).is_err());
+
+    let mut pack = Package {
+        id: utils::longid(),
+        data: b"let a = 1;".to_vec(),
+        ..Default::default()
+    };
+    pack.manifest.version = "1.0.0".to_string();
+    assert!(matches!(store.publish(&pack), Err(ActError::Store(_))));
+    assert!(store.package_versions_of(&pack.id).unwrap().is_empty());
+
+    let log = data::Log {
+        id: utils::longid(),
+        ..Default::default()
+    };
+    assert!(matches!(store.logs().create(&log), Err(ActError::Store(_))));
+    assert_eq!(store.logs().query(&Query::new()).unwrap().count, 0);
+}
+
 #[tokio::test]
 async fn store_models() {
     let store = store().await;
@@ -159,6 +253,7 @@ async fn store_model_query_by_id() {
         update_time: 0,
         data: "{}".to_string(),
         timestamp: 0,
+        code: Vec::new(),
     };
     store.models().create(&model).expect("create model");
     let q = Query::new().push(Cond::and().push(Expr::eq("id", model.id)));
@@ -180,6 +275,7 @@ async fn store_model_query_by_offset_count() {
             update_time: 0,
             data: "{}".to_string(),
             timestamp: utils::time::timestamp(),
+            code: Vec::new(),
         };
         store.models().create(&model).expect("create model");
     }
@@ -215,6 +311,7 @@ async fn store_model_query_by_cond_and() {
             update_time: 0,
             data: "{}".to_string(),
             timestamp: utils::time::timestamp(),
+            code: Vec::new(),
         };
         store.models().create(&model).expect("create model");
     }
@@ -250,6 +347,7 @@ async fn store_model_query_by_cond_or() {
             update_time: 0,
             data: "{}".to_string(),
             timestamp: utils::time::timestamp(),
+            code: Vec::new(),
         };
         store.models().create(&model).expect("create model");
     }
@@ -263,6 +361,7 @@ async fn store_model_query_by_cond_or() {
             update_time: 0,
             data: "{}".to_string(),
             timestamp: utils::time::timestamp(),
+            code: Vec::new(),
         };
         store.models().create(&model).expect("create model");
     }
@@ -294,6 +393,7 @@ async fn store_model_query_by_order() {
             update_time: 0,
             data: "{}".to_string(),
             timestamp: utils::time::timestamp(),
+            code: Vec::new(),
         };
         store.models().create(&model).expect("create model");
     }
@@ -382,8 +482,11 @@ async fn store_proc_query_by_id() {
         end_time: 0,
         timestamp: utils::time::timestamp(),
         model: "{}".to_string(),
+        ver: 0,
+        inputs: "{}".to_string(),
         env_local: "{}".to_string(),
         err: None,
+        parent_id: String::new(),
     };
 
     store.procs().create(&proc).expect("create proc");
@@ -406,8 +509,11 @@ async fn store_proc_query_by_offset_count() {
             end_time: 0,
             timestamp: utils::time::timestamp(),
             model: "{}".to_string(),
+            ver: 0,
+            inputs: "{}".to_string(),
             env_local: "{}".to_string(),
             err: None,
+            parent_id: String::new(),
         };
         store.procs().create(&proc).expect("create proc");
     }
@@ -443,8 +549,11 @@ async fn store_proc_query_by_cond_and() {
             end_time: 0,
             timestamp: utils::time::timestamp(),
             model: "{}".to_string(),
+            ver: 0,
+            inputs: "{}".to_string(),
             env_local: "{}".to_string(),
             err: None,
+            parent_id: String::new(),
         };
         store.procs().create(&proc).expect("create proc");
     }
@@ -480,8 +589,11 @@ async fn store_proc_query_by_cond_or() {
             end_time: 0,
             timestamp: utils::time::timestamp(),
             model: "{}".to_string(),
+            ver: 0,
+            inputs: "{}".to_string(),
             env_local: "{}".to_string(),
             err: None,
+            parent_id: String::new(),
         };
         store.procs().create(&proc).expect("create proc");
     }
@@ -496,8 +608,11 @@ async fn store_proc_query_by_cond_or() {
             end_time: 0,
             timestamp: utils::time::timestamp(),
             model: "{}".to_string(),
+            ver: 0,
+            inputs: "{}".to_string(),
             env_local: "{}".to_string(),
             err: None,
+            parent_id: String::new(),
         };
         store.procs().create(&proc).expect("create proc");
     }
@@ -529,8 +644,11 @@ async fn store_proc_query_by_order() {
             end_time: 0,
             timestamp: utils::time::timestamp(),
             model: "{}".to_string(),
+            ver: 0,
+            inputs: "{}".to_string(),
             env_local: "{}".to_string(),
             err: None,
+            parent_id: String::new(),
         };
         store.procs().create(&proc).expect("create proc");
     }
@@ -1316,6 +1434,10 @@ async fn store_package_create() {
         create_time: 0,
         update_time: 0,
         timestamp: 0,
+        limits: Limits::default(),
+        manifest: Manifest::default(),
+        pack_id: String::new(),
+        code: Vec::new(),
     };
 
     store.packages().create(&package).unwrap();
@@ -1336,6 +1458,10 @@ async fn store_package_query_by_id() {
         create_time: 0,
         update_time: 0,
         timestamp: 0,
+        limits: Limits::default(),
+        manifest: Manifest::default(),
+        pack_id: String::new(),
+        code: Vec::new(),
     };
     store.packages().create(&package).unwrap();
     let q = Query::new().push(Cond::and().push(Expr::eq("id", package.id)));
@@ -1355,6 +1481,10 @@ async fn store_package_query_by_offset_count() {
             create_time: 100,
             update_time: 0,
             timestamp: 0,
+            limits: Limits::default(),
+            manifest: Manifest::default(),
+            pack_id: String::new(),
+            code: Vec::new(),
         };
         store.packages().create(&package).unwrap();
     }
@@ -1388,6 +1518,10 @@ async fn store_package_query_by_cond_and() {
             create_time: 200,
             update_time: 0,
             timestamp: 0,
+            limits: Limits::default(),
+            manifest: Manifest::default(),
+            pack_id: String::new(),
+            code: Vec::new(),
         };
         store.packages().create(&package).unwrap();
     }
@@ -1421,6 +1555,10 @@ async fn store_package_query_by_cond_or() {
             create_time: 300,
             update_time: 0,
             timestamp: 0,
+            limits: Limits::default(),
+            manifest: Manifest::default(),
+            pack_id: String::new(),
+            code: Vec::new(),
         };
         store.packages().create(&package).unwrap();
     }
@@ -1434,6 +1572,10 @@ async fn store_package_query_by_cond_or() {
             create_time: 300,
             update_time: 0,
             timestamp: 0,
+            limits: Limits::default(),
+            manifest: Manifest::default(),
+            pack_id: String::new(),
+            code: Vec::new(),
         };
         store.packages().create(&package).unwrap();
     }
@@ -1463,6 +1605,10 @@ async fn store_package_query_by_order() {
             create_time: 400,
             update_time: 0,
             timestamp: utils::time::timestamp(),
+            limits: Limits::default(),
+            manifest: Manifest::default(),
+            pack_id: String::new(),
+            code: Vec::new(),
         };
         store.packages().create(&package).unwrap();
     }
@@ -1497,6 +1643,10 @@ async fn store_package_update() {
         create_time: 0,
         update_time: 0,
         timestamp: 0,
+        limits: Limits::default(),
+        manifest: Manifest::default(),
+        pack_id: String::new(),
+        code: Vec::new(),
     };
     store.packages().create(&package).unwrap();
     let mut p = store.packages().find(&package.id).unwrap();
@@ -1524,6 +1674,10 @@ async fn store_package_remove() {
         create_time: 0,
         update_time: 0,
         timestamp: 0,
+        limits: Limits::default(),
+        manifest: Manifest::default(),
+        pack_id: String::new(),
+        code: Vec::new(),
     };
     store.packages().create(&package).unwrap();
     store.packages().delete(&package.id).unwrap();
@@ -1548,7 +1702,188 @@ fn create_proc(id: &str, state: TaskState, model: &Workflow) -> Proc {
         end_time: 0,
         timestamp: utils::time::timestamp(),
         model: model.to_json().unwrap(),
+        ver: 0,
+        inputs: "{}".to_string(),
         env_local: "{}".to_string(),
         err: None,
+        parent_id: String::new(),
+    }
+}
+
+#[tokio::test]
+async fn store_publish_package_versions() {
+    let store = store().await;
+    let id = utils::longid();
+    for ver in ["1.0.0", "1.2.0", "1.1.0"] {
+        let package = Package {
+            id: id.clone(),
+            name: "test package".to_string(),
+            data: format!("let v = '{ver}';").into_bytes(),
+            manifest: Manifest::new().with_version(ver),
+            ..Default::default()
+        };
+        store.publish(&package).unwrap();
     }
+
+    let versions = store.package_versions_of(&id).unwrap();
+    assert_eq!(
+        versions
+            .iter()
+            .map(|p| p.manifest.version.as_str())
+            .collect::<Vec<_>>(),
+        ["1.2.0", "1.1.0", "1.0.0"]
+    );
+    assert!(versions.iter().all(|p| p.id == id));
+    assert_eq!(
+        store.packages().find(&id).unwrap().manifest.version,
+        "1.1.0"
+    );
+
+    // the versions are queried by the package id column
+    let q = Query::new().push(Cond::and().push(Expr::eq("pack_id", id.clone())));
+    assert_eq!(store.package_versions().query(&q).unwrap().count, 3);
+}
+
+#[tokio::test]
+async fn store_find_package_by_version() {
+    let store = store().await;
+    let id = utils::longid();
+    for ver in ["1.0.0", "1.2.0", "2.0.0"] {
+        let package = Package {
+            id: id.clone(),
+            name: "test package".to_string(),
+            data: format!("let v = '{ver}';").into_bytes(),
+            manifest: Manifest::new().with_version(ver),
+            ..Default::default()
+        };
+        store.publish(&package).unwrap();
+    }
+
+    let pack = store.find_package(&id, Some("1.0.0")).unwrap();
+    assert_eq!(pack.id, id);
+    assert_eq!(pack.data, b"let v = '1.0.0';");
+    assert_eq!(
+        store
+            .find_package(&id, Some("^1.0"))
+            .unwrap()
+            .manifest
+            .version,
+        "1.2.0"
+    );
+    assert_eq!(
+        store.find_package(&id, None).unwrap().manifest.version,
+        "2.0.0"
+    );
+    assert!(store.find_package(&id, Some("3.0.0")).is_err());
+}
+
+#[tokio::test]
+async fn store_publish_package_without_version() {
+    let store = store().await;
+    let id = utils::longid();
+    let package = Package {
+        id: id.clone(),
+        name: "test package".to_string(),
+        data: b"let a = 1;".to_vec(),
+        ..Default::default()
+    };
+    store.publish(&package).unwrap();
+    assert!(store.package_versions_of(&id).unwrap().is_empty());
+    assert!(store.find_package(&id, Some("1.0.0")).is_err());
+}
+
+#[tokio::test]
+async fn store_publish_package_dependencies() {
+    let store = store().await;
+    let dep = utils::longid();
+    let id = utils::longid();
+    let package = Package {
+        id: id.clone(),
+        name: "test package".to_string(),
+        data: b"let a = 1;".to_vec(),
+        manifest: Manifest::new()
+            .with_version("1.0.0")
+            .with_dependency(&dep, "^1.0"),
+        ..Default::default()
+    };
+    let result = store.publish(&package);
+    assert_eq!(
+        result.err().unwrap(),
+        ActError::Action(format!(
+            "cannot find the dependency '{dep}@^1.0' of package '{id}'"
+        ))
+    );
+
+    store
+        .publish(&Package {
+            id: dep.clone(),
+            name: "dep".to_string(),
+            data: b"let b = 1;".to_vec(),
+            manifest: Manifest::new().with_version("1.3.0"),
+            ..Default::default()
+        })
+        .unwrap();
+    assert!(store.publish(&package).is_ok());
+}
+
+#[tokio::test]
+async fn store_publish_package_invalid_version() {
+    let store = store().await;
+    let package = Package {
+        id: utils::longid(),
+        name: "test package".to_string(),
+        data: b"let a = 1;".to_vec(),
+        manifest: Manifest::new().with_version("v1"),
+        ..Default::default()
+    };
+    assert!(matches!(store.publish(&package), Err(ActError::Action(_))));
+}
+
+#[tokio::test]
+async fn store_save_logs() {
+    let store = store().await;
+    let pid = utils::longid();
+    let logs = (0..3)
+        .map(|i| LogInfo {
+            level: "log".to_string(),
+            message: i.to_string(),
+            time: i,
+        })
+        .collect::<Vec<_>>();
+    store.save_logs(&pid, "tid1", "step1", &logs[..2]).unwrap();
+    // the logs are replaced when the task is saved again
+    store.save_logs(&pid, "tid1", "step1", &logs).unwrap();
+
+    let logs = store.logs_of(&pid, "tid1").unwrap();
+    assert_eq!(
+        logs.iter()
+            .map(|log| log.message.as_str())
+            .collect::<Vec<_>>(),
+        ["0", "1", "2"]
+    );
+    assert_eq!(
+        store.logs().find(&format!("{pid}:tid1")).unwrap().nid,
+        "step1"
+    );
+}
+
+#[tokio::test]
+async fn store_logs_of_empty() {
+    let store = store().await;
+    let logs = store.logs_of(&utils::longid(), "tid1").unwrap();
+    assert!(logs.is_empty());
+}
+
+#[tokio::test]
+async fn store_remove_proc_logs() {
+    let store = store().await;
+    let pid = utils::longid();
+    let log = LogInfo {
+        level: "error".to_string(),
+        message: "error".to_string(),
+        time: 0,
+    };
+    store.save_logs(&pid, "tid1", "step1", &[log]).unwrap();
+    store.remove_proc(&pid).unwrap();
+    assert!(store.logs_of(&pid, "tid1").unwrap().is_empty());
 }
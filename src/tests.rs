@@ -1,5 +1,6 @@
-use crate::{utils, Act, Builder, Engine, Vars, Workflow};
+use crate::{utils, Act, Builder, Engine, ManualClock, Vars, Workflow};
 use serde_json::json;
+use std::time::Duration;
 
 #[tokio::test]
 async fn engine_start() {
@@ -152,6 +153,12 @@ async fn engine_build_cache_size() {
     assert_eq!(engine.config().cache_cap, 100)
 }
 
+#[tokio::test]
+async fn engine_build_try_build() {
+    let engine = Builder::new().cache_size(10).try_build().unwrap();
+    assert_eq!(engine.config().cache_cap, 10)
+}
+
 #[tokio::test]
 async fn engine_build_data_dir() {
     let engine = Builder::new().data_dir("test").build();
@@ -188,6 +195,12 @@ async fn engine_build_max_message_retry_times() {
     assert_eq!(engine.config().max_message_retry_times, 100)
 }
 
+#[tokio::test]
+async fn engine_build_script_log_limit() {
+    let engine = Builder::new().script_log_limit(10).build();
+    assert_eq!(engine.config().script_log_limit, 10)
+}
+
 #[tokio::test]
 async fn engine_drop() {
     let engine = Engine::new();
@@ -195,3 +208,11 @@ async fn engine_drop() {
     let engine = Engine::new();
     drop(engine)
 }
+
+#[tokio::test]
+async fn engine_build_clock() {
+    let clock = ManualClock::at(1000);
+    let engine = Builder::new().clock(&clock).build();
+    clock.advance(Duration::from_secs(5));
+    assert_eq!(engine.runtime().clock().now(), 6000)
+}
@@ -12,6 +12,8 @@ pub const INITIATOR: &str = "initiator";
 
 pub const ACT_USE_PARENT_PROC_ID: &str = "use_parent_pid";
 pub const ACT_USE_PARENT_TASK_ID: &str = "use_parent_tid";
+pub const ACT_CALL_PROC_ID: &str = "call_pid";
+pub const ACT_CALL_PARENT_PROC_ID: &str = "call_parent_pid";
 
 pub const FOR_ACT_KEY_UID: &str = "uid";
 pub const STEP_NODE_ID: &str = "node_id";
@@ -21,6 +23,11 @@ pub const STEP_KEY: &str = "step";
 
 pub const ACT_ERR_MESSAGE: &str = "error";
 pub const ACT_ERR_CODE: &str = "ecode";
+pub const ACT_ERR_LOGS: &str = "logs";
+pub const ACT_ERR_FIELDS: &str = "fields";
+pub const ACT_LIMIT_ECODE: &str = "limit_exceeded";
+#[cfg(feature = "quickjs")]
+pub const PACK_MODULE_PREFIX: &str = "@pack/";
 
 pub const ACT_INDEX: &str = "act_index";
 pub const ACT_VALUE: &str = "act_value";
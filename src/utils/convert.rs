@@ -1,33 +1,19 @@
-use crate::{sch::Task, Context, Vars};
-use regex::Regex;
+use super::{unescape, Template};
+use crate::{sch::Task, Context, Result, Vars};
 use serde_json::Value as JsonValue;
 use std::sync::Arc;
+use tracing::warn;
 
 /// fill the vars
 /// 1. if the inputs is an expression, just calculate it
 ///    or insert the input itself
+/// 2. the expressions embedded in the strings, objects and arrays are also calculated
+/// 3. the value is kept if the calculation fails, such as the shell variable `echo ${HOME}`
 pub fn fill_inputs(inputs: &Vars, ctx: &Context) -> Vars {
     let mut ret = Vars::new();
     for (k, ref v) in inputs {
-        if let JsonValue::String(string) = v {
-            if let Some(expr) = get_expr(string) {
-                let result = Context::scope(ctx.clone(), move || {
-                    ctx.runtime.env().eval::<JsonValue>(&expr)
-                });
-                let new_value = match result {
-                    Ok(v) => v,
-                    Err(err) => {
-                        eprintln!("fill_inputs: expr:{string}, err={err}");
-                        JsonValue::Null
-                    }
-                };
-
-                // satisfies the rule 1
-                ret.insert(k.to_string(), new_value);
-                continue;
-            }
-        }
-        ret.insert(k.to_string(), v.clone());
+        // satisfies the rule 1 and 2
+        ret.insert(k.to_string(), fill_value(v, ctx, "fill_inputs"));
     }
 
     ret
@@ -40,25 +26,6 @@ pub fn fill_outputs(outputs: &Vars, ctx: &Context) -> Vars {
     // println!("fill_outputs: outputs={outputs}");
     let mut ret = Vars::new();
     for (ref k, ref v) in outputs {
-        if let JsonValue::String(string) = v {
-            if let Some(expr) = get_expr(string) {
-                let result = Context::scope(ctx.clone(), move || {
-                    ctx.runtime.env().eval::<JsonValue>(&expr)
-                });
-                let new_value = match result {
-                    Ok(v) => v,
-                    Err(err) => {
-                        eprintln!("fill_outputs: expr:{string}, err={err}");
-                        JsonValue::Null
-                    }
-                };
-
-                // satisfies the rule 1
-                ret.insert(k.to_string(), new_value);
-                continue;
-            }
-        }
-
         // rule 2
         if v.is_null() {
             // the env value
@@ -67,8 +34,8 @@ pub fn fill_outputs(outputs: &Vars, ctx: &Context) -> Vars {
                 None => ret.insert(k.to_string(), v.clone()),
             };
         } else {
-            // insert the orign value
-            ret.insert(k.to_string(), v.clone());
+            // satisfies the rule 1 or insert the orign value
+            ret.insert(k.to_string(), fill_value(v, ctx, "fill_outputs"));
         }
     }
 
@@ -79,12 +46,14 @@ pub fn fill_proc_vars(task: &Arc<Task>, values: &Vars, ctx: &Context) -> Vars {
     let mut ret = Vars::new();
     for (ref k, ref v) in values {
         if let JsonValue::String(string) = v {
-            if let Some(expr) = get_expr(string) {
-                let result =
-                    Context::scope(ctx.clone(), || ctx.runtime.env().eval::<JsonValue>(&expr));
+            if let Some(template) = Template::parse(string) {
+                let result = eval_template(&template, ctx);
                 let new_value = match result {
                     Ok(v) => v,
-                    Err(_err) => JsonValue::Null,
+                    Err(err) => {
+                        warn!("fill_proc_vars: expr:{string}, err={err}");
+                        v.clone()
+                    }
                 };
 
                 // satisfies the rule 1
@@ -103,14 +72,38 @@ pub fn fill_proc_vars(task: &Arc<Task>, values: &Vars, ctx: &Context) -> Vars {
     ret
 }
 
+/// gets the expression if the whole text is `${ expr }`
 pub fn get_expr(text: &str) -> Option<String> {
-    let re = Regex::new(r"^\$\{(.+)\}$").unwrap();
-    let caps = re.captures(text);
+    Template::parse(text).and_then(|template| template.expr())
+}
 
-    if let Some(caps) = caps {
-        let value = caps.get(1).map_or("", |m| m.as_str());
-        return Some(value.trim().to_string());
+/// calculates the expressions in the value, the strings in the objects and arrays are also calculated
+fn fill_value(value: &JsonValue, ctx: &Context, name: &str) -> JsonValue {
+    match value {
+        JsonValue::String(string) => match Template::parse(string) {
+            Some(template) => match eval_template(&template, ctx) {
+                Ok(v) => v,
+                Err(err) => {
+                    warn!("{name}: expr:{string}, err={err}");
+                    value.clone()
+                }
+            },
+            None => JsonValue::String(unescape(string)),
+        },
+        JsonValue::Array(items) => {
+            JsonValue::Array(items.iter().map(|v| fill_value(v, ctx, name)).collect())
+        }
+        JsonValue::Object(map) => JsonValue::Object(
+            map.iter()
+                .map(|(k, v)| (k.clone(), fill_value(v, ctx, name)))
+                .collect(),
+        ),
+        _ => value.clone(),
     }
+}
 
-    None
+/// evaluates all of the template expressions in one script and renders the template
+fn eval_template(template: &Template, ctx: &Context) -> Result<JsonValue> {
+    let value = ctx.eval::<JsonValue>(&template.script())?;
+    template.render(value)
 }
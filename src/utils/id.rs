@@ -18,6 +18,23 @@ pub fn shortid() -> String {
     nanoid!(8, &ALPHABETS)
 }
 
+/// generates the short id by the key, the same key always gets the same id
+pub fn hashid(key: &str) -> String {
+    // fnv-1a hash
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for b in key.bytes() {
+        hash ^= b as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+
+    let mut ret = String::new();
+    for _ in 0..8 {
+        ret.push(ALPHABETS[(hash % ALPHABETS.len() as u64) as usize]);
+        hash /= ALPHABETS.len() as u64;
+    }
+    ret
+}
+
 #[derive(Debug)]
 pub struct Id<'a> {
     pid: &'a str,
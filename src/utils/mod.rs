@@ -1,7 +1,9 @@
 pub mod consts;
 mod convert;
 mod id;
+mod template;
 pub mod time;
 
 pub use convert::*;
 pub use id::*;
+pub use template::*;
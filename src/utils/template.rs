@@ -0,0 +1,297 @@
+use super::time;
+use crate::{ActError, Result};
+use regex::Regex;
+use serde_json::Value as JsonValue;
+
+/// the pipe filters which can be used in the template expression, such as `${ $("a") | upper }`
+pub const FILTERS: [&str; 4] = ["upper", "default", "date", "json"];
+
+#[derive(Debug, Clone, PartialEq)]
+struct Filter {
+    name: String,
+    /// the filter args script, such as `"n/a"` in `default("n/a")`
+    args: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Part {
+    Text(String),
+    Expr {
+        /// the raw text between `${` and `}`
+        raw: String,
+        script: String,
+        filters: Vec<Filter>,
+    },
+}
+
+/// the template string with the embedded expressions, such as `Order ${ $("id") } approved`
+///
+/// all of the expressions and the filter args are evaluated in one script,
+/// and the filters are applied to the evaluated values
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+    parts: Vec<Part>,
+}
+
+impl Template {
+    /// parses the text, returns `None` if there is no expression in the text
+    /// the `$${` is the escaped `${`, which is kept as the text
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut parts = Vec::new();
+        let mut rest = text;
+        let mut prefix = String::new();
+        while let Some(start) = rest.find("${") {
+            if rest[..start].ends_with('$') {
+                prefix.push_str(&rest[..start - 1]);
+                prefix.push_str("${");
+                rest = &rest[start + 2..];
+                continue;
+            }
+            let end = match find_close(&rest[start + 2..]) {
+                Some(end) => start + 2 + end,
+                None => break,
+            };
+            prefix.push_str(&rest[..start]);
+            if !prefix.is_empty() {
+                parts.push(Part::Text(std::mem::take(&mut prefix)));
+            }
+            let raw = rest[start + 2..end].to_string();
+            let (script, filters) = split_filters(&raw);
+            parts.push(Part::Expr {
+                raw,
+                script,
+                filters,
+            });
+            rest = &rest[end + 1..];
+        }
+
+        if !parts.iter().any(|p| matches!(p, Part::Expr { .. })) {
+            return None;
+        }
+        prefix.push_str(&unescape(rest));
+        if !prefix.is_empty() {
+            parts.push(Part::Text(prefix));
+        }
+
+        Some(Self { parts })
+    }
+
+    /// the raw expression if the whole text is one expression
+    pub fn expr(&self) -> Option<String> {
+        match &self.parts[..] {
+            [Part::Expr { raw, .. }] => Some(raw.trim().to_string()),
+            _ => None,
+        }
+    }
+
+    /// the whole text is one expression without filters, the value is not converted
+    pub fn is_expr(&self) -> bool {
+        matches!(&self.parts[..], [Part::Expr { filters, .. }] if filters.is_empty())
+    }
+
+    /// the script to evaluate the template
+    /// it is the expression itself by `is_expr`, or an array of the expression values and the filter args
+    pub fn script(&self) -> String {
+        if let [Part::Expr {
+            script, filters, ..
+        }] = &self.parts[..]
+        {
+            if filters.is_empty() {
+                return script.clone();
+            }
+        }
+
+        let mut items = Vec::new();
+        for part in &self.parts {
+            if let Part::Expr {
+                script, filters, ..
+            } = part
+            {
+                items.push(format!("({script})"));
+                for filter in filters {
+                    if let Some(args) = &filter.args {
+                        items.push(format!("[{args}]"));
+                    }
+                }
+            }
+        }
+        format!("[{}]", items.join(", "))
+    }
+
+    /// renders the template by the evaluated value of `script`
+    pub fn render(&self, value: JsonValue) -> Result<JsonValue> {
+        if self.is_expr() {
+            return Ok(value);
+        }
+
+        let mut values = match value {
+            JsonValue::Array(values) => values.into_iter(),
+            value => {
+                return Err(ActError::Script(format!(
+                    "the template values should be an array, but got {value}"
+                )))
+            }
+        };
+        let mut next = || values.next().unwrap_or(JsonValue::Null);
+
+        let mut outputs = Vec::new();
+        for part in &self.parts {
+            match part {
+                Part::Text(text) => outputs.push(JsonValue::String(text.clone())),
+                Part::Expr { filters, .. } => {
+                    let mut value = next();
+                    for filter in filters {
+                        let args = match filter.args {
+                            Some(_) => match next() {
+                                JsonValue::Array(args) => args,
+                                arg => vec![arg],
+                            },
+                            None => Vec::new(),
+                        };
+                        value = apply(&filter.name, value, &args)?;
+                    }
+                    outputs.push(value);
+                }
+            }
+        }
+
+        if let [Part::Expr { .. }] = &self.parts[..] {
+            return Ok(outputs.pop().unwrap_or_default());
+        }
+        Ok(JsonValue::String(outputs.iter().map(to_text).collect()))
+    }
+}
+
+/// replaces the escaped `$${` with `${` in the text without expressions
+pub fn unescape(text: &str) -> String {
+    text.replace("$${", "${")
+}
+
+/// the text of the value in the template, `null` is empty
+fn to_text(value: &JsonValue) -> String {
+    match value {
+        JsonValue::Null => "".to_string(),
+        JsonValue::String(s) => s.clone(),
+        v => v.to_string(),
+    }
+}
+
+fn apply(name: &str, value: JsonValue, args: &[JsonValue]) -> Result<JsonValue> {
+    match name {
+        "upper" => {
+            if value.is_null() {
+                return Ok(value);
+            }
+            Ok(JsonValue::String(to_text(&value).to_uppercase()))
+        }
+        "default" => {
+            if value.is_null() {
+                return Ok(args.first().cloned().unwrap_or_default());
+            }
+            Ok(value)
+        }
+        "date" => {
+            if value.is_null() {
+                return Ok(value);
+            }
+            let fmt = args
+                .first()
+                .and_then(|v| v.as_str())
+                .unwrap_or(time::DATE_FORMAT);
+            let date = time::parse_time(&value)
+                .ok_or(ActError::Script(format!("the value {value} is not a date")))?;
+            time::format_time(&date, fmt).map(JsonValue::String)
+        }
+        "json" => serde_json::to_string(&value)
+            .map(JsonValue::String)
+            .map_err(|err| ActError::Script(err.to_string())),
+        name => Err(ActError::Script(format!(
+            "the filter '{name}' is not supported"
+        ))),
+    }
+}
+
+/// finds the `}` which closes the expression, the braces in strings and nested blocks are skipped
+fn find_close(text: &str) -> Option<usize> {
+    let mut depth = 0;
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+    for (index, c) in text.char_indices() {
+        if let Some(q) = quote {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '"' | '\'' | '`' => quote = Some(c),
+            '{' => depth += 1,
+            '}' if depth == 0 => return Some(index),
+            '}' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// splits the expression by the top-level `|`, the trailing segments which are
+/// the known filters are the filters, so the `|` operator is still kept in the script
+fn split_filters(expr: &str) -> (String, Vec<Filter>) {
+    let mut pipes = Vec::new();
+    let mut depth = 0;
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+    let chars = expr.char_indices().collect::<Vec<_>>();
+    for (i, &(index, c)) in chars.iter().enumerate() {
+        if let Some(q) = quote {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '"' | '\'' | '`' => quote = Some(c),
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth -= 1,
+            '|' if depth == 0 => {
+                let prev = i.checked_sub(1).map(|i| chars[i].1);
+                let next = chars.get(i + 1).map(|(_, c)| *c);
+                if prev != Some('|') && next != Some('|') && next != Some('=') {
+                    pipes.push(index);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let re = Regex::new(r"^\s*([a-z]+)\s*(?:\(([\s\S]*)\))?\s*$").unwrap();
+    let mut end = expr.len();
+    let mut filters = Vec::new();
+    for &pipe in pipes.iter().rev() {
+        let caps = match re.captures(&expr[pipe + 1..end]) {
+            Some(caps) if FILTERS.contains(&&caps[1]) => caps,
+            _ => break,
+        };
+        filters.insert(
+            0,
+            Filter {
+                name: caps[1].to_string(),
+                args: caps
+                    .get(2)
+                    .map(|m| m.as_str().trim().to_string())
+                    .filter(|args| !args.is_empty()),
+            },
+        );
+        end = pipe;
+    }
+
+    (expr[..end].trim().to_string(), filters)
+}
@@ -1,3 +1,10 @@
+use crate::{ActError, Result};
+use chrono::{format::StrftimeItems, DateTime, NaiveDate, NaiveDateTime, Utc};
+use serde_json::Value as JsonValue;
+
+/// the default time format, which is the iso string with millis
+pub const DATE_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.3fZ";
+
 pub fn time_millis() -> i64 {
     let time: chrono::DateTime<chrono::Utc> = chrono::Utc::now();
     time.timestamp_millis()
@@ -7,3 +14,35 @@ pub fn timestamp() -> i64 {
     let time: chrono::DateTime<chrono::Utc> = chrono::Utc::now();
     time.timestamp_micros()
 }
+
+/// parses the time from the rfc3339 string, the date string or the timestamp millis
+pub fn parse_time(value: &JsonValue) -> Option<DateTime<Utc>> {
+    match value {
+        JsonValue::Number(n) => n.as_i64().and_then(DateTime::from_timestamp_millis),
+        JsonValue::String(s) => DateTime::parse_from_rfc3339(s)
+            .map(|t| t.with_timezone(&Utc))
+            .ok()
+            .or_else(|| {
+                NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S")
+                    .ok()
+                    .map(|t| t.and_utc())
+            })
+            .or_else(|| {
+                NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                    .ok()
+                    .and_then(|d| d.and_hms_opt(0, 0, 0))
+                    .map(|t| t.and_utc())
+            }),
+        _ => None,
+    }
+}
+
+/// formats the time by the `strftime` format
+pub fn format_time(time: &DateTime<Utc>, fmt: &str) -> Result<String> {
+    if StrftimeItems::new(fmt).any(|item| item == chrono::format::Item::Error) {
+        return Err(ActError::Script(format!(
+            "the date format '{fmt}' is invalid"
+        )));
+    }
+    Ok(time.format(fmt).to_string())
+}